@@ -17,8 +17,15 @@ export type FixStatus =
   | "failure"
   | "running"
   | "error"
+  | "cancelled"
+  | "skipped"
   | "unstarted";
 
+export type FixBatchFailurePolicy =
+  | "abortRemaining"
+  | "continueAll"
+  | "skipDependents";
+
 export enum ActionKind {
   Create = "create",
   Delete = "delete",
@@ -77,6 +84,15 @@ export type Fix = {
   finishedAt?: string;
 };
 
+export type FixPlanStep = {
+  attributeValueId: AttributeValueId;
+  componentId: ComponentId;
+  componentName: string;
+  actionPrototypeId: ActionPrototypeId;
+  actionKind: ActionKind;
+  dependsOn: number[];
+};
+
 // TODO(nick): use real user data and real timestamps. This is dependent on the backend.
 export type FixBatchId = string;
 export type FixBatch = {
@@ -278,13 +294,44 @@ export const useFixesStore = () => {
             onSuccess: (response) => {
               this.fixBatches = response;
               this.runningFixBatch = response.find(
-                (batch) => !["success", "failure"].includes(batch.status ?? ""),
+                (batch) =>
+                  !["success", "failure", "cancelled"].includes(batch.status ?? ""),
               )?.id;
             },
           });
         },
+        async CANCEL_FIX(fixId: FixId) {
+          return new ApiRequest({
+            method: "post",
+            params: {
+              id: fixId,
+              visibility_change_set_pk: nilId(),
+            },
+            url: "/fix/cancel",
+            onSuccess: () => {
+              this.LOAD_FIX_BATCHES();
+            },
+          });
+        },
+        async PLAN_FIXES_FROM_RECOMMENDATIONS(
+          recommendations: Array<Recommendation>,
+        ) {
+          return new ApiRequest<{ steps: FixPlanStep[] }>({
+            method: "post",
+            params: {
+              list: recommendations.map((r) => ({
+                attributeValueId: r.confirmationAttributeValueId,
+                componentId: r.componentId,
+                actionPrototypeId: r.actionPrototypeId,
+              })),
+              visibility_change_set_pk: nilId(),
+            },
+            url: "/fix/plan",
+          });
+        },
         async EXECUTE_FIXES_FROM_RECOMMENDATIONS(
           recommendations: Array<Recommendation>,
+          failurePolicy: FixBatchFailurePolicy = "continueAll",
         ) {
           return new ApiRequest({
             method: "post",
@@ -294,6 +341,7 @@ export const useFixesStore = () => {
                 componentId: r.componentId,
                 actionPrototypeId: r.actionPrototypeId,
               })),
+              failurePolicy,
               visibility_change_set_pk: nilId(),
             },
             url: "/fix/run",
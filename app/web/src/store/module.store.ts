@@ -85,7 +85,7 @@ export type RemoteModuleSummary = {
   hash: ModuleHash;
   hashCreatedAt: IsoDateString;
   ownerDisplayName: string;
-  ownerUserId: string; // userid?
+  ownerUserId: string | null; // userid?
 };
 
 export type RemoteModuleDetails = RemoteModuleSummary & {
@@ -23,6 +23,10 @@ pub(crate) struct Args {
     #[arg(long)]
     pub(crate) nats_url: Option<String>,
 
+    /// NATS key/value bucket used to persist coordination state [default: council-state]
+    #[arg(long)]
+    pub(crate) state_bucket: Option<String>,
+
     /// Disable OpenTelemetry on startup
     #[arg(long)]
     pub(crate) disable_opentelemetry: bool,
@@ -36,6 +40,9 @@ impl TryFrom<Args> for Config {
             if let Some(url) = args.nats_url {
                 config_map.set("nats.url", url);
             }
+            if let Some(state_bucket) = args.state_bucket {
+                config_map.set("state_bucket", state_bucket);
+            }
         })?
         .try_into()
     }
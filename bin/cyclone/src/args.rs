@@ -1,9 +1,12 @@
-use std::{net::SocketAddr, path::PathBuf, time::Duration};
+use std::{ffi::OsString, net::SocketAddr, path::PathBuf, time::Duration};
 
 use clap::{ArgAction, Parser};
-use cyclone_server::{Config, ConfigError, IncomingStream};
+use cyclone_server::{
+    Config, ConfigError, HttpSecretBackendConfig, IncomingStream, ResourceLimits, SecretBackends,
+};
 
 const NAME: &str = "cyclone";
+const NODE_OPTIONS: &str = "NODE_OPTIONS";
 
 /// Parse, validate, and return the CLI arguments as a typed struct.
 pub(crate) fn parse() -> Args {
@@ -100,6 +103,56 @@ pub(crate) struct Args {
     #[arg(long, group = "request_limiting")]
     pub(crate) limit_requests: Option<u32>,
 
+    /// Terminates a function execution after the given number of seconds [default: 1800]
+    ///
+    /// Requests may set a timeout of their own, which takes precedence.
+    #[arg(long)]
+    pub(crate) execution_timeout: Option<u64>,
+
+    /// Limits the JavaScript heap of each function execution to the given number of bytes
+    #[arg(long)]
+    pub(crate) limit_memory_bytes: Option<u64>,
+
+    /// Limits the CPU time of each function execution to the given number of seconds
+    #[arg(long)]
+    pub(crate) limit_cpu_time: Option<u64>,
+
+    /// Limits the number of files each function execution may open
+    #[arg(long)]
+    pub(crate) limit_open_files: Option<u64>,
+
+    /// Terminates a function execution once it produced more than the given number of bytes of
+    /// output
+    #[arg(long)]
+    pub(crate) max_output_bytes: Option<u64>,
+
+    /// Passes the given environment variable through to function executions [example: PATH]
+    ///
+    /// May be given multiple times. When unset, function executions inherit the whole
+    /// environment.
+    #[arg(long = "env-allow", value_name = "NAME", action = ArgAction::Append)]
+    pub(crate) env_allow: Option<Vec<String>>,
+
+    /// Resolves "file" secret references relative to the given directory, under a subdirectory
+    /// named after the workspace of the secret [example: /run/secrets]
+    #[arg(long)]
+    pub(crate) secret_backend_file_root: Option<PathBuf>,
+
+    /// Resolves "http" secret references against the given Vault-compatible HTTP API, under a
+    /// path prefix named after the workspace of the secret
+    /// [example: https://vault.example.com:8200]
+    #[arg(long)]
+    pub(crate) secret_backend_http_address: Option<String>,
+
+    /// Token sent to the HTTP secret backend
+    #[arg(
+        long,
+        env = "SI_SECRET_BACKEND_HTTP_TOKEN",
+        hide_env_values = true,
+        requires = "secret_backend_http_address"
+    )]
+    pub(crate) secret_backend_http_token: Option<String>,
+
     /// Cyclone decryption key file location [example: /run/cyclone/cyclone.key]
     #[arg(long)]
     pub(crate) decryption_key: PathBuf,
@@ -144,10 +197,52 @@ impl TryFrom<Args> for Config {
             builder.limit_requests(limit_requests);
         }
 
+        if let Some(execution_timeout) = args.execution_timeout {
+            builder.execution_timeout(Duration::from_secs(execution_timeout));
+        }
+
+        builder.resource_limits(ResourceLimits {
+            memory_bytes: args.limit_memory_bytes,
+            cpu_time_secs: args.limit_cpu_time,
+            open_files: args.limit_open_files,
+            max_output_bytes: args.max_output_bytes,
+        });
+        if let Some(env_allow) = &args.env_allow {
+            builder.execution_env(allowed_env(env_allow));
+        }
+        builder.inherited_node_options(inherited_node_options(args.env_allow.as_deref()));
+        builder.secret_backends(SecretBackends::new(
+            args.secret_backend_file_root,
+            args.secret_backend_http_address
+                .map(|address| HttpSecretBackendConfig {
+                    address,
+                    token: args.secret_backend_http_token,
+                }),
+        ));
+
         builder.build().map_err(Into::into)
     }
 }
 
+/// Collects the variables of cyclone's environment which are passed through to function
+/// executions.
+#[allow(clippy::disallowed_methods)] // Resolves the environment injected into the server
+fn allowed_env(env_allow: &[String]) -> Vec<(OsString, OsString)> {
+    env_allow
+        .iter()
+        .filter_map(|name| std::env::var_os(name).map(|value| (name.into(), value)))
+        .collect()
+}
+
+/// Gets the `NODE_OPTIONS` of cyclone's environment, if function executions inherit them.
+#[allow(clippy::disallowed_methods)] // Resolves the environment injected into the server
+fn inherited_node_options(env_allow: Option<&[String]>) -> Option<OsString> {
+    let passes = env_allow.map_or(true, |env_allow| {
+        env_allow.iter().any(|allowed| allowed == NODE_OPTIONS)
+    });
+    std::env::var_os(NODE_OPTIONS).filter(|_| passes)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
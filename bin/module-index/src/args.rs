@@ -49,6 +49,14 @@ pub(crate) struct Args {
     #[arg(long, env)]
     pub(crate) socket_addr: Option<String>,
 
+    /// Where modules are stored [possible values: s3, filesystem]
+    #[arg(long, env)]
+    pub(crate) storage: Option<String>,
+
+    /// The directory modules are stored in when using the filesystem storage
+    #[arg(long, env)]
+    pub(crate) filesystem_root: Option<String>,
+
     /// The s3 bucket access key id
     #[arg(long, env)]
     pub(crate) s3_access_key_id: Option<String>,
@@ -69,6 +77,14 @@ pub(crate) struct Args {
     #[arg(long, env)]
     pub(crate) s3_path_prefix: Option<String>,
 
+    /// The endpoint of an S3-compatible object store [example: http://localhost:9000]
+    #[arg(long, env)]
+    pub(crate) s3_endpoint: Option<String>,
+
+    /// Use path-style addressing of the s3 bucket, as most S3-compatible object stores require
+    #[arg(long, env)]
+    pub(crate) s3_path_style: bool,
+
     /// The path to the JWT public signing key
     #[arg(long, env)]
     pub(crate) jwt_public_key: Option<String>,
@@ -83,6 +99,10 @@ pub(crate) struct Args {
 
     #[arg(long, env)]
     pub(crate) restrict_listing: bool,
+
+    /// Reject uploads of unsigned modules instead of flagging them
+    #[arg(long, env)]
+    pub(crate) require_signed_modules: bool,
 }
 
 impl TryFrom<Args> for Config {
@@ -112,6 +132,12 @@ impl TryFrom<Args> for Config {
                 config_map.set("socket_addr", socket_addr);
             }
 
+            if let Some(storage) = args.storage {
+                config_map.set("storage", storage);
+            }
+            if let Some(filesystem_root) = args.filesystem_root {
+                config_map.set("filesystem.root", filesystem_root);
+            }
             if let Some(s3_access_key_id) = args.s3_access_key_id {
                 config_map.set("s3.access_key_id", s3_access_key_id);
             }
@@ -127,12 +153,21 @@ impl TryFrom<Args> for Config {
             if let Some(s3_path_prefix) = args.s3_path_prefix {
                 config_map.set("s3.path_prefix", s3_path_prefix);
             }
+            if let Some(s3_endpoint) = args.s3_endpoint {
+                config_map.set("s3.endpoint", s3_endpoint);
+            }
+            if args.s3_path_style {
+                config_map.set("s3.path_style", true);
+            }
             if let Some(jwt_public_key) = args.jwt_public_key {
                 config_map.set("jwt_signing_public_key_path", jwt_public_key);
             }
             if args.restrict_listing {
                 config_map.set("restrict_listing", true);
             }
+            if args.require_signed_modules {
+                config_map.set("require_signed_modules", true);
+            }
 
             // if let Some(migration_mode) = args.migration_mode {
             //     config_map.set("migration_mode", migration_mode);
@@ -56,6 +56,7 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
 
     // this is the SeaOrm-managed Pg Pool
     let pg_pool = Server::create_db_connection(config.pg_pool()).await?;
+    Server::spawn_backfill_module_categories(&config, pg_pool.clone())?;
 
     start_tracing_level_signal_handler_task(&telemetry)?;
 
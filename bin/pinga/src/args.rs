@@ -57,6 +57,18 @@ pub(crate) struct Args {
     #[arg(long)]
     pub(crate) concurrency: Option<u32>,
 
+    /// The number of fixes in a batch that can run at the same time [default: 5]
+    #[arg(long)]
+    pub(crate) fix_concurrency: Option<u32>,
+
+    /// The number of times a job is attempted before it is dead-lettered [default: 3]
+    ///
+    /// Only failures caused by an unavailable service (e.g. NATS, PostgreSQL or Veritech) are
+    /// retried. Fixes are always attempted once unless configured otherwise, since they may have
+    /// run actions before failing.
+    #[arg(long)]
+    pub(crate) job_max_attempts: Option<u32>,
+
     /// Instance ID [example: 01GWEAANW5BVFK5KDRVS6DEY0F"]
     ///
     /// And instance ID is used when tracking the execution of jobs in a way that can be traced
@@ -94,6 +106,12 @@ impl TryFrom<Args> for Config {
             if let Some(concurrency) = args.concurrency {
                 config_map.set("concurrency_limit", i64::from(concurrency));
             }
+            if let Some(fix_concurrency) = args.fix_concurrency {
+                config_map.set("fix_concurrency_limit", i64::from(fix_concurrency));
+            }
+            if let Some(max_attempts) = args.job_max_attempts {
+                config_map.set("retry.default.max_attempts", i64::from(max_attempts));
+            }
             if let Some(instance_id) = args.instance_id {
                 config_map.set("instance_id", instance_id);
             }
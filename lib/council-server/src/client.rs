@@ -3,7 +3,7 @@ use si_data_nats::{NatsClient, Subscription};
 use std::time::Duration;
 use telemetry::prelude::*;
 
-use crate::{Graph, Id, Request, Response};
+use crate::{Graph, Id, Introspection, Request, Response};
 
 #[remain::sorted]
 #[derive(Debug)]
@@ -125,6 +125,23 @@ impl Client {
         })
     }
 
+    /// Asks council for a snapshot of the state it is holding. Unlike the other methods, this
+    /// doesn't need a job, so it can be used from anywhere to debug jobs that appear stuck.
+    pub async fn introspect(nats: &NatsClient, subject_prefix: &str) -> Result<Introspection> {
+        let message = serde_json::to_vec(&Request::Introspect)?;
+        let msg = nats
+            .request_timeout(
+                format!("{subject_prefix}.introspect"),
+                message,
+                Duration::from_secs(10),
+            )
+            .await?;
+        if msg.data().is_empty() {
+            return Err(Error::NoListenerAvailable);
+        }
+        Ok(serde_json::from_slice(msg.data())?)
+    }
+
     pub fn clone_into_pub(&self) -> PubClient {
         PubClient {
             pub_channel: self.pub_channel.clone(),
@@ -210,3 +227,98 @@ pub enum Error {
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use si_data_nats::NatsConfig;
+    use tokio::sync::watch;
+
+    use super::*;
+    use crate::server::{Config, Server};
+
+    fn nats_config() -> NatsConfig {
+        let mut config = NatsConfig::default();
+        #[allow(clippy::disallowed_methods)] // Used only in tests & so prefixed with `SI_TEST_`
+        if let Ok(value) = std::env::var("SI_TEST_NATS_URL") {
+            config.url = value;
+        }
+        config.subject_prefix = Some(ulid::Ulid::new().to_string());
+        config
+    }
+
+    #[tokio::test]
+    async fn introspect_round_trip() {
+        let nats_config = nats_config();
+        let subject = format!(
+            "{}.council",
+            nats_config
+                .subject_prefix
+                .as_deref()
+                .expect("prefix is set")
+        );
+        let config = Config::builder()
+            .nats(nats_config.clone())
+            .build()
+            .expect("could not build config");
+        let server = Server::new_with_config(config)
+            .await
+            .expect("could not create server");
+        let (subscription_started_tx, mut subscription_started_rx) = watch::channel(());
+        let (shutdown_request_tx, shutdown_request_rx) = watch::channel(());
+        let server_handle = tokio::spawn(server.run(subscription_started_tx, shutdown_request_rx));
+        subscription_started_rx
+            .changed()
+            .await
+            .expect("server went away before subscribing");
+
+        let nats = NatsClient::new(&nats_config)
+            .await
+            .expect("failed to connect to NATS");
+        assert_eq!(
+            Introspection::default(),
+            Client::introspect(&nats, &subject)
+                .await
+                .expect("could not introspect")
+        );
+
+        let change_set_id = Id::default();
+        let (first, second) = (Id::default(), Id::default());
+        let mut client = Client::new(nats.clone(), &subject, Id::default(), change_set_id)
+            .await
+            .expect("could not create client");
+        client
+            .register_dependency_graph(HashMap::from([(second, vec![first])]))
+            .await
+            .expect("could not register graph");
+        match client.fetch_response().await.expect("no response") {
+            Some(Response::OkToProcess { node_ids }) => assert_eq!(vec![first], node_ids),
+            response => panic!("unexpected response: {response:?}"),
+        }
+
+        let introspection = Client::introspect(&nats, &subject)
+            .await
+            .expect("could not introspect");
+        assert_eq!(None, introspection.creating_values);
+        let graph = introspection
+            .change_sets
+            .get(&change_set_id)
+            .expect("change set is missing");
+        assert_eq!(2, graph.len());
+        assert_eq!(
+            Some(&client.reply_channel),
+            graph[&first].processing_reply_channel.as_ref()
+        );
+        assert_eq!(None, graph[&second].processing_reply_channel);
+        assert_eq!(vec![first], graph[&second].depends_on_node_ids);
+
+        shutdown_request_tx
+            .send(())
+            .expect("server went away before shutdown");
+        server_handle
+            .await
+            .expect("server task panicked")
+            .expect("server failed");
+    }
+}
@@ -60,6 +60,7 @@ pub enum Request {
         change_set_id: Id,
     },
     CreateValues,
+    Introspect,
     ProcessedValue {
         change_set_id: Id,
         node_id: Id,
@@ -85,3 +86,25 @@ pub enum Response {
     OkToProcess { node_ids: Vec<Id> },
     Shutdown,
 }
+
+/// A snapshot of the state council is holding, used to debug jobs that appear stuck.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct Introspection {
+    /// The job that is currently creating values, if any.
+    pub creating_values: Option<String>,
+    /// The jobs waiting for their turn to create values, in order.
+    pub waiting_to_create_values: Vec<String>,
+    /// The pending nodes of each change set's graph.
+    pub change_sets: HashMap<Id, HashMap<Id, NodeIntrospection>>,
+}
+
+/// The state of a single pending node in a change set's graph.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct NodeIntrospection {
+    /// The reply channels of the jobs that want this node processed, in order.
+    pub wanted_by_reply_channels: Vec<String>,
+    /// The reply channel of the job currently processing this node, if any.
+    pub processing_reply_channel: Option<String>,
+    /// The nodes that have to be processed before this one.
+    pub depends_on_node_ids: Vec<Id>,
+}
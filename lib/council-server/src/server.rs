@@ -1,4 +1,4 @@
-use crate::{Graph, Id, Request, Response};
+use crate::{Graph, Id, Introspection, Request, Response};
 use std::time::Duration;
 
 use futures::StreamExt;
@@ -8,20 +8,89 @@ use tokio::{signal, sync::watch};
 
 pub mod config;
 mod graph;
+mod state_store;
 pub use config::Config;
 
 use graph::{ChangeSetGraph, ValueCreationQueue};
+use state_store::StateStore;
 
 #[derive(Debug, Clone)]
 pub struct Server {
     nats: NatsClient,
+    state_store: Option<StateStore>,
 }
 
 impl Server {
     pub async fn new_with_config(config: config::Config) -> Result<Self> {
-        Ok(Self {
-            nats: NatsClient::new(config.nats()).await?,
-        })
+        let nats = NatsClient::new(config.nats()).await?;
+        let state_store = match config.state_bucket() {
+            Some(bucket) => match StateStore::new(&nats, bucket).await {
+                Ok(state_store) => Some(state_store),
+                // JetStream may not be enabled on the NATS server, in which case coordinating
+                // jobs is still better than refusing to start
+                Err(err) => {
+                    warn!(
+                        %bucket,
+                        "Unable to open the council state bucket, state will only be kept in \
+                        memory: {err}",
+                    );
+                    None
+                }
+            },
+            None => None,
+        };
+
+        Ok(Self { nats, state_store })
+    }
+
+    /// Restores the state persisted by a previous council, if any, and tells every job that was
+    /// already cleared to do work about it again, since the original message may have been lost.
+    async fn restore_state(&self) -> Result<(ValueCreationQueue, ChangeSetGraph)> {
+        let Some(state_store) = &self.state_store else {
+            return Ok(Default::default());
+        };
+        let (value_create_queue, complete_graph) = match state_store.load().await {
+            Ok(state) => state,
+            Err(err) => {
+                warn!("Unable to restore council state, starting from scratch: {err}");
+                return Ok(Default::default());
+            }
+        };
+
+        if let Some(reply_channel) = value_create_queue.processing() {
+            info!(%reply_channel, "Restored job that was creating AttributeValues");
+            self.nats
+                .publish(
+                    reply_channel.to_owned(),
+                    serde_json::to_vec(&Response::OkToCreate)?,
+                )
+                .await?;
+        }
+        for (reply_channel, node_id) in complete_graph.processing_nodes() {
+            info!(%reply_channel, %node_id, "Restored job that was processing AttributeValue");
+            self.nats
+                .publish(
+                    reply_channel,
+                    serde_json::to_vec(&Response::OkToProcess {
+                        node_ids: vec![node_id],
+                    })?,
+                )
+                .await?;
+        }
+
+        Ok((value_create_queue, complete_graph))
+    }
+
+    async fn persist_state(
+        &self,
+        value_create_queue: &mut ValueCreationQueue,
+        complete_graph: &mut ChangeSetGraph,
+    ) {
+        if let Some(state_store) = &self.state_store {
+            if let Err(err) = state_store.save(value_create_queue, complete_graph).await {
+                error!("Unable to persist council state: {err}");
+            }
+        }
     }
 
     pub async fn run(
@@ -68,8 +137,7 @@ impl Server {
             }
         });
 
-        let mut value_create_queue = ValueCreationQueue::default();
-        let mut complete_graph = ChangeSetGraph::default();
+        let (mut value_create_queue, mut complete_graph) = self.restore_state().await?;
         loop {
             if let Some(reply_channel) = value_create_queue.fetch_next() {
                 info!(%reply_channel, "OK to create AttributeValues");
@@ -96,6 +164,9 @@ impl Server {
                     .unwrap();
             }
 
+            self.persist_state(&mut value_create_queue, &mut complete_graph)
+                .await;
+
             let sleep = tokio::time::sleep(Duration::from_secs(60));
             tokio::pin!(sleep);
             // FIXME: handle timeouts
@@ -139,20 +210,25 @@ impl Server {
                 else => unreachable!(),
             };
 
-            match request {
+            let result = match request {
                 Request::CreateValues => {
-                    // Move to a falible wrapper
                     job_would_like_to_create_attribute_values(
                         &mut value_create_queue,
                         reply_channel,
                     )
                     .await
-                    .unwrap();
+                }
+                Request::Introspect => {
+                    council_state_requested(
+                        &self.nats,
+                        &complete_graph,
+                        &value_create_queue,
+                        reply_channel,
+                    )
+                    .await
                 }
                 Request::ValueCreationDone => {
-                    job_finished_value_creation(&mut value_create_queue, reply_channel)
-                        .await
-                        .unwrap();
+                    job_finished_value_creation(&mut value_create_queue, reply_channel).await
                 }
                 Request::ValueDependencyGraph {
                     change_set_id,
@@ -165,7 +241,6 @@ impl Server {
                         dependency_graph,
                     )
                     .await
-                    .unwrap();
                 }
                 Request::ProcessedValue {
                     change_set_id,
@@ -179,7 +254,6 @@ impl Server {
                         node_id,
                     )
                     .await
-                    .unwrap();
                 }
                 Request::Bye { change_set_id } => {
                     job_is_going_away(
@@ -189,7 +263,6 @@ impl Server {
                         change_set_id,
                     )
                     .await
-                    .unwrap();
                 }
                 Request::ValueProcessingFailed {
                     change_set_id,
@@ -203,9 +276,14 @@ impl Server {
                         node_id,
                     )
                     .await
-                    .unwrap();
                 }
             };
+            if let Err(err) = result {
+                error!("Unable to handle council request: {err}");
+            }
+
+            self.persist_state(&mut value_create_queue, &mut complete_graph)
+                .await;
         }
 
         Ok(())
@@ -246,6 +324,8 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error(transparent)]
     Nats(#[from] si_data_nats::Error),
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
     #[error("Job reported finishing processing, but we expected a different job to be processing")]
     ShouldNotBeProcessingByJob,
     #[error("Unexpected JobId")]
@@ -339,6 +419,24 @@ pub async fn job_failed_processing_a_value(
     Ok(())
 }
 
+#[instrument(level = "info", skip(nats, complete_graph, value_create_queue))]
+pub async fn council_state_requested(
+    nats: &NatsClient,
+    complete_graph: &ChangeSetGraph,
+    value_create_queue: &ValueCreationQueue,
+    reply_channel: String,
+) -> Result<(), Error> {
+    debug!(%reply_channel, "Council state requested");
+    let mut introspection = Introspection::default();
+    value_create_queue.introspect(&mut introspection);
+    complete_graph.introspect(&mut introspection);
+
+    nats.publish(reply_channel, serde_json::to_vec(&introspection)?)
+        .await?;
+
+    Ok(())
+}
+
 #[instrument(level = "info")]
 pub async fn job_is_going_away(
     complete_graph: &mut ChangeSetGraph,
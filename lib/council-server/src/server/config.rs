@@ -18,15 +18,32 @@ pub type Result<T, E = ConfigError> = std::result::Result<T, E>;
 pub struct Config {
     #[builder(default = "NatsConfig::default()")]
     nats: NatsConfig,
+
+    /// The NATS key/value bucket used to persist coordination state. When `None`, or when the
+    /// bucket can't be opened because JetStream isn't available, state is only kept in memory and
+    /// lost on restart.
+    #[builder(setter(into, strip_option), default)]
+    state_bucket: Option<String>,
 }
 
 impl StandardConfig for Config {
     type Builder = ConfigBuilder;
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ConfigFile {
     nats: NatsConfig,
+    #[serde(default = "default_state_bucket")]
+    state_bucket: Option<String>,
+}
+
+impl Default for ConfigFile {
+    fn default() -> Self {
+        Self {
+            nats: Default::default(),
+            state_bucket: default_state_bucket(),
+        }
+    }
 }
 
 impl StandardConfigFile for ConfigFile {
@@ -39,6 +56,9 @@ impl TryFrom<ConfigFile> for Config {
     fn try_from(value: ConfigFile) -> Result<Self> {
         let mut config = Config::builder();
         config.nats(value.nats);
+        if let Some(state_bucket) = value.state_bucket {
+            config.state_bucket(state_bucket);
+        }
         config.build().map_err(Into::into)
     }
 }
@@ -54,4 +74,13 @@ impl Config {
     pub fn subject_prefix(&self) -> Option<&str> {
         self.nats.subject_prefix.as_deref()
     }
+
+    /// Gets the name of the key/value bucket used to persist coordination state, if any.
+    pub fn state_bucket(&self) -> Option<&str> {
+        self.state_bucket.as_deref()
+    }
+}
+
+fn default_state_bucket() -> Option<String> {
+    Some("council-state".to_string())
 }
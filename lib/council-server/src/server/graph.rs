@@ -1,19 +1,39 @@
-use crate::{server::Error, Graph, Id};
+use crate::{server::Error, Graph, Id, Introspection, NodeIntrospection};
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
 
 mod node_metadata;
 
 use node_metadata::NodeMetadata;
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Deserialize, Serialize)]
 pub struct ValueCreationQueue {
     processing: Option<String>,
     queue: VecDeque<String>,
+    /// Whether the queue changed since it was last persisted.
+    #[serde(skip)]
+    dirty: bool,
 }
 
 impl ValueCreationQueue {
     pub fn push(&mut self, reply_channel: String) {
         self.queue.push_back(reply_channel);
+        self.dirty = true;
+    }
+
+    /// The job that was told it can create values, but has not reported back yet.
+    pub fn processing(&self) -> Option<&str> {
+        self.processing.as_deref()
+    }
+
+    pub fn introspect(&self, introspection: &mut Introspection) {
+        introspection.creating_values = self.processing.clone();
+        introspection.waiting_to_create_values = self.queue.iter().cloned().collect();
+    }
+
+    /// Returns whether the queue changed since the last call, resetting the flag.
+    pub fn take_dirty(&mut self) -> bool {
+        std::mem::take(&mut self.dirty)
     }
 
     pub fn is_busy(&self) -> bool {
@@ -26,6 +46,7 @@ impl ValueCreationQueue {
         }
         let next_channel = self.queue.pop_front();
         self.processing = next_channel.clone();
+        self.dirty |= next_channel.is_some();
 
         next_channel
     }
@@ -36,6 +57,7 @@ impl ValueCreationQueue {
         }
 
         self.processing = None;
+        self.dirty = true;
 
         Ok(())
     }
@@ -43,12 +65,15 @@ impl ValueCreationQueue {
     pub fn remove(&mut self, reply_channel: &str) {
         self.processing = self.processing.take().filter(|el| *el != reply_channel);
         self.queue.retain(|el| reply_channel != el);
+        self.dirty = true;
     }
 }
 
 #[derive(Default, Debug)]
 pub struct ChangeSetGraph {
     dependency_data: HashMap<Id, HashMap<Id, NodeMetadata>>,
+    /// Change sets whose graph changed since they were last persisted.
+    dirty_change_sets: HashSet<Id>,
 }
 
 impl ChangeSetGraph {
@@ -58,22 +83,77 @@ impl ChangeSetGraph {
 
     pub fn fetch_all_available(&mut self) -> Vec<(String, Id)> {
         let mut result = Vec::new();
-        for graph in self.dependency_data.values_mut() {
+        for (change_set_id, graph) in self.dependency_data.iter_mut() {
             for (id, metadata) in graph.iter_mut() {
                 if let Some(reply_channel) = metadata.next_to_process() {
                     result.push((reply_channel, *id));
+                    self.dirty_change_sets.insert(*change_set_id);
+                }
+            }
+        }
+        result
+    }
+
+    pub fn introspect(&self, introspection: &mut Introspection) {
+        introspection.change_sets = self
+            .dependency_data
+            .iter()
+            .map(|(change_set_id, graph)| {
+                let nodes = graph
+                    .iter()
+                    .map(|(id, metadata)| (*id, metadata.introspect()))
+                    .collect();
+                (*change_set_id, nodes)
+            })
+            .collect();
+    }
+
+    /// Every node that a job was told it can process, but has not reported back on yet.
+    pub fn processing_nodes(&self) -> Vec<(String, Id)> {
+        let mut result = Vec::new();
+        for graph in self.dependency_data.values() {
+            for (id, metadata) in graph {
+                if let Some(reply_channel) = metadata.processing_reply_channel() {
+                    result.push((reply_channel.clone(), *id));
                 }
             }
         }
         result
     }
 
+    /// Returns the change sets whose graph changed since the last call, clearing the list.
+    pub fn take_dirty_change_sets(&mut self) -> HashSet<Id> {
+        std::mem::take(&mut self.dirty_change_sets)
+    }
+
+    /// Serializes the graph for a change set, or returns `None` if there is no graph for it.
+    pub fn serialize_change_set(&self, change_set_id: Id) -> Result<Option<Vec<u8>>, Error> {
+        self.dependency_data
+            .get(&change_set_id)
+            .map(serde_json::to_vec)
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    /// Restores the graph for a change set from the output of [`Self::serialize_change_set`].
+    pub fn restore_change_set(&mut self, change_set_id: Id, data: &[u8]) -> Result<(), Error> {
+        let mut graph: HashMap<Id, NodeMetadata> = serde_json::from_slice(data)?;
+        for metadata in graph.values_mut() {
+            metadata.mark_restored();
+        }
+        if !graph.is_empty() {
+            self.dependency_data.insert(change_set_id, graph);
+        }
+        Ok(())
+    }
+
     pub fn merge_dependency_graph(
         &mut self,
         reply_channel: String,
         new_dependency_data: Graph,
         change_set_id: Id,
     ) -> Result<(), Error> {
+        self.dirty_change_sets.insert(change_set_id);
         let change_set_graph_data = self.dependency_data.entry(change_set_id).or_default();
 
         for (attribute_value_id, dependencies) in new_dependency_data {
@@ -113,6 +193,7 @@ impl ChangeSetGraph {
         change_set_id: Id,
         node_id: Id,
     ) -> Result<HashSet<String>, Error> {
+        self.dirty_change_sets.insert(change_set_id);
         let change_set_graph_data = self.dependency_data.get_mut(&change_set_id).unwrap();
 
         let (ok_to_remove_node, wanted_by_reply_channels) =
@@ -139,6 +220,7 @@ impl ChangeSetGraph {
 
     pub fn remove_channel(&mut self, change_set_id: Id, reply_channel: &str) {
         if let Some(graph) = self.dependency_data.get_mut(&change_set_id) {
+            self.dirty_change_sets.insert(change_set_id);
             let mut to_remove = Vec::new();
             for (id, metadata) in graph.iter_mut() {
                 metadata.remove_channel(reply_channel);
@@ -164,6 +246,7 @@ impl ChangeSetGraph {
         node_id: Id,
     ) -> Result<Vec<(String, Id)>, Error> {
         let mut failure_notifications = Vec::new();
+        self.dirty_change_sets.insert(change_set_id);
         let change_set_graph_data = self.dependency_data.get_mut(&change_set_id).unwrap();
 
         let mut node_ids_to_fail = VecDeque::new();
@@ -202,3 +285,65 @@ impl ChangeSetGraph {
         Ok(failure_notifications)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn change_set_graph_survives_restore() {
+        let change_set_id = Id::default();
+        let (first, second) = (Id::default(), Id::default());
+        let mut graph = ChangeSetGraph::default();
+        graph
+            .merge_dependency_graph(
+                "job-a".to_string(),
+                HashMap::from([(second, vec![first])]),
+                change_set_id,
+            )
+            .expect("could not merge graph");
+        assert_eq!(
+            vec![("job-a".to_string(), first)],
+            graph.fetch_all_available()
+        );
+        assert_eq!(
+            HashSet::from([change_set_id]),
+            graph.take_dirty_change_sets()
+        );
+
+        let data = graph
+            .serialize_change_set(change_set_id)
+            .expect("could not serialize graph")
+            .expect("no graph for change set");
+        let mut restored = ChangeSetGraph::default();
+        restored
+            .restore_change_set(change_set_id, &data)
+            .expect("could not restore graph");
+
+        // The in-flight node is still assigned, so nothing new is handed out until it's processed
+        assert_eq!(
+            vec![("job-a".to_string(), first)],
+            restored.processing_nodes()
+        );
+        assert!(restored.fetch_all_available().is_empty());
+
+        let mut introspection = Introspection::default();
+        restored.introspect(&mut introspection);
+        let first_node = &introspection.change_sets[&change_set_id][&first];
+        assert_eq!(
+            Some("job-a"),
+            first_node.processing_reply_channel.as_deref()
+        );
+        assert_eq!(
+            vec![first],
+            introspection.change_sets[&change_set_id][&second].depends_on_node_ids
+        );
+        restored
+            .mark_node_as_processed("job-a".to_string(), change_set_id, first)
+            .expect("could not mark node as processed");
+        assert_eq!(
+            vec![("job-a".to_string(), second)],
+            restored.fetch_all_available()
+        );
+    }
+}
@@ -3,16 +3,21 @@ use std::{
     time::Instant,
 };
 
-use crate::{server::Error, Id};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+use crate::{server::Error, Id, NodeIntrospection};
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct NodeMetadata {
     // This should really be an ordered set, to remove duplicates, but we'll deal with
     // that later.
     wanted_by_reply_channels: VecDeque<String>,
     processing_reply_channel: Option<String>,
     depends_on_node_ids: HashSet<Id>,
+    // Instants only make sense within a single process, so these are reset when restored.
+    #[serde(skip)]
     processing_started_at: Option<Instant>,
+    #[serde(skip, default = "Instant::now")]
     last_updated_at: Instant,
 }
 
@@ -43,6 +48,14 @@ impl NodeMetadata {
         self.depends_on_node_ids.contains(&node_id)
     }
 
+    pub fn introspect(&self) -> NodeIntrospection {
+        NodeIntrospection {
+            wanted_by_reply_channels: self.wanted_by_reply_channels.iter().cloned().collect(),
+            processing_reply_channel: self.processing_reply_channel.clone(),
+            depends_on_node_ids: self.depends_on_node_ids.iter().copied().collect(),
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.wanted_by_reply_channels.is_empty() && self.processing_reply_channel.is_none()
     }
@@ -88,6 +101,16 @@ impl NodeMetadata {
         }
     }
 
+    /// Resets the timestamps of a node that was restored from persisted state, so that a job that
+    /// was already processing it is not treated as stale.
+    pub fn mark_restored(&mut self) {
+        self.last_updated_at = Instant::now();
+        self.processing_started_at = self
+            .processing_reply_channel
+            .as_ref()
+            .map(|_| self.last_updated_at);
+    }
+
     pub fn merge_metadata(&mut self, reply_channel: String, dependencies: &Vec<Id>) {
         self.last_updated_at = Instant::now();
 
@@ -0,0 +1,176 @@
+use si_data_nats::{KeyValueStore, NatsClient};
+use telemetry::prelude::*;
+
+use super::{
+    graph::{ChangeSetGraph, ValueCreationQueue},
+    Result,
+};
+use crate::Id;
+
+const VALUE_CREATION_QUEUE_KEY: &str = "value_creation_queue";
+const CHANGE_SET_KEY_PREFIX: &str = "change_set.";
+
+/// Persists the council's coordination state in a NATS key/value bucket, so a restarted council
+/// can pick up where the previous one left off instead of stranding every waiting job.
+#[derive(Debug, Clone)]
+pub struct StateStore {
+    kv: KeyValueStore,
+}
+
+impl StateStore {
+    pub async fn new(nats: &NatsClient, bucket: &str) -> Result<Self> {
+        // Bucket names may only contain alphanumerics, dashes and underscores.
+        let bucket = match nats.metadata().subject_prefix() {
+            Some(prefix) => format!("{prefix}-{bucket}"),
+            None => bucket.to_string(),
+        }
+        .replace(
+            |c: char| !c.is_ascii_alphanumeric() && c != '-' && c != '_',
+            "_",
+        );
+
+        Ok(Self {
+            kv: nats.key_value_store(bucket).await?,
+        })
+    }
+
+    /// Loads the persisted state. Entries that can't be understood are logged and skipped.
+    pub async fn load(&self) -> Result<(ValueCreationQueue, ChangeSetGraph)> {
+        let mut value_create_queue = ValueCreationQueue::default();
+        let mut complete_graph = ChangeSetGraph::default();
+
+        for key in self.kv.keys().await? {
+            let Some(data) = self.kv.get(&key).await? else {
+                continue;
+            };
+
+            if key == VALUE_CREATION_QUEUE_KEY {
+                match serde_json::from_slice(&data) {
+                    Ok(queue) => value_create_queue = queue,
+                    Err(err) => error!(%key, "Unable to restore value creation queue: {err}"),
+                }
+            } else if let Some(change_set_id) = key
+                .strip_prefix(CHANGE_SET_KEY_PREFIX)
+                .and_then(|id| Id::from_string(id).ok())
+            {
+                if let Err(err) = complete_graph.restore_change_set(change_set_id, &data) {
+                    error!(%key, "Unable to restore change set graph: {err}");
+                }
+            } else {
+                warn!(%key, "Ignoring unknown key in council state bucket");
+            }
+        }
+
+        Ok((value_create_queue, complete_graph))
+    }
+
+    /// Writes out whatever changed since the last save.
+    pub async fn save(
+        &self,
+        value_create_queue: &mut ValueCreationQueue,
+        complete_graph: &mut ChangeSetGraph,
+    ) -> Result<()> {
+        if value_create_queue.take_dirty() {
+            self.kv
+                .put(
+                    VALUE_CREATION_QUEUE_KEY,
+                    serde_json::to_vec(value_create_queue)?,
+                )
+                .await?;
+        }
+
+        for change_set_id in complete_graph.take_dirty_change_sets() {
+            let key = format!("{CHANGE_SET_KEY_PREFIX}{change_set_id}");
+            match complete_graph.serialize_change_set(change_set_id)? {
+                Some(data) => {
+                    self.kv.put(&key, data).await?;
+                }
+                None => self.kv.delete(&key).await?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use si_data_nats::NatsConfig;
+
+    use super::*;
+    use crate::Introspection;
+
+    async fn nats() -> NatsClient {
+        let mut config = NatsConfig::default();
+        #[allow(clippy::disallowed_methods)] // Used only in tests & so prefixed with `SI_TEST_`
+        if let Ok(value) = std::env::var("SI_TEST_NATS_URL") {
+            config.url = value;
+        }
+        config.subject_prefix = Some(ulid::Ulid::new().to_string());
+        NatsClient::new(&config)
+            .await
+            .expect("failed to connect to NATS")
+    }
+
+    #[tokio::test]
+    async fn state_survives_a_new_store() {
+        let nats = nats().await;
+        let store = StateStore::new(&nats, "council-state")
+            .await
+            .expect("could not open state store");
+
+        let mut value_create_queue = ValueCreationQueue::default();
+        value_create_queue.push("job-a".to_string());
+        value_create_queue.push("job-b".to_string());
+        assert_eq!(Some("job-a".to_string()), value_create_queue.fetch_next());
+
+        let change_set_id = Id::default();
+        let (first, second) = (Id::default(), Id::default());
+        let mut complete_graph = ChangeSetGraph::default();
+        complete_graph
+            .merge_dependency_graph(
+                "job-c".to_string(),
+                HashMap::from([(second, vec![first])]),
+                change_set_id,
+            )
+            .expect("could not merge graph");
+        assert_eq!(
+            vec![("job-c".to_string(), first)],
+            complete_graph.fetch_all_available()
+        );
+        store
+            .save(&mut value_create_queue, &mut complete_graph)
+            .await
+            .expect("could not save state");
+
+        // A restarted council opens the same bucket
+        let (restored_queue, restored_graph) = StateStore::new(&nats, "council-state")
+            .await
+            .expect("could not open state store")
+            .load()
+            .await
+            .expect("could not load state");
+        let mut introspection = Introspection::default();
+        restored_queue.introspect(&mut introspection);
+        assert_eq!(Some("job-a"), restored_queue.processing());
+        assert_eq!(
+            vec!["job-b".to_string()],
+            introspection.waiting_to_create_values
+        );
+        assert_eq!(
+            vec![("job-c".to_string(), first)],
+            restored_graph.processing_nodes()
+        );
+
+        // Change sets without pending nodes don't come back
+        complete_graph.remove_channel(change_set_id, "job-c");
+        store
+            .save(&mut value_create_queue, &mut complete_graph)
+            .await
+            .expect("could not save state");
+        let (_, restored_graph) = store.load().await.expect("could not load state");
+        assert!(restored_graph.is_empty());
+    }
+}
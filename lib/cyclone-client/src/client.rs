@@ -453,7 +453,7 @@ mod tests {
     use buck2_resources::Buck2Resources;
     use cyclone_core::{
         ComponentKind, ComponentView, FunctionResult, ProgressMessage, ResolverFunctionComponent,
-        ValidationRequest,
+        ResourceLimits, ValidationRequest,
     };
     use cyclone_server::{Config, ConfigBuilder, DecryptionKey, Server, UdsIncomingStream};
     use futures::StreamExt;
@@ -788,6 +788,7 @@ mod tests {
                     return v;
                 }"#,
             ),
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -878,6 +879,7 @@ mod tests {
                     return v;
                 }"#,
             ),
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -1048,6 +1050,7 @@ mod tests {
                     return { status: 'ok' };
                 }"#,
             ),
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -1123,6 +1126,7 @@ mod tests {
                     return { status: 'ok' };
                 }"#,
             ),
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -1179,6 +1183,265 @@ mod tests {
         }
     }
 
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn uds_execute_action_run_timeout() {
+        let (_, key) = gen_keys();
+        let tmp_socket = rand_uds();
+        let mut builder = Config::builder();
+        let mut client =
+            uds_client_for_running_server(builder.enable_action_run(true), &tmp_socket, key).await;
+
+        let req = ActionRunRequest {
+            execution_id: "1234".to_string(),
+            handler: "hang".to_string(),
+            args: Default::default(),
+            code_base64: base64_encode(
+                r#"function hang() {
+                    return new Promise((resolve) => setTimeout(resolve, 60000));
+                }"#,
+            ),
+            timeout_secs: Some(1),
+        };
+
+        // Start the protocol
+        let mut progress = client
+            .execute_action_run(req)
+            .await
+            .expect("failed to establish websocket stream")
+            .start()
+            .await
+            .expect("failed to start protocol");
+
+        loop {
+            match progress.next().await {
+                None => break,
+                Some(Ok(ProgressMessage::Heartbeat)) => continue,
+                Some(unexpected) => panic!("output stream should be done: {unexpected:?}"),
+            };
+        }
+        let result = progress.finish().await.expect("failed to return result");
+        match result {
+            FunctionResult::Success(success) => {
+                panic!("result should be failure; success={success:?}")
+            }
+            FunctionResult::Failure(failure) => {
+                assert_eq!(failure.execution_id, "1234");
+                assert!(failure.error.is_timeout());
+            }
+        }
+    }
+
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn uds_execute_action_run_cancel() {
+        let (_, key) = gen_keys();
+        let tmp_socket = rand_uds();
+        let mut builder = Config::builder();
+        let mut client =
+            uds_client_for_running_server(builder.enable_action_run(true), &tmp_socket, key).await;
+
+        let req = ActionRunRequest {
+            execution_id: "1234".to_string(),
+            handler: "hang".to_string(),
+            args: Default::default(),
+            code_base64: base64_encode(
+                r#"function hang() {
+                    return new Promise((resolve) => setTimeout(resolve, 60000));
+                }"#,
+            ),
+            timeout_secs: None,
+        };
+
+        // Start the protocol
+        let mut progress = client
+            .execute_action_run(req)
+            .await
+            .expect("failed to establish websocket stream")
+            .start()
+            .await
+            .expect("failed to start protocol");
+
+        progress.cancel().await.expect("failed to cancel execution");
+
+        loop {
+            match progress.next().await {
+                None => break,
+                Some(Ok(ProgressMessage::Heartbeat)) => continue,
+                Some(unexpected) => panic!("output stream should be done: {unexpected:?}"),
+            };
+        }
+        let result = progress.finish().await.expect("failed to return result");
+        match result {
+            FunctionResult::Success(success) => {
+                panic!("result should be failure; success={success:?}")
+            }
+            FunctionResult::Failure(failure) => {
+                assert_eq!(failure.execution_id, "1234");
+                assert!(failure.error.is_cancelled());
+            }
+        }
+    }
+
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn uds_execute_action_run_output_limit() {
+        let (_, key) = gen_keys();
+        let tmp_socket = rand_uds();
+        let mut builder = Config::builder();
+        builder.resource_limits(ResourceLimits {
+            max_output_bytes: Some(1024),
+            ..Default::default()
+        });
+        let mut client =
+            uds_client_for_running_server(builder.enable_action_run(true), &tmp_socket, key).await;
+
+        let req = ActionRunRequest {
+            execution_id: "1234".to_string(),
+            handler: "chatty".to_string(),
+            args: Default::default(),
+            code_base64: base64_encode(
+                r#"function chatty() {
+                    for (let i = 0; i < 1000; i++) {
+                        console.log('all work and no play makes jack a dull boy');
+                    }
+                    return { status: 'ok' };
+                }"#,
+            ),
+            timeout_secs: None,
+        };
+
+        // Start the protocol
+        let mut progress = client
+            .execute_action_run(req)
+            .await
+            .expect("failed to establish websocket stream")
+            .start()
+            .await
+            .expect("failed to start protocol");
+
+        let mut output_lines = 0;
+        loop {
+            match progress.next().await {
+                None => break,
+                Some(Ok(ProgressMessage::OutputStream(_))) => output_lines += 1,
+                Some(Ok(ProgressMessage::Heartbeat)) => continue,
+                Some(Err(err)) => panic!("failed to receive output: err={err:?}"),
+            };
+        }
+        assert!(output_lines < 1000);
+
+        let result = progress.finish().await.expect("failed to return result");
+        match result {
+            FunctionResult::Success(success) => {
+                panic!("result should be failure; success={success:?}")
+            }
+            FunctionResult::Failure(failure) => {
+                assert!(failure.error.is_output_limit_exceeded());
+            }
+        }
+    }
+
+    async fn execute_action_run_over_resource_limits(
+        resource_limits: ResourceLimits,
+        handler: &str,
+        code: &str,
+    ) -> FunctionResult<ActionRunResultSuccess> {
+        let (_, key) = gen_keys();
+        let tmp_socket = rand_uds();
+        let mut builder = Config::builder();
+        builder.resource_limits(resource_limits);
+        let mut client =
+            uds_client_for_running_server(builder.enable_action_run(true), &tmp_socket, key).await;
+
+        let req = ActionRunRequest {
+            execution_id: "1234".to_string(),
+            handler: handler.to_string(),
+            args: Default::default(),
+            code_base64: base64_encode(code),
+            timeout_secs: Some(60),
+        };
+
+        // Start the protocol
+        let mut progress = client
+            .execute_action_run(req)
+            .await
+            .expect("failed to establish websocket stream")
+            .start()
+            .await
+            .expect("failed to start protocol");
+
+        loop {
+            match progress.next().await {
+                None => break,
+                Some(Ok(_)) => continue,
+                Some(Err(err)) => panic!("failed to receive output: err={err:?}"),
+            };
+        }
+        progress.finish().await.expect("failed to return result")
+    }
+
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn uds_execute_action_run_cpu_time_limit() {
+        let result = execute_action_run_over_resource_limits(
+            ResourceLimits {
+                cpu_time_secs: Some(1),
+                ..Default::default()
+            },
+            "spin",
+            r#"function spin() {
+                while (true) {}
+            }"#,
+        )
+        .await;
+
+        match result {
+            FunctionResult::Success(success) => {
+                panic!("result should be failure; success={success:?}")
+            }
+            FunctionResult::Failure(failure) => {
+                assert_eq!(failure.execution_id, "1234");
+                assert!(
+                    failure.error.is_resource_limit_exceeded(),
+                    "unexpected failure: {failure:?}"
+                );
+            }
+        }
+    }
+
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn uds_execute_action_run_memory_limit() {
+        let result = execute_action_run_over_resource_limits(
+            ResourceLimits {
+                memory_bytes: Some(128 * 1024 * 1024),
+                ..Default::default()
+            },
+            "hoard",
+            r#"function hoard() {
+                const hoard = [];
+                while (true) {
+                    hoard.push(new Array(1024 * 1024).fill('all work and no play'));
+                }
+            }"#,
+        )
+        .await;
+
+        match result {
+            FunctionResult::Success(success) => {
+                panic!("result should be failure; success={success:?}")
+            }
+            FunctionResult::Failure(failure) => {
+                assert_eq!(failure.execution_id, "1234");
+                assert!(
+                    failure.error.is_resource_limit_exceeded(),
+                    "unexpected failure: {failure:?}"
+                );
+            }
+        }
+    }
+
     #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
     #[test(tokio::test)]
     async fn http_execute_reconciliation() {
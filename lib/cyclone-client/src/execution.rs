@@ -119,6 +119,19 @@ where
     pub async fn finish(self) -> Result<FunctionResult<Success>, ExecutionError<Success>> {
         ExecutionClosing::try_from(self)?.finish().await
     }
+
+    /// Asks the server to stop the execution. The server terminates the function and replies with
+    /// a failure result, so the stream should still be consumed before calling
+    /// [`finish`](Self::finish).
+    pub async fn cancel(&mut self) -> Result<(), ExecutionError<Success>> {
+        let msg = Message::<()>::Cancel
+            .serialize_to_string()
+            .map_err(ExecutionError::JSONSerialize)?;
+        self.stream
+            .send(WebSocketMessage::Text(msg))
+            .await
+            .map_err(ExecutionError::WSSendIO)
+    }
 }
 
 impl<T, Success> Stream for ExecutionStarted<T, Success>
@@ -137,6 +150,9 @@ where
                 match msg {
                     // We got a heartbeat message, pass it on
                     Message::Heartbeat => Poll::Ready(Some(Ok(ProgressMessage::Heartbeat))),
+                    // The execution was cancelled and its failure result follows, so treat
+                    // this as a sign of life
+                    Message::Cancelled => Poll::Ready(Some(Ok(ProgressMessage::Heartbeat))),
                     // We got an output message, pass it on
                     Message::OutputStream(output_stream) => {
                         Poll::Ready(Some(Ok(ProgressMessage::OutputStream(output_stream))))
@@ -7,6 +7,9 @@ pub struct ActionRunRequest {
     pub handler: String,
     pub code_base64: String,
     pub args: serde_json::Value,
+    /// Overrides the execution timeout configured on the server, in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
 }
 
 #[remain::sorted]
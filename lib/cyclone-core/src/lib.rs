@@ -21,6 +21,7 @@ mod progress;
 mod readiness;
 mod reconciliation;
 mod resolver_function;
+mod resource_limits;
 mod schema_variant_definition;
 mod sensitive_container;
 mod validation;
@@ -40,6 +41,7 @@ pub use resolver_function::{
     ResolverFunctionComponent, ResolverFunctionRequest, ResolverFunctionResponseType,
     ResolverFunctionResultSuccess,
 };
+pub use resource_limits::ResourceLimits;
 pub use schema_variant_definition::{
     SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess,
 };
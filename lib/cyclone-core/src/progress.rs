@@ -1,3 +1,5 @@
+use std::{fmt, time::Duration};
+
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 /// A line of output, streamed from an executing function.
@@ -54,6 +56,11 @@ pub enum ProgressMessage {
 #[remain::sorted]
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum Message<R> {
+    /// Sent by a client to request that the in-flight execution be stopped.
+    Cancel,
+    /// Sent by the server once an execution was stopped at the client's request. A failure result
+    /// follows.
+    Cancelled,
     Fail(Fail),
     Finish,
     Heartbeat,
@@ -104,12 +111,119 @@ pub struct FunctionResultFailure {
     pub timestamp: u64,
 }
 
+impl FunctionResultFailure {
+    /// Creates a failure for an execution that was killed at the request of a client.
+    pub fn new_for_cancellation(execution_id: impl Into<String>, timestamp: u64) -> Self {
+        Self {
+            execution_id: execution_id.into(),
+            error: FunctionResultFailureError {
+                kind: FunctionResultFailureError::CANCELLED_KIND.to_string(),
+                message: "function execution was cancelled".to_string(),
+            },
+            timestamp,
+        }
+    }
+
+    /// Creates a failure for an execution that was killed after running past its deadline.
+    pub fn new_for_timeout(
+        execution_id: impl Into<String>,
+        timeout: Duration,
+        timestamp: u64,
+    ) -> Self {
+        Self {
+            execution_id: execution_id.into(),
+            error: FunctionResultFailureError {
+                kind: FunctionResultFailureError::TIMEOUT_KIND.to_string(),
+                message: format!(
+                    "function execution exceeded its timeout of {} seconds and was terminated",
+                    timeout.as_secs()
+                ),
+            },
+            timestamp,
+        }
+    }
+
+    /// Creates a failure for an execution that was killed after producing more output than it is
+    /// allowed to.
+    pub fn new_for_output_limit(
+        execution_id: impl Into<String>,
+        max_output_bytes: u64,
+        timestamp: u64,
+    ) -> Self {
+        Self {
+            execution_id: execution_id.into(),
+            error: FunctionResultFailureError {
+                kind: FunctionResultFailureError::OUTPUT_LIMIT_KIND.to_string(),
+                message: format!(
+                    "function execution exceeded its output limit of {max_output_bytes} bytes and was terminated"
+                ),
+            },
+            timestamp,
+        }
+    }
+
+    /// Creates a failure for an execution that was killed by the kernel or by V8 for going over
+    /// one of its resource limits, described by `limit` (e.g. "CPU time limit of 5 seconds").
+    pub fn new_for_resource_limit(
+        execution_id: impl Into<String>,
+        limit: impl fmt::Display,
+        timestamp: u64,
+    ) -> Self {
+        Self {
+            execution_id: execution_id.into(),
+            error: FunctionResultFailureError {
+                kind: FunctionResultFailureError::RESOURCE_LIMIT_KIND.to_string(),
+                message: format!("function execution exceeded its {limit} and was terminated"),
+            },
+            timestamp,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize, Clone)]
 pub struct FunctionResultFailureError {
     pub kind: String,
     pub message: String,
 }
 
+impl FunctionResultFailureError {
+    /// The kind reported when an execution is terminated by the server because a client asked
+    /// for it to be cancelled.
+    pub const CANCELLED_KIND: &'static str = "ExecutionCancelled";
+
+    /// The kind reported when an execution runs past its deadline and is terminated by the
+    /// server, as opposed to failing on its own.
+    pub const TIMEOUT_KIND: &'static str = "ExecutionTimeout";
+
+    /// The kind reported when an execution produces more output than it is allowed to and is
+    /// terminated by the server.
+    pub const OUTPUT_LIMIT_KIND: &'static str = "OutputLimitExceeded";
+
+    /// The kind reported when an execution is killed for going over its CPU time or memory
+    /// limit.
+    pub const RESOURCE_LIMIT_KIND: &'static str = "ResourceLimitExceeded";
+
+    /// Whether the execution was terminated because it was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.kind == Self::CANCELLED_KIND
+    }
+
+    /// Whether the execution was terminated for running past its deadline.
+    pub fn is_timeout(&self) -> bool {
+        self.kind == Self::TIMEOUT_KIND
+    }
+
+    /// Whether the execution was terminated for producing too much output.
+    pub fn is_output_limit_exceeded(&self) -> bool {
+        self.kind == Self::OUTPUT_LIMIT_KIND
+    }
+
+    /// Whether the execution was killed for going over its CPU time or memory limit.
+    pub fn is_resource_limit_exceeded(&self) -> bool {
+        self.kind == Self::RESOURCE_LIMIT_KIND
+    }
+}
+
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Fail {
     pub message: String,
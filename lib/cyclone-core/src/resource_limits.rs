@@ -0,0 +1,151 @@
+use std::{
+    ffi::{OsStr, OsString},
+    io,
+};
+
+use nix::sys::resource::{setrlimit, Resource};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+/// Limits applied to every child process spawned to execute a function.
+///
+/// Unset limits are inherited from the parent process.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ResourceLimits {
+    /// The maximum size of the JavaScript heap of the process, in bytes.
+    ///
+    /// This is enforced by V8 (with `--max-old-space-size`) rather than by limiting the address
+    /// space of the process, since V8 reserves far more address space than it ever uses and fails
+    /// to start under such a limit.
+    #[serde(default)]
+    pub memory_bytes: Option<u64>,
+    /// The amount of CPU time the process may consume, in seconds.
+    ///
+    /// The process is sent `SIGXCPU` once it has used up this much time, and is killed a second
+    /// later if it is still running.
+    #[serde(default)]
+    pub cpu_time_secs: Option<u64>,
+    /// The maximum number of file descriptors the process may open.
+    #[serde(default)]
+    pub open_files: Option<u64>,
+    /// The maximum number of bytes of output (including the result) the execution may produce.
+    ///
+    /// Unlike the others, this limit is not enforced by the kernel but by the server reading the
+    /// output.
+    #[serde(default)]
+    pub max_output_bytes: Option<u64>,
+}
+
+impl ResourceLimits {
+    /// Whether any limit has to be applied to the child process by the kernel.
+    fn has_rlimits(&self) -> bool {
+        self.cpu_time_secs.is_some() || self.open_files.is_some()
+    }
+
+    /// Configures the command so that the limits are set on the child process before it
+    /// executes.
+    ///
+    /// The memory limit is passed to Node through `NODE_OPTIONS`, after any options the child
+    /// would otherwise inherit (`inherited_node_options`) so that they are kept.
+    pub fn apply(&self, command: &mut Command, inherited_node_options: Option<&OsStr>) {
+        if let Some(memory_bytes) = self.memory_bytes {
+            command.env(
+                "NODE_OPTIONS",
+                node_options(memory_bytes, inherited_node_options),
+            );
+        }
+        if !self.has_rlimits() {
+            return;
+        }
+
+        let limits = *self;
+        // Safety: the closure runs in the forked child before `exec` and only calls
+        // `setrlimit(2)`, which is async-signal-safe, without allocating.
+        unsafe {
+            command.pre_exec(move || limits.set_rlimits().map_err(io::Error::from));
+        }
+    }
+
+    fn set_rlimits(&self) -> nix::Result<()> {
+        if let Some(cpu_time_secs) = self.cpu_time_secs {
+            // The soft limit delivers `SIGXCPU`, which tells a limit kill apart from any other
+            // `SIGKILL`; the hard limit only catches a process that handles it and keeps going.
+            setrlimit(
+                Resource::RLIMIT_CPU,
+                cpu_time_secs,
+                cpu_time_secs.saturating_add(1),
+            )?;
+        }
+        if let Some(open_files) = self.open_files {
+            setrlimit(Resource::RLIMIT_NOFILE, open_files, open_files)?;
+        }
+        Ok(())
+    }
+}
+
+/// Builds the Node options limiting the heap to the given number of bytes, rounded up to whole
+/// mebibytes as V8 expects. The limit comes last so that it wins over one in the inherited
+/// options.
+fn node_options(memory_bytes: u64, inherited: Option<&OsStr>) -> OsString {
+    const MEBIBYTE: u64 = 1024 * 1024;
+    let memory_mb = (memory_bytes / MEBIBYTE + u64::from(memory_bytes % MEBIBYTE != 0)).max(1);
+
+    let mut node_options = OsString::new();
+    if let Some(inherited) = inherited.filter(|inherited| !inherited.is_empty()) {
+        node_options.push(inherited);
+        node_options.push(" ");
+    }
+    node_options.push(format!("--max-old-space-size={memory_mb}"));
+    node_options
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node_options_of(command: &Command) -> Option<&OsStr> {
+        command
+            .as_std()
+            .get_envs()
+            .find(|(name, _)| *name == OsStr::new("NODE_OPTIONS"))
+            .and_then(|(_, value)| value)
+    }
+
+    #[test]
+    fn memory_limit_is_passed_to_node() {
+        let limits = ResourceLimits {
+            memory_bytes: Some(512 * 1024 * 1024 + 1),
+            ..Default::default()
+        };
+        let mut command = Command::new("lang-js");
+        limits.apply(&mut command, None);
+
+        assert_eq!(
+            Some(OsStr::new("--max-old-space-size=513")),
+            node_options_of(&command)
+        );
+    }
+
+    #[test]
+    fn memory_limit_keeps_inherited_node_options() {
+        let limits = ResourceLimits {
+            memory_bytes: Some(64 * 1024 * 1024),
+            ..Default::default()
+        };
+        let mut command = Command::new("lang-js");
+        limits.apply(&mut command, Some(OsStr::new("--enable-source-maps")));
+
+        assert_eq!(
+            Some(OsStr::new("--enable-source-maps --max-old-space-size=64")),
+            node_options_of(&command)
+        );
+    }
+
+    #[test]
+    fn no_memory_limit_leaves_node_alone() {
+        let mut command = Command::new("lang-js");
+        ResourceLimits::default().apply(&mut command, Some(OsStr::new("--enable-source-maps")));
+
+        assert_eq!(0, command.as_std().get_envs().count());
+    }
+}
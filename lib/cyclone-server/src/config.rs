@@ -1,9 +1,13 @@
 use std::{
+    ffi::{OsStr, OsString},
     net::{SocketAddr, ToSocketAddrs},
     path::{Path, PathBuf},
     time::Duration,
 };
 
+use cyclone_core::ResourceLimits;
+
+use crate::SecretBackends;
 use derive_builder::Builder;
 use si_settings::{CanonicalFile, CanonicalFileError};
 use thiserror::Error;
@@ -23,6 +27,8 @@ pub enum ConfigError {
 
 type Result<T> = std::result::Result<T, ConfigError>;
 
+const DEFAULT_EXECUTION_TIMEOUT_SECS: u64 = 30 * 60;
+
 #[derive(Debug, Builder)]
 pub struct Config {
     #[builder(default)]
@@ -54,6 +60,21 @@ pub struct Config {
 
     #[builder(setter(into), default)]
     limit_requests: Option<u32>,
+
+    #[builder(default = "Duration::from_secs(DEFAULT_EXECUTION_TIMEOUT_SECS)")]
+    execution_timeout: Duration,
+
+    #[builder(default)]
+    resource_limits: ResourceLimits,
+
+    #[builder(setter(into), default)]
+    execution_env: Option<Vec<(OsString, OsString)>>,
+
+    #[builder(setter(into), default)]
+    inherited_node_options: Option<OsString>,
+
+    #[builder(default)]
+    secret_backends: SecretBackends,
 }
 
 impl Config {
@@ -122,6 +143,40 @@ impl Config {
     pub fn limit_requests(&self) -> Option<u32> {
         self.limit_requests
     }
+
+    /// Gets the config's execution timeout, after which a function execution is terminated unless
+    /// its request sets a timeout of its own.
+    #[must_use]
+    pub fn execution_timeout(&self) -> Duration {
+        self.execution_timeout
+    }
+
+    /// Gets a reference to the config's resource limits for function executions.
+    #[must_use]
+    pub fn resource_limits(&self) -> &ResourceLimits {
+        &self.resource_limits
+    }
+
+    /// Gets a reference to the config's environment for function executions. When unset,
+    /// executions inherit the server's whole environment.
+    #[must_use]
+    pub fn execution_env(&self) -> Option<&[(OsString, OsString)]> {
+        self.execution_env.as_deref()
+    }
+
+    /// Gets a reference to the config's `NODE_OPTIONS` that function executions inherit, which are
+    /// kept when a memory limit is added to them.
+    #[must_use]
+    pub fn inherited_node_options(&self) -> Option<&OsStr> {
+        self.inherited_node_options.as_deref()
+    }
+
+    /// Gets a reference to the config's external secret backends, which resolve secret references
+    /// right before a function executes.
+    #[must_use]
+    pub fn secret_backends(&self) -> &SecretBackends {
+        &self.secret_backends
+    }
 }
 
 impl ConfigBuilder {
@@ -62,6 +62,15 @@ impl DecryptionKey {
         sodiumoxide::crypto::sealedbox::open(&crypted, &self.public_key, &self.secret_key)
             .map_err(|_| DecryptionKeyError::DecryptionFailed)
     }
+
+    /// Encrypts a message for this key and base64 encodes it, the reverse of
+    /// [`Self::decode_and_decrypt`].
+    pub fn encrypt_and_encode(&self, message: &[u8]) -> String {
+        general_purpose::STANDARD_NO_PAD.encode(sodiumoxide::crypto::sealedbox::seal(
+            message,
+            &self.public_key,
+        ))
+    }
 }
 
 impl From<BoxSecretKey> for DecryptionKey {
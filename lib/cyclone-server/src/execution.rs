@@ -1,6 +1,7 @@
 use std::{
     fmt, io,
     marker::{PhantomData, Unpin},
+    os::unix::process::ExitStatusExt,
     path::PathBuf,
     process::Stdio,
     sync::Arc,
@@ -12,7 +13,7 @@ use bytes_lines_codec::BytesLinesCodec;
 use cyclone_core::{
     process::{self, ShutdownError},
     FunctionResult, FunctionResultFailure, FunctionResultFailureError, Message, OutputStream,
-    SensitiveString,
+    ResourceLimits, SensitiveString,
 };
 use futures::{SinkExt, StreamExt, TryStreamExt};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -21,28 +22,38 @@ use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::{
     process::{Child, ChildStderr, ChildStdin, ChildStdout, Command},
+    task::JoinHandle,
     time,
 };
 use tokio_serde::{formats::SymmetricalJson, Deserializer, Framed, SymmetricallyFramed};
 use tokio_util::codec::{Decoder, FramedRead, FramedWrite};
 
 use crate::{
-    request::{DecryptRequest, ListSecrets},
-    DecryptionKey, DecryptionKeyError, WebSocketMessage,
+    request::{DecryptRequest, ExecutionRequest, ListSecrets},
+    DecryptionKey, DecryptionKeyError, Sandbox, SecretBackendError, SecretBackends,
+    WebSocketMessage,
 };
 
 const TX_TIMEOUT_SECS: Duration = Duration::from_secs(5);
+/// How long to wait for a child that closed its output to exit before assuming it is still alive.
+const CHILD_EXIT_TIMEOUT: Duration = Duration::from_secs(1);
 
 pub fn new<Request, LangServerSuccess, Success>(
     lang_server_path: impl Into<PathBuf>,
     lang_server_debugging: bool,
     key: Arc<DecryptionKey>,
+    sandbox: Arc<Sandbox>,
+    secret_backends: Arc<SecretBackends>,
+    execution_timeout: Duration,
     command: String,
 ) -> Execution<Request, LangServerSuccess, Success> {
     Execution {
         lang_server_path: lang_server_path.into(),
         lang_server_debugging,
         key,
+        sandbox,
+        secret_backends,
+        execution_timeout,
         command,
         request_marker: PhantomData,
         lang_server_success_marker: PhantomData,
@@ -69,6 +80,8 @@ pub enum ExecutionError {
     JSONSerialize(#[source] serde_json::Error),
     #[error("key pair error: {0}")]
     KeyPair(#[from] DecryptionKeyError),
+    #[error("failed to resolve secret reference: {0}")]
+    SecretBackend(#[from] SecretBackendError),
     #[error("send timeout")]
     SendTimeout(#[source] tokio::time::error::Elapsed),
     #[error("unexpected websocket message type: {0:?}")]
@@ -90,6 +103,9 @@ pub struct Execution<Request, LangServerSuccess, Success> {
     lang_server_path: PathBuf,
     lang_server_debugging: bool,
     key: Arc<DecryptionKey>,
+    sandbox: Arc<Sandbox>,
+    secret_backends: Arc<SecretBackends>,
+    execution_timeout: Duration,
     command: String,
     request_marker: PhantomData<Request>,
     lang_server_success_marker: PhantomData<LangServerSuccess>,
@@ -98,7 +114,13 @@ pub struct Execution<Request, LangServerSuccess, Success> {
 
 impl<Request, LangServerSuccess, Success> Execution<Request, LangServerSuccess, Success>
 where
-    Request: DecryptRequest + ListSecrets + Serialize + DeserializeOwned + Unpin + core::fmt::Debug,
+    Request: DecryptRequest
+        + ExecutionRequest
+        + ListSecrets
+        + Serialize
+        + DeserializeOwned
+        + Unpin
+        + core::fmt::Debug,
     LangServerSuccess: DeserializeOwned,
     Success: Serialize,
 {
@@ -109,14 +131,23 @@ where
         // Send start is the initial communication before we read the request.
         Self::ws_send_start(ws).await?;
         // Now that the server said to start, I am going to read my message!
-        let request = Self::read_request(ws).await?;
+        let mut request = Self::read_request(ws).await?;
+        // Secrets stored in external backends are fetched as late as possible
+        for message in request.secret_messages_mut() {
+            self.secret_backends
+                .resolve_reference(message, &self.key)
+                .await?;
+        }
         let credentials: Vec<SensitiveString> = request.list_secrets(&self.key)?;
+        let execution_id = request.execution_id().to_owned();
+        let timeout = request.timeout().unwrap_or(self.execution_timeout);
         let mut command = Command::new(&self.lang_server_path);
         command
             .arg(&self.command)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
+        self.sandbox.configure(&mut command);
         if self.lang_server_debugging {
             command.env("DEBUG", "*").env("DEBUG_DEPTH", "5");
         }
@@ -124,6 +155,9 @@ where
         let mut child = command
             .spawn()
             .map_err(|err| ExecutionError::ChildSpawn(err, self.lang_server_path.clone()))?;
+        // The clock starts as soon as the child exists, so a child that hangs while reading its
+        // request is also terminated.
+        let deadline = time::Instant::now() + timeout;
 
         let stdin = child.stdin.take().ok_or(ExecutionError::ChildIO("stdin"))?;
         Self::child_send_function_request(stdin, request, &self.key).await?;
@@ -150,6 +184,10 @@ where
             stdout,
             stderr,
             credentials,
+            execution_id,
+            timeout,
+            deadline,
+            resource_limits: *self.sandbox.resource_limits(),
             success_marker: self.success_marker,
         })
     }
@@ -212,17 +250,26 @@ pub struct ExecutionStarted<LangServerSuccess, Success> {
     stdout: SiFramed<SiMessage<LangServerSuccess>>,
     stderr: FramedRead<ChildStderr, BytesLinesCodec>,
     credentials: Vec<SensitiveString>,
+    execution_id: String,
+    timeout: Duration,
+    deadline: time::Instant,
+    resource_limits: ResourceLimits,
     success_marker: PhantomData<Success>,
 }
 
+/// What V8 prints to stderr right before aborting when the heap can't grow any further.
+const HEAP_EXHAUSTED_MESSAGE: &str = "JavaScript heap out of memory";
+
 // TODO: implement shutdown oneshot
+/// Forwards the child's stderr, returning whether the child reported running out of heap.
 async fn handle_stderr(
     stderr: FramedRead<ChildStderr, BytesLinesCodec>,
     credentials: Vec<SensitiveString>,
-) {
+) -> bool {
     async fn handle_stderr_fallible(
         mut stderr: FramedRead<ChildStderr, BytesLinesCodec>,
         credentials: Vec<SensitiveString>,
+        heap_exhausted: &mut bool,
     ) -> Result<()> {
         while let Some(line) = stderr.next().await {
             let line = line.map_err(ExecutionError::ChildRecvIO)?;
@@ -235,13 +282,16 @@ async fn handle_stderr(
                     line = line.replace(credential.as_str(), "[redacted]").into();
                 }
             }
+            *heap_exhausted |= line.contains(HEAP_EXHAUSTED_MESSAGE);
             eprintln!("{line}");
         }
         Ok(())
     }
-    if let Err(error) = handle_stderr_fallible(stderr, credentials).await {
+    let mut heap_exhausted = false;
+    if let Err(error) = handle_stderr_fallible(stderr, credentials, &mut heap_exhausted).await {
         error!("Unable to collect stderr: {}", error);
     }
+    heap_exhausted
 }
 
 impl<LangServerSuccess, Success> ExecutionStarted<LangServerSuccess, Success>
@@ -252,7 +302,7 @@ where
     SiDecoderError: From<SiJsonError<LangServerSuccess>>,
 {
     pub async fn process(self, ws: &mut WebSocket) -> Result<ExecutionClosing<Success>> {
-        tokio::spawn(handle_stderr(self.stderr, self.credentials.clone()));
+        let stderr = tokio::spawn(handle_stderr(self.stderr, self.credentials.clone()));
 
         let mut stream = self
             .stdout
@@ -280,16 +330,167 @@ where
                 Err(err) => Err(err),
             });
 
-        while let Some(msg) = stream.try_next().await? {
-            ws.send(msg).await.map_err(ExecutionError::WSSendIO)?;
+        let max_output_bytes = self.resource_limits.max_output_bytes;
+        let forward = async {
+            let mut output_bytes: u64 = 0;
+            let mut ws_closed = false;
+            loop {
+                tokio::select! {
+                    msg = stream.try_next() => {
+                        let msg = match msg? {
+                            Some(msg) => msg,
+                            None => break,
+                        };
+                        if let WebSocketMessage::Text(text) = &msg {
+                            output_bytes = output_bytes.saturating_add(text.len() as u64);
+                        }
+                        if let Some(max_output_bytes) = max_output_bytes {
+                            if output_bytes > max_output_bytes {
+                                return Ok(Some(Termination::OutputLimitExceeded(max_output_bytes)));
+                            }
+                        }
+                        ws.send(msg).await.map_err(ExecutionError::WSSendIO)?;
+                    }
+                    // The client may ask for the execution to be cancelled while it is running
+                    ws_msg = ws.next(), if !ws_closed => match ws_msg {
+                        Some(Ok(WebSocketMessage::Text(json_str))) => {
+                            match Message::<Value>::deserialize_from_str(&json_str) {
+                                Ok(Message::Cancel) => return Ok(Some(Termination::Cancelled)),
+                                Ok(unexpected) => {
+                                    warn!(
+                                        message = ?unexpected,
+                                        "ignoring unexpected client message",
+                                    );
+                                }
+                                Err(err) => {
+                                    warn!(error = ?err, "failed to deserialize client message");
+                                }
+                            }
+                        }
+                        Some(Ok(_)) => {}
+                        Some(Err(err)) => return Err(ExecutionError::WSRecvIO(err)),
+                        None => ws_closed = true,
+                    },
+                }
+            }
+            Ok::<_, ExecutionError>(None)
+        };
+
+        let mut child = self.child;
+        let termination = match time::timeout_at(self.deadline, forward).await {
+            Ok(result) => match result? {
+                Some(termination) => Some(termination),
+                // The child closed its output, which it also does when it gets killed
+                None => {
+                    Self::resource_limit_termination(&mut child, stderr, self.resource_limits).await
+                }
+            },
+            Err(_elapsed) => Some(Termination::Timeout(self.timeout)),
+        };
+        if let Some(termination) = termination {
+            warn!(
+                execution_id = %self.execution_id,
+                ?termination,
+                "function execution stopped by server, terminating child process",
+            );
+            if let Err(err) =
+                process::child_shutdown(&mut child, Some(process::Signal::SIGTERM), None).await
+            {
+                warn!(error = ?err, "failed to shutdown terminated child cleanly");
+            }
+            Self::ws_send_termination(ws, self.execution_id, termination).await?;
         }
 
         Ok(ExecutionClosing {
-            child: self.child,
+            child,
             success_marker: PhantomData,
         })
     }
 
+    async fn ws_send_termination(
+        ws: &mut WebSocket,
+        execution_id: String,
+        termination: Termination,
+    ) -> Result<()> {
+        let timestamp = crate::timestamp();
+        let failure = match termination {
+            Termination::Cancelled => {
+                Self::ws_send(ws, Message::<Success>::Cancelled).await?;
+                FunctionResultFailure::new_for_cancellation(execution_id, timestamp)
+            }
+            Termination::OutputLimitExceeded(max_output_bytes) => {
+                FunctionResultFailure::new_for_output_limit(
+                    execution_id,
+                    max_output_bytes,
+                    timestamp,
+                )
+            }
+            Termination::ResourceLimitExceeded(limit) => {
+                FunctionResultFailure::new_for_resource_limit(execution_id, limit, timestamp)
+            }
+            Termination::Timeout(timeout) => {
+                FunctionResultFailure::new_for_timeout(execution_id, timeout, timestamp)
+            }
+        };
+        Self::ws_send(
+            ws,
+            Message::<Success>::Result(FunctionResult::Failure(failure)),
+        )
+        .await
+    }
+
+    /// Works out whether a child that stopped producing output was killed for going over one of
+    /// its resource limits: `SIGXCPU` for its CPU time, or V8 aborting once the heap is full.
+    async fn resource_limit_termination(
+        child: &mut Child,
+        stderr: JoinHandle<bool>,
+        resource_limits: ResourceLimits,
+    ) -> Option<Termination> {
+        let exit_status = match time::timeout(CHILD_EXIT_TIMEOUT, child.wait()).await {
+            Ok(Ok(exit_status)) => exit_status,
+            // Still running (or not ours to wait on), so it was not killed
+            Ok(Err(_)) | Err(_) => return None,
+        };
+        let signal = exit_status.signal()?;
+
+        if signal == process::Signal::SIGXCPU as i32 {
+            if let Some(cpu_time_secs) = resource_limits.cpu_time_secs {
+                return Some(Termination::ResourceLimitExceeded(format!(
+                    "CPU time limit of {cpu_time_secs} seconds"
+                )));
+            }
+        }
+        if signal == process::Signal::SIGABRT as i32 {
+            if let Some(memory_bytes) = resource_limits.memory_bytes {
+                // The child's stderr is closed once it has exited
+                let heap_exhausted = time::timeout(CHILD_EXIT_TIMEOUT, stderr)
+                    .await
+                    .ok()
+                    .and_then(|joined| joined.ok())
+                    .unwrap_or(false);
+                if heap_exhausted {
+                    return Some(Termination::ResourceLimitExceeded(format!(
+                        "memory limit of {memory_bytes} bytes"
+                    )));
+                }
+            }
+        }
+
+        None
+    }
+
+    async fn ws_send(ws: &mut WebSocket, msg: Message<Success>) -> Result<()> {
+        let msg = msg
+            .serialize_to_string()
+            .map_err(ExecutionError::JSONSerialize)?;
+
+        time::timeout(TX_TIMEOUT_SECS, ws.send(WebSocketMessage::Text(msg)))
+            .await
+            .map_err(ExecutionError::SendTimeout)?
+            .map_err(ExecutionError::WSSendIO)?;
+        Ok(())
+    }
+
     fn filter_output(output: &mut LangServerOutput, credentials: &[SensitiveString]) -> Result<()> {
         // Note: This brings a possibility of random substrings being matched out of context,
         // exposing that we have a secret by censoring it But trying to infer word boundary might
@@ -335,6 +536,16 @@ where
     }
 }
 
+/// Why an execution was terminated by the server before it could finish on its own.
+#[remain::sorted]
+#[derive(Debug)]
+enum Termination {
+    Cancelled,
+    OutputLimitExceeded(u64),
+    ResourceLimitExceeded(String),
+    Timeout(Duration),
+}
+
 #[derive(Debug)]
 pub struct ExecutionClosing<Success> {
     child: Child,
@@ -3,6 +3,7 @@ use std::{
     marker::{PhantomData, Unpin},
     path::PathBuf,
     sync::Arc,
+    time::Duration,
 };
 
 use axum::{
@@ -25,12 +26,15 @@ use telemetry::prelude::*;
 use super::extract::LimitRequestGuard;
 use crate::{
     execution::{self, Execution},
-    request::{DecryptRequest, ListSecrets},
+    request::{DecryptRequest, ExecutionRequest, ListSecrets},
     result::{
         LangServerActionRunResultSuccess, LangServerReconciliationResultSuccess,
         LangServerResolverFunctionResultSuccess, LangServerValidationResultSuccess,
     },
-    state::{DecryptionKey, LangServerPath, TelemetryLevel, WatchKeepalive},
+    state::{
+        DecryptionKey, ExecutionTimeout, LangServerPath, Sandbox, SecretBackends, TelemetryLevel,
+        WatchKeepalive,
+    },
     watch,
 };
 
@@ -86,6 +90,9 @@ pub async fn ws_execute_resolver(
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
     State(telemetry_level): State<TelemetryLevel>,
+    State(execution_timeout): State<ExecutionTimeout>,
+    State(sandbox): State<Sandbox>,
+    State(secret_backends): State<SecretBackends>,
     limit_request_guard: LimitRequestGuard,
 ) -> impl IntoResponse {
     let lang_server_path = lang_server_path.as_path().to_path_buf();
@@ -98,6 +105,9 @@ pub async fn ws_execute_resolver(
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
+            sandbox.into(),
+            secret_backends.into(),
+            execution_timeout.duration(),
             limit_request_guard,
             "resolverfunction".to_owned(),
             request,
@@ -113,6 +123,9 @@ pub async fn ws_execute_validation(
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
     State(telemetry_level): State<TelemetryLevel>,
+    State(execution_timeout): State<ExecutionTimeout>,
+    State(sandbox): State<Sandbox>,
+    State(secret_backends): State<SecretBackends>,
     limit_request_guard: LimitRequestGuard,
 ) -> impl IntoResponse {
     let lang_server_path = lang_server_path.as_path().to_path_buf();
@@ -125,6 +138,9 @@ pub async fn ws_execute_validation(
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
+            sandbox.into(),
+            secret_backends.into(),
+            execution_timeout.duration(),
             limit_request_guard,
             "validation".to_owned(),
             request,
@@ -140,6 +156,9 @@ pub async fn ws_execute_action_run(
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
     State(telemetry_level): State<TelemetryLevel>,
+    State(execution_timeout): State<ExecutionTimeout>,
+    State(sandbox): State<Sandbox>,
+    State(secret_backends): State<SecretBackends>,
     limit_request_guard: LimitRequestGuard,
 ) -> impl IntoResponse {
     let lang_server_path = lang_server_path.as_path().to_path_buf();
@@ -152,6 +171,9 @@ pub async fn ws_execute_action_run(
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
+            sandbox.into(),
+            secret_backends.into(),
+            execution_timeout.duration(),
             limit_request_guard,
             "actionRun".to_owned(),
             request,
@@ -167,6 +189,9 @@ pub async fn ws_execute_reconciliation(
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
     State(telemetry_level): State<TelemetryLevel>,
+    State(execution_timeout): State<ExecutionTimeout>,
+    State(sandbox): State<Sandbox>,
+    State(secret_backends): State<SecretBackends>,
     limit_request_guard: LimitRequestGuard,
 ) -> impl IntoResponse {
     let lang_server_path = lang_server_path.as_path().to_path_buf();
@@ -179,6 +204,9 @@ pub async fn ws_execute_reconciliation(
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
+            sandbox.into(),
+            secret_backends.into(),
+            execution_timeout.duration(),
             limit_request_guard,
             "reconciliation".to_owned(),
             request,
@@ -194,6 +222,9 @@ pub async fn ws_execute_schema_variant_definition(
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
     State(telemetry_level): State<TelemetryLevel>,
+    State(execution_timeout): State<ExecutionTimeout>,
+    State(sandbox): State<Sandbox>,
+    State(secret_backends): State<SecretBackends>,
     limit_request_guard: LimitRequestGuard,
 ) -> impl IntoResponse {
     let lang_server_path = lang_server_path.as_path().to_path_buf();
@@ -206,6 +237,9 @@ pub async fn ws_execute_schema_variant_definition(
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
+            sandbox.into(),
+            secret_backends.into(),
+            execution_timeout.duration(),
             limit_request_guard,
             "schemaVariantDefinition".to_owned(),
             request,
@@ -221,19 +255,35 @@ async fn handle_socket<Request, LangServerSuccess, Success>(
     lang_server_path: PathBuf,
     lang_server_debugging: bool,
     key: Arc<crate::DecryptionKey>,
+    sandbox: Arc<crate::Sandbox>,
+    secret_backends: Arc<crate::SecretBackends>,
+    execution_timeout: Duration,
     _limit_request_guard: LimitRequestGuard,
     sub_command: String,
     _request_marker: PhantomData<Request>,
     _lang_server_success_marker: PhantomData<LangServerSuccess>,
     success_marker: PhantomData<Success>,
 ) where
-    Request: DecryptRequest + ListSecrets + Serialize + DeserializeOwned + Unpin + fmt::Debug,
+    Request: DecryptRequest
+        + ExecutionRequest
+        + ListSecrets
+        + Serialize
+        + DeserializeOwned
+        + Unpin
+        + fmt::Debug,
     Success: Serialize + Unpin + fmt::Debug,
     LangServerSuccess: Serialize + DeserializeOwned + Unpin + fmt::Debug + Into<Success>,
 {
     let proto = {
-        let execution: Execution<Request, LangServerSuccess, Success> =
-            execution::new(lang_server_path, lang_server_debugging, key, sub_command);
+        let execution: Execution<Request, LangServerSuccess, Success> = execution::new(
+            lang_server_path,
+            lang_server_debugging,
+            key,
+            sandbox,
+            secret_backends,
+            execution_timeout,
+            sub_command,
+        );
         match execution.start(&mut socket).await {
             Ok(started) => started,
             Err(err) => {
@@ -6,6 +6,8 @@ mod handlers;
 mod request;
 mod result;
 mod routes;
+mod sandbox;
+mod secret_backend;
 mod server;
 mod state;
 mod timestamp;
@@ -15,7 +17,10 @@ mod watch;
 
 pub use axum::extract::ws::Message as WebSocketMessage;
 pub use config::{Config, ConfigBuilder, ConfigError, IncomingStream};
+pub use cyclone_core::ResourceLimits;
 pub use decryption_key::{DecryptionKey, DecryptionKeyError};
+pub use sandbox::Sandbox;
+pub use secret_backend::{HttpSecretBackendConfig, SecretBackendError, SecretBackends};
 pub use server::{Server, ShutdownSource};
 pub use timestamp::timestamp;
 pub use uds::{UdsIncomingStream, UdsIncomingStreamError};
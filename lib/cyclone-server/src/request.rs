@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use cyclone_core::{
     ActionRunRequest, ComponentKind, ComponentView, ReconciliationRequest, ResolverFunctionRequest,
     SchemaVariantDefinitionRequest, SensitiveString, ValidationRequest,
@@ -13,6 +15,40 @@ pub trait ListSecrets {
 
 pub trait DecryptRequest {
     fn decrypt_request(self, key: &DecryptionKey) -> Result<serde_json::Value, DecryptionKeyError>;
+
+    /// The secret messages of the request's credential components, so that secret references can
+    /// be resolved before the request gets decrypted. References anywhere else are left alone.
+    fn secret_messages_mut(&mut self) -> Vec<&mut Value> {
+        Vec::new()
+    }
+}
+
+/// The secret messages of a component's properties. dal only puts secrets (or references to them)
+/// in the `message` of the values of a credential component's secret select props, which are the
+/// children of its root.
+fn component_secret_messages_mut(kind: ComponentKind, properties: &mut Value) -> Vec<&mut Value> {
+    if kind != ComponentKind::Credential {
+        return Vec::new();
+    }
+    match properties
+        .pointer_mut("/root")
+        .and_then(Value::as_object_mut)
+    {
+        Some(root) => root
+            .values_mut()
+            .filter_map(|value| value.get_mut("message"))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+pub trait ExecutionRequest {
+    fn execution_id(&self) -> &str;
+
+    /// Overrides the execution timeout configured on the server.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
 }
 
 impl ListSecrets for ComponentView {
@@ -79,6 +115,10 @@ impl ListSecrets for ComponentView {
 }
 
 impl DecryptRequest for ComponentView {
+    fn secret_messages_mut(&mut self) -> Vec<&mut Value> {
+        component_secret_messages_mut(self.kind, &mut self.properties)
+    }
+
     fn decrypt_request(self, key: &DecryptionKey) -> Result<Value, DecryptionKeyError> {
         let mut value = serde_json::to_value(&self)?;
         if self.kind != ComponentKind::Credential {
@@ -146,6 +186,14 @@ impl ListSecrets for ResolverFunctionRequest {
 }
 
 impl DecryptRequest for ResolverFunctionRequest {
+    fn secret_messages_mut(&mut self) -> Vec<&mut Value> {
+        let mut values = self.component.data.secret_messages_mut();
+        for parent in &mut self.component.parents {
+            values.extend(parent.secret_messages_mut());
+        }
+        values
+    }
+
     fn decrypt_request(self, key: &DecryptionKey) -> Result<serde_json::Value, DecryptionKeyError> {
         let mut value = serde_json::to_value(&self)?;
 
@@ -181,20 +229,43 @@ impl DecryptRequest for ResolverFunctionRequest {
 impl ListSecrets for ActionRunRequest {
     fn list_secrets(
         &self,
-        _key: &DecryptionKey,
+        key: &DecryptionKey,
     ) -> Result<Vec<SensitiveString>, DecryptionKeyError> {
-        // TODO(fnichol): we'll need to populate/consume secrets here shortly
-        Ok(vec![])
+        // Actions are run with the view of their component as their argument
+        match serde_json::from_value::<ComponentView>(self.args.clone()) {
+            Ok(component) => component.list_secrets(key),
+            Err(_) => Ok(vec![]),
+        }
     }
 }
 
 impl DecryptRequest for ActionRunRequest {
-    fn decrypt_request(
-        self,
-        _key: &DecryptionKey,
-    ) -> Result<serde_json::Value, DecryptionKeyError> {
-        let value = serde_json::to_value(&self)?;
-        // TODO(fnichol): we'll need to process the request with decrypted secrets
+    fn secret_messages_mut(&mut self) -> Vec<&mut Value> {
+        // The args are the component the action runs for, as long as they look like one
+        let kind = match self.args.get("kind").cloned().map(serde_json::from_value) {
+            Some(Ok(kind)) => kind,
+            _ => return Vec::new(),
+        };
+        match self.args.get_mut("properties") {
+            Some(properties) => component_secret_messages_mut(kind, properties),
+            None => Vec::new(),
+        }
+    }
+
+    fn decrypt_request(self, key: &DecryptionKey) -> Result<serde_json::Value, DecryptionKeyError> {
+        let mut value = serde_json::to_value(&self)?;
+
+        if let Ok(component) = serde_json::from_value::<ComponentView>(self.args) {
+            match value.pointer_mut("/args") {
+                Some(v) => *v = component.decrypt_request(key)?,
+                None => {
+                    return Err(DecryptionKeyError::JSONPointerNotFound(
+                        value,
+                        "/args".to_owned(),
+                    ));
+                }
+            }
+        }
         Ok(value)
     }
 }
@@ -262,6 +333,44 @@ impl DecryptRequest for SchemaVariantDefinitionRequest {
     }
 }
 
+impl ExecutionRequest for ResolverFunctionRequest {
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
+    }
+}
+
+impl ExecutionRequest for ActionRunRequest {
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
+    }
+}
+
+impl ExecutionRequest for ReconciliationRequest {
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+}
+
+impl ExecutionRequest for ValidationRequest {
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+}
+
+impl ExecutionRequest for SchemaVariantDefinitionRequest {
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use base64::{engine::general_purpose, Engine};
@@ -356,4 +465,79 @@ mod tests {
         });
         assert_eq!(json, decrypted_json);
     }
+
+    #[test]
+    fn decrypt_action_run() {
+        let (pkey, skey) = gen_keypair();
+        let decryption_key = DecryptionKey::from(skey);
+
+        let secret_json = serde_json::json!({
+            "my-super-secret": "Varginha's UFO",
+        });
+        let secret = serde_json::to_string(&secret_json).expect("Unable to serialize secret");
+        let encoded = encrypt_and_encode(secret.as_bytes(), &pkey);
+
+        let request = ActionRunRequest {
+            execution_id: "ufo".to_owned(),
+            handler: "create".to_owned(),
+            code_base64: "".to_owned(),
+            args: serde_json::json!({
+                "kind": "credential",
+                "properties": {
+                    "secret": {
+                        "message": {
+                            "cycloneEncryptedDataMarker": true,
+                            "encryptedSecret": encoded,
+                        },
+                    },
+                },
+            }),
+            timeout_secs: None,
+        };
+        let secrets = request
+            .list_secrets(&decryption_key)
+            .expect("Unable to list secrets");
+        assert_eq!(secrets[0].as_str(), "Varginha's UFO");
+
+        let json = request
+            .decrypt_request(&decryption_key)
+            .expect("Unable to decrypt action run");
+        assert_eq!(json["args"]["properties"]["secret"]["message"], secret_json);
+    }
+
+    #[test]
+    fn secret_messages_of_credentials() {
+        let properties = serde_json::json!({
+            "root": {
+                "secret": { "name": "ufo", "message": { "cycloneSecretReferenceMarker": true } },
+                "region": "us-east-2",
+            },
+            "nested": { "message": { "cycloneSecretReferenceMarker": true } },
+        });
+
+        let mut credential = ComponentView {
+            kind: ComponentKind::Credential,
+            properties: properties.clone(),
+        };
+        let messages = credential.secret_messages_mut();
+        assert_eq!(1, messages.len());
+        assert_eq!(properties["root"]["secret"]["message"], messages[0].clone());
+
+        let mut standard = ComponentView {
+            kind: ComponentKind::Standard,
+            properties: properties.clone(),
+        };
+        assert!(standard.secret_messages_mut().is_empty());
+
+        let mut action = ActionRunRequest {
+            execution_id: "ufo".to_owned(),
+            handler: "create".to_owned(),
+            code_base64: "".to_owned(),
+            args: serde_json::json!({ "kind": "credential", "properties": properties }),
+            timeout_secs: None,
+        };
+        assert_eq!(1, action.secret_messages_mut().len());
+        action.args = serde_json::json!({ "properties": properties });
+        assert!(action.secret_messages_mut().is_empty());
+    }
 }
@@ -0,0 +1,45 @@
+use std::ffi::OsString;
+
+use cyclone_core::ResourceLimits;
+use tokio::process::Command;
+
+/// Restrictions applied to every lang server child process, so that a runaway function cannot
+/// starve other executions on the same host.
+#[derive(Clone, Debug, Default)]
+pub struct Sandbox {
+    resource_limits: ResourceLimits,
+    env: Option<Vec<(OsString, OsString)>>,
+    inherited_node_options: Option<OsString>,
+}
+
+impl Sandbox {
+    pub fn new(
+        resource_limits: ResourceLimits,
+        env: Option<Vec<(OsString, OsString)>>,
+        inherited_node_options: Option<OsString>,
+    ) -> Self {
+        Self {
+            resource_limits,
+            env,
+            inherited_node_options,
+        }
+    }
+
+    /// Gets the sandbox's resource limits.
+    pub fn resource_limits(&self) -> &ResourceLimits {
+        &self.resource_limits
+    }
+
+    /// Configures a command's environment and resource limits before it is spawned.
+    ///
+    /// When an environment is set, the child process gets only those variables instead of
+    /// inheriting the server's environment.
+    pub fn configure(&self, command: &mut Command) {
+        if let Some(env) = &self.env {
+            command.env_clear();
+            command.envs(env.iter().map(|(name, value)| (name, value)));
+        }
+        self.resource_limits
+            .apply(command, self.inherited_node_options.as_deref());
+    }
+}
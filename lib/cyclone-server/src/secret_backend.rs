@@ -0,0 +1,309 @@
+use std::{
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::Deserialize;
+use serde_json::Value;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{DecryptionKey, DecryptionKeyError};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum SecretBackendError {
+    #[error("http secret backend request failed")]
+    Http(#[from] reqwest::Error),
+    #[error("invalid secret reference: {0}")]
+    InvalidReference(String),
+    #[error("json serialize/deserialize error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("failed to read secret reference {1}")]
+    ReadFile(#[source] io::Error, PathBuf),
+    #[error("secret backend not configured: {0}")]
+    Unconfigured(String),
+    #[error("unknown secret backend: {0}")]
+    UnknownBackend(String),
+    #[error("failed to unseal secret reference")]
+    Unseal(#[source] DecryptionKeyError),
+}
+
+type Result<T> = std::result::Result<T, SecretBackendError>;
+
+/// Settings of an HTTP secret backend speaking the Vault HTTP API.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HttpSecretBackendConfig {
+    /// The address of the backend, i.e. `https://vault.example.com:8200`.
+    pub address: String,
+    /// The token sent in the `X-Vault-Token` header, if any.
+    pub token: Option<String>,
+}
+
+/// The external stores that secret references can be resolved from.
+///
+/// Secrets stored in Postgres arrive encrypted with the request, whereas secrets stored in an
+/// external backend arrive as a reference which is resolved here right before the function
+/// executes, so the secret material never goes through the rest of the system.
+#[derive(Clone, Debug, Default)]
+pub struct SecretBackends {
+    file_root: Option<PathBuf>,
+    http: Option<HttpSecretBackendConfig>,
+    http_client: reqwest::Client,
+}
+
+impl SecretBackends {
+    pub fn new(file_root: Option<PathBuf>, http: Option<HttpSecretBackendConfig>) -> Self {
+        Self {
+            file_root,
+            http,
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    /// Replaces the secret message with the secret it points to if it is a secret reference,
+    /// encrypted for this server so that it goes through the same decryption and redaction as
+    /// secrets stored in Postgres.
+    ///
+    /// Only the secret messages of credential components are given here, and references are
+    /// sealed with this server's key, so a request can't make up a reference to another
+    /// workspace's secret.
+    pub async fn resolve_reference(&self, message: &mut Value, key: &DecryptionKey) -> Result<()> {
+        if let Some(secret_reference) = secret_reference(message, key)? {
+            let secret = self.resolve(&secret_reference).await?;
+            let encoded = key.encrypt_and_encode(&serde_json::to_vec(&secret)?);
+            *message = serde_json::json!({
+                "cycloneEncryptedDataMarker": true,
+                "encryptedSecret": encoded,
+            });
+        }
+        Ok(())
+    }
+
+    /// Fetches the secret message a reference points to from its backend. References are scoped
+    /// to the workspace of the secret, so a workspace can only reach its own secrets.
+    async fn resolve(&self, secret_reference: &SecretReference) -> Result<Value> {
+        let SecretReference {
+            backend,
+            workspace_pk,
+            reference,
+        } = secret_reference;
+        debug!(%backend, %workspace_pk, %reference, "resolving secret reference");
+        let path = scoped_reference_path(workspace_pk, reference)?;
+        match backend.as_str() {
+            "file" => self.resolve_file(path).await,
+            "http" => self.resolve_http(&path).await,
+            _ => Err(SecretBackendError::UnknownBackend(backend.to_owned())),
+        }
+    }
+
+    async fn resolve_file(&self, path: PathBuf) -> Result<Value> {
+        let root = self
+            .file_root
+            .as_deref()
+            .ok_or_else(|| SecretBackendError::Unconfigured("file".to_owned()))?;
+        let path = root.join(path);
+        let contents = tokio::fs::read(&path)
+            .await
+            .map_err(|err| SecretBackendError::ReadFile(err, path))?;
+        Ok(serde_json::from_slice(&contents)?)
+    }
+
+    async fn resolve_http(&self, path: &Path) -> Result<Value> {
+        let config = self
+            .http
+            .as_ref()
+            .ok_or_else(|| SecretBackendError::Unconfigured("http".to_owned()))?;
+
+        let url = format!(
+            "{}/v1/{}",
+            config.address.trim_end_matches('/'),
+            path.display()
+        );
+        let mut request = self.http_client.get(url);
+        if let Some(token) = &config.token {
+            request = request.header("X-Vault-Token", token);
+        }
+        let body: Value = request.send().await?.error_for_status()?.json().await?;
+
+        Ok(vault_secret_data(body))
+    }
+}
+
+/// A reference to a secret stored in an external backend.
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct SecretReference {
+    backend: String,
+    workspace_pk: String,
+    reference: String,
+}
+
+/// Returns the secret reference sealed in a secret reference object, or `None` if the value isn't
+/// one. References that weren't sealed for this server are refused.
+fn secret_reference(value: &Value, key: &DecryptionKey) -> Result<Option<SecretReference>> {
+    let object = match value.as_object() {
+        Some(object) => object,
+        None => return Ok(None),
+    };
+    let is_secret_reference = object
+        .get("cycloneSecretReferenceMarker")
+        .map_or(false, |v| v.as_bool() == Some(true));
+    if !is_secret_reference {
+        return Ok(None);
+    }
+
+    let sealed = object
+        .get("sealedReference")
+        .and_then(Value::as_str)
+        .ok_or_else(|| SecretBackendError::InvalidReference(value.to_string()))?;
+    let unsealed = key
+        .decode_and_decrypt(sealed)
+        .map_err(SecretBackendError::Unseal)?;
+    Ok(Some(serde_json::from_slice(&unsealed)?))
+}
+
+/// Returns the path of a reference within its workspace, i.e. `<workspace_pk>/<reference>`,
+/// refusing references that would escape it.
+fn scoped_reference_path(workspace_pk: &str, reference: &str) -> Result<PathBuf> {
+    let workspace_pk = Path::new(workspace_pk);
+    if !matches!(
+        workspace_pk.components().collect::<Vec<_>>().as_slice(),
+        [Component::Normal(_)]
+    ) {
+        return Err(SecretBackendError::InvalidReference(
+            workspace_pk.display().to_string(),
+        ));
+    }
+
+    let relative = Path::new(reference);
+    let escapes_workspace = relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)));
+    if reference.is_empty() || escapes_workspace {
+        return Err(SecretBackendError::InvalidReference(reference.to_owned()));
+    }
+    Ok(workspace_pk.join(relative))
+}
+
+/// Extracts the secret from a Vault response: KV version 2 nests it under `data.data`, whereas KV
+/// version 1 puts it directly under `data`.
+fn vault_secret_data(mut body: Value) -> Value {
+    if body.pointer("/data/data").map_or(false, Value::is_object) {
+        return body["data"]["data"].take();
+    }
+    if body.get("data").is_some() {
+        return body["data"].take();
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scoped_reference_path_stays_in_workspace() {
+        assert_eq!(
+            PathBuf::from("01GW/aws/prod.json"),
+            scoped_reference_path("01GW", "aws/prod.json").expect("valid reference")
+        );
+        assert!(scoped_reference_path("01GW", "../01GX/aws/prod.json").is_err());
+        assert!(scoped_reference_path("01GW", "aws/../../01GX/prod.json").is_err());
+        assert!(scoped_reference_path("01GW", "/etc/passwd").is_err());
+        assert!(scoped_reference_path("01GW", "").is_err());
+        assert!(scoped_reference_path("..", "aws/prod.json").is_err());
+        assert!(scoped_reference_path("01GW/01GX", "aws/prod.json").is_err());
+        assert!(scoped_reference_path("", "aws/prod.json").is_err());
+    }
+
+    #[test]
+    fn vault_secret_data_supports_both_kv_versions() {
+        let secret = serde_json::json!({"username": "Tyler", "password": "Childers"});
+        assert_eq!(
+            secret,
+            vault_secret_data(serde_json::json!({"data": {"data": secret, "metadata": {}}}))
+        );
+        assert_eq!(
+            secret,
+            vault_secret_data(serde_json::json!({"data": secret, "lease_duration": 0}))
+        );
+    }
+
+    #[tokio::test]
+    async fn resolve_file_references() {
+        let root = tempfile::TempDir::new().expect("unable to create secrets dir");
+        tokio::fs::create_dir_all(root.path().join("01GW"))
+            .await
+            .expect("unable to create workspace secrets dir");
+        let secret = serde_json::json!({"my-super-secret": "Varginha's UFO"});
+        tokio::fs::write(
+            root.path().join("01GW").join("ufo.json"),
+            serde_json::to_vec(&secret).expect("unable to serialize secret"),
+        )
+        .await
+        .expect("unable to write secret");
+
+        let decryption_key = DecryptionKey::from(sodiumoxide::crypto::box_::gen_keypair().1);
+        let secret_backends = SecretBackends::new(Some(root.path().to_path_buf()), None);
+        let reference = |workspace_pk: &str| {
+            let sealed = serde_json::json!({
+                "backend": "file",
+                "workspacePk": workspace_pk,
+                "reference": "ufo.json",
+            });
+            serde_json::json!({
+                "cycloneSecretReferenceMarker": true,
+                "sealedReference": decryption_key.encrypt_and_encode(
+                    &serde_json::to_vec(&sealed).expect("unable to serialize reference")
+                ),
+            })
+        };
+
+        // Another workspace can't reach the secret
+        assert!(matches!(
+            secret_backends
+                .resolve_reference(&mut reference("01GX"), &decryption_key)
+                .await,
+            Err(SecretBackendError::ReadFile(_, _))
+        ));
+
+        // References that weren't sealed by dal are refused
+        let mut forged = serde_json::json!({
+            "cycloneSecretReferenceMarker": true,
+            "backend": "file",
+            "workspacePk": "01GW",
+            "reference": "ufo.json",
+        });
+        assert!(matches!(
+            secret_backends
+                .resolve_reference(&mut forged, &decryption_key)
+                .await,
+            Err(SecretBackendError::InvalidReference(_))
+        ));
+        let other_key = DecryptionKey::from(sodiumoxide::crypto::box_::gen_keypair().1);
+        assert!(matches!(
+            secret_backends
+                .resolve_reference(&mut reference("01GW"), &other_key)
+                .await,
+            Err(SecretBackendError::Unseal(_))
+        ));
+
+        let mut message = reference("01GW");
+        secret_backends
+            .resolve_reference(&mut message, &decryption_key)
+            .await
+            .expect("unable to resolve reference");
+
+        let encoded = message["encryptedSecret"]
+            .as_str()
+            .expect("reference was not replaced by an encrypted secret");
+        let decrypted = decryption_key
+            .decode_and_decrypt(encoded)
+            .expect("unable to decrypt");
+        assert_eq!(
+            secret,
+            serde_json::from_slice::<Value>(&decrypted).expect("unable to deserialize")
+        );
+    }
+}
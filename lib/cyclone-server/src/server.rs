@@ -18,7 +18,7 @@ use tower_http::trace::{DefaultMakeSpan, TraceLayer};
 
 use crate::{
     routes::routes, state::AppState, Config, DecryptionKey, DecryptionKeyError, IncomingStream,
-    UdsIncomingStream, UdsIncomingStreamError,
+    Sandbox, UdsIncomingStream, UdsIncomingStreamError,
 };
 
 #[remain::sorted]
@@ -149,7 +149,18 @@ fn build_service(
 ) -> Result<(IntoMakeService<Router>, oneshot::Receiver<()>)> {
     let (shutdown_tx, shutdown_rx) = mpsc::channel(4);
 
-    let state = AppState::new(config.lang_server_path(), decryption_key, telemetry_level);
+    let state = AppState::new(
+        config.lang_server_path(),
+        decryption_key,
+        telemetry_level,
+        config.execution_timeout(),
+        Sandbox::new(
+            *config.resource_limits(),
+            config.execution_env().map(<[_]>::to_vec),
+            config.inherited_node_options().map(ToOwned::to_owned),
+        ),
+        config.secret_backends().clone(),
+    );
 
     let routes = routes(config, state, shutdown_tx)
         // TODO(fnichol): customize http tracing further, using:
@@ -13,6 +13,9 @@ pub struct AppState {
     lang_server_path: LangServerPath,
     decryption_key: DecryptionKey,
     telemetry_level: TelemetryLevel,
+    execution_timeout: ExecutionTimeout,
+    sandbox: Sandbox,
+    secret_backends: SecretBackends,
 }
 
 impl AppState {
@@ -20,11 +23,17 @@ impl AppState {
         lang_server_path: impl Into<PathBuf>,
         decryption_key: crate::DecryptionKey,
         telemetry_level: Box<dyn telemetry::TelemetryLevel>,
+        execution_timeout: Duration,
+        sandbox: crate::Sandbox,
+        secret_backends: crate::SecretBackends,
     ) -> Self {
         Self {
             lang_server_path: LangServerPath(Arc::new(lang_server_path.into())),
             decryption_key: DecryptionKey(Arc::new(decryption_key)),
             telemetry_level: TelemetryLevel(Arc::new(telemetry_level)),
+            execution_timeout: ExecutionTimeout(execution_timeout),
+            sandbox: Sandbox(Arc::new(sandbox)),
+            secret_backends: SecretBackends(Arc::new(secret_backends)),
         }
     }
 }
@@ -55,6 +64,24 @@ impl From<DecryptionKey> for Arc<crate::DecryptionKey> {
     }
 }
 
+#[derive(Clone, Debug, FromRef)]
+pub struct Sandbox(Arc<crate::Sandbox>);
+
+impl From<Sandbox> for Arc<crate::Sandbox> {
+    fn from(value: Sandbox) -> Self {
+        value.0
+    }
+}
+
+#[derive(Clone, Debug, FromRef)]
+pub struct SecretBackends(Arc<crate::SecretBackends>);
+
+impl From<SecretBackends> for Arc<crate::SecretBackends> {
+    fn from(value: SecretBackends) -> Self {
+        value.0
+    }
+}
+
 #[derive(Clone, FromRef)]
 pub struct TelemetryLevel(Arc<Box<dyn telemetry::TelemetryLevel>>);
 
@@ -66,6 +93,15 @@ impl Deref for TelemetryLevel {
     }
 }
 
+#[derive(Clone, Copy, Debug, FromRef)]
+pub struct ExecutionTimeout(Duration);
+
+impl ExecutionTimeout {
+    pub fn duration(&self) -> Duration {
+        self.0
+    }
+}
+
 pub struct WatchKeepalive {
     tx: mpsc::Sender<()>,
     timeout: Duration,
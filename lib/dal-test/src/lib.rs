@@ -429,6 +429,8 @@ pub fn pinga_server(services_context: &ServicesContext) -> Result<pinga_server::
     let server = pinga_server::Server::from_services(
         config.instance_id(),
         config.concurrency(),
+        config.fix_concurrency(),
+        config.retry_policies().clone(),
         services_context.encryption_key(),
         services_context.nats_conn().clone(),
         services_context.pg_pool().clone(),
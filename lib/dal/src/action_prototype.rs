@@ -8,9 +8,11 @@ use si_data_nats::NatsError;
 use si_data_pg::PgError;
 use si_pkg::ActionFuncSpecKind;
 use telemetry::prelude::*;
+use veritech_client::FunctionResult;
 
 use crate::{
-    component::view::ComponentViewError, func::backend::js_action::ActionRunResult,
+    component::view::ComponentViewError,
+    func::backend::{js_action::ActionRunResult, FuncBackendError},
     impl_standard_model, pk, standard_model, standard_model_accessor, Component, ComponentId,
     ComponentView, DalContext, FuncBinding, FuncBindingError, FuncBindingReturnValueError, FuncId,
     HistoryEventError, SchemaVariantId, StandardModel, StandardModelError, Tenancy, Timestamp,
@@ -61,6 +63,18 @@ pub enum ActionPrototypeError {
     WsEvent(#[from] WsEventError),
 }
 
+impl ActionPrototypeError {
+    /// Whether the action's execution was cancelled while it was running.
+    pub fn is_cancelled(&self) -> bool {
+        matches!(
+            self,
+            Self::FuncBinding(FuncBindingError::FuncBackend(
+                FuncBackendError::FunctionResultActionRun(FunctionResult::Failure(failure))
+            )) if failure.error.is_cancelled()
+        )
+    }
+}
+
 pub type ActionPrototypeResult<T> = Result<T, ActionPrototypeError>;
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Copy)]
@@ -307,12 +321,26 @@ impl ActionPrototype {
         ctx: &DalContext,
         component_id: ComponentId,
         trigger_dependent_values_update: bool,
+    ) -> ActionPrototypeResult<Option<ActionRunResult>> {
+        self.run_with_execution_id(ctx, component_id, trigger_dependent_values_update, None)
+            .await
+    }
+
+    /// Like [`Self::run()`], but tags the execution in veritech with the given id so that it can
+    /// be cancelled while it runs.
+    pub async fn run_with_execution_id(
+        &self,
+        ctx: &DalContext,
+        component_id: ComponentId,
+        trigger_dependent_values_update: bool,
+        execution_id: Option<String>,
     ) -> ActionPrototypeResult<Option<ActionRunResult>> {
         let component_view = ComponentView::new(ctx, component_id).await?;
-        let (_, return_value) = FuncBinding::create_and_execute(
+        let (_, return_value) = FuncBinding::create_and_execute_with_execution_id(
             ctx,
             serde_json::to_value(component_view)?,
             self.func_id(),
+            execution_id,
         )
         .await?;
 
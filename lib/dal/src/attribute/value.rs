@@ -64,9 +64,9 @@ use crate::{
     standard_model::{self, TypeHint},
     standard_model_accessor, standard_model_belongs_to, standard_model_has_many,
     AttributeContextError, AttributePrototypeArgumentError, Component, ComponentId, DalContext,
-    Func, FuncBinding, FuncError, HistoryEventError, IndexMap, InternalProvider,
-    InternalProviderId, Prop, PropError, PropId, PropKind, StandardModel, StandardModelError,
-    Tenancy, Timestamp, TransactionsError, Visibility, WsEventError,
+    Func, FuncBinding, FuncError, HistoryActor, HistoryEvent, HistoryEventError, IndexMap,
+    InternalProvider, InternalProviderId, Prop, PropError, PropId, PropKind, StandardModel,
+    StandardModelError, Tenancy, Timestamp, TransactionsError, Visibility, WsEventError,
 };
 
 pub mod view;
@@ -773,6 +773,22 @@ impl AttributeValue {
 
         let new_attribute_value_id: AttributeValueId = row.try_get("new_attribute_value_id")?;
 
+        // Record who changed the values of components. The value itself is left out, since it may
+        // be sensitive and the history is kept forever.
+        if !context.is_component_unset() && matches!(ctx.history_actor(), HistoryActor::User(_)) {
+            let _history_event = HistoryEvent::new(
+                ctx,
+                "attribute_value.update_for_context",
+                "Attribute Value updated",
+                &serde_json::json![{
+                    "id": new_attribute_value_id,
+                    "component_id": context.component_id(),
+                    "prop_id": context.prop_id(),
+                }],
+            )
+            .await?;
+        }
+
         // TODO(fnichol): we might want to fire off a status even at this point, however we've
         // already updated the initial attribute value, so is there much value?
 
@@ -10,7 +10,7 @@ use crate::{
         intrinsics::IntrinsicFunc,
     },
     installed_pkg::InstalledPkg,
-    pkg::import_pkg_from_pkg,
+    pkg::{import_pkg_from_pkg, ImportOptions},
     BuiltinsError, BuiltinsResult, DalContext, Func, FuncBackendKind, FuncBackendResponseType,
     StandardModel,
 };
@@ -63,7 +63,16 @@ pub async fn migrate_intrinsics(ctx: &DalContext) -> BuiltinsResult<()> {
         .await?
         .is_none()
     {
-        import_pkg_from_pkg(ctx, &intrinsics_pkg, &name, None).await?;
+        import_pkg_from_pkg(
+            ctx,
+            &intrinsics_pkg,
+            &name,
+            Some(ImportOptions {
+                skip_trust_policy: true,
+                ..Default::default()
+            }),
+        )
+        .await?;
         ctx.blocking_commit().await?;
     }
 
@@ -147,8 +147,9 @@ pub async fn migrate_pkg(
             ctx,
             &pkg,
             pkg_filename,
-            schemas.map(|schemas| ImportOptions {
-                schemas: Some(schemas),
+            Some(ImportOptions {
+                schemas,
+                skip_trust_policy: true,
                 ..Default::default()
             }),
         )
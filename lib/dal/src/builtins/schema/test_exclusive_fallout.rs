@@ -205,6 +205,7 @@ impl MigrationDriver {
             "test:fallout",
             Some(crate::pkg::ImportOptions {
                 schemas: Some(vec!["fallout".into()]),
+                skip_trust_policy: true,
                 ..Default::default()
             }),
         )
@@ -286,6 +286,7 @@ impl MigrationDriver {
             "test:starfield",
             Some(crate::pkg::ImportOptions {
                 schemas: Some(vec!["starfield".into()]),
+                skip_trust_policy: true,
                 ..Default::default()
             }),
         )
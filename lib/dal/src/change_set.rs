@@ -6,49 +6,81 @@ use strum::{Display, EnumString};
 use telemetry::prelude::*;
 use thiserror::Error;
 
+use crate::change_status::ChangeStatusError;
 use crate::label_list::LabelList;
 use crate::standard_model::object_option_from_row_option;
 use crate::ws_event::{WsEvent, WsEventError, WsPayload};
 use crate::{
-    pk, HistoryEvent, HistoryEventError, LabelListError, StandardModelError, Tenancy, Timestamp,
-    TransactionsError, UserError, UserPk, Visibility,
+    pk, AttributeValueId, ComponentId, HistoryEvent, HistoryEventError, LabelListError, PropId,
+    StandardModelError, Tenancy, Timestamp, TransactionsError, UserError, UserPk, Visibility,
+};
+use crate::{
+    Component, ComponentError, ComponentViewError, DalContext, WorkspaceError, WsEventResult,
 };
-use crate::{Component, ComponentError, DalContext, WsEventResult};
+
+pub mod approval;
+pub mod diff;
+pub mod rebase;
 
 const CHANGE_SET_OPEN_LIST: &str = include_str!("queries/change_set/open_list.sql");
 const CHANGE_SET_GET_BY_PK: &str = include_str!("queries/change_set/get_by_pk.sql");
+const CHANGE_SET_LIST_CONFLICTS: &str = include_str!("queries/change_set/list_conflicts.sql");
 
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum ChangeSetError {
+    #[error("approval has not been requested for change set {0}")]
+    ApprovalNotRequested(ChangeSetPk),
+    #[error("change set {0} does not satisfy the workspace approval policy")]
+    ApprovalPolicyNotSatisfied(ChangeSetPk),
+    #[error("only users can approve or reject a change set")]
+    ApprovalRequiresUser,
+    #[error("change set not found: {0}")]
+    ChangeSetNotFound(ChangeSetPk),
+    #[error(transparent)]
+    ChangeStatus(#[from] ChangeStatusError),
     #[error(transparent)]
     Component(#[from] ComponentError),
     #[error(transparent)]
+    ComponentView(#[from] ComponentViewError),
+    #[error("change set conflicts with head on {} attribute value(s)", .0.len())]
+    Conflicts(Vec<ChangeSetConflict>),
+    #[error(transparent)]
     HistoryEvent(#[from] HistoryEventError),
     #[error("invalid user actor pk")]
     InvalidActor(UserPk),
+    #[error("invalid change status: {0}")]
+    InvalidChangeStatus(String),
     #[error(transparent)]
     LabelList(#[from] LabelListError),
     #[error(transparent)]
     Nats(#[from] NatsError),
+    #[error("change set {0} is not open (status: {1})")]
+    NotOpen(ChangeSetPk, ChangeSetStatus),
     #[error(transparent)]
     Pg(#[from] PgError),
+    #[error("change set {0} can't be approved by {1}, who requested its approval")]
+    SelfApproval(ChangeSetPk, UserPk),
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
     StandardModel(#[from] StandardModelError),
     #[error(transparent)]
     Transactions(#[from] TransactionsError),
+    #[error("rebase conflicts without a resolution: {0:?}")]
+    UnresolvedRebaseConflicts(Vec<AttributeValueId>),
     #[error(transparent)]
     User(#[from] UserError),
     #[error(transparent)]
+    Workspace(#[from] WorkspaceError),
+    #[error(transparent)]
     WsEvent(#[from] WsEventError),
 }
 
 pub type ChangeSetResult<T> = Result<T, ChangeSetError>;
 
 #[remain::sorted]
-#[derive(Deserialize, Serialize, Debug, Display, EnumString, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Debug, Display, EnumString, PartialEq, Eq, Clone, Copy)]
 pub enum ChangeSetStatus {
     Abandoned,
     Applied,
@@ -59,12 +91,30 @@ pub enum ChangeSetStatus {
 
 pk!(ChangeSetPk);
 
+/// An [`AttributeValue`](crate::AttributeValue) that was edited both in a [`ChangeSet`] and on
+/// _head_ since the [`ChangeSet`] was created (or last rebased).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetConflict {
+    pub attribute_value_id: AttributeValueId,
+    pub component_id: ComponentId,
+    pub prop_id: PropId,
+    pub head_updated_at: DateTime<Utc>,
+    pub change_set_updated_at: DateTime<Utc>,
+}
+
 #[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 pub struct ChangeSet {
     pub pk: ChangeSetPk,
     pub name: String,
     pub note: Option<String>,
     pub status: ChangeSetStatus,
+    /// When the change set was last rebased onto _head_, if ever.
+    pub rebased_at: Option<DateTime<Utc>>,
+    /// When approval was last requested for the change set, if ever.
+    pub approval_requested_at: Option<DateTime<Utc>>,
+    /// Who last requested approval for the change set, if anybody.
+    pub approval_requested_by: Option<UserPk>,
     #[serde(flatten)]
     pub tenancy: Tenancy,
     #[serde(flatten)]
@@ -109,12 +159,58 @@ impl ChangeSet {
         Utc::now().format("%Y-%m-%d-%H:%M").to_string()
     }
 
+    /// Lists every [`ChangeSetConflict`] between this [`ChangeSet`] and _head_.
+    #[instrument(skip_all)]
+    pub async fn list_conflicts(
+        &self,
+        ctx: &DalContext,
+    ) -> ChangeSetResult<Vec<ChangeSetConflict>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                CHANGE_SET_LIST_CONFLICTS,
+                &[&self.tenancy, &self.pk, &self.rebase_base()],
+            )
+            .await?;
+
+        let mut conflicts = Vec::with_capacity(rows.len());
+        for row in rows {
+            conflicts.push(ChangeSetConflict {
+                attribute_value_id: row.try_get("attribute_value_id")?,
+                component_id: row.try_get("component_id")?,
+                prop_id: row.try_get("prop_id")?,
+                head_updated_at: row.try_get("head_updated_at")?,
+                change_set_updated_at: row.try_get("change_set_updated_at")?,
+            });
+        }
+        Ok(conflicts)
+    }
+
+    /// Applies the [`ChangeSet`] to _head_.
+    ///
+    /// This fails with [`ChangeSetError::ApprovalPolicyNotSatisfied`] until the
+    /// [`Workspace's`](crate::Workspace) approval policy is met. Unless `force` is set, it also
+    /// fails with [`ChangeSetError::Conflicts`] if _head_ moved under any value that was also
+    /// edited in the [`ChangeSet`], since applying would clobber it.
     #[instrument(skip(ctx))]
     pub async fn apply_raw(
         &mut self,
         ctx: &mut DalContext,
         run_confirmations: bool,
+        force: bool,
     ) -> ChangeSetResult<()> {
+        if !self.approval_state(ctx).await?.satisfied {
+            return Err(ChangeSetError::ApprovalPolicyNotSatisfied(self.pk));
+        }
+        if !force {
+            let conflicts = self.list_conflicts(ctx).await?;
+            if !conflicts.is_empty() {
+                return Err(ChangeSetError::Conflicts(conflicts));
+            }
+        }
+
         let actor = serde_json::to_value(ctx.history_actor())?;
         let row = ctx
             .txns()
@@ -154,7 +250,73 @@ impl ChangeSet {
 
     #[instrument(skip(ctx))]
     pub async fn apply(&mut self, ctx: &mut DalContext) -> ChangeSetResult<()> {
-        self.apply_raw(ctx, true).await?;
+        self.apply_raw(ctx, true, false).await?;
+        Ok(())
+    }
+
+    /// Abandons the [`ChangeSet`], soft-deleting every row that is only visible within it. The
+    /// change set will no longer show up in [`Self::list_open`].
+    #[instrument(skip(ctx))]
+    pub async fn abandon(&mut self, ctx: &mut DalContext) -> ChangeSetResult<()> {
+        self.cancel(ctx, ChangeSetStatus::Abandoned, true).await
+    }
+
+    /// Closes the [`ChangeSet`] without applying it. Unlike [`Self::abandon`], rows written in the
+    /// change set are left in place (detached from any open change set) so they can still be
+    /// inspected.
+    #[instrument(skip(ctx))]
+    pub async fn close(&mut self, ctx: &mut DalContext) -> ChangeSetResult<()> {
+        self.cancel(ctx, ChangeSetStatus::Closed, false).await
+    }
+
+    async fn cancel(
+        &mut self,
+        ctx: &mut DalContext,
+        status: ChangeSetStatus,
+        soft_delete: bool,
+    ) -> ChangeSetResult<()> {
+        if self.status != ChangeSetStatus::Open {
+            return Err(ChangeSetError::NotOpen(self.pk, self.status));
+        }
+
+        let actor = serde_json::to_value(ctx.history_actor())?;
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT timestamp_updated_at FROM change_set_abandon_v1($1, $2, $3, $4, $5)",
+                &[
+                    &self.pk,
+                    &status.to_string(),
+                    &soft_delete,
+                    &actor,
+                    ctx.tenancy(),
+                ],
+            )
+            .await?;
+        // The function only updates the change set when it's visible from our tenancy.
+        let updated_at: Option<DateTime<Utc>> = row.try_get("timestamp_updated_at")?;
+        self.timestamp.updated_at = updated_at.ok_or(ChangeSetError::ChangeSetNotFound(self.pk))?;
+        self.status = status;
+
+        let (label, message) = match status {
+            ChangeSetStatus::Abandoned => ("change_set.abandon", "Change Set abandoned"),
+            _ => ("change_set.close", "Change Set closed"),
+        };
+        let _history_event =
+            HistoryEvent::new(ctx, label, message, &serde_json::json![{ "pk": &self.pk }]).await?;
+
+        WsEvent::change_set_canceled(ctx, self.pk)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        // The change set is gone, so anything still looking at it should move back to head.
+        if ctx.visibility().change_set_pk == self.pk {
+            ctx.update_visibility(Visibility::new_head(false));
+        }
+
         Ok(())
     }
 
@@ -0,0 +1,284 @@
+//! This module contains the review workflow for a [`ChangeSet`].
+//!
+//! Every [`Workspace`](crate::Workspace) carries a required number of approvals. Once approval
+//! has been requested, users record their decision with [`ChangeSet::approve()`] or
+//! [`ChangeSet::reject()`], and [`ChangeSet::apply_raw()`] refuses to run until enough users have
+//! approved and nobody has rejected. The user who requested approval can't approve their own
+//! [`ChangeSet`], and approvals given before the [`ChangeSet`] was last edited don't count.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use telemetry::prelude::*;
+
+use crate::change_set::{ChangeSetError, ChangeSetResult};
+use crate::standard_model::{object_from_row, objects_from_rows};
+use crate::{
+    pk, ChangeSet, ChangeSetPk, ChangeSetStatus, DalContext, HistoryActor, HistoryEvent, Tenancy,
+    Timestamp, UserPk, Workspace, WsEvent, WsEventResult, WsPayload,
+};
+
+const LIST_APPROVALS: &str = include_str!("../queries/change_set/list_approvals.sql");
+
+pk!(ChangeSetApprovalPk);
+
+/// The decision a user made when reviewing a [`ChangeSet`].
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Display, EnumString, PartialEq, Eq, Clone, Copy)]
+pub enum ChangeSetApprovalStatus {
+    Approved,
+    Rejected,
+}
+
+/// A single user's decision for a [`ChangeSet`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ChangeSetApproval {
+    pub pk: ChangeSetApprovalPk,
+    pub change_set_pk: ChangeSetPk,
+    pub user_pk: UserPk,
+    pub status: ChangeSetApprovalStatus,
+    pub note: Option<String>,
+    #[serde(flatten)]
+    pub tenancy: Tenancy,
+    #[serde(flatten)]
+    pub timestamp: Timestamp,
+}
+
+/// Where a [`ChangeSet`] stands against its [`Workspace's`](crate::Workspace) approval policy.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetApprovalState {
+    pub change_set_pk: ChangeSetPk,
+    pub requested_at: Option<DateTime<Utc>>,
+    pub requested_by: Option<UserPk>,
+    /// When anything in the [`ChangeSet`] was last written. Approvals older than this are stale.
+    pub edited_at: Option<DateTime<Utc>>,
+    pub required_approvals: i32,
+    pub approvals: Vec<ChangeSetApproval>,
+    /// Whether the [`ChangeSet`] may be applied.
+    pub satisfied: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetApprovalPayload {
+    change_set_pk: ChangeSetPk,
+    user_pk: Option<UserPk>,
+    status: Option<ChangeSetApprovalStatus>,
+}
+
+impl ChangeSet {
+    /// Starts a new round of review for the [`ChangeSet`]. Decisions from any previous round are
+    /// discarded.
+    #[instrument(skip(ctx))]
+    pub async fn request_approval(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
+        if self.status != ChangeSetStatus::Open {
+            return Err(ChangeSetError::NotOpen(self.pk, self.status));
+        }
+        let user_pk = match ctx.history_actor() {
+            HistoryActor::User(user_pk) => Some(*user_pk),
+            HistoryActor::SystemInit => None,
+        };
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT timestamp_approval_requested_at
+                FROM change_set_request_approval_v1($1, $2, $3)",
+                &[&self.pk, &user_pk, &self.tenancy],
+            )
+            .await?;
+        let requested_at: DateTime<Utc> = row.try_get("timestamp_approval_requested_at")?;
+        self.approval_requested_at = Some(requested_at);
+        self.approval_requested_by = user_pk;
+        self.timestamp.updated_at = requested_at;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "change_set.request_approval",
+            "Change Set approval requested",
+            &serde_json::json![{ "pk": &self.pk }],
+        )
+        .await?;
+
+        WsEvent::change_set_approval_updated(ctx, self.pk, None, None)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Records that the current user approves the [`ChangeSet`].
+    #[instrument(skip(ctx))]
+    pub async fn approve(
+        &self,
+        ctx: &DalContext,
+        note: Option<String>,
+    ) -> ChangeSetResult<ChangeSetApproval> {
+        self.record_approval(ctx, ChangeSetApprovalStatus::Approved, note)
+            .await
+    }
+
+    /// Records that the current user rejects the [`ChangeSet`]. A single rejection blocks the
+    /// [`ChangeSet`] from being applied until approval is requested again.
+    #[instrument(skip(ctx))]
+    pub async fn reject(
+        &self,
+        ctx: &DalContext,
+        note: Option<String>,
+    ) -> ChangeSetResult<ChangeSetApproval> {
+        self.record_approval(ctx, ChangeSetApprovalStatus::Rejected, note)
+            .await
+    }
+
+    async fn record_approval(
+        &self,
+        ctx: &DalContext,
+        status: ChangeSetApprovalStatus,
+        note: Option<String>,
+    ) -> ChangeSetResult<ChangeSetApproval> {
+        if self.status != ChangeSetStatus::Open {
+            return Err(ChangeSetError::NotOpen(self.pk, self.status));
+        }
+        if self.approval_requested_at.is_none() {
+            return Err(ChangeSetError::ApprovalNotRequested(self.pk));
+        }
+        let user_pk = match ctx.history_actor() {
+            HistoryActor::User(user_pk) => *user_pk,
+            HistoryActor::SystemInit => return Err(ChangeSetError::ApprovalRequiresUser),
+        };
+        if status == ChangeSetApprovalStatus::Approved
+            && self.approval_requested_by == Some(user_pk)
+        {
+            return Err(ChangeSetError::SelfApproval(self.pk, user_pk));
+        }
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM change_set_approval_upsert_v1($1, $2, $3, $4, $5)",
+                &[
+                    &self.pk,
+                    &user_pk,
+                    &status.to_string(),
+                    &note,
+                    &self.tenancy,
+                ],
+            )
+            .await?;
+        let approval: ChangeSetApproval = object_from_row(row)?;
+
+        let (label, message) = match status {
+            ChangeSetApprovalStatus::Approved => ("change_set.approve", "Change Set approved"),
+            ChangeSetApprovalStatus::Rejected => ("change_set.reject", "Change Set rejected"),
+        };
+        let _history_event = HistoryEvent::new(
+            ctx,
+            label,
+            message,
+            &serde_json::json![{ "pk": &self.pk, "user_pk": &user_pk, "note": &note }],
+        )
+        .await?;
+
+        WsEvent::change_set_approval_updated(ctx, self.pk, Some(user_pk), Some(status))
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(approval)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn list_approvals(
+        &self,
+        ctx: &DalContext,
+    ) -> ChangeSetResult<Vec<ChangeSetApproval>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST_APPROVALS, &[&self.tenancy, &self.pk])
+            .await?;
+        Ok(objects_from_rows(rows)?)
+    }
+
+    /// When anything in the [`ChangeSet`] was last written, if ever.
+    #[instrument(skip_all)]
+    pub async fn last_edited_at(&self, ctx: &DalContext) -> ChangeSetResult<Option<DateTime<Utc>>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT last_edited_at FROM change_set_last_edited_at_v1($1, $2)",
+                &[&self.pk, &self.tenancy],
+            )
+            .await?;
+        Ok(row.try_get("last_edited_at")?)
+    }
+
+    /// Evaluates the [`ChangeSet`] against its [`Workspace's`](crate::Workspace) approval policy.
+    #[instrument(skip_all)]
+    pub async fn approval_state(
+        &self,
+        ctx: &DalContext,
+    ) -> ChangeSetResult<ChangeSetApprovalState> {
+        let required_approvals = match self.tenancy.workspace_pk() {
+            Some(workspace_pk) => Workspace::get_by_pk(ctx, &workspace_pk)
+                .await?
+                .map(|workspace| *workspace.change_set_required_approvals())
+                .unwrap_or_default(),
+            None => 0,
+        };
+        let approvals = self.list_approvals(ctx).await?;
+        let edited_at = self.last_edited_at(ctx).await?;
+
+        let approved = approvals
+            .iter()
+            .filter(|approval| approval.status == ChangeSetApprovalStatus::Approved)
+            .filter(|approval| match edited_at {
+                Some(edited_at) => approval.timestamp.updated_at >= edited_at,
+                None => true,
+            })
+            .count();
+        let rejected = approvals
+            .iter()
+            .any(|approval| approval.status == ChangeSetApprovalStatus::Rejected);
+        let satisfied =
+            required_approvals <= 0 || (!rejected && approved >= required_approvals as usize);
+
+        Ok(ChangeSetApprovalState {
+            change_set_pk: self.pk,
+            requested_at: self.approval_requested_at,
+            requested_by: self.approval_requested_by,
+            edited_at,
+            required_approvals,
+            approvals,
+            satisfied,
+        })
+    }
+}
+
+impl WsEvent {
+    pub async fn change_set_approval_updated(
+        ctx: &DalContext,
+        change_set_pk: ChangeSetPk,
+        user_pk: Option<UserPk>,
+        status: Option<ChangeSetApprovalStatus>,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::ChangeSetApprovalUpdated(ChangeSetApprovalPayload {
+                change_set_pk,
+                user_pk,
+                status,
+            }),
+        )
+        .await
+    }
+}
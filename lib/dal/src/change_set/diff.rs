@@ -0,0 +1,196 @@
+//! This module contains [`ChangeSetDiff`], which describes everything a [`ChangeSet`] would change
+//! on _head_ if it were applied.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use telemetry::prelude::*;
+
+use crate::change_set::{ChangeSetError, ChangeSetResult};
+use crate::change_status::{ChangeStatus, ComponentChangeStatus, ComponentChangeStatusGroup};
+use crate::edge::EdgeId;
+use crate::{
+    ChangeSet, ComponentId, ComponentView, ComponentViewProperties, DalContext, FuncId,
+    SchemaVariantId, Visibility,
+};
+
+const DIFF_EDGES: &str = include_str!("../queries/change_set/diff_edges.sql");
+const DIFF_FUNCS: &str = include_str!("../queries/change_set/diff_funcs.sql");
+const DIFF_SCHEMA_VARIANTS: &str = include_str!("../queries/change_set/diff_schema_variants.sql");
+
+/// A single object (e.g. a [`Func`](crate::Func)) that was added, deleted or modified in the
+/// [`ChangeSet`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectDiff<T> {
+    pub id: T,
+    pub name: Option<String>,
+    pub status: ChangeStatus,
+}
+
+/// The change to a single leaf value in a [`Component's`](crate::Component) properties tree.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PropValueDiff {
+    /// The location of the value in the properties tree (e.g. "/root/domain/region").
+    pub path: String,
+    /// The value on _head_, if any.
+    pub before: Option<Value>,
+    /// The value in the [`ChangeSet`], if any.
+    pub after: Option<Value>,
+}
+
+/// A [`Component`](crate::Component) that was added, deleted or modified in the [`ChangeSet`],
+/// along with every prop value that changed as a result.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentChangeSetDiff {
+    pub component_id: ComponentId,
+    pub component_name: String,
+    pub status: ChangeStatus,
+    pub props: Vec<PropValueDiff>,
+}
+
+/// Everything that was added, deleted or modified in a [`ChangeSet`] when compared against
+/// _head_. Generated by [`ChangeSet::diff()`].
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetDiff {
+    pub components: Vec<ComponentChangeSetDiff>,
+    pub edges: Vec<ObjectDiff<EdgeId>>,
+    pub schema_variants: Vec<ObjectDiff<SchemaVariantId>>,
+    pub funcs: Vec<ObjectDiff<FuncId>>,
+}
+
+impl ChangeSet {
+    /// Generates a [`ChangeSetDiff`] for this [`ChangeSet`] against _head_.
+    #[instrument(skip_all)]
+    pub async fn diff(&self, ctx: &DalContext) -> ChangeSetResult<ChangeSetDiff> {
+        let ctx = &ctx.clone_with_new_visibility(Visibility::new_change_set(self.pk, false));
+        let head_ctx = &ctx.clone_with_head();
+
+        let mut groups = Vec::new();
+        groups.extend(ComponentChangeStatus::list_added(ctx).await?);
+        groups.extend(ComponentChangeStatus::list_deleted(ctx).await?);
+        groups.extend(ComponentChangeStatus::list_modified(ctx).await?);
+
+        let mut components = Vec::with_capacity(groups.len());
+        for group in groups {
+            components.push(component_diff(ctx, head_ctx, group).await?);
+        }
+
+        Ok(ChangeSetDiff {
+            components,
+            edges: self.list_object_diffs(ctx, DIFF_EDGES).await?,
+            schema_variants: self.list_object_diffs(ctx, DIFF_SCHEMA_VARIANTS).await?,
+            funcs: self.list_object_diffs(ctx, DIFF_FUNCS).await?,
+        })
+    }
+
+    async fn list_object_diffs<T>(
+        &self,
+        ctx: &DalContext,
+        query: &str,
+    ) -> ChangeSetResult<Vec<ObjectDiff<T>>>
+    where
+        T: for<'a> postgres_types::FromSql<'a>,
+    {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(query, &[&self.tenancy, &self.pk])
+            .await?;
+
+        let mut diffs = Vec::with_capacity(rows.len());
+        for row in rows {
+            let status: String = row.try_get("status")?;
+            diffs.push(ObjectDiff {
+                id: row.try_get("id")?,
+                name: row.try_get("name")?,
+                status: ChangeStatus::from_str(&status)
+                    .map_err(|_| ChangeSetError::InvalidChangeStatus(status))?,
+            });
+        }
+        Ok(diffs)
+    }
+}
+
+async fn component_diff(
+    ctx: &DalContext,
+    head_ctx: &DalContext,
+    group: ComponentChangeStatusGroup,
+) -> ChangeSetResult<ComponentChangeSetDiff> {
+    let mut before = BTreeMap::new();
+    let mut after = BTreeMap::new();
+
+    if group.component_status != ChangeStatus::Added {
+        let view = ComponentView::new(head_ctx, group.component_id).await?;
+        flatten_properties(&component_view_value(view)?, "/root", &mut before);
+    }
+    if group.component_status != ChangeStatus::Deleted {
+        let view = ComponentView::new(ctx, group.component_id).await?;
+        flatten_properties(&component_view_value(view)?, "/root", &mut after);
+    }
+
+    let mut props = Vec::new();
+    for (path, before_value) in &before {
+        let after_value = after.get(path);
+        if after_value != Some(before_value) {
+            props.push(PropValueDiff {
+                path: path.clone(),
+                before: Some(before_value.clone()),
+                after: after_value.cloned(),
+            });
+        }
+    }
+    for (path, after_value) in after {
+        if !before.contains_key(&path) {
+            props.push(PropValueDiff {
+                path,
+                before: None,
+                after: Some(after_value),
+            });
+        }
+    }
+    props.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(ComponentChangeSetDiff {
+        component_id: group.component_id,
+        component_name: group.component_name().to_owned(),
+        status: group.component_status,
+        props,
+    })
+}
+
+/// Renders the user-facing portion ("/root/si" and "/root/domain") of a [`ComponentView`].
+fn component_view_value(view: ComponentView) -> ChangeSetResult<Value> {
+    if view.properties.is_null() {
+        return Ok(Value::Null);
+    }
+    let mut properties = ComponentViewProperties::try_from(view)?;
+    properties.drop_private();
+    Ok(properties.to_value()?)
+}
+
+/// Collects every leaf of a properties tree keyed by its path.
+fn flatten_properties(value: &Value, path: &str, leaves: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                flatten_properties(child, &format!("{path}/{key}"), leaves);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for (index, child) in items.iter().enumerate() {
+                flatten_properties(child, &format!("{path}/{index}"), leaves);
+            }
+        }
+        Value::Null => {}
+        leaf => {
+            leaves.insert(path.to_owned(), leaf.clone());
+        }
+    }
+}
@@ -0,0 +1,208 @@
+//! This module contains the ability to rebase an open [`ChangeSet`] onto the latest _head_.
+//!
+//! Rows in a [`ChangeSet`] shadow the rows on _head_ with the same id. When _head_ moves on (e.g.
+//! another [`ChangeSet`] was applied), values that were not touched in the [`ChangeSet`] are
+//! picked up automatically, but their dependent values are not recalculated and any value edited
+//! on both sides silently keeps the [`ChangeSet`] version. Rebasing reports those conflicts,
+//! resolves them as directed by the caller and then re-runs
+//! [`DependentValuesUpdate`](crate::DependentValuesUpdate) for everything that changed.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use telemetry::prelude::*;
+
+use crate::change_set::{ChangeSetConflict, ChangeSetError, ChangeSetResult};
+use crate::edge::EdgeId;
+use crate::{
+    AttributeValueId, ChangeSet, ChangeSetStatus, ComponentId, DalContext, DependentValuesUpdate,
+    HistoryEvent, Visibility,
+};
+
+const LIST_HEAD_ATTRIBUTE_VALUES: &str =
+    include_str!("../queries/change_set/rebase_list_head_attribute_values.sql");
+const LIST_HEAD_EDGES: &str = include_str!("../queries/change_set/rebase_list_head_edges.sql");
+const LIST_HEAD_COMPONENTS: &str =
+    include_str!("../queries/change_set/rebase_list_head_components.sql");
+
+/// Decides which side wins for a [`ChangeSetConflict`].
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Display, EnumString, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum RebaseResolution {
+    /// Keep the value written in the [`ChangeSet`].
+    Ours,
+    /// Drop the value written in the [`ChangeSet`], along with everything the [`ChangeSet`]
+    /// changed beneath it, in favor of the one on _head_.
+    Theirs,
+}
+
+/// Everything that changed on _head_ since the [`ChangeSet`] was created (or last rebased).
+/// Generated by [`ChangeSet::rebase_summary()`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RebaseSummary {
+    /// The point in time _head_ is compared against.
+    pub base: DateTime<Utc>,
+    pub attribute_values: Vec<AttributeValueId>,
+    pub components: Vec<ComponentId>,
+    pub edges: Vec<EdgeId>,
+    pub conflicts: Vec<ChangeSetConflict>,
+    /// The subset of [`Self::attribute_values`] that can still be seen from within the
+    /// [`ChangeSet`] (i.e. they have not been deleted on either side).
+    #[serde(skip)]
+    visible_attribute_values: Vec<AttributeValueId>,
+}
+
+impl ChangeSet {
+    /// The point in time from which changes on _head_ are considered "new" for this
+    /// [`ChangeSet`].
+    pub fn rebase_base(&self) -> DateTime<Utc> {
+        self.rebased_at.unwrap_or(self.timestamp.created_at)
+    }
+
+    /// Computes what changed on _head_ since the [`ChangeSet`] was created (or last rebased),
+    /// without modifying anything.
+    #[instrument(skip_all)]
+    pub async fn rebase_summary(&self, ctx: &DalContext) -> ChangeSetResult<RebaseSummary> {
+        let base = self.rebase_base();
+        let conflicts = self.list_conflicts(ctx).await?;
+        let txns = ctx.txns().await?;
+
+        let mut attribute_values = Vec::new();
+        let mut visible_attribute_values = Vec::new();
+        let rows = txns
+            .pg()
+            .query(
+                LIST_HEAD_ATTRIBUTE_VALUES,
+                &[&self.tenancy, &base, &self.pk],
+            )
+            .await?;
+        for row in rows {
+            let id: AttributeValueId = row.try_get("id")?;
+            let visible: bool = row.try_get("visible_in_change_set")?;
+            attribute_values.push(id);
+            if visible {
+                visible_attribute_values.push(id);
+            }
+        }
+
+        let mut edges = Vec::new();
+        for row in txns
+            .pg()
+            .query(LIST_HEAD_EDGES, &[&self.tenancy, &base])
+            .await?
+        {
+            edges.push(row.try_get("id")?);
+        }
+
+        let mut components = Vec::new();
+        for row in txns
+            .pg()
+            .query(LIST_HEAD_COMPONENTS, &[&self.tenancy, &base])
+            .await?
+        {
+            components.push(row.try_get("id")?);
+        }
+
+        Ok(RebaseSummary {
+            base,
+            attribute_values,
+            components,
+            edges,
+            conflicts,
+            visible_attribute_values,
+        })
+    }
+
+    /// Rebases the [`ChangeSet`] onto the latest _head_.
+    ///
+    /// Every [`ChangeSetConflict`] found must have an entry in `resolutions`, otherwise nothing is
+    /// modified and [`ChangeSetError::UnresolvedRebaseConflicts`] is returned. Once conflicts are
+    /// resolved, a [`DependentValuesUpdate`] is enqueued in the [`ChangeSet`] for every affected
+    /// [`AttributeValue`](crate::AttributeValue).
+    #[instrument(skip(ctx, resolutions))]
+    pub async fn rebase(
+        &mut self,
+        ctx: &DalContext,
+        resolutions: &HashMap<AttributeValueId, RebaseResolution>,
+    ) -> ChangeSetResult<RebaseSummary> {
+        if self.status != ChangeSetStatus::Open {
+            return Err(ChangeSetError::NotOpen(self.pk, self.status));
+        }
+
+        let summary = self.rebase_summary(ctx).await?;
+
+        let unresolved: Vec<AttributeValueId> = summary
+            .conflicts
+            .iter()
+            .map(|conflict| conflict.attribute_value_id)
+            .filter(|id| !resolutions.contains_key(id))
+            .collect();
+        if !unresolved.is_empty() {
+            return Err(ChangeSetError::UnresolvedRebaseConflicts(unresolved));
+        }
+
+        let mut affected: HashSet<AttributeValueId> =
+            summary.visible_attribute_values.iter().copied().collect();
+        for conflict in &summary.conflicts {
+            match resolutions.get(&conflict.attribute_value_id) {
+                Some(RebaseResolution::Theirs) => {
+                    ctx.txns()
+                        .await?
+                        .pg()
+                        .execute(
+                            "SELECT change_set_rebase_take_theirs_v1($1, $2, $3)",
+                            &[&self.pk, &conflict.attribute_value_id, &self.tenancy],
+                        )
+                        .await?;
+                }
+                // Our value stays, but it may still need to react to whatever moved around it.
+                Some(RebaseResolution::Ours) | None => {}
+            }
+            affected.insert(conflict.attribute_value_id);
+        }
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT timestamp_rebased_at FROM change_set_rebase_v1($1, $2)",
+                &[&self.pk, &self.tenancy],
+            )
+            .await?;
+        let rebased_at: DateTime<Utc> = row.try_get("timestamp_rebased_at")?;
+        self.rebased_at = Some(rebased_at);
+        self.timestamp.updated_at = rebased_at;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "change_set.rebase",
+            "Change Set rebased",
+            &serde_json::json![{
+                "pk": &self.pk,
+                "base": &summary.base,
+                "conflicts": &summary.conflicts,
+                "resolutions": resolutions,
+            }],
+        )
+        .await?;
+
+        if !affected.is_empty() {
+            let mut affected: Vec<AttributeValueId> = affected.into_iter().collect();
+            affected.sort();
+            ctx.enqueue_job(DependentValuesUpdate::new(
+                ctx.access_builder(),
+                Visibility::new_change_set(self.pk, false),
+                affected,
+            ))
+            .await?;
+        }
+
+        Ok(summary)
+    }
+}
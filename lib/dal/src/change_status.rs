@@ -134,6 +134,10 @@ pub struct ComponentChangeStatusGroup {
 }
 
 impl ComponentChangeStatusGroup {
+    pub fn component_name(&self) -> &str {
+        &self.component_name
+    }
+
     pub fn new_from_rows(
         rows: Vec<PgRow>,
         component_status: ChangeStatus,
@@ -139,11 +139,35 @@ impl ComponentView {
             for (_key, value) in object {
                 if let Some(raw_id) = value.as_str() {
                     let id = SecretId::from_str(raw_id)?;
-                    let decrypted_secret = EncryptedSecret::get_by_id(ctx, &id)
+                    let encrypted_secret = EncryptedSecret::get_by_id(ctx, &id)
                         .await?
-                        .ok_or(ComponentViewError::SecretNotFound(id))?
-                        .decrypt(ctx)
-                        .await?;
+                        .ok_or(ComponentViewError::SecretNotFound(id))?;
+
+                    // Secrets stored in an external backend are resolved by cyclone right before
+                    // executing, so only the reference is passed along. It is sealed for cyclone
+                    // so that nobody else can point it at another workspace's secrets
+                    if let Some(reference) = encrypted_secret.reference() {
+                        let reference = serde_json::json!({
+                            "backend": encrypted_secret.backend(),
+                            "workspacePk": encrypted_secret.tenancy().workspace_pk(),
+                            "reference": reference,
+                        });
+                        let sealed = ctx
+                            .encryption_key()
+                            .encrypt_and_encode(serde_json::to_string(&reference)?);
+                        *value = serde_json::json!({
+                            "name": encrypted_secret.name(),
+                            "object_type": encrypted_secret.object_type(),
+                            "secret_kind": encrypted_secret.kind(),
+                            "message": {
+                                "cycloneSecretReferenceMarker": true,
+                                "sealedReference": sealed,
+                            },
+                        });
+                        continue;
+                    }
+
+                    let decrypted_secret = encrypted_secret.decrypt(ctx).await?;
                     let encoded = ctx
                         .encryption_key()
                         .encrypt_and_encode(serde_json::to_string(&decrypted_secret.message())?);
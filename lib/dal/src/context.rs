@@ -14,7 +14,7 @@ use crate::{
         processor::{JobQueueProcessor, JobQueueProcessorError},
         producer::{BlockingJobError, BlockingJobResult, JobProducer},
     },
-    HistoryActor, StandardModel, Tenancy, TenancyError, Visibility,
+    HistoryActor, StandardModel, Tenancy, TenancyError, Visibility, DEFAULT_FIX_CONCURRENCY_LIMIT,
 };
 
 /// A context type which contains handles to common core service dependencies.
@@ -37,6 +37,8 @@ pub struct ServicesContext {
     pkgs_path: Option<PathBuf>,
     /// The URL of the module index
     module_index_url: Option<String>,
+    /// The maximum number of fixes in a batch that may run at the same time
+    fix_concurrency_limit: usize,
 }
 
 impl ServicesContext {
@@ -58,9 +60,17 @@ impl ServicesContext {
             encryption_key,
             pkgs_path,
             module_index_url,
+            fix_concurrency_limit: DEFAULT_FIX_CONCURRENCY_LIMIT,
         }
     }
 
+    /// Sets the maximum number of fixes in a batch that may run at the same time, which
+    /// otherwise defaults to [`DEFAULT_FIX_CONCURRENCY_LIMIT`].
+    pub fn with_fix_concurrency_limit(mut self, fix_concurrency_limit: usize) -> Self {
+        self.fix_concurrency_limit = fix_concurrency_limit.max(1);
+        self
+    }
+
     /// Consumes and returns [`DalContextBuilder`].
     pub fn into_builder(self, blocking: bool) -> DalContextBuilder {
         DalContextBuilder {
@@ -458,6 +468,11 @@ impl DalContext {
         self.services_context.module_index_url.as_deref()
     }
 
+    /// Gets the maximum number of fixes in a batch that may run at the same time
+    pub fn fix_concurrency_limit(&self) -> usize {
+        self.services_context.fix_concurrency_limit
+    }
+
     /// Determines if a standard model object matches the tenancy of the current context and
     /// is in the same visibility.
     pub async fn check_tenancy<T: StandardModel>(
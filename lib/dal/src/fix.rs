@@ -3,6 +3,7 @@
 use chrono::Utc;
 use postgres_types::{FromSql, ToSql};
 use serde::{Deserialize, Serialize};
+use si_data_nats::NatsError;
 use si_data_pg::PgError;
 use strum::{AsRefStr, Display, EnumIter, EnumString};
 use telemetry::prelude::*;
@@ -15,13 +16,16 @@ use crate::{
     func::backend::js_action::ActionRunResult, impl_standard_model, pk, standard_model,
     standard_model_accessor, standard_model_accessor_ro, standard_model_belongs_to, ActionKind,
     ActionPrototype, ActionPrototypeError, ActionPrototypeId, AttributeValueId, Component,
-    ComponentError, ComponentId, DalContext, FixBatch, FixResolverError, FuncError,
-    HistoryEventError, ResourceView, SchemaError, StandardModel, StandardModelError, Tenancy,
-    Timestamp, TransactionsError, Visibility, WsEvent, WsEventError, WsEventResult, WsPayload,
+    ComponentError, ComponentId, DalContext, EdgeError, FixBatch, FixResolverError, FuncError,
+    HistoryEvent, HistoryEventError, ResourceView, SchemaError, StandardModel, StandardModelError,
+    Tenancy, Timestamp, TransactionsError, Visibility, WsEvent, WsEventError, WsEventResult,
+    WsPayload,
 };
 use veritech_client::ResourceStatus;
 
 pub mod batch;
+pub mod dependency;
+pub mod plan;
 pub mod resolver;
 
 /// The completion status of a [`Fix`] or [`FixBatch`](crate::FixBatch).
@@ -44,12 +48,19 @@ pub mod resolver;
 #[serde(rename_all = "camelCase")]
 #[strum(serialize_all = "camelCase")]
 pub enum FixCompletionStatus {
+    /// The [`Fix`] or at least one [`Fix`] in the [`FixBatch`](crate::FixBatch) was cancelled
+    /// while executing.
+    Cancelled,
     /// The [`Fix`] or at least one [`Fix`] in the [`FixBatch`](crate::FixBatch) executed with
     /// error(s).
     Error,
     /// The [`Fix`] or at least one [`Fix`] in the [`FixBatch`](crate::FixBatch) was or may have
     /// been executed without error, but it or they were unsuccessful during execution.
     Failure,
+    /// The [`Fix`] was not executed because another [`Fix`] in the
+    /// [`FixBatch`](crate::FixBatch) did not succeed, as per the batch's
+    /// [`failure policy`](crate::FixBatchFailurePolicy).
+    Skipped,
     /// The [`Fix`] or all [`Fixes`](Fix) in the [`FixBatch`](crate::FixBatch) were executed and
     /// successful.
     Success,
@@ -73,8 +84,12 @@ pub enum FixError {
     BatchAlreadyFinished(FixId, FixBatchId),
     #[error("cannot set batch for {0}: fix batch ({1}) already started")]
     BatchAlreadyStarted(FixId, FixBatchId),
+    #[error("cannot cancel fix {0} since it already finished")]
+    CancelFinished(FixId),
     #[error(transparent)]
     Component(#[from] ComponentError),
+    #[error(transparent)]
+    Edge(#[from] EdgeError),
     #[error("completion status is empty")]
     EmptyCompletionStatus,
     #[error(transparent)]
@@ -87,6 +102,10 @@ pub enum FixError {
     HistoryEvent(#[from] HistoryEventError),
     #[error("action run status cannot be converted to fix completion status")]
     IncompatibleActionRunStatus,
+    #[error("action prototype not found for id: {0}")]
+    MissingActionPrototype(ActionPrototypeId),
+    #[error("component not found for id: {0}")]
+    MissingComponent(ComponentId),
     #[error("missing finished timestamp for fix: {0}")]
     MissingFinishedTimestampForFix(FixId),
     #[error("fix not found for id: {0}")]
@@ -95,6 +114,8 @@ pub enum FixError {
     MissingFixBatch(FixBatchId),
     #[error("missing started timestamp for fix: {0}")]
     MissingStartedTimestampForFix(FixId),
+    #[error(transparent)]
+    Nats(#[from] NatsError),
     #[error("no fixes in batch: fix batch is empty")]
     NoFixesInBatch(FixBatchId),
     #[error("cannot stamp batch or fix as finished since it has not yet been started")]
@@ -110,6 +131,8 @@ pub enum FixError {
     #[error(transparent)]
     Transactions(#[from] TransactionsError),
     #[error(transparent)]
+    VeritechClient(#[from] veritech_client::ClientError),
+    #[error(transparent)]
     WsEvent(#[from] WsEventError),
 }
 
@@ -157,6 +180,10 @@ pub struct Fix {
 
     /// Contains a message related to the completion.
     completion_message: Option<String>,
+    // TODO(nick): convert to Option<DateTime<Utc>> once standard model accessor can accommodate both
+    // Option<T<U>> and can handle "timestamp with time zone <--> DateTime<Utc>".
+    /// Indicates when cancelling the [`Fix`] was requested when populated.
+    cancel_requested_at: Option<String>,
 }
 
 impl_standard_model! {
@@ -211,6 +238,7 @@ impl Fix {
         FixResult
     );
     standard_model_accessor!(completion_message, Option<String>, FixResult);
+    standard_model_accessor!(cancel_requested_at, Option<String>, FixResult);
     standard_model_accessor!(resource, OptionJson<JsonValue>, FixResult);
 
     standard_model_belongs_to!(
@@ -249,8 +277,24 @@ impl Fix {
         // Stamp started and run the workflow.
         self.stamp_started(ctx).await?;
 
+        // Nothing was listening for the cancellation before the fix started, so it's honored here.
+        if self.cancel_requested_at.is_some() {
+            info!(fix_id = %self.id, "fix was cancelled before it started");
+            self.stamp_finished(
+                ctx,
+                FixCompletionStatus::Cancelled,
+                Some("Fix was cancelled before it started".into()),
+                None,
+            )
+            .await?;
+            return Ok(None);
+        }
+
         Ok(
-            match action_prototype.run(ctx, self.component_id, false).await {
+            match action_prototype
+                .run_with_execution_id(ctx, self.component_id, false, Some(self.execution_id()))
+                .await
+            {
                 Ok(Some(run_result)) => {
                     let completion_status = match run_result.status {
                         ResourceStatus::Ok | ResourceStatus::Warning => {
@@ -281,6 +325,18 @@ impl Fix {
 
                     None
                 }
+                Err(e) if e.is_cancelled() => {
+                    info!(fix_id = %self.id, "fix was cancelled");
+                    self.stamp_finished(
+                        ctx,
+                        FixCompletionStatus::Cancelled,
+                        Some("Fix was cancelled".into()),
+                        None,
+                    )
+                    .await?;
+
+                    None
+                }
                 Err(e) => {
                     error!("Unable to run fix: {e}");
                     self.stamp_finished(
@@ -297,6 +353,45 @@ impl Fix {
         )
     }
 
+    /// The id the [`Fix's`](Self) action is executed with in veritech.
+    pub fn execution_id(&self) -> String {
+        self.id.to_string()
+    }
+
+    /// Cancels the [`Fix`]. The job running the [`Fix`] then stamps it as
+    /// [`FixCompletionStatus::Cancelled`].
+    ///
+    /// The request is recorded on the [`Fix`], so that it never starts if it hasn't yet (e.g. it
+    /// is still waiting for an earlier [`Fix`] in its [`FixBatch`](crate::FixBatch)), and veritech
+    /// is asked to stop its action on commit in case it is already executing.
+    pub async fn cancel(&mut self, ctx: &DalContext) -> FixResult<()> {
+        if self.finished_at.is_some() {
+            return Err(FixError::CancelFinished(self.id));
+        }
+
+        self.set_cancel_requested_at(ctx, Some(Utc::now().to_rfc3339()))
+            .await?;
+        ctx.txns()
+            .await?
+            .nats()
+            .publish(
+                ctx.veritech()
+                    .cancel_execution_subject(&self.execution_id()),
+                &serde_json::Value::Null,
+            )
+            .await?;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "fix.cancel",
+            "Fix cancellation requested",
+            &serde_json::json![{ "id": &self.id }],
+        )
+        .await?;
+
+        Ok(())
+    }
+
     /// A safe wrapper around setting completion-related columns.
     pub async fn stamp_finished(
         &mut self,
@@ -338,6 +433,49 @@ impl Fix {
         }
     }
 
+    /// Stamps a [`Fix`] that will never be started as [`FixCompletionStatus::Skipped`].
+    pub async fn stamp_skipped(
+        &mut self,
+        ctx: &DalContext,
+        completion_message: impl Into<String>,
+    ) -> FixResult<()> {
+        if self.started_at.is_some() {
+            Err(FixError::AlreadyStarted)
+        } else if self.finished_at.is_some() {
+            Err(FixError::AlreadyFinished)
+        } else {
+            self.set_finished_at(ctx, Some(Utc::now().to_rfc3339()))
+                .await?;
+            self.set_completion_status(ctx, Some(FixCompletionStatus::Skipped))
+                .await?;
+            self.set_completion_message(ctx, Some(completion_message.into()))
+                .await?;
+            Ok(())
+        }
+    }
+
+    /// Stamps a [`Fix`] whose run errored before it could record how it went as
+    /// [`FixCompletionStatus::Error`], whether or not it got to start.
+    pub async fn stamp_errored(
+        &mut self,
+        ctx: &DalContext,
+        completion_message: impl Into<String>,
+    ) -> FixResult<()> {
+        if self.finished_at.is_some() {
+            return Err(FixError::AlreadyFinished);
+        }
+        if self.started_at.is_none() {
+            self.stamp_started(ctx).await?;
+        }
+        self.stamp_finished(
+            ctx,
+            FixCompletionStatus::Error,
+            Some(completion_message.into()),
+            None,
+        )
+        .await
+    }
+
     /// Generates a [`FixHistoryView`] based on [`self`](Fix).
     pub async fn history_view(
         &self,
@@ -367,7 +505,12 @@ impl Fix {
 
         Ok(Some(FixHistoryView {
             id: self.id,
-            status: if resource.is_none() {
+            status: if let Some(
+                status @ (FixCompletionStatus::Cancelled | FixCompletionStatus::Skipped),
+            ) = self.completion_status()
+            {
+                *status
+            } else if resource.is_none() {
                 FixCompletionStatus::Unstarted
             } else {
                 self.completion_status()
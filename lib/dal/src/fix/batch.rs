@@ -3,6 +3,7 @@
 
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumIter, EnumString};
 use telemetry::prelude::*;
 
 use crate::{
@@ -15,6 +16,36 @@ use crate::{
 pk!(FixBatchPk);
 pk!(FixBatchId);
 
+/// What happens to the remaining [`Fixes`](crate::Fix) of a [`FixBatch`] when one of them does
+/// not succeed.
+#[remain::sorted]
+#[derive(
+    Deserialize,
+    Serialize,
+    AsRefStr,
+    Display,
+    EnumIter,
+    EnumString,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum FixBatchFailurePolicy {
+    /// Skip every [`Fix`](crate::Fix) that has not started yet.
+    AbortRemaining,
+    /// Run every [`Fix`](crate::Fix) regardless.
+    #[default]
+    ContinueAll,
+    /// Skip the [`Fixes`](crate::Fix) that depend on the one that did not succeed, directly or
+    /// not, and run the rest.
+    SkipDependents,
+}
+
 /// A batch of [`Fixs`](crate::Fix). Every [`Fix`](crate::Fix)
 /// must belong at one and only one [`batch`](Self).
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
@@ -41,6 +72,8 @@ pub struct FixBatch {
     finished_at: Option<String>,
     /// Indicates the state of the [`FixBatch`] when finished.
     completion_status: Option<FixCompletionStatus>,
+    /// Indicates what happens to the remaining [`Fixes`](crate::Fix) when one does not succeed.
+    failure_policy: FixBatchFailurePolicy,
 }
 
 impl_standard_model! {
@@ -76,6 +109,7 @@ impl FixBatch {
         Option<Enum(FixCompletionStatus)>,
         FixResult
     );
+    standard_model_accessor!(failure_policy, Enum(FixBatchFailurePolicy), FixResult);
 
     // TODO(nick): store the order (and what's sequential, conditional, parallel, etc.) someday.
     standard_model_has_many!(
@@ -99,10 +133,17 @@ impl FixBatch {
                     .completion_status()
                     .ok_or(FixError::EmptyCompletionStatus)?
                 {
-                    FixCompletionStatus::Success => {}
+                    // Skipped fixes are the consequence of another fix not succeeding.
+                    FixCompletionStatus::Success | FixCompletionStatus::Skipped => {}
                     FixCompletionStatus::Failure => {
                         // If we see failures, we should still continue to see if there's an error.
-                        batch_completion_status = FixCompletionStatus::Failure
+                        if batch_completion_status != FixCompletionStatus::Cancelled {
+                            batch_completion_status = FixCompletionStatus::Failure
+                        }
+                    }
+                    FixCompletionStatus::Cancelled => {
+                        // Cancellations take precedence over failures, but not over errors.
+                        batch_completion_status = FixCompletionStatus::Cancelled
                     }
                     FixCompletionStatus::Error | FixCompletionStatus::Unstarted => {
                         // Only break on an error since errors take precedence over failures.
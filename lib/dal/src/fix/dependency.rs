@@ -0,0 +1,95 @@
+//! This module contains the ordering rules between the [`Fixes`](crate::Fix) of a
+//! [`FixBatch`](crate::FixBatch).
+
+use std::collections::{HashMap, HashSet};
+
+use crate::fix::FixResult;
+use crate::{ActionKind, ComponentId, DalContext, Edge};
+
+/// Computes which actions of a [`FixBatch`](crate::FixBatch) have to finish before each of them
+/// can start, given the [`Component`](crate::Component) and [`ActionKind`] of every action in the
+/// order they were requested. The result has one entry per action, holding the indices of the
+/// actions it depends on.
+///
+/// The rules are:
+///
+/// - actions on the same [`Component`](crate::Component) run in the order they were requested
+/// - non-delete actions run after the non-delete actions of the components they receive
+///   configuration from, directly or not (i.e. "create" before "dependent")
+/// - delete actions run after the delete actions of the components that receive configuration
+///   from them (i.e. "delete" after "dependent")
+///
+/// Components that (indirectly) receive configuration from each other fall back to the requested
+/// order, so the result never contains a cycle.
+pub async fn fix_dependencies(
+    ctx: &DalContext,
+    actions: &[(ComponentId, ActionKind)],
+) -> FixResult<Vec<Vec<usize>>> {
+    // Components being deleted still need their edges taken into account.
+    let ctx_with_deleted = &ctx.clone_with_delete_visibility();
+
+    let mut ancestors_by_component: HashMap<ComponentId, HashSet<ComponentId>> = HashMap::new();
+    for (component_id, _) in actions {
+        if !ancestors_by_component.contains_key(component_id) {
+            let ancestors = ancestors(ctx_with_deleted, *component_id).await?;
+            ancestors_by_component.insert(*component_id, ancestors);
+        }
+    }
+    let is_ancestor = |ancestor: ComponentId, component: ComponentId| {
+        ancestors_by_component
+            .get(&component)
+            .map(|ancestors| ancestors.contains(&ancestor))
+            .unwrap_or(false)
+    };
+
+    let mut dependencies = Vec::with_capacity(actions.len());
+    for (index, (component_id, kind)) in actions.iter().enumerate() {
+        let is_delete = *kind == ActionKind::Delete;
+        let mut action_dependencies = Vec::new();
+
+        for (other_index, (other_component_id, other_kind)) in actions.iter().enumerate() {
+            if other_index == index || is_delete != (*other_kind == ActionKind::Delete) {
+                if other_component_id == component_id && other_index < index {
+                    action_dependencies.push(other_index);
+                }
+                continue;
+            }
+
+            let (upstream, downstream) = if is_delete {
+                (*component_id, *other_component_id)
+            } else {
+                (*other_component_id, *component_id)
+            };
+            let forward = is_ancestor(upstream, downstream);
+            let backward = is_ancestor(downstream, upstream);
+            let depends = if other_component_id == component_id || (forward && backward) {
+                other_index < index
+            } else {
+                forward
+            };
+            if depends {
+                action_dependencies.push(other_index);
+            }
+        }
+
+        dependencies.push(action_dependencies);
+    }
+
+    Ok(dependencies)
+}
+
+/// Every [`Component`](crate::Component) the given one receives configuration from, directly or
+/// not.
+async fn ancestors(ctx: &DalContext, component_id: ComponentId) -> FixResult<HashSet<ComponentId>> {
+    let mut ancestors = HashSet::new();
+    let mut work_queue = vec![component_id];
+    while let Some(current) = work_queue.pop() {
+        for parent in Edge::list_parents_for_component(ctx, current).await? {
+            if ancestors.insert(parent) {
+                work_queue.push(parent);
+            }
+        }
+    }
+    ancestors.remove(&component_id);
+    Ok(ancestors)
+}
@@ -0,0 +1,102 @@
+//! This module contains the ability to preview the actions a [`FixBatch`](crate::FixBatch) would
+//! run, without running anything.
+
+use std::collections::{BTreeSet, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::fix::dependency::fix_dependencies;
+use crate::fix::{FixError, FixResult};
+use crate::{
+    ActionKind, ActionPrototype, ActionPrototypeId, AttributeValueId, Component, ComponentId,
+    DalContext, StandardModel,
+};
+
+/// An action that was selected to run as part of a [`FixBatch`](crate::FixBatch).
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FixPlanRequest {
+    pub attribute_value_id: AttributeValueId,
+    pub component_id: ComponentId,
+    pub action_prototype_id: ActionPrototypeId,
+}
+
+/// A single action of a [`FixPlan`], in the order it would run.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FixPlanStep {
+    pub attribute_value_id: AttributeValueId,
+    pub component_id: ComponentId,
+    pub component_name: String,
+    pub action_prototype_id: ActionPrototypeId,
+    pub action_kind: ActionKind,
+    /// The positions in the plan of the steps that have to finish before this one can start.
+    pub depends_on: Vec<usize>,
+}
+
+/// The actions a [`FixBatch`](crate::FixBatch) would run, in an order that respects the
+/// dependencies between them. Steps that don't depend on each other may run concurrently.
+pub type FixPlan = Vec<FixPlanStep>;
+
+/// Builds the [`FixPlan`] for the given requests without running anything. Steps that could run
+/// at the same time keep the order they were requested in.
+pub async fn plan(ctx: &DalContext, requests: &[FixPlanRequest]) -> FixResult<FixPlan> {
+    let mut actions = Vec::with_capacity(requests.len());
+    for request in requests {
+        let action = ActionPrototype::get_by_id(ctx, &request.action_prototype_id)
+            .await?
+            .ok_or(FixError::MissingActionPrototype(
+                request.action_prototype_id,
+            ))?;
+        actions.push((request.component_id, *action.kind()));
+    }
+    let dependencies = fix_dependencies(ctx, &actions).await?;
+
+    // Order the requests topologically, picking the earliest requested one that's ready.
+    let mut order = Vec::with_capacity(requests.len());
+    let mut position_by_index = vec![0; requests.len()];
+    let mut done = HashSet::new();
+    let mut ready: BTreeSet<usize> = (0..requests.len())
+        .filter(|index| dependencies[*index].is_empty())
+        .collect();
+    while let Some(index) = ready.iter().next().copied() {
+        ready.remove(&index);
+        position_by_index[index] = order.len();
+        order.push(index);
+        done.insert(index);
+        for (candidate, deps) in dependencies.iter().enumerate() {
+            if !done.contains(&candidate)
+                && deps.contains(&index)
+                && deps.iter().all(|dep| done.contains(dep))
+            {
+                ready.insert(candidate);
+            }
+        }
+    }
+
+    let ctx_with_deleted = &ctx.clone_with_delete_visibility();
+    let mut steps = Vec::with_capacity(order.len());
+    for index in order {
+        let request = requests[index];
+        let component = Component::get_by_id(ctx_with_deleted, &request.component_id)
+            .await?
+            .ok_or(FixError::MissingComponent(request.component_id))?;
+
+        let mut depends_on: Vec<usize> = dependencies[index]
+            .iter()
+            .map(|dep| position_by_index[*dep])
+            .collect();
+        depends_on.sort_unstable();
+
+        steps.push(FixPlanStep {
+            attribute_value_id: request.attribute_value_id,
+            component_id: request.component_id,
+            component_name: component.name(ctx_with_deleted).await?,
+            action_prototype_id: request.action_prototype_id,
+            action_kind: actions[index].1,
+            depends_on,
+        });
+    }
+
+    Ok(steps)
+}
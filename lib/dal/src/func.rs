@@ -107,6 +107,9 @@ pub struct Func {
     handler: Option<String>,
     code_base64: Option<String>,
     code_sha256: String,
+    /// Overrides how long an execution may run, in seconds, before it is terminated.
+    #[serde(default)]
+    timeout_secs: Option<i64>,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -180,6 +183,7 @@ impl Func {
         new_func.set_builtin(ctx, self.builtin).await?;
         new_func.set_handler(ctx, self.handler()).await?;
         new_func.set_code_base64(ctx, self.code_base64()).await?;
+        new_func.set_timeout_secs(ctx, self.timeout_secs).await?;
 
         Ok(new_func)
     }
@@ -258,4 +262,5 @@ impl Func {
     standard_model_accessor!(handler, Option<String>, FuncResult);
     standard_model_accessor!(code_base64, Option<String>, FuncResult);
     standard_model_accessor_ro!(code_sha256, String);
+    standard_model_accessor!(timeout_secs, OptionBigInt<i64>, FuncResult);
 }
@@ -181,6 +181,11 @@ impl ToLabelList for FuncBackendKind {}
 pub struct FuncDispatchContext {
     pub veritech: VeritechClient,
     pub output_tx: mpsc::Sender<OutputStream>,
+    /// The id to tag the execution with in veritech, when the caller needs to refer to it while
+    /// it runs (e.g. to cancel it).
+    pub execution_id: Option<String>,
+    /// Overrides the execution timeout configured in cyclone, see [`Func::timeout_secs()`].
+    pub timeout_secs: Option<u64>,
 }
 
 impl FuncDispatchContext {
@@ -190,11 +195,18 @@ impl FuncDispatchContext {
             Self {
                 veritech: ctx.veritech().clone(),
                 output_tx,
+                execution_id: None,
+                timeout_secs: None,
             },
             rx,
         )
     }
 
+    pub fn with_execution_id(mut self, execution_id: Option<String>) -> Self {
+        self.execution_id = execution_id;
+        self
+    }
+
     pub fn into_inner(self) -> (VeritechClient, mpsc::Sender<OutputStream>) {
         (self.veritech, self.output_tx)
     }
@@ -231,6 +243,12 @@ pub trait FuncDispatch: std::fmt::Debug {
         let handler = func
             .handler()
             .ok_or_else(|| FuncBackendError::DispatchMissingHandler(*func.id()))?;
+        let context = FuncDispatchContext {
+            timeout_secs: func
+                .timeout_secs()
+                .and_then(|secs| u64::try_from(*secs).ok()),
+            ..context
+        };
         let value = Self::new(context, code_base64, handler, args);
         Ok(value)
     }
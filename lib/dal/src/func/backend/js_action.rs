@@ -31,12 +31,16 @@ impl FuncDispatch for FuncBackendJsAction {
         args: Self::Args,
     ) -> Box<Self> {
         let request = ActionRunRequest {
-            // Once we start tracking the state of these executions, then this id will be useful,
-            // but for now it's passed along and back, and is opaue
-            execution_id: "ayrtonsennajscommand".to_string(),
+            // The id is only meaningful when the caller asked for one (e.g. so that a running fix
+            // can be cancelled), otherwise it's passed along and back, and is opaque
+            execution_id: context
+                .execution_id
+                .clone()
+                .unwrap_or_else(|| "ayrtonsennajscommand".to_string()),
             handler: handler.into(),
             code_base64: code_base64.into(),
             args: serde_json::to_value(args).unwrap(),
+            timeout_secs: context.timeout_secs,
         };
 
         Box::new(Self { context, request })
@@ -38,6 +38,7 @@ impl FuncDispatch for FuncBackendJsAttribute {
             component: args.component,
             response_type: args.response_type,
             code_base64: code_base64.into(),
+            timeout_secs: context.timeout_secs,
         };
 
         Box::new(Self { context, request })
@@ -179,13 +179,26 @@ impl FuncBinding {
         ctx: &DalContext,
         args: serde_json::Value,
         func_id: FuncId,
+    ) -> FuncBindingResult<(Self, FuncBindingReturnValue)> {
+        Self::create_and_execute_with_execution_id(ctx, args, func_id, None).await
+    }
+
+    /// Like [`Self::create_and_execute()`], but tags the execution in veritech with the given id
+    /// (see [`Self::execute_with_execution_id()`]).
+    pub async fn create_and_execute_with_execution_id(
+        ctx: &DalContext,
+        args: serde_json::Value,
+        func_id: FuncId,
+        execution_id: Option<String>,
     ) -> FuncBindingResult<(Self, FuncBindingReturnValue)> {
         let func = Func::get_by_id(ctx, &func_id)
             .await?
             .ok_or(FuncError::NotFound(func_id))?;
         let func_binding = Self::new(ctx, args, func_id, func.backend_kind).await?;
 
-        let func_binding_return_value: FuncBindingReturnValue = func_binding.execute(ctx).await?;
+        let func_binding_return_value: FuncBindingReturnValue = func_binding
+            .execute_with_execution_id(ctx, execution_id)
+            .await?;
 
         Ok((func_binding, func_binding_return_value))
     }
@@ -206,7 +219,20 @@ impl FuncBinding {
 
     // For a given [`FuncBinding`](Self), execute using veritech.
     pub async fn execute(&self, ctx: &DalContext) -> FuncBindingResult<FuncBindingReturnValue> {
+        self.execute_with_execution_id(ctx, None).await
+    }
+
+    /// Executes using veritech, tagging the execution with the given id so that it can be
+    /// referred to while it runs (e.g. with
+    /// [`VeritechClient::cancel_execution()`](veritech_client::Client::cancel_execution)).
+    /// Currently only honored by [`FuncBackendKind::JsAction`].
+    pub async fn execute_with_execution_id(
+        &self,
+        ctx: &DalContext,
+        execution_id: Option<String>,
+    ) -> FuncBindingResult<FuncBindingReturnValue> {
         let (func, execution, context, mut rx) = self.prepare_execution(ctx).await?;
+        let context = context.with_execution_id(execution_id);
         let value = self.execute_critical_section(func.clone(), context).await?;
 
         let mut output = Vec::new();
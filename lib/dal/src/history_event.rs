@@ -1,4 +1,5 @@
 use crate::{Tenancy, TransactionsError};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use strum::Display as StrumDisplay;
 use thiserror::Error;
@@ -7,7 +8,11 @@ use si_data_nats::NatsError;
 use si_data_pg::PgError;
 use telemetry::prelude::*;
 
-use crate::{pk, DalContext, Timestamp, UserPk};
+use crate::{pk, ChangeSetPk, ComponentId, DalContext, Timestamp, UserPk};
+
+const LIST: &str = include_str!("queries/history_event/list.sql");
+const LIST_BEFORE: &str = include_str!("queries/history_event/list_before.sql");
+const COUNT: &str = include_str!("queries/history_event/count.sql");
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -58,6 +63,8 @@ pub struct HistoryEvent {
     pub actor: HistoryActor,
     pub message: String,
     pub data: serde_json::Value,
+    pub change_set_pk: Option<ChangeSetPk>,
+    pub component_id: Option<ComponentId>,
     #[serde(flatten)]
     pub tenancy: Tenancy,
     #[serde(flatten)]
@@ -75,12 +82,21 @@ impl HistoryEvent {
         let label = label.as_ref();
         let message = message.as_ref();
         let actor = serde_json::to_value(ctx.history_actor())?;
+        let component_id = component_id_from_data(label, data);
         let txns = ctx.txns().await?;
         let row = txns
             .pg()
             .query_one(
-                "SELECT object FROM history_event_create_v1($1, $2, $3, $4, $5)",
-                &[&label.to_string(), &actor, &message, &data, ctx.tenancy()],
+                "SELECT object FROM history_event_create_v1($1, $2, $3, $4, $5, $6, $7)",
+                &[
+                    &label.to_string(),
+                    &actor,
+                    &message,
+                    &data,
+                    ctx.tenancy(),
+                    &ctx.visibility().change_set_pk,
+                    &component_id,
+                ],
             )
             .await?;
         let json: serde_json::Value = row.try_get("object")?;
@@ -89,4 +105,142 @@ impl HistoryEvent {
         let object: HistoryEvent = serde_json::from_value(json)?;
         Ok(object)
     }
+
+    /// Lists the events of the [`DalContext`]'s tenancy matching the filter, most recent first.
+    ///
+    /// Skips the first `offset` events and returns at most `limit` events, or all of them if no
+    /// limit is given.
+    #[instrument(skip(ctx))]
+    pub async fn list(
+        ctx: &DalContext,
+        filter: &HistoryEventFilter,
+        offset: i64,
+        limit: Option<i64>,
+    ) -> HistoryEventResult<Vec<HistoryEvent>> {
+        let actor = filter.actor.map(serde_json::to_value).transpose()?;
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                LIST,
+                &[
+                    ctx.tenancy(),
+                    &actor,
+                    &filter.label_prefix,
+                    &filter.since,
+                    &filter.until,
+                    &filter.change_set_pk,
+                    &filter.component_id,
+                    &limit,
+                    &offset,
+                ],
+            )
+            .await?;
+
+        let mut events = Vec::with_capacity(rows.len());
+        for row in rows {
+            let json: serde_json::Value = row.try_get("object")?;
+            events.push(serde_json::from_value(json)?);
+        }
+        Ok(events)
+    }
+
+    /// Lists at most `limit` events of the [`DalContext`]'s tenancy matching the filter, most
+    /// recent first, starting right after the `before` event in that order.
+    ///
+    /// Unlike [`Self::list`], passing the last event of a page to fetch the next one costs the
+    /// same however deep the page is.
+    #[instrument(skip(ctx, before))]
+    pub async fn list_before(
+        ctx: &DalContext,
+        filter: &HistoryEventFilter,
+        before: Option<&HistoryEvent>,
+        limit: i64,
+    ) -> HistoryEventResult<Vec<HistoryEvent>> {
+        let actor = filter.actor.map(serde_json::to_value).transpose()?;
+        let before_created_at = before.map(|event| event.timestamp.created_at);
+        let before_pk = before.map(|event| event.pk);
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                LIST_BEFORE,
+                &[
+                    ctx.tenancy(),
+                    &actor,
+                    &filter.label_prefix,
+                    &filter.since,
+                    &filter.until,
+                    &filter.change_set_pk,
+                    &filter.component_id,
+                    &before_created_at,
+                    &before_pk,
+                    &limit,
+                ],
+            )
+            .await?;
+
+        let mut events = Vec::with_capacity(rows.len());
+        for row in rows {
+            let json: serde_json::Value = row.try_get("object")?;
+            events.push(serde_json::from_value(json)?);
+        }
+        Ok(events)
+    }
+
+    /// Counts the events of the [`DalContext`]'s tenancy matching the filter.
+    #[instrument(skip(ctx))]
+    pub async fn count(ctx: &DalContext, filter: &HistoryEventFilter) -> HistoryEventResult<i64> {
+        let actor = filter.actor.map(serde_json::to_value).transpose()?;
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                COUNT,
+                &[
+                    ctx.tenancy(),
+                    &actor,
+                    &filter.label_prefix,
+                    &filter.since,
+                    &filter.until,
+                    &filter.change_set_pk,
+                    &filter.component_id,
+                ],
+            )
+            .await?;
+        Ok(row.try_get("count")?)
+    }
+}
+
+/// Narrows down the [`HistoryEvents`](HistoryEvent) returned by [`HistoryEvent::list`]. Every
+/// criteria left empty matches all events.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEventFilter {
+    /// Only events recorded by this actor
+    pub actor: Option<HistoryActor>,
+    /// Only events whose label starts with this prefix, i.e. `component.` or `secret.`
+    pub label_prefix: Option<String>,
+    /// Only events recorded at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only events recorded before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Only events recorded in this change set
+    pub change_set_pk: Option<ChangeSetPk>,
+    /// Only events about this component
+    pub component_id: Option<ComponentId>,
+}
+
+/// Returns the [`Component`](crate::Component) an event is about: the `component_id` of its data,
+/// or the `id` of the events recorded by components themselves.
+fn component_id_from_data(label: &str, data: &serde_json::Value) -> Option<ComponentId> {
+    let component_id = match data.get("component_id") {
+        Some(component_id) => component_id,
+        None if label.starts_with("component.") => data.get("id")?,
+        None => return None,
+    };
+    serde_json::from_value(component_id.clone()).ok()
 }
@@ -5,9 +5,9 @@ use telemetry::prelude::*;
 use thiserror::Error;
 
 use crate::{
-    impl_standard_model, pk, standard_model, standard_model_accessor, DalContext,
-    HistoryEventError, StandardModel, StandardModelError, Tenancy, Timestamp, TransactionsError,
-    Visibility,
+    impl_standard_model, pk, standard_model, standard_model_accessor, standard_model_accessor_ro,
+    DalContext, HistoryEventError, StandardModel, StandardModelError, Tenancy, Timestamp,
+    TransactionsError, Visibility,
 };
 
 pub mod asset;
@@ -54,6 +54,10 @@ pub struct InstalledPkg {
     id: InstalledPkgId,
     name: String,
     root_hash: String,
+    /// The name from the package metadata, unknown for packages installed before it was kept.
+    pkg_name: Option<String>,
+    /// The version from the package metadata, unknown for packages installed before it was kept.
+    pkg_version: Option<String>,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -77,16 +81,27 @@ impl InstalledPkg {
         ctx: &DalContext,
         name: impl AsRef<str>,
         root_hash: impl AsRef<str>,
+        pkg_name: impl AsRef<str>,
+        pkg_version: impl AsRef<str>,
     ) -> InstalledPkgResult<Self> {
         let name = name.as_ref();
         let root_hash = root_hash.as_ref();
+        let pkg_name = pkg_name.as_ref();
+        let pkg_version = pkg_version.as_ref();
         let row = ctx
             .txns()
             .await?
             .pg()
             .query_one(
-                "SELECT object FROM installed_pkg_create_v1($1, $2, $3, $4)",
-                &[ctx.tenancy(), ctx.visibility(), &name, &root_hash],
+                "SELECT object FROM installed_pkg_create_v1($1, $2, $3, $4, $5, $6)",
+                &[
+                    ctx.tenancy(),
+                    ctx.visibility(),
+                    &name,
+                    &root_hash,
+                    &pkg_name,
+                    &pkg_version,
+                ],
             )
             .await?;
         let object = standard_model::finish_create_from_row(ctx, row).await?;
@@ -95,8 +110,17 @@ impl InstalledPkg {
 
     standard_model_accessor!(name, String, InstalledPkgResult);
     standard_model_accessor!(root_hash, String, InstalledPkgResult);
+    standard_model_accessor_ro!(pkg_name, Option<String>);
+    standard_model_accessor_ro!(pkg_version, Option<String>);
 
     pub async fn find_by_hash(ctx: &DalContext, hash: &str) -> InstalledPkgResult<Option<Self>> {
         Ok(Self::find_by_attr(ctx, "root_hash", &hash).await?.pop())
     }
+
+    pub async fn list_for_pkg_name(
+        ctx: &DalContext,
+        pkg_name: &str,
+    ) -> InstalledPkgResult<Vec<Self>> {
+        Ok(Self::find_by_attr(ctx, "pkg_name", &pkg_name).await?)
+    }
 }
@@ -78,6 +78,34 @@ pub enum JobConsumerError {
     WsEvent(#[from] WsEventError),
 }
 
+impl JobConsumerError {
+    /// Whether the error was caused by a service being unavailable or slow to respond (e.g. NATS,
+    /// Postgres or veritech), in which case running the job again may succeed.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Council(_)
+            | Self::Io(_)
+            | Self::Nats(_)
+            | Self::NatsUnavailable
+            | Self::PgPool(_) => true,
+            _ => {
+                let mut source = std::error::Error::source(self);
+                while let Some(err) = source {
+                    if err.is::<NatsError>()
+                        || err.is::<PgPoolError>()
+                        || err.is::<veritech_client::ClientError>()
+                        || err.is::<std::io::Error>()
+                    {
+                        return true;
+                    }
+                    source = err.source();
+                }
+                false
+            }
+        }
+    }
+}
+
 impl From<JobConsumerError> for std::io::Error {
     fn from(jce: JobConsumerError) -> Self {
         Self::new(std::io::ErrorKind::InvalidData, jce)
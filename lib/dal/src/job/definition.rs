@@ -2,6 +2,6 @@ mod dependent_values_update;
 mod fix;
 mod refresh;
 
-pub use dependent_values_update::DependentValuesUpdate;
-pub use fix::{FixItem, FixesJob};
+pub use dependent_values_update::{council_state_to_dot, DependentValuesUpdate};
+pub use fix::{FixItem, FixesJob, DEFAULT_FIX_CONCURRENCY_LIMIT};
 pub use refresh::RefreshJob;
@@ -92,12 +92,7 @@ impl JobConsumer for DependentValuesUpdate {
         )
     )]
     async fn run(&self, ctx: &mut DalContext) -> JobConsumerResult<()> {
-        let council_subject =
-            if let Some(subject_prefix) = ctx.nats_conn().metadata().subject_prefix() {
-                format!("{subject_prefix}.council")
-            } else {
-                "council".to_string()
-            };
+        let council_subject = council_subject(ctx);
         let jid = council_server::Id::from_string(&self.job_id().unwrap())?;
         let mut council = council_server::Client::new(
             ctx.nats_conn().clone(),
@@ -209,6 +204,7 @@ impl DependentValuesUpdate {
         ctx.rollback().await?;
 
         let mut update_tasks = JoinSet::new();
+        let mut node_progress = NodeProgress::default();
 
         while !dependency_graph.is_empty() {
             match council.fetch_response().await? {
@@ -217,13 +213,33 @@ impl DependentValuesUpdate {
                         debug!(?node_ids, job_id = ?self.job_id(), "Ok to start processing nodes");
                         for node_id in node_ids {
                             let id = AttributeValueId::from(node_id);
+                            match node_progress.ok_to_process(id) {
+                                NodeAssignment::Process => {}
+                                NodeAssignment::AlreadyProcessing => {
+                                    debug!(?id, job_id = ?self.job_id(), "Already processing node");
+                                    continue;
+                                }
+                                // Council may have lost the outcome while it was down, and won't
+                                // tell anyone the node is done until it gets it again.
+                                NodeAssignment::AlreadyProcessed { succeeded } => {
+                                    warn!(?id, job_id = ?self.job_id(), "Told to process a node we've already processed");
+                                    if succeeded {
+                                        pub_council.processed_value(node_id).await?;
+                                    } else {
+                                        pub_council.failed_processing_value(node_id).await?;
+                                    }
+                                    continue;
+                                }
+                            }
 
                             status_updater.values_running(ctx, vec![id]).await;
                             // Status updater reads from the database and uses its own connection
                             // from the pg_pool to do writes
                             ctx.rollback().await?;
 
-                            let task_ctx = ctx_builder.build(self.access_builder().build(self.visibility())).await?;
+                            let task_ctx = ctx_builder
+                                .build(self.access_builder().build(self.visibility()))
+                                .await?;
 
                             let attribute_value = AttributeValue::get_by_id(&task_ctx, &id)
                                 .await?
@@ -266,9 +282,13 @@ impl DependentValuesUpdate {
                     }
                     // If we receive an OkToCreate here, it's because council is telling us that it's Ok to run
                     // `AttributeValue::create_dependent_values` after it has already told us to do that, and after
-                    // we have told it that we've finished doing so. This should never be able to happen normally,
-                    // as it breaks the protocol contract we have with council.
-                    council_server::Response::OkToCreate => return Err(JobConsumerError::CouncilProtocol("Told to create values again after we've finished creating values. Multiple instances of council running?".to_string())),
+                    // we have told it that we've finished doing so. This happens when council restarts and
+                    // re-announces the job it thinks is creating values, which means it may have missed our
+                    // "finished creating values" message, so we send it again.
+                    council_server::Response::OkToCreate => {
+                        warn!(job_id = ?self.job_id(), "Told to create values again after we've finished creating values");
+                        council.finished_creating_values().await?;
+                    }
                     council_server::Response::Shutdown => break,
                 },
                 // FIXME: reconnect
@@ -297,8 +317,8 @@ impl DependentValuesUpdate {
                 // `Some`, the outermost `Result` is a `JoinError` to let us know if
                 // anything went wrong in joining the task.
                 match future_result {
-                    // We have successfully updated a value
-                    Ok(Ok(())) => {}
+                    // We have processed a value, whether or not updating it succeeded
+                    Ok(Ok((id, succeeded))) => node_progress.finished(id, succeeded),
                     // There was an error (with our code) when updating the value
                     Ok(Err(err)) => {
                         warn!(error = ?err, "error updating value");
@@ -343,8 +363,44 @@ impl DependentValuesUpdate {
     }
 }
 
+/// What a [`DependentValuesUpdate`] should do when council says it may process a node.
+#[derive(Debug, PartialEq, Eq)]
+enum NodeAssignment {
+    Process,
+    AlreadyProcessing,
+    AlreadyProcessed { succeeded: bool },
+}
+
+/// The nodes council told a [`DependentValuesUpdate`] to process. Council re-sends its in-flight
+/// assignments when it restarts, so we may be told to process a node more than once.
+#[derive(Debug, Default)]
+struct NodeProgress {
+    spawned: HashSet<AttributeValueId>,
+    /// Whether updating each node that has been processed succeeded.
+    finished: HashMap<AttributeValueId, bool>,
+}
+
+impl NodeProgress {
+    fn ok_to_process(&mut self, id: AttributeValueId) -> NodeAssignment {
+        if let Some(succeeded) = self.finished.get(&id) {
+            NodeAssignment::AlreadyProcessed {
+                succeeded: *succeeded,
+            }
+        } else if self.spawned.insert(id) {
+            NodeAssignment::Process
+        } else {
+            NodeAssignment::AlreadyProcessing
+        }
+    }
+
+    fn finished(&mut self, id: AttributeValueId, succeeded: bool) {
+        self.finished.insert(id, succeeded);
+    }
+}
+
 /// Wrapper around `AttributeValue.update_from_prototype_function(&ctx)` to get it to
-/// play more nicely with being spawned into a `JoinSet`.
+/// play more nicely with being spawned into a `JoinSet`. Returns the id of the value and whether
+/// updating it succeeded.
 #[instrument(
     name = "dependent_values_update.update_value",
     skip_all,
@@ -357,7 +413,7 @@ async fn update_value(
     ctx: DalContext,
     mut attribute_value: AttributeValue,
     council: council_server::PubClient,
-) -> JobConsumerResult<()> {
+) -> JobConsumerResult<(AttributeValueId, bool)> {
     let update_result = attribute_value.update_from_prototype_function(&ctx).await;
     // We don't propagate the error up, because we want the rest of the nodes in the graph to make progress
     // if they are able to.
@@ -375,7 +431,7 @@ async fn update_value(
         council.processed_value(attribute_value.id().into()).await?;
     }
 
-    Ok(())
+    Ok((*attribute_value.id(), update_result.is_ok()))
 }
 
 impl TryFrom<JobInfo> for DependentValuesUpdate {
@@ -392,6 +448,51 @@ impl TryFrom<JobInfo> for DependentValuesUpdate {
     }
 }
 
+fn council_subject(ctx: &DalContext) -> String {
+    if let Some(subject_prefix) = ctx.nats_conn().metadata().subject_prefix() {
+        format!("{subject_prefix}.council")
+    } else {
+        "council".to_string()
+    }
+}
+
+/// Asks council for the state it is holding and renders it as a DOT digraph, with a cluster per
+/// change set. Useful to see why a [`DependentValuesUpdate`] appears stuck.
+///
+/// Save the output to a file and execute the following: "dot <file> -Tsvg -o <newfile>.svg"
+pub async fn council_state_to_dot(ctx: &DalContext) -> JobConsumerResult<String> {
+    let introspection =
+        council_server::Client::introspect(ctx.nats_conn(), &council_subject(ctx)).await?;
+    Ok(introspection_to_dot(&introspection))
+}
+
+fn introspection_to_dot(introspection: &council_server::Introspection) -> String {
+    let mut graph_definitions = format!(
+        "\"value_creation_queue\"[shape=box,label=\"Creating values: {processing}\\lWaiting: {waiting}\\l\"];",
+        processing = introspection.creating_values.as_deref().unwrap_or("none"),
+        waiting = introspection.waiting_to_create_values.join(", "),
+    );
+
+    for (change_set_id, graph) in &introspection.change_sets {
+        let mut cluster = String::new();
+        for (node_id, node) in graph {
+            let processing = node.processing_reply_channel.as_deref().unwrap_or("none");
+            let wanted_by = node.wanted_by_reply_channels.join("\\l");
+            cluster.push_str(&format!(
+                "\"{node_id}\"[label=\"\\lAttribute Value: {node_id}\\lProcessing: {processing}\\lWanted by:\\l{wanted_by}\\l\"];",
+            ));
+            for dependency in &node.depends_on_node_ids {
+                cluster.push_str(&format!("\"{dependency}\" -> \"{node_id}\";"));
+            }
+        }
+        graph_definitions.push_str(&format!(
+            "subgraph \"cluster_{change_set_id}\" {{label=\"Change Set: {change_set_id}\";{cluster}}}",
+        ));
+    }
+
+    format!("digraph G {{{graph_definitions}}}")
+}
+
 #[allow(unused)]
 async fn dependency_graph_to_dot(
     ctx: &DalContext,
@@ -429,3 +530,101 @@ async fn dependency_graph_to_dot(
 
     Ok(dot_digraph)
 }
+
+#[cfg(test)]
+mod tests {
+    use council_server::{Id, Introspection, NodeIntrospection};
+
+    use super::*;
+
+    #[test]
+    fn empty_council_state_to_dot() {
+        assert_eq!(
+            "digraph G {\"value_creation_queue\"[shape=box,label=\"Creating values: none\\lWaiting: \\l\"];}",
+            introspection_to_dot(&Introspection::default()),
+        );
+    }
+
+    #[test]
+    fn council_state_to_dot_has_a_cluster_per_change_set() {
+        let change_set_id = Id::default();
+        let (first, second) = (Id::default(), Id::default());
+        let introspection = Introspection {
+            creating_values: Some("job-a".to_string()),
+            waiting_to_create_values: vec!["job-b".to_string(), "job-c".to_string()],
+            change_sets: HashMap::from([(
+                change_set_id,
+                HashMap::from([
+                    (
+                        first,
+                        NodeIntrospection {
+                            wanted_by_reply_channels: vec!["job-d".to_string()],
+                            processing_reply_channel: Some("job-d".to_string()),
+                            depends_on_node_ids: vec![],
+                        },
+                    ),
+                    (
+                        second,
+                        NodeIntrospection {
+                            wanted_by_reply_channels: vec![
+                                "job-d".to_string(),
+                                "job-e".to_string(),
+                            ],
+                            processing_reply_channel: None,
+                            depends_on_node_ids: vec![first],
+                        },
+                    ),
+                ]),
+            )]),
+        };
+
+        let dot = introspection_to_dot(&introspection);
+
+        assert!(dot.starts_with(
+            "digraph G {\"value_creation_queue\"[shape=box,label=\"Creating values: job-a\\lWaiting: job-b, job-c\\l\"];"
+        ));
+        assert!(dot.contains(&format!(
+            "subgraph \"cluster_{change_set_id}\" {{label=\"Change Set: {change_set_id}\";"
+        )));
+        assert!(dot.contains(&format!(
+            "\"{first}\"[label=\"\\lAttribute Value: {first}\\lProcessing: job-d\\lWanted by:\\ljob-d\\l\"];"
+        )));
+        assert!(dot.contains(&format!(
+            "\"{second}\"[label=\"\\lAttribute Value: {second}\\lProcessing: none\\lWanted by:\\ljob-d\\ljob-e\\l\"];"
+        )));
+        assert!(dot.contains(&format!("\"{first}\" -> \"{second}\";")));
+        assert!(!dot.contains(&format!("\"{second}\" -> \"{first}\";")));
+        assert!(dot.ends_with("}}"));
+    }
+
+    #[test]
+    fn nodes_are_processed_once_and_their_outcome_is_kept() {
+        let mut node_progress = NodeProgress::default();
+        let (succeeding, failing) = (AttributeValueId::generate(), AttributeValueId::generate());
+
+        assert_eq!(
+            NodeAssignment::Process,
+            node_progress.ok_to_process(succeeding)
+        );
+        assert_eq!(
+            NodeAssignment::Process,
+            node_progress.ok_to_process(failing)
+        );
+        assert_eq!(
+            NodeAssignment::AlreadyProcessing,
+            node_progress.ok_to_process(succeeding)
+        );
+
+        // Council re-sending the assignments after a restart gets the outcome again.
+        node_progress.finished(succeeding, true);
+        node_progress.finished(failing, false);
+        assert_eq!(
+            NodeAssignment::AlreadyProcessed { succeeded: true },
+            node_progress.ok_to_process(succeeding)
+        );
+        assert_eq!(
+            NodeAssignment::AlreadyProcessed { succeeded: false },
+            node_progress.ok_to_process(failing)
+        );
+    }
+}
@@ -1,10 +1,13 @@
+use std::collections::{HashSet, VecDeque};
 use std::convert::TryFrom;
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+use tokio::task::JoinSet;
 
 use crate::{
-    fix::FixError,
+    fix::{dependency::fix_dependencies, FixError},
     job::{
         consumer::{
             JobConsumer, JobConsumerError, JobConsumerMetadata, JobConsumerResult, JobInfo,
@@ -12,10 +15,15 @@ use crate::{
         producer::{JobProducer, JobProducerResult},
     },
     AccessBuilder, ActionKind, ActionPrototype, ActionPrototypeId, AttributeValueId, Component,
-    ComponentId, DalContext, DependentValuesUpdate, Fix, FixBatch, FixBatchId, FixCompletionStatus,
-    FixId, FixResolver, RootPropChild, StandardModel, Visibility, WsEvent,
+    ComponentId, DalContext, DependentValuesUpdate, Fix, FixBatch, FixBatchFailurePolicy,
+    FixBatchId, FixCompletionStatus, FixId, FixResolver, RootPropChild, StandardModel, Visibility,
+    WsEvent,
 };
 
+/// The number of fixes in a batch that may run at the same time, unless configured otherwise on
+/// the [`ServicesContext`](crate::ServicesContext).
+pub const DEFAULT_FIX_CONCURRENCY_LIMIT: usize = 5;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FixItem {
     pub id: FixId,
@@ -44,6 +52,8 @@ impl From<FixesJob> for FixesJobArgs {
 #[derive(Clone, Debug, Serialize)]
 pub struct FixesJob {
     fixes: Vec<FixItem>,
+    /// Set on jobs enqueued by older versions, which ran one fix per job and re-enqueued
+    /// themselves with the remaining fixes.
     started: bool,
     batch_id: FixBatchId,
     access_builder: AccessBuilder,
@@ -53,26 +63,12 @@ pub struct FixesJob {
 
 impl FixesJob {
     pub fn new(ctx: &DalContext, fixes: Vec<FixItem>, batch_id: FixBatchId) -> Box<Self> {
-        Self::new_raw(ctx, fixes, batch_id, false)
-    }
-
-    /// Used for creating another fix job in a "fixes" sequence.
-    fn new_iteration(ctx: &DalContext, fixes: Vec<FixItem>, batch_id: FixBatchId) -> Box<Self> {
-        Self::new_raw(ctx, fixes, batch_id, true)
-    }
-
-    fn new_raw(
-        ctx: &DalContext,
-        fixes: Vec<FixItem>,
-        batch_id: FixBatchId,
-        started: bool,
-    ) -> Box<Self> {
         let access_builder = AccessBuilder::from(ctx.clone());
         let visibility = *ctx.visibility();
 
         Box::new(Self {
             fixes,
-            started,
+            started: false,
             batch_id,
             access_builder,
             visibility,
@@ -110,105 +106,340 @@ impl JobConsumer for FixesJob {
                 .await?
                 .ok_or(JobConsumerError::MissingFixBatch(self.batch_id))?;
             batch.stamp_started(ctx).await?;
+            // Commit the batch being started, as every fix runs with its own context.
+            ctx.commit().await?;
         }
 
-        if self.fixes.is_empty() {
-            return finish_batch(ctx, self.batch_id).await;
+        // Whatever happens while running the fixes, the batch has to be finished, or it would
+        // appear to be running forever.
+        if let Err(err) = self.run_fixes(ctx).await {
+            error!(batch_id = %self.batch_id, "error running fix batch: {err}");
+            ctx.rollback().await?;
+            finish_batch(ctx, self.batch_id).await?;
+            ctx.commit().await?;
+            return Err(err);
         }
-        let fix_item = &self.fixes[0];
 
-        let deleted_ctx = &ctx.clone_with_delete_visibility();
-        // Get the workflow for the action we need to run.
-        let component = Component::get_by_id(deleted_ctx, &fix_item.component_id)
-            .await?
-            .ok_or(JobConsumerError::ComponentNotFound(fix_item.component_id))?;
+        finish_batch(ctx, self.batch_id).await
+    }
+}
 
-        let action = ActionPrototype::get_by_id(ctx, &fix_item.action_prototype_id)
-            .await?
-            .ok_or_else(|| {
-                JobConsumerError::ActionPrototypeNotFound(fix_item.action_prototype_id)
-            })?;
+impl FixesJob {
+    /// Runs the fixes of the batch in dependency order, applying the batch's
+    /// [`FixBatchFailurePolicy`] to every fix that does not succeed, errored ones included.
+    async fn run_fixes(&self, ctx: &DalContext) -> JobConsumerResult<()> {
+        if self.fixes.is_empty() {
+            return Ok(());
+        }
 
-        // Run the fix (via the action prototype).
-        let mut fix = Fix::get_by_id(ctx, &fix_item.id)
+        let mut actions = Vec::with_capacity(self.fixes.len());
+        for fix_item in &self.fixes {
+            let action = ActionPrototype::get_by_id(ctx, &fix_item.action_prototype_id)
+                .await?
+                .ok_or_else(|| {
+                    JobConsumerError::ActionPrototypeNotFound(fix_item.action_prototype_id)
+                })?;
+            actions.push((fix_item.component_id, *action.kind()));
+        }
+        let dependencies = fix_dependencies(ctx, &actions).await?;
+        let failure_policy = *FixBatch::get_by_id(ctx, &self.batch_id)
             .await?
-            .ok_or(FixError::MissingFix(fix_item.id))?;
-        let resource = fix.run(ctx, &action).await?;
-        let completion_status: FixCompletionStatus = *fix
-            .completion_status()
-            .ok_or(FixError::EmptyCompletionStatus)?;
-
-        // Upsert the fix resolver.
-        FixResolver::upsert(
-            ctx,
-            *action.id(),
-            fix_item.attribute_value_id,
-            Some(matches!(completion_status, FixCompletionStatus::Success)),
-            *fix.id(),
-        )
-        .await?;
+            .ok_or(JobConsumerError::MissingFixBatch(self.batch_id))?
+            .failure_policy();
+
+        let mut pending_dependencies: Vec<HashSet<usize>> = dependencies
+            .iter()
+            .map(|deps| deps.iter().copied().collect())
+            .collect();
+        let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); self.fixes.len()];
+        for (index, deps) in dependencies.iter().enumerate() {
+            for dependency in deps {
+                dependents[*dependency].push(index);
+            }
+        }
+        let mut ready: VecDeque<usize> = (0..self.fixes.len())
+            .filter(|index| pending_dependencies[*index].is_empty())
+            .collect();
+        // Whether each fix was either started or skipped.
+        let mut handled = vec![false; self.fixes.len()];
+
+        let concurrency_limit = ctx.fix_concurrency_limit();
+        let ctx_builder = ctx.services_context().into_builder(ctx.blocking());
+        let mut fix_tasks = JoinSet::new();
+        // Dropping the set would abort the fixes that are still running halfway through their
+        // actions, so once something goes wrong no more fixes are started, but the running ones
+        // are waited for before the error is returned.
+        let mut first_error: Option<JobConsumerError> = None;
+
+        loop {
+            while first_error.is_none() && fix_tasks.len() < concurrency_limit {
+                let Some(index) = ready.pop_front() else {
+                    break;
+                };
+                handled[index] = true;
+                let task_ctx = match ctx_builder
+                    .build(self.access_builder().build(self.visibility()))
+                    .await
+                {
+                    Ok(task_ctx) => task_ctx,
+                    Err(err) => {
+                        first_error = Some(err.into());
+                        break;
+                    }
+                };
+                let fix_item = self.fixes[index].clone();
+                let batch_id = self.batch_id;
+                fix_tasks
+                    .spawn(async move { (index, run_fix(task_ctx, fix_item, batch_id).await) });
+            }
+
+            let Some(joined) = fix_tasks.join_next().await else {
+                break;
+            };
+            if first_error.is_some() {
+                if let Ok((index, Err(err))) = joined {
+                    error!(fix_id = %self.fixes[index].id, "error running fix: {err}");
+                }
+                continue;
+            }
+
+            let handled_fix = async {
+                let (index, result) = joined?;
+                let completion_status = match result {
+                    Ok(completion_status) => completion_status,
+                    Err(err) => {
+                        // An error in one fix must not strand the rest of the batch, so it is
+                        // recorded like any other fix that did not succeed.
+                        error!(fix_id = %self.fixes[index].id, "error running fix: {err}");
+                        fail_fix(
+                            ctx,
+                            &self.fixes[index],
+                            actions[index].1,
+                            self.batch_id,
+                            err,
+                        )
+                        .await?;
+                        ctx.commit().await?;
+                        FixCompletionStatus::Error
+                    }
+                };
+
+                if completion_status != FixCompletionStatus::Success {
+                    let to_skip = match failure_policy {
+                        FixBatchFailurePolicy::AbortRemaining => (0..self.fixes.len()).collect(),
+                        FixBatchFailurePolicy::ContinueAll => Vec::new(),
+                        FixBatchFailurePolicy::SkipDependents => {
+                            transitive_dependents(&dependents, index)
+                        }
+                    };
+                    let mut skipped_any = false;
+                    for skipped in to_skip {
+                        if !handled[skipped] {
+                            handled[skipped] = true;
+                            skipped_any = true;
+                            skip_fix(ctx, &self.fixes[skipped], actions[skipped].1, self.batch_id)
+                                .await?;
+                        }
+                    }
+                    if skipped_any {
+                        ready.retain(|index| !handled[*index]);
+                        ctx.commit().await?;
+                    }
+                }
+
+                for dependent in &dependents[index] {
+                    pending_dependencies[*dependent].remove(&index);
+                    // Skipped fixes must never start, even once nothing holds them back anymore.
+                    if pending_dependencies[*dependent].is_empty() && !handled[*dependent] {
+                        ready.push_back(*dependent);
+                    }
+                }
+
+                Ok::<_, JobConsumerError>(())
+            }
+            .await;
+            if let Err(err) = handled_fix {
+                first_error = Some(err);
+            }
+        }
 
-        let logs: Vec<_> = match resource {
-            Some(r) => r
-                .logs
-                .iter()
-                .flat_map(|l| l.split('\n'))
-                .map(|l| l.to_owned())
-                .collect(),
-            None => vec![],
-        };
-
-        let attribute_value = Component::root_prop_child_attribute_value_for_component(
-            ctx,
-            *component.id(),
-            RootPropChild::Resource,
-        )
-        .await?;
+        match first_error {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+}
 
-        // Always retriggers confirmations, and propagates resource if it changed.
-        ctx.enqueue_job(DependentValuesUpdate::new(
-            ctx.access_builder(),
-            *ctx.visibility(),
-            vec![*attribute_value.id()],
-        ))
-        .await?;
+/// Every fix that depends on the given one, directly or not.
+fn transitive_dependents(dependents: &[Vec<usize>], index: usize) -> Vec<usize> {
+    let mut found = HashSet::new();
+    let mut work_queue = dependents[index].clone();
+    while let Some(dependent) = work_queue.pop() {
+        if found.insert(dependent) {
+            work_queue.extend(dependents[dependent].iter().copied());
+        }
+    }
+    found.into_iter().collect()
+}
 
-        // Commit progress so far, and wait for dependent values propagation so we can run
-        // consecutive fixes that depend on the /root/resource from the previous fix.
-        // `blocking_commit()` will wait for any jobs that have ben created through
-        // `enqueue_job(...)` to finish before moving on.
-        ctx.blocking_commit().await?;
-
-        component.act(ctx, ActionKind::Refresh).await?;
-
-        ctx.blocking_commit().await?;
-
-        WsEvent::fix_return(
-            ctx,
-            *fix.id(),
-            self.batch_id,
-            fix_item.attribute_value_id,
-            *action.kind(),
-            completion_status,
-            logs,
-        )
+/// Stamps a fix that will not run as per the batch's [`FixBatchFailurePolicy`].
+async fn skip_fix(
+    ctx: &DalContext,
+    fix_item: &FixItem,
+    action_kind: ActionKind,
+    batch_id: FixBatchId,
+) -> JobConsumerResult<()> {
+    let mut fix = Fix::get_by_id(ctx, &fix_item.id)
         .await?
-        .publish_on_commit(ctx)
-        .await?;
+        .ok_or(FixError::MissingFix(fix_item.id))?;
+    fix.stamp_skipped(
+        ctx,
+        "Skipped since another fix in the batch did not succeed",
+    )
+    .await?;
+
+    WsEvent::fix_return(
+        ctx,
+        fix_item.id,
+        batch_id,
+        fix_item.attribute_value_id,
+        action_kind,
+        FixCompletionStatus::Skipped,
+        vec![],
+    )
+    .await?
+    .publish_on_commit(ctx)
+    .await?;
 
-        if self.fixes.len() == 1 {
-            finish_batch(ctx, self.batch_id).await?;
-        } else {
-            ctx.enqueue_job(FixesJob::new_iteration(
-                ctx,
-                self.fixes.iter().skip(1).cloned().collect(),
-                self.batch_id,
-            ))
-            .await?;
-        }
+    Ok(())
+}
 
-        Ok(())
+/// Stamps a fix whose run errored, unless it got as far as recording how it went.
+async fn fail_fix(
+    ctx: &DalContext,
+    fix_item: &FixItem,
+    action_kind: ActionKind,
+    batch_id: FixBatchId,
+    err: JobConsumerError,
+) -> JobConsumerResult<()> {
+    let mut fix = Fix::get_by_id(ctx, &fix_item.id)
+        .await?
+        .ok_or(FixError::MissingFix(fix_item.id))?;
+    if fix.finished_at().is_none() {
+        fix.stamp_errored(ctx, err.to_string()).await?;
     }
+
+    WsEvent::fix_return(
+        ctx,
+        fix_item.id,
+        batch_id,
+        fix_item.attribute_value_id,
+        action_kind,
+        FixCompletionStatus::Error,
+        vec![],
+    )
+    .await?
+    .publish_on_commit(ctx)
+    .await?;
+
+    Ok(())
+}
+
+/// Runs a single fix of a [`FixesJob`] with its own context, committing when done.
+#[instrument(
+    name = "fixes_job.run_fix",
+    skip_all,
+    level = "info",
+    fields(
+        fix.id = %fix_item.id,
+        component.id = %fix_item.component_id,
+    )
+)]
+async fn run_fix(
+    ctx: DalContext,
+    fix_item: FixItem,
+    batch_id: FixBatchId,
+) -> JobConsumerResult<FixCompletionStatus> {
+    let deleted_ctx = &ctx.clone_with_delete_visibility();
+    // Get the workflow for the action we need to run.
+    let component = Component::get_by_id(deleted_ctx, &fix_item.component_id)
+        .await?
+        .ok_or(JobConsumerError::ComponentNotFound(fix_item.component_id))?;
+
+    let action = ActionPrototype::get_by_id(&ctx, &fix_item.action_prototype_id)
+        .await?
+        .ok_or_else(|| JobConsumerError::ActionPrototypeNotFound(fix_item.action_prototype_id))?;
+
+    // Run the fix (via the action prototype).
+    let mut fix = Fix::get_by_id(&ctx, &fix_item.id)
+        .await?
+        .ok_or(FixError::MissingFix(fix_item.id))?;
+    let resource = fix.run(&ctx, &action).await?;
+    let completion_status: FixCompletionStatus = *fix
+        .completion_status()
+        .ok_or(FixError::EmptyCompletionStatus)?;
+
+    // Upsert the fix resolver.
+    FixResolver::upsert(
+        &ctx,
+        *action.id(),
+        fix_item.attribute_value_id,
+        Some(matches!(completion_status, FixCompletionStatus::Success)),
+        *fix.id(),
+    )
+    .await?;
+
+    let logs: Vec<_> = match resource {
+        Some(r) => r
+            .logs
+            .iter()
+            .flat_map(|l| l.split('\n'))
+            .map(|l| l.to_owned())
+            .collect(),
+        None => vec![],
+    };
+
+    let attribute_value = Component::root_prop_child_attribute_value_for_component(
+        &ctx,
+        *component.id(),
+        RootPropChild::Resource,
+    )
+    .await?;
+
+    // Always retriggers confirmations, and propagates resource if it changed.
+    ctx.enqueue_job(DependentValuesUpdate::new(
+        ctx.access_builder(),
+        *ctx.visibility(),
+        vec![*attribute_value.id()],
+    ))
+    .await?;
+
+    // Commit progress so far, and wait for dependent values propagation so we can run
+    // consecutive fixes that depend on the /root/resource from the previous fix.
+    // `blocking_commit()` will wait for any jobs that have ben created through
+    // `enqueue_job(...)` to finish before moving on.
+    ctx.blocking_commit().await?;
+
+    component.act(&ctx, ActionKind::Refresh).await?;
+
+    ctx.blocking_commit().await?;
+
+    WsEvent::fix_return(
+        &ctx,
+        *fix.id(),
+        batch_id,
+        fix_item.attribute_value_id,
+        *action.kind(),
+        completion_status,
+        logs,
+    )
+    .await?
+    .publish_on_commit(&ctx)
+    .await?;
+
+    ctx.commit().await?;
+
+    Ok(completion_status)
 }
 
 impl TryFrom<JobInfo> for FixesJob {
@@ -229,10 +460,19 @@ impl TryFrom<JobInfo> for FixesJob {
 }
 
 async fn finish_batch(ctx: &DalContext, id: FixBatchId) -> JobConsumerResult<()> {
-    // Mark the batch as completed.
     let mut batch = FixBatch::get_by_id(ctx, &id)
         .await?
         .ok_or(JobConsumerError::MissingFixBatch(id))?;
+
+    // Fixes can only be left without an outcome if the batch stopped early.
+    for mut fix in batch.fixes(ctx).await? {
+        if fix.finished_at().is_none() {
+            fix.stamp_errored(ctx, "The fix batch stopped before this fix finished")
+                .await?;
+        }
+    }
+
+    // Mark the batch as completed.
     let batch_completion_status = batch.stamp_finished(ctx).await?;
     WsEvent::fix_batch_return(ctx, *batch.id(), batch_completion_status)
         .await?
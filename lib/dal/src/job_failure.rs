@@ -1,24 +1,43 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use si_data_pg::PgError;
 use telemetry::prelude::*;
 use thiserror::Error;
 
+use crate::job::consumer::{JobConsumerError, JobInfo};
+use crate::job::definition::{FixesJob, RefreshJob};
+use crate::job::producer::JobProducer;
+use crate::standard_model::{object_option_from_row_option, objects_from_rows};
 use crate::{
-    impl_standard_model, pk, standard_model, standard_model_accessor_ro, DalContext, PgPoolError,
+    impl_standard_model, pk, standard_model, standard_model_accessor, standard_model_accessor_ro,
+    DalContext, DependentValuesUpdate, HistoryEventError, PgPoolError, StandardModel,
     StandardModelError, Tenancy, Timestamp, TransactionsError, Visibility,
 };
 
+const FIND_BY_PK: &str = include_str!("queries/job_failure/find_by_pk.sql");
+const LIST_UNSOLVED: &str = include_str!("queries/job_failure/list_unsolved.sql");
+
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum JobFailureError {
+    #[error(transparent)]
+    HistoryEvent(#[from] HistoryEventError),
+    #[error(transparent)]
+    JobConsumer(#[from] JobConsumerError),
+    #[error("job failure {0} did not record the job that failed and cannot be replayed")]
+    MissingJobInfo(JobFailurePk),
     #[error(transparent)]
     Pg(#[from] PgError),
     #[error(transparent)]
     PgPool(#[from] PgPoolError),
     #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    #[error(transparent)]
     StandardModel(#[from] StandardModelError),
     #[error(transparent)]
     Transactions(#[from] TransactionsError),
+    #[error("cannot replay job failure of unknown kind: {0}")]
+    UnknownJobKind(String),
 }
 
 pub type JobFailureResult<T, E = JobFailureError> = Result<T, E>;
@@ -34,6 +53,9 @@ pk!(JobFailureId);
 /// The failure will be set to the user's tenancy and visibility, with the user
 /// as the actor. For now we don't support drastic failures that happen before
 /// `pinga` can obtain this metadata, they will only be logged
+///
+/// When the [`JobInfo`] of the failed job is recorded, the job can be enqueued again with
+/// [`JobFailure::replay()`].
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct JobFailure {
     pk: JobFailurePk,
@@ -41,6 +63,7 @@ pub struct JobFailure {
     kind: String,
     message: String,
     solved: bool,
+    job_info: Option<Value>,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -64,17 +87,19 @@ impl JobFailure {
         ctx: &DalContext,
         kind: impl AsRef<str>,
         message: impl AsRef<str>,
+        job_info: Option<&JobInfo>,
     ) -> JobFailureResult<Self> {
         let kind = kind.as_ref();
         let message = message.as_ref();
+        let job_info = job_info.map(serde_json::to_value).transpose()?;
 
         let row = ctx
             .txns()
             .await?
             .pg()
             .query_one(
-                "SELECT object FROM job_failure_create_v1($1, $2, $3, $4)",
-                &[ctx.tenancy(), ctx.visibility(), &kind, &message],
+                "SELECT object FROM job_failure_create_v2($1, $2, $3, $4, $5)",
+                &[ctx.tenancy(), ctx.visibility(), &kind, &message, &job_info],
             )
             .await?;
         let object = standard_model::finish_create_from_row(ctx, row).await?;
@@ -83,4 +108,75 @@ impl JobFailure {
 
     standard_model_accessor_ro!(kind, String);
     standard_model_accessor_ro!(message, String);
+    standard_model_accessor!(solved, bool, JobFailureResult);
+
+    /// Gets the [`JobInfo`] of the job that failed, if it was recorded.
+    pub fn job_info(&self) -> JobFailureResult<Option<JobInfo>> {
+        Ok(self
+            .job_info
+            .clone()
+            .map(serde_json::from_value)
+            .transpose()?)
+    }
+
+    /// Finds a [`JobFailure`] in the workspace by its pk, whatever change set it was recorded in.
+    #[instrument(skip_all)]
+    pub async fn find_by_pk(ctx: &DalContext, pk: JobFailurePk) -> JobFailureResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(FIND_BY_PK, &[ctx.tenancy(), &pk])
+            .await?;
+        Ok(object_option_from_row_option(row)?)
+    }
+
+    /// Lists every [`JobFailure`] in the workspace that has not been solved yet, across all
+    /// change sets, most recent first.
+    #[instrument(skip_all)]
+    pub async fn list_unsolved(ctx: &DalContext) -> JobFailureResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST_UNSOLVED, &[ctx.tenancy()])
+            .await?;
+        Ok(objects_from_rows(rows)?)
+    }
+
+    /// Acknowledges the [`JobFailure`] so it is no longer listed by [`Self::list_unsolved()`].
+    #[instrument(skip_all)]
+    pub async fn mark_solved(&mut self, ctx: &DalContext) -> JobFailureResult<()> {
+        // The failure lives in the visibility of the job that failed, which is not necessarily
+        // the one of the caller.
+        let ctx = &ctx.clone_with_new_visibility(self.visibility);
+        self.set_solved(ctx, true).await
+    }
+
+    /// Enqueues the job that failed again, with the same arguments, and marks the
+    /// [`JobFailure`] as solved. Should the job fail again, a new [`JobFailure`] is recorded.
+    #[instrument(skip_all)]
+    pub async fn replay(&mut self, ctx: &DalContext) -> JobFailureResult<()> {
+        let mut job_info = self
+            .job_info()?
+            .ok_or(JobFailureError::MissingJobInfo(self.pk))?;
+        job_info.blocking = false;
+
+        let job = match job_info.kind.as_str() {
+            stringify!(DependentValuesUpdate) => {
+                Box::new(DependentValuesUpdate::try_from(job_info)?)
+                    as Box<dyn JobProducer + Send + Sync>
+            }
+            stringify!(FixesJob) => {
+                Box::new(FixesJob::try_from(job_info)?) as Box<dyn JobProducer + Send + Sync>
+            }
+            stringify!(RefreshJob) => {
+                Box::new(RefreshJob::try_from(job_info)?) as Box<dyn JobProducer + Send + Sync>
+            }
+            kind => return Err(JobFailureError::UnknownJobKind(kind.to_owned())),
+        };
+        ctx.enqueue_job(job).await?;
+
+        self.mark_solved(ctx).await
+    }
 }
@@ -7,8 +7,8 @@ use telemetry::prelude::*;
 use thiserror::Error;
 
 use crate::{
-    pk, standard_model_accessor_ro, DalContext, HistoryEvent, HistoryEventError, Timestamp,
-    TransactionsError, Workspace, WorkspaceError, WorkspacePk,
+    pk, standard_model_accessor_ro, DalContext, EncryptedSecret, HistoryEvent, HistoryEventError,
+    SecretError, Timestamp, TransactionsError, Workspace, WorkspaceError, WorkspacePk,
 };
 
 mod key_pair_box_public_key_serde;
@@ -16,6 +16,8 @@ mod key_pair_box_secret_key_serde;
 
 const PUBLIC_KEY_GET_CURRENT: &str = include_str!("./queries/public_key_get_current.sql");
 const KEY_PAIR_GET_BY_PK: &str = include_str!("queries/key_pair_get_by_pk.sql");
+const KEY_PAIR_RETIRE_BY_PK: &str = include_str!("queries/key_pair_retire_by_pk.sql");
+const KEY_PAIR_LIST_FOR_WORKSPACE: &str = include_str!("queries/key_pair_list_for_workspace.sql");
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -30,6 +32,8 @@ pub enum KeyPairError {
     NoCurrentKeyPair,
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
+    #[error(transparent)]
+    Secret(#[from] Box<SecretError>),
     #[error("error serializing/deserializing json: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error("transactions error: {0}")]
@@ -120,6 +124,62 @@ impl KeyPair {
         Ok(serde_json::from_value(json)?)
     }
 
+    /// Replaces every key pair of the workspace with a new one: every secret of the workspace is
+    /// re-encrypted to the new key pair, after which all of the older key pairs are retired and
+    /// can no longer be fetched. Returns the new key pair.
+    pub async fn rotate(ctx: &DalContext) -> KeyPairResult<Self> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                KEY_PAIR_LIST_FOR_WORKSPACE,
+                &[&ctx.tenancy().workspace_pk()],
+            )
+            .await?;
+        let mut old_key_pairs = Vec::with_capacity(rows.len());
+        for row in rows {
+            let json: serde_json::Value = row.try_get("object")?;
+            old_key_pairs.push(serde_json::from_value::<Self>(json)?);
+        }
+        // The list is ordered from the newest key pair, which is the current one
+        let current_key_pair = old_key_pairs
+            .first()
+            .ok_or(KeyPairError::NoCurrentKeyPair)?;
+        let new_key_pair = Self::new(ctx, &current_key_pair.name).await?;
+
+        let mut reencrypted_secrets = 0;
+        for old_key_pair in &old_key_pairs {
+            reencrypted_secrets +=
+                EncryptedSecret::reencrypt_for_key_pair(ctx, old_key_pair, &new_key_pair)
+                    .await
+                    .map_err(Box::new)?;
+            ctx.txns()
+                .await?
+                .pg()
+                .execute(KEY_PAIR_RETIRE_BY_PK, &[&old_key_pair.pk])
+                .await?;
+        }
+
+        let retired_key_pair_pks: Vec<KeyPairPk> =
+            old_key_pairs.iter().map(|key_pair| key_pair.pk).collect();
+        // HistoryEvent won't be accessible by any tenancy (null tenancy_workspace_pk)
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "key_pair.rotate".to_owned(),
+            "Key Pair rotated".to_owned(),
+            &serde_json::json![{
+                "workspace_pk": new_key_pair.workspace_pk,
+                "retired_key_pair_pks": retired_key_pair_pks,
+                "key_pair_pk": new_key_pair.pk,
+                "reencrypted_secrets": reencrypted_secrets,
+            }],
+        )
+        .await?;
+
+        Ok(new_key_pair)
+    }
+
     standard_model_accessor_ro!(name, String);
     standard_model_accessor_ro!(workspace_pk, WorkspacePk);
     standard_model_accessor_ro!(public_key, BoxPublicKey);
@@ -89,7 +89,7 @@ pub use attribute::{
     },
 };
 pub use builtins::{BuiltinsError, BuiltinsResult};
-pub use change_set::{ChangeSet, ChangeSetError, ChangeSetPk, ChangeSetStatus};
+pub use change_set::{ChangeSet, ChangeSetConflict, ChangeSetError, ChangeSetPk, ChangeSetStatus};
 pub use code_view::{CodeLanguage, CodeView};
 pub use component::{
     resource::ResourceView, status::ComponentStatus, status::HistoryActorTimestamp, Component,
@@ -104,7 +104,7 @@ pub use diagram::{
     connection::Connection, connection::DiagramEdgeView, Diagram, DiagramError, DiagramKind,
 };
 pub use edge::{Edge, EdgeError, EdgeResult};
-pub use fix::batch::{FixBatch, FixBatchId};
+pub use fix::batch::{FixBatch, FixBatchFailurePolicy, FixBatchId};
 pub use fix::resolver::{FixResolver, FixResolverError, FixResolverId};
 pub use fix::{Fix, FixCompletionStatus, FixError, FixId};
 pub use func::argument::FuncArgument;
@@ -116,9 +116,9 @@ pub use func::{
     binding::{FuncBinding, FuncBindingError, FuncBindingId},
     Func, FuncError, FuncId, FuncResult,
 };
-pub use history_event::{HistoryActor, HistoryEvent, HistoryEventError};
+pub use history_event::{HistoryActor, HistoryEvent, HistoryEventError, HistoryEventFilter};
 pub use index_map::IndexMap;
-pub use job::definition::DependentValuesUpdate;
+pub use job::definition::{DependentValuesUpdate, DEFAULT_FIX_CONCURRENCY_LIMIT};
 pub use job::processor::{JobQueueProcessor, NatsProcessor};
 pub use job_failure::{JobFailure, JobFailureError, JobFailureResult};
 pub use jwt_key::JwtPublicSigningKey;
@@ -148,8 +148,8 @@ pub use schema::variant::root_prop::RootPropChild;
 pub use schema::variant::SchemaVariantError;
 pub use schema::{Schema, SchemaError, SchemaId, SchemaPk, SchemaVariant, SchemaVariantId};
 pub use secret::{
-    DecryptedSecret, EncryptedSecret, Secret, SecretAlgorithm, SecretError, SecretId, SecretKind,
-    SecretObjectType, SecretPk, SecretResult, SecretVersion,
+    DecryptedSecret, EncryptedSecret, Secret, SecretAlgorithm, SecretBackend, SecretError,
+    SecretId, SecretKind, SecretObjectType, SecretPk, SecretResult, SecretVersion,
 };
 pub use socket::{Socket, SocketArity, SocketId};
 pub use standard_model::{StandardModel, StandardModelError, StandardModelResult};
@@ -158,7 +158,7 @@ pub use status::{
 };
 pub use tenancy::{Tenancy, TenancyError};
 pub use timestamp::{Timestamp, TimestampError};
-pub use user::{User, UserClaim, UserError, UserPk, UserResult};
+pub use user::{User, UserClaim, UserError, UserPk, UserResult, WorkspaceRole};
 pub use validation::prototype::{
     context::ValidationPrototypeContext, ValidationPrototype, ValidationPrototypeError,
     ValidationPrototypeId,
@@ -167,7 +167,9 @@ pub use validation::resolver::{
     ValidationResolver, ValidationResolverError, ValidationResolverId, ValidationStatus,
 };
 pub use visibility::{Visibility, VisibilityError};
-pub use workspace::{Workspace, WorkspaceError, WorkspacePk, WorkspaceResult, WorkspaceSignup};
+pub use workspace::{
+    PkgTrustPolicy, Workspace, WorkspaceError, WorkspacePk, WorkspaceResult, WorkspaceSignup,
+};
 pub use ws_event::{WsEvent, WsEventError, WsEventResult, WsPayload};
 
 #[remain::sorted]
@@ -0,0 +1,62 @@
+CREATE OR REPLACE FUNCTION change_set_abandon_v1(this_change_set_pk ident,
+                                                 this_status text,
+                                                 this_soft_delete bool,
+                                                 this_actor jsonb,
+                                                 this_tenancy jsonb,
+                                                 OUT timestamp_updated_at timestamp with time zone) AS
+$$
+DECLARE
+    standard_model  standard_models%ROWTYPE;
+    this_table_name regclass;
+    query           text;
+    updated_model   change_set_update_type_v1;
+BEGIN
+    UPDATE change_sets
+    SET status     = this_status,
+        updated_at = clock_timestamp()
+    WHERE pk = this_change_set_pk
+      AND in_tenancy_v1(this_tenancy, tenancy_workspace_pk)
+    RETURNING updated_at INTO timestamp_updated_at;
+
+    -- The change set isn't visible from this tenancy: leave the timestamp NULL so the caller can
+    -- report it as not found, and don't touch any of its rows.
+    IF NOT FOUND THEN
+        RETURN;
+    END IF;
+
+    -- Closing a change set leaves its rows in place (they are only ever visible
+    -- through the change set itself), abandoning it soft-deletes them so they
+    -- stop showing up anywhere.
+    IF NOT this_soft_delete THEN
+        RETURN;
+    END IF;
+
+    FOR standard_model IN SELECT * FROM standard_models
+        LOOP
+            this_table_name := standard_model.table_name::regclass;
+
+            query := format('UPDATE %1$I ' ||
+                            '  SET visibility_deleted_at = clock_timestamp(), updated_at = clock_timestamp() ' ||
+                            'WHERE visibility_change_set_pk = %2$L ' ||
+                            '  AND visibility_deleted_at IS NULL ' ||
+                            '  AND in_tenancy_v1(%3$L, tenancy_workspace_pk) ' ||
+                            'RETURNING pk, id, tenancy_workspace_pk',
+                            this_table_name, this_change_set_pk, this_tenancy);
+
+            FOR updated_model IN EXECUTE query
+                LOOP
+                    PERFORM history_event_create_v1(standard_model.history_event_label_base || '.change_set.abandon',
+                                                    this_actor,
+                                                    standard_model.history_event_message_name ||
+                                                    ' discarded by abandoned change set',
+                                                    jsonb_build_object(
+                                                            'pk', updated_model.pk,
+                                                            'id', updated_model.id,
+                                                            'change_set_pk', this_change_set_pk
+                                                        ),
+                                                    jsonb_build_object('tenancy_workspace_pk', updated_model.tenancy_workspace_pk)
+                        );
+                END LOOP;
+        END LOOP;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
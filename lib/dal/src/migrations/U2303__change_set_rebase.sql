@@ -0,0 +1,59 @@
+ALTER TABLE change_sets
+    ADD COLUMN rebased_at timestamp with time zone;
+
+-- Taking head's version of an attribute value has to take head's version of
+-- everything below it too: values the change set added under it must go, and
+-- the ones it edited or deleted must show head's copy again. Otherwise the
+-- change set keeps children that hang off a parent it no longer owns.
+CREATE OR REPLACE FUNCTION change_set_rebase_take_theirs_v1(this_change_set_pk ident,
+                                                            this_attribute_value_id ident,
+                                                            this_tenancy jsonb)
+    RETURNS VOID AS
+$$
+DECLARE
+    this_attribute_value_ids ident[];
+BEGIN
+    WITH RECURSIVE descendants AS (SELECT this_attribute_value_id AS id
+                                   UNION
+                                   SELECT avbtav.object_id AS id
+                                   FROM attribute_value_belongs_to_attribute_value AS avbtav
+                                            INNER JOIN descendants ON descendants.id = avbtav.belongs_to_id
+                                   WHERE avbtav.visibility_change_set_pk IN (ident_nil_v1(), this_change_set_pk)
+                                     AND in_tenancy_v1(this_tenancy, avbtav.tenancy_workspace_pk))
+    SELECT array_agg(id)
+    INTO this_attribute_value_ids
+    FROM descendants;
+
+    DELETE
+    FROM attribute_value_belongs_to_attribute_value
+    WHERE object_id = ANY (this_attribute_value_ids)
+      AND visibility_change_set_pk = this_change_set_pk
+      AND in_tenancy_v1(this_tenancy, tenancy_workspace_pk);
+
+    DELETE
+    FROM attribute_value_belongs_to_attribute_prototype
+    WHERE object_id = ANY (this_attribute_value_ids)
+      AND visibility_change_set_pk = this_change_set_pk
+      AND in_tenancy_v1(this_tenancy, tenancy_workspace_pk);
+
+    DELETE
+    FROM attribute_values
+    WHERE id = ANY (this_attribute_value_ids)
+      AND visibility_change_set_pk = this_change_set_pk
+      AND in_tenancy_v1(this_tenancy, tenancy_workspace_pk);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION change_set_rebase_v1(this_change_set_pk ident,
+                                                this_tenancy jsonb,
+                                                OUT timestamp_rebased_at timestamp with time zone) AS
+$$
+BEGIN
+    UPDATE change_sets
+    SET rebased_at = clock_timestamp(),
+        updated_at = clock_timestamp()
+    WHERE pk = this_change_set_pk
+      AND in_tenancy_v1(this_tenancy, tenancy_workspace_pk)
+    RETURNING rebased_at INTO timestamp_rebased_at;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
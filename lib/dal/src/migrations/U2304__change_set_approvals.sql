@@ -0,0 +1,121 @@
+ALTER TABLE workspaces
+    ADD COLUMN change_set_required_approvals integer NOT NULL DEFAULT 0;
+
+ALTER TABLE change_sets
+    ADD COLUMN approval_requested_at timestamp with time zone,
+    ADD COLUMN approval_requested_by ident;
+
+CREATE TABLE change_set_approvals
+(
+    pk                   ident primary key default ident_create_v1(),
+    change_set_pk        ident                    NOT NULL,
+    user_pk              ident                    NOT NULL,
+    status               text                     NOT NULL,
+    note                 text,
+    tenancy_workspace_pk ident,
+    created_at           timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at           timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP()
+);
+CREATE UNIQUE INDEX ON change_set_approvals (change_set_pk, user_pk);
+
+CREATE OR REPLACE FUNCTION workspace_update_change_set_required_approvals_v1(this_pk ident,
+                                                                             this_required_approvals integer,
+                                                                             OUT object json) AS
+$$
+DECLARE
+    this_updated_row workspaces%ROWTYPE;
+BEGIN
+    UPDATE workspaces
+    SET change_set_required_approvals = this_required_approvals,
+        updated_at                    = clock_timestamp()
+    WHERE pk = this_pk
+    RETURNING * INTO this_updated_row;
+
+    object := row_to_json(this_updated_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- Starts a new round of approvals, discarding every decision made in a previous one and
+-- remembering who asked for it, since they aren't allowed to approve their own change set.
+CREATE OR REPLACE FUNCTION change_set_request_approval_v1(this_change_set_pk ident,
+                                                          this_user_pk ident,
+                                                          this_tenancy jsonb,
+                                                          OUT timestamp_approval_requested_at timestamp with time zone) AS
+$$
+BEGIN
+    DELETE
+    FROM change_set_approvals
+    WHERE change_set_pk = this_change_set_pk
+      AND in_tenancy_v1(this_tenancy, tenancy_workspace_pk);
+
+    UPDATE change_sets
+    SET approval_requested_at = clock_timestamp(),
+        approval_requested_by = this_user_pk,
+        updated_at            = clock_timestamp()
+    WHERE pk = this_change_set_pk
+      AND in_tenancy_v1(this_tenancy, tenancy_workspace_pk)
+    RETURNING approval_requested_at INTO timestamp_approval_requested_at;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION change_set_approval_upsert_v1(this_change_set_pk ident,
+                                                         this_user_pk ident,
+                                                         this_status text,
+                                                         this_note text,
+                                                         this_tenancy jsonb,
+                                                         OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record tenancy_record_v1;
+    this_new_row        change_set_approvals%ROWTYPE;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+
+    INSERT INTO change_set_approvals (change_set_pk, user_pk, status, note, tenancy_workspace_pk)
+    VALUES (this_change_set_pk, this_user_pk, this_status, this_note, this_tenancy_record.tenancy_workspace_pk)
+    ON CONFLICT (change_set_pk, user_pk)
+        DO UPDATE SET status     = EXCLUDED.status,
+                      note       = EXCLUDED.note,
+                      updated_at = clock_timestamp()
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- The last time anything in the change set was written. Approvals given before this point were
+-- given for different contents and no longer count.
+CREATE OR REPLACE FUNCTION change_set_last_edited_at_v1(this_change_set_pk ident,
+                                                        this_tenancy jsonb,
+                                                        OUT last_edited_at timestamp with time zone) AS
+$$
+BEGIN
+    SELECT GREATEST(
+                   (SELECT max(updated_at)
+                    FROM components
+                    WHERE visibility_change_set_pk = this_change_set_pk
+                      AND in_tenancy_v1(this_tenancy, tenancy_workspace_pk)),
+                   (SELECT max(updated_at)
+                    FROM attribute_values
+                    WHERE visibility_change_set_pk = this_change_set_pk
+                      AND in_tenancy_v1(this_tenancy, tenancy_workspace_pk)),
+                   (SELECT max(updated_at)
+                    FROM edges
+                    WHERE visibility_change_set_pk = this_change_set_pk
+                      AND in_tenancy_v1(this_tenancy, tenancy_workspace_pk)),
+                   (SELECT max(updated_at)
+                    FROM props
+                    WHERE visibility_change_set_pk = this_change_set_pk
+                      AND in_tenancy_v1(this_tenancy, tenancy_workspace_pk)),
+                   (SELECT max(updated_at)
+                    FROM funcs
+                    WHERE visibility_change_set_pk = this_change_set_pk
+                      AND in_tenancy_v1(this_tenancy, tenancy_workspace_pk)),
+                   (SELECT max(updated_at)
+                    FROM schema_variants
+                    WHERE visibility_change_set_pk = this_change_set_pk
+                      AND in_tenancy_v1(this_tenancy, tenancy_workspace_pk))
+               )
+    INTO last_edited_at;
+END;
+$$ LANGUAGE PLPGSQL STABLE;
@@ -0,0 +1,30 @@
+ALTER TABLE job_failures
+    ADD COLUMN job_info jsonb;
+
+CREATE OR REPLACE FUNCTION job_failure_create_v2(
+    this_tenancy jsonb,
+    this_visibility jsonb,
+    this_kind text,
+    this_message text,
+    this_job_info jsonb,
+    OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record    tenancy_record_v1;
+    this_visibility_record visibility_record_v1;
+    this_new_row           job_failures%ROWTYPE;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+    this_visibility_record := visibility_json_to_columns_v1(this_visibility);
+
+    INSERT INTO job_failures (tenancy_workspace_pk,
+                              visibility_change_set_pk,
+                              kind, message, job_info)
+    VALUES (this_tenancy_record.tenancy_workspace_pk,
+            this_visibility_record.visibility_change_set_pk,
+            this_kind, this_message, this_job_info)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
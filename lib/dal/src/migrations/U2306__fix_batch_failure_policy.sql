@@ -0,0 +1,2 @@
+ALTER TABLE fix_batches
+    ADD COLUMN failure_policy text NOT NULL DEFAULT 'continueAll';
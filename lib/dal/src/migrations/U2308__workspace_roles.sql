@@ -0,0 +1,19 @@
+-- Everyone that already belongs to a workspace keeps being able to do everything in it
+ALTER TABLE user_belongs_to_workspaces
+    ADD COLUMN role text NOT NULL DEFAULT 'owner';
+ALTER TABLE user_belongs_to_workspaces
+    ALTER COLUMN role SET DEFAULT 'editor';
+
+CREATE OR REPLACE FUNCTION user_set_workspace_role_v1(
+    this_user_pk ident,
+    this_workspace_pk ident,
+    this_role text
+    ) RETURNS void AS
+$$
+BEGIN
+    INSERT INTO user_belongs_to_workspaces (user_pk, workspace_pk, role)
+        VALUES (this_user_pk, this_workspace_pk, this_role)
+        ON CONFLICT (user_pk, workspace_pk)
+        DO UPDATE SET role = this_role, updated_at = CLOCK_TIMESTAMP();
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
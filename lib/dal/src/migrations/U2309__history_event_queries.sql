@@ -0,0 +1,41 @@
+ALTER TABLE history_events
+    ADD COLUMN change_set_pk ident,
+    ADD COLUMN component_id  ident;
+
+-- Best effort for the events recorded before these columns existed
+UPDATE history_events
+SET change_set_pk = (data -> 'visibility' ->> 'visibility_change_set_pk')::ident
+WHERE data -> 'visibility' ->> 'visibility_change_set_pk' IS NOT NULL;
+UPDATE history_events
+SET component_id = (data ->> 'id')::ident
+WHERE label LIKE 'component.%' AND data ->> 'id' IS NOT NULL;
+
+-- Walking through the history one page at a time from the most recent event
+CREATE INDEX ON history_events (tenancy_workspace_pk, created_at DESC, pk DESC);
+CREATE INDEX ON history_events (change_set_pk);
+CREATE INDEX ON history_events (component_id);
+
+DROP FUNCTION history_event_create_v1(text, jsonb, text, jsonb, jsonb);
+
+CREATE OR REPLACE FUNCTION history_event_create_v1(this_label text,
+                                                   this_actor jsonb,
+                                                   this_message text,
+                                                   this_data jsonb,
+                                                   this_tenancy jsonb,
+                                                   this_change_set_pk ident,
+                                                   this_component_id ident,
+                                                   OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record tenancy_record_v1;
+    this_new_row        history_events%ROWTYPE;
+BEGIN
+    SELECT * FROM tenancy_json_to_columns_v1(this_tenancy) INTO this_tenancy_record;
+    INSERT INTO history_events (label, actor, message, data, tenancy_workspace_pk, change_set_pk,
+                                component_id)
+    VALUES (this_label, this_actor, this_message, this_data,
+            this_tenancy_record.tenancy_workspace_pk, this_change_set_pk, this_component_id)
+    RETURNING * INTO this_new_row;
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
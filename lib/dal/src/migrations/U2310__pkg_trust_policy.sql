@@ -0,0 +1,22 @@
+ALTER TABLE workspaces
+    ADD COLUMN pkg_trust_policy text   NOT NULL DEFAULT 'allowUnsigned',
+    ADD COLUMN pkg_trusted_keys text[] NOT NULL DEFAULT '{}';
+
+CREATE OR REPLACE FUNCTION workspace_update_pkg_trust_policy_v1(this_pk ident,
+                                                                this_pkg_trust_policy text,
+                                                                this_pkg_trusted_keys text[],
+                                                                OUT object json) AS
+$$
+DECLARE
+    this_updated_row workspaces%ROWTYPE;
+BEGIN
+    UPDATE workspaces
+    SET pkg_trust_policy = this_pkg_trust_policy,
+        pkg_trusted_keys = this_pkg_trusted_keys,
+        updated_at       = clock_timestamp()
+    WHERE pk = this_pk
+    RETURNING * INTO this_updated_row;
+
+    object := row_to_json(this_updated_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,4 @@
+-- How long an execution of the function may run, in seconds, before cyclone terminates it. When
+-- unset, the timeout configured for cyclone applies.
+ALTER TABLE funcs
+    ADD COLUMN timeout_secs bigint;
@@ -0,0 +1,2 @@
+ALTER TABLE fixes
+    ADD COLUMN cancel_requested_at text;
@@ -8,6 +8,7 @@ pub use export::export_pkg_as_bytes;
 pub use export::get_component_type;
 pub use import::{import_pkg, import_pkg_from_pkg, ImportOptions};
 
+use module_index_client::IndexClientError;
 use si_pkg::{FuncSpecBackendKind, FuncSpecBackendResponseType, SiPkgError, SpecError};
 
 use crate::schema::variant::definition::SchemaVariantDefinitionId;
@@ -24,8 +25,9 @@ use crate::{
     AttributePrototypeArgumentId, AttributePrototypeError, AttributePrototypeId,
     AttributeReadContext, AttributeValueError, ExternalProviderError, ExternalProviderId,
     FuncBackendKind, FuncBackendResponseType, FuncError, FuncId, InternalProviderError,
-    InternalProviderId, PropError, PropId, PropKind, SchemaError, SchemaId, SchemaVariantError,
-    SchemaVariantId, StandardModelError, ValidationPrototypeError,
+    InternalProviderId, PkgTrustPolicy, PropError, PropId, PropKind, SchemaError, SchemaId,
+    SchemaVariantError, SchemaVariantId, StandardModelError, ValidationPrototypeError,
+    WorkspaceError, WorkspacePk,
 };
 
 #[remain::sorted]
@@ -59,6 +61,10 @@ pub enum PkgError {
     AttributeValue(#[from] AttributeValueError),
     #[error("map item prop {0} has both custom key prototypes and custom prop only prototype")]
     ConflictingMapKeyPrototypes(PropId),
+    #[error("downloaded package {0} {1} does not satisfy the dependency on {2} {3}")]
+    DependencyMismatch(String, String, String, String),
+    #[error("dependency {0} was installed without being recorded")]
+    DependencyNotRecorded(String),
     #[error("Cannot find Socket for explicit InternalProvider {0}")]
     ExplicitInternalProviderMissingSocket(InternalProviderId),
     #[error(transparent)]
@@ -87,6 +93,8 @@ pub enum PkgError {
     InternalProviderMissingProp(InternalProviderId, PropId),
     #[error("Leaf Function {0} has invalid argument {1}")]
     InvalidLeafArgument(FuncId, String),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
     #[error("Missing AttributePrototype {0} for explicit InternalProvider {1}")]
     MissingAttributePrototypeForInputSocket(AttributePrototypeId, InternalProviderId),
     #[error("Missing AttributePrototype {0} for ExternalProvider {1}")]
@@ -113,6 +121,8 @@ pub enum PkgError {
     MissingProp(PropId),
     #[error("Cannot find schema_variant_definition {0}")]
     MissingSchemaVariantDefinition(SchemaVariantId),
+    #[error(transparent)]
+    ModuleIndex(#[from] IndexClientError),
     #[error("Package with that hash already installed: {0}")]
     PackageAlreadyInstalled(String),
     #[error(transparent)]
@@ -145,10 +155,20 @@ pub enum PkgError {
     StandardModelMissingBelongsTo(&'static str, &'static str, String),
     #[error("standard model relationship {0} found multiple belongs_to for {1} with id {2}")]
     StandardModelMultipleBelongsTo(&'static str, &'static str, String),
+    #[error("package does not satisfy the workspace trust policy {0}: {1}")]
+    TrustPolicyViolation(PkgTrustPolicy, #[source] SiPkgError),
+    #[error(transparent)]
+    Ulid(#[from] ulid::DecodeError),
+    #[error("no package satisfies the dependency on {0} {1}")]
+    UnsatisfiableDependency(String, String),
     #[error(transparent)]
     UrlParse(#[from] ParseError),
     #[error("Validation creation error: {0}")]
     Validation(#[from] ValidationPrototypeError),
+    #[error(transparent)]
+    Workspace(#[from] WorkspaceError),
+    #[error("workspace not found: {0}")]
+    WorkspaceNotFound(WorkspacePk),
 }
 
 impl PkgError {
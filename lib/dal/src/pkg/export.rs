@@ -96,6 +96,11 @@ async fn build_pkg(
     Ok(pkg)
 }
 
+/// Returns the unique id a func has in the packages it is exported to.
+pub(super) fn func_unique_id(func: &Func) -> PkgResult<FuncUniqueId> {
+    Ok(build_func_spec(func, &[])?.unique_id)
+}
+
 fn build_func_spec(func: &Func, args: &[FuncArgument]) -> PkgResult<FuncSpec> {
     let mut func_spec_builder = FuncSpec::builder();
 
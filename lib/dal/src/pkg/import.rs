@@ -1,11 +1,16 @@
-use std::path::Path;
+use async_recursion::async_recursion;
+use module_index_client::{IndexClient, ListModulesRequest};
+use std::{cmp::Ordering, path::Path};
 use telemetry::prelude::*;
 use tokio::sync::Mutex;
+use ulid::Ulid;
+use url::Url;
 
 use si_pkg::{
-    FuncUniqueId, SchemaVariantSpecPropRoot, SiPkg, SiPkgActionFunc, SiPkgAttrFuncInputView,
-    SiPkgError, SiPkgFunc, SiPkgFuncDescription, SiPkgLeafFunction, SiPkgProp, SiPkgSchema,
-    SiPkgSchemaVariant, SiPkgSocket, SiPkgValidation, SocketSpecKind,
+    compare_versions, FuncUniqueId, SchemaVariantSpecPropRoot, SiPkg, SiPkgActionFunc,
+    SiPkgAttrFuncInputView, SiPkgDependency, SiPkgError, SiPkgFunc, SiPkgFuncDescription,
+    SiPkgLeafFunction, SiPkgProp, SiPkgSchema, SiPkgSchemaVariant, SiPkgSocket, SiPkgValidation,
+    SocketSpecKind,
 };
 
 use crate::{
@@ -26,11 +31,11 @@ use crate::{
     ActionPrototype, ActionPrototypeContext, AttributeContextBuilder, AttributePrototypeArgument,
     AttributeReadContext, AttributeValue, AttributeValueError, DalContext, ExternalProvider,
     ExternalProviderId, Func, FuncArgument, FuncDescription, FuncDescriptionContents, FuncError,
-    FuncId, InternalProvider, Prop, PropId, PropKind, Schema, SchemaId, SchemaVariant,
-    SchemaVariantError, SchemaVariantId, StandardModel,
+    FuncId, InternalProvider, PkgTrustPolicy, Prop, PropId, PropKind, Schema, SchemaId,
+    SchemaVariant, SchemaVariantError, SchemaVariantId, StandardModel, Workspace,
 };
 
-use super::{PkgError, PkgResult};
+use super::{export::func_unique_id, PkgError, PkgResult};
 
 type FuncMap = std::collections::HashMap<FuncUniqueId, Func>;
 
@@ -41,6 +46,12 @@ pub struct ImportOptions {
     /// If set to `true`, the importer will install the assets from the module
     /// but will not make a record of the install as an "installed module".
     pub no_record: bool,
+    /// If set to `true`, the package is installed whatever the trust policy of the workspace.
+    /// Only meant for packages built by SI itself, which are never signed.
+    pub skip_trust_policy: bool,
+    /// The token used to download missing dependencies from the module index. Dependencies are
+    /// only looked up in the local packages path without it.
+    pub module_index_auth_token: Option<String>,
 }
 
 pub async fn import_pkg_from_pkg(
@@ -59,20 +70,33 @@ pub async fn import_pkg_from_pkg(
         return Err(PkgError::PackageAlreadyInstalled(root_hash));
     }
 
-    // TODO: store pkg.metadata()?.name() instead of file_name, but we'll need
-    // to also store the file name unless we stop using the .sipkg file
-    // completely after install
+    if !options.skip_trust_policy {
+        check_trust_policy(ctx, pkg).await?;
+    }
+
+    // TODO: stop storing the file name once we stop using the .sipkg file completely after
+    // install
     let installed_pkg_id = if options.no_record {
         None
     } else {
+        let metadata = pkg.metadata()?;
         Some(
-            *InstalledPkg::new(ctx, &file_name, pkg.hash()?.to_string())
-                .await?
-                .id(),
+            *InstalledPkg::new(
+                ctx,
+                &file_name,
+                pkg.hash()?.to_string(),
+                metadata.name(),
+                metadata.version(),
+            )
+            .await?
+            .id(),
         )
     };
 
-    let mut funcs_by_unique_id = FuncMap::new();
+    // Recording the package before its dependencies means a dependency cycle ends once it gets
+    // back to this package. The funcs of the dependencies can be used by the package's schemas
+    let mut funcs_by_unique_id = install_dependencies(ctx, pkg, &options).await?;
+
     for func_spec in pkg.funcs()? {
         info!(
             "installing function '{}' from {}",
@@ -133,6 +157,258 @@ pub async fn import_pkg_from_pkg(
     Ok((installed_pkg_id, installed_schema_variant_ids))
 }
 
+/// Checks who signed the package against the trust policy of the workspace it's installed in.
+async fn check_trust_policy(ctx: &DalContext, pkg: &SiPkg) -> PkgResult<()> {
+    let workspace_pk = match ctx.tenancy().workspace_pk() {
+        Some(workspace_pk) => workspace_pk,
+        None => return Ok(()),
+    };
+    let workspace = Workspace::get_by_pk(ctx, &workspace_pk)
+        .await?
+        .ok_or(PkgError::WorkspaceNotFound(workspace_pk))?;
+
+    let policy = *workspace.pkg_trust_policy();
+    let result = match policy {
+        PkgTrustPolicy::AllowUnsigned => return Ok(()),
+        PkgTrustPolicy::RequireSigned => pkg.signers().and_then(|signers| {
+            if signers.is_empty() {
+                Err(SiPkgError::Unsigned)
+            } else {
+                Ok(())
+            }
+        }),
+        PkgTrustPolicy::RequireTrusted => pkg.verify(workspace.pkg_trusted_keys()).map(|_| ()),
+    };
+
+    result.map_err(|err| PkgError::TrustPolicyViolation(policy, err))
+}
+
+/// Installs the dependencies of the package which aren't satisfied by an installed package yet,
+/// returning the funcs of all of them. Each one is looked up in the local packages path first,
+/// then in the module index.
+#[async_recursion]
+async fn install_dependencies(
+    ctx: &DalContext,
+    pkg: &SiPkg,
+    options: &ImportOptions,
+) -> PkgResult<FuncMap> {
+    let mut funcs_by_unique_id = FuncMap::new();
+    for dependency in pkg.dependencies()? {
+        let installed_pkg_id = match find_installed_dependency(ctx, &dependency).await? {
+            Some(installed_pkg_id) => installed_pkg_id,
+            None => install_dependency(ctx, pkg, &dependency, options).await?,
+        };
+        funcs_by_unique_id.extend(installed_pkg_funcs(ctx, installed_pkg_id).await?);
+    }
+
+    Ok(funcs_by_unique_id)
+}
+
+async fn install_dependency(
+    ctx: &DalContext,
+    pkg: &SiPkg,
+    dependency: &SiPkgDependency<'_>,
+    options: &ImportOptions,
+) -> PkgResult<InstalledPkgId> {
+    let dependency_pkg = match find_local_dependency(ctx, dependency).await? {
+        Some(dependency_pkg) => dependency_pkg,
+        None => match find_module_index_dependency(ctx, dependency, options).await? {
+            Some(dependency_pkg) => dependency_pkg,
+            None => {
+                return Err(PkgError::UnsatisfiableDependency(
+                    dependency.name().to_owned(),
+                    dependency.version_req().to_string(),
+                ))
+            }
+        },
+    };
+
+    // Packages installed before their name and version were recorded can only be found by hash
+    if let Some(installed_pkg) =
+        InstalledPkg::find_by_hash(ctx, &dependency_pkg.hash()?.to_string()).await?
+    {
+        return Ok(*installed_pkg.id());
+    }
+
+    let dependency_name = dependency_pkg.metadata()?.name().to_owned();
+    info!(
+        "installing dependency '{}' of {}",
+        dependency_name,
+        pkg.metadata()?.name()
+    );
+    let (installed_pkg_id, _) = import_pkg_from_pkg(
+        ctx,
+        &dependency_pkg,
+        &dependency_name,
+        Some(ImportOptions {
+            module_index_auth_token: options.module_index_auth_token.clone(),
+            ..Default::default()
+        }),
+    )
+    .await?;
+
+    installed_pkg_id.ok_or(PkgError::DependencyNotRecorded(dependency_name))
+}
+
+/// Returns the installed package satisfying the dependency, if there is one.
+async fn find_installed_dependency(
+    ctx: &DalContext,
+    dependency: &SiPkgDependency<'_>,
+) -> PkgResult<Option<InstalledPkgId>> {
+    if let Some(pinned_hash) = dependency.pinned_hash() {
+        return Ok(InstalledPkg::find_by_hash(ctx, &pinned_hash.to_string())
+            .await?
+            .map(|installed_pkg| *installed_pkg.id()));
+    }
+
+    Ok(InstalledPkg::list_for_pkg_name(ctx, dependency.name())
+        .await?
+        .iter()
+        .find(|installed_pkg| {
+            installed_pkg
+                .pkg_version()
+                .as_deref()
+                .map_or(false, |version| {
+                    dependency.is_satisfied_by(
+                        dependency.name(),
+                        version,
+                        installed_pkg.root_hash(),
+                    )
+                })
+        })
+        .map(|installed_pkg| *installed_pkg.id()))
+}
+
+/// Returns the funcs installed by a package, keyed by their unique id in packages.
+async fn installed_pkg_funcs(
+    ctx: &DalContext,
+    installed_pkg_id: InstalledPkgId,
+) -> PkgResult<FuncMap> {
+    let mut funcs_by_unique_id = FuncMap::new();
+    for asset in InstalledPkgAsset::list_for_installed_pkg_id(ctx, installed_pkg_id).await? {
+        if *asset.asset_kind() != InstalledPkgAssetKind::Func {
+            continue;
+        }
+        if let InstalledPkgAssetTyped::Func { id, .. } = asset.as_installed_func()? {
+            let func = Func::get_by_id(ctx, &id)
+                .await?
+                .ok_or(PkgError::InstalledFuncMissing(id))?;
+            funcs_by_unique_id.insert(func_unique_id(&func)?, func);
+        }
+    }
+
+    Ok(funcs_by_unique_id)
+}
+
+/// Finds the highest version satisfying the dependency among the packages in the local packages
+/// path.
+async fn find_local_dependency(
+    ctx: &DalContext,
+    dependency: &SiPkgDependency<'_>,
+) -> PkgResult<Option<SiPkg>> {
+    let pkgs_path = match ctx.pkgs_path() {
+        Some(pkgs_path) => pkgs_path,
+        None => return Ok(None),
+    };
+
+    let mut best: Option<(String, SiPkg)> = None;
+    let mut entries = tokio::fs::read_dir(pkgs_path).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let candidate = match SiPkg::load_from_file(entry.path()).await {
+            Ok(candidate) => candidate,
+            Err(err) => {
+                debug!("skipping {}: {}", entry.path().display(), err);
+                continue;
+            }
+        };
+
+        let metadata = candidate.metadata()?;
+        let version = metadata.version().to_owned();
+        if !dependency.is_satisfied_by(metadata.name(), &version, &candidate.hash()?.to_string()) {
+            continue;
+        }
+        if best.as_ref().map_or(true, |(best_version, _)| {
+            compare_versions(&version, best_version) == Ordering::Greater
+        }) {
+            best = Some((version, candidate));
+        }
+    }
+
+    Ok(best.map(|(_, pkg)| pkg))
+}
+
+/// Finds the highest version satisfying the dependency in the module index, if there is one and
+/// we have a token for it.
+async fn find_module_index_dependency(
+    ctx: &DalContext,
+    dependency: &SiPkgDependency<'_>,
+    options: &ImportOptions,
+) -> PkgResult<Option<SiPkg>> {
+    let (module_index_url, auth_token) =
+        match (ctx.module_index_url(), &options.module_index_auth_token) {
+            (Some(module_index_url), Some(auth_token)) => (module_index_url, auth_token),
+            _ => return Ok(None),
+        };
+    let client = IndexClient::new(Url::parse(module_index_url)?, auth_token);
+
+    // The name filter matches on substrings, so the exact name is checked here
+    let mut request = ListModulesRequest {
+        name: Some(dependency.name().to_owned()),
+        ..Default::default()
+    };
+    let module = loop {
+        let response = client.list_modules(&request).await?;
+        if let Some(module) = response
+            .modules
+            .into_iter()
+            .find(|module| module.name == dependency.name())
+        {
+            break module;
+        }
+        match response.next_cursor {
+            Some(cursor) => request.cursor = Some(cursor),
+            None => return Ok(None),
+        }
+    };
+    let module_id = Ulid::from_string(&module.id)?;
+
+    let best_version = client
+        .list_module_versions(module_id)
+        .await?
+        .versions
+        .into_iter()
+        .filter(|version| {
+            dependency.is_satisfied_by(dependency.name(), &version.version, &version.hash)
+        })
+        .max_by(|a, b| compare_versions(&a.version, &b.version));
+
+    let version = match best_version {
+        Some(version) => version,
+        None => return Ok(None),
+    };
+    let pkg_data = client
+        .download_module_version(module_id, &version.version)
+        .await?;
+    let pkg = SiPkg::load_from_bytes(pkg_data)?;
+
+    // The listing is only what the index claims, the package itself has the final say
+    let metadata = pkg.metadata()?;
+    if !dependency.is_satisfied_by(
+        metadata.name(),
+        metadata.version(),
+        &pkg.hash()?.to_string(),
+    ) {
+        return Err(PkgError::DependencyMismatch(
+            metadata.name().to_owned(),
+            metadata.version().to_owned(),
+            dependency.name().to_owned(),
+            dependency.version_req().to_string(),
+        ));
+    }
+
+    Ok(Some(pkg))
+}
+
 pub async fn import_pkg(ctx: &DalContext, pkg_file_path: impl AsRef<Path>) -> PkgResult<SiPkg> {
     let pkg_file_path_str = pkg_file_path.as_ref().to_string_lossy().to_string();
 
@@ -179,7 +455,7 @@ async fn create_func(
                 .await?;
             func.set_description(ctx, func_spec.description()).await?;
             func.set_handler(ctx, Some(func_spec.handler())).await?;
-            func.set_hidden(ctx, func.hidden()).await?;
+            func.set_hidden(ctx, func_spec.hidden()).await?;
             func.set_link(ctx, func_spec.link().map(|l| l.to_string()))
                 .await?;
 
@@ -0,0 +1,19 @@
+SELECT change_set_rows.id AS id,
+       NULL::text AS name,
+       CASE
+           WHEN change_set_rows.visibility_deleted_at IS NOT NULL THEN 'deleted'
+           WHEN head_rows.id IS NULL THEN 'added'
+           ELSE 'modified'
+           END            AS status
+FROM edges AS change_set_rows
+         LEFT JOIN edges AS head_rows
+                   ON head_rows.id = change_set_rows.id
+                       AND head_rows.tenancy_workspace_pk = change_set_rows.tenancy_workspace_pk
+                       AND head_rows.visibility_change_set_pk = ident_nil_v1()
+                       AND head_rows.visibility_deleted_at IS NULL
+WHERE change_set_rows.visibility_change_set_pk = $2
+  AND in_tenancy_v1($1, change_set_rows.tenancy_workspace_pk)
+
+  -- Rows that were both added and deleted within the change set never made it to head
+  AND NOT (change_set_rows.visibility_deleted_at IS NOT NULL AND head_rows.id IS NULL)
+ORDER BY change_set_rows.id
@@ -0,0 +1,15 @@
+SELECT change_set_values.id                             AS attribute_value_id,
+       change_set_values.attribute_context_component_id AS component_id,
+       change_set_values.attribute_context_prop_id      AS prop_id,
+       head_values.updated_at                           AS head_updated_at,
+       change_set_values.updated_at                     AS change_set_updated_at
+FROM attribute_values AS change_set_values
+         -- Both sides have their own copy of the value and head's moved since we last looked
+         INNER JOIN attribute_values AS head_values
+                    ON head_values.id = change_set_values.id
+                        AND head_values.tenancy_workspace_pk = change_set_values.tenancy_workspace_pk
+                        AND head_values.visibility_change_set_pk = ident_nil_v1()
+                        AND head_values.updated_at > $3
+WHERE change_set_values.visibility_change_set_pk = $2
+  AND in_tenancy_v1($1, change_set_values.tenancy_workspace_pk)
+ORDER BY change_set_values.id
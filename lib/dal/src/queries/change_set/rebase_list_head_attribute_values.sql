@@ -0,0 +1,17 @@
+SELECT attribute_values.id                             AS id,
+       attribute_values.attribute_context_component_id AS component_id,
+       -- Only values that are still visible from within the change set can be
+       -- used to kick off dependent values updates there
+       (attribute_values.visibility_deleted_at IS NULL
+           AND NOT EXISTS(SELECT 1
+                          FROM attribute_values AS change_set_values
+                          WHERE change_set_values.id = attribute_values.id
+                            AND change_set_values.tenancy_workspace_pk = attribute_values.tenancy_workspace_pk
+                            AND change_set_values.visibility_change_set_pk = $3
+                            AND change_set_values.visibility_deleted_at IS NOT NULL)
+           )                                           AS visible_in_change_set
+FROM attribute_values
+WHERE attribute_values.visibility_change_set_pk = ident_nil_v1()
+  AND attribute_values.updated_at > $2
+  AND in_tenancy_v1($1, attribute_values.tenancy_workspace_pk)
+ORDER BY attribute_values.id
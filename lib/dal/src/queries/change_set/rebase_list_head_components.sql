@@ -0,0 +1,6 @@
+SELECT components.id AS id
+FROM components
+WHERE components.visibility_change_set_pk = ident_nil_v1()
+  AND components.updated_at > $2
+  AND in_tenancy_v1($1, components.tenancy_workspace_pk)
+ORDER BY components.id
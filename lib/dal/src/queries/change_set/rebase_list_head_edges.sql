@@ -0,0 +1,6 @@
+SELECT edges.id AS id
+FROM edges
+WHERE edges.visibility_change_set_pk = ident_nil_v1()
+  AND edges.updated_at > $2
+  AND in_tenancy_v1($1, edges.tenancy_workspace_pk)
+ORDER BY edges.id
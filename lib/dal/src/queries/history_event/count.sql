@@ -0,0 +1,9 @@
+SELECT count(*) AS count
+FROM history_events
+WHERE in_tenancy_v1($1, history_events.tenancy_workspace_pk)
+  AND ($2::jsonb IS NULL OR history_events.actor = $2::jsonb)
+  AND ($3::text IS NULL OR starts_with(history_events.label, $3::text))
+  AND ($4::timestamptz IS NULL OR history_events.created_at >= $4::timestamptz)
+  AND ($5::timestamptz IS NULL OR history_events.created_at < $5::timestamptz)
+  AND ($6::ident IS NULL OR history_events.change_set_pk = $6::ident)
+  AND ($7::ident IS NULL OR history_events.component_id = $7::ident)
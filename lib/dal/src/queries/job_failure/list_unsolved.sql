@@ -0,0 +1,6 @@
+SELECT row_to_json(job_failures.*) AS object
+FROM job_failures
+WHERE in_tenancy_v1($1, job_failures.tenancy_workspace_pk)
+  AND job_failures.visibility_deleted_at IS NULL
+  AND job_failures.solved = false
+ORDER BY job_failures.created_at DESC
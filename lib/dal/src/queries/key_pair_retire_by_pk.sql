@@ -0,0 +1,3 @@
+UPDATE key_pairs
+SET visibility_deleted_at = clock_timestamp(), updated_at = clock_timestamp()
+WHERE pk = $1 AND visibility_deleted_at IS NULL
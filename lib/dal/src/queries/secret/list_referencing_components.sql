@@ -0,0 +1,25 @@
+SELECT DISTINCT av.attribute_context_component_id AS component_id
+FROM attribute_values AS av
+         JOIN func_binding_return_values AS fbrv
+              ON fbrv.id = av.func_binding_return_value_id
+                  AND in_tenancy_v1($1, fbrv.tenancy_workspace_pk)
+WHERE in_tenancy_v1($1, av.tenancy_workspace_pk)
+  AND av.visibility_deleted_at IS NULL
+  AND fbrv.value = to_jsonb($2::text)
+  AND EXISTS(SELECT 1
+             FROM props
+             WHERE props.id = av.attribute_context_prop_id
+               AND props.widget_kind = 'secretSelect')
+  AND EXISTS(SELECT 1
+             FROM components
+             WHERE components.id = av.attribute_context_component_id
+               AND components.visibility_change_set_pk IN (ident_nil_v1(), av.visibility_change_set_pk)
+               AND components.visibility_deleted_at IS NULL
+               AND in_tenancy_v1($1, components.tenancy_workspace_pk))
+  -- Head and every change set that may still be applied
+  AND (av.visibility_change_set_pk = ident_nil_v1()
+    OR av.visibility_change_set_pk IN (SELECT pk
+                                       FROM change_sets
+                                       WHERE status = 'Open'
+                                         AND in_tenancy_v1($1, tenancy_workspace_pk)))
+ORDER BY component_id
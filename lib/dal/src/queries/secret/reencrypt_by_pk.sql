@@ -0,0 +1,3 @@
+UPDATE encrypted_secrets
+SET crypted = $2, key_pair_pk = $3, version = $4, algorithm = $5, updated_at = clock_timestamp()
+WHERE pk = $1
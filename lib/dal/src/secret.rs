@@ -19,11 +19,16 @@ use crate::{
     key_pair::KeyPairPk,
     pk,
     standard_model::{self, TypeHint},
-    standard_model_accessor, standard_model_accessor_ro, DalContext, HistoryEvent,
+    standard_model_accessor, standard_model_accessor_ro, ComponentId, DalContext, HistoryEvent,
     HistoryEventError, KeyPair, KeyPairError, StandardModel, StandardModelError, Timestamp,
     Visibility,
 };
 
+const LIST_FOR_KEY_PAIR: &str = include_str!("queries/secret/list_for_key_pair.sql");
+const LIST_REFERENCING_COMPONENTS: &str =
+    include_str!("queries/secret/list_referencing_components.sql");
+const REENCRYPT_BY_PK: &str = include_str!("queries/secret/reencrypt_by_pk.sql");
+
 /// Error type for Secrets.
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -32,14 +37,22 @@ pub enum SecretError {
     DecryptionFailed,
     #[error("error deserializing message: {0}")]
     DeserializeMessage(#[source] serde_json::Error),
+    #[error("secret is stored in the {0} backend and can only be resolved by cyclone")]
+    ExternalBackend(SecretBackend),
     #[error("history event error: {0}")]
     HistoryEvent(#[from] HistoryEventError),
     #[error("key pair error: {0}")]
     KeyPair(#[from] KeyPairError),
+    #[error("key pair {0} is not the current key pair of the workspace")]
+    KeyPairNotCurrent(KeyPairPk),
     #[error("key pair not found for secret")]
     KeyPairNotFound,
+    #[error("the {0} backend stores secrets in the database and cannot hold a reference")]
+    NotAReferenceBackend(SecretBackend),
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
+    #[error("secret {0} is still used by components: {1:?}")]
+    SecretInUse(SecretId, Vec<ComponentId>),
     #[error("standard model error: {0}")]
     StandardModelError(#[from] StandardModelError),
     #[error("transactions error: {0}")]
@@ -64,6 +77,7 @@ pub struct Secret {
     object_type: SecretObjectType,
     key_pair_pk: KeyPairPk,
     kind: SecretKind,
+    backend: SecretBackend,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -117,10 +131,59 @@ impl Secret {
     // Once created, these object fields are to be considered immutable
     standard_model_accessor_ro!(object_type, SecretObjectType);
     standard_model_accessor_ro!(kind, SecretKind);
+    standard_model_accessor_ro!(backend, SecretBackend);
 
     pub async fn key_pair(&self, ctx: &DalContext) -> SecretResult<KeyPair> {
         Ok(KeyPair::get_by_pk(ctx, self.key_pair_pk).await?)
     }
+
+    /// Lists the [`Components`](crate::Component) that have this secret selected in one of their
+    /// properties, on head or in any open [`ChangeSet`](crate::ChangeSet), since secrets are
+    /// shared by every change set of the workspace.
+    pub async fn list_referencing_components(
+        &self,
+        ctx: &DalContext,
+    ) -> SecretResult<Vec<ComponentId>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                LIST_REFERENCING_COMPONENTS,
+                &[ctx.tenancy(), &self.id.to_string()],
+            )
+            .await?;
+
+        let mut component_ids = Vec::with_capacity(rows.len());
+        for row in rows {
+            component_ids.push(row.try_get("component_id")?);
+        }
+        Ok(component_ids)
+    }
+
+    /// Deletes the secret, refusing to do so while any [`Component`](crate::Component) still
+    /// references it.
+    // Delete from the underlying `encrypted_secrets` table rather than attempting to delete from
+    // the `secrets` view
+    pub async fn delete(&mut self, ctx: &DalContext) -> SecretResult<()> {
+        let component_ids = self.list_referencing_components(ctx).await?;
+        if !component_ids.is_empty() {
+            return Err(SecretError::SecretInUse(self.id, component_ids));
+        }
+
+        let deleted_at = standard_model::delete_by_id(ctx, "encrypted_secrets", self.id()).await?;
+        let _history_event = HistoryEvent::new(
+            ctx,
+            Self::history_event_label(vec!["deleted"]),
+            Self::history_event_message("deleted"),
+            &serde_json::json!({"pk": self.pk, "id": self.id, "visibility": self.visibility}),
+        )
+        .await?;
+        self.visibility.deleted_at = Some(deleted_at);
+        self.timestamp.updated_at = deleted_at;
+
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -130,6 +193,7 @@ pub struct SecretView {
     pub name: String,
     pub object_type: SecretObjectType,
     pub kind: SecretKind,
+    pub backend: SecretBackend,
 }
 
 impl From<Secret> for SecretView {
@@ -139,6 +203,7 @@ impl From<Secret> for SecretView {
             name: secret.name().to_owned(),
             object_type: *secret.object_type(),
             kind: *secret.kind(),
+            backend: *secret.backend(),
         }
     }
 }
@@ -162,6 +227,8 @@ pub struct EncryptedSecret {
     crypted: Vec<u8>,
     version: SecretVersion,
     algorithm: SecretAlgorithm,
+    backend: SecretBackend,
+    reference: Option<String>,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -180,6 +247,7 @@ impl fmt::Debug for EncryptedSecret {
             .field("kind", &self.kind)
             .field("version", &self.version)
             .field("algorithm", &self.algorithm)
+            .field("backend", &self.backend)
             .field("tenancy", &self.tenancy)
             .field("timestamp", &self.timestamp)
             .field("visibility", &self.visibility)
@@ -209,6 +277,7 @@ impl EncryptedSecret {
         version: SecretVersion,
         algorithm: SecretAlgorithm,
     ) -> SecretResult<Secret> {
+        Self::ensure_current_key_pair(ctx, key_pair_pk).await?;
         let name = name.as_ref();
 
         let row = ctx
@@ -235,6 +304,53 @@ impl EncryptedSecret {
         Ok(object)
     }
 
+    /// Creates a new secret whose material lives in an external [`SecretBackend`] and returns a
+    /// corresponding [`Secret`] representation. Only the reference is stored, and it gets
+    /// resolved by cyclone right before a function using the secret executes, relative to the
+    /// `<workspace_pk>/` prefix of the workspace in the backend.
+    #[allow(clippy::new_ret_no_self)]
+    pub async fn new_reference(
+        ctx: &DalContext,
+        name: impl AsRef<str>,
+        object_type: SecretObjectType,
+        kind: SecretKind,
+        backend: SecretBackend,
+        reference: impl AsRef<str>,
+        key_pair_pk: KeyPairPk,
+    ) -> SecretResult<Secret> {
+        if !backend.is_external() {
+            return Err(SecretError::NotAReferenceBackend(backend));
+        }
+        Self::ensure_current_key_pair(ctx, key_pair_pk).await?;
+        let name = name.as_ref();
+        let reference = reference.as_ref();
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM encrypted_secret_reference_create_v1($1, $2, $3, $4, $5, $6, \
+                    $7, $8, $9, $10)",
+                &[
+                    ctx.tenancy(),
+                    ctx.visibility(),
+                    &name,
+                    &object_type.as_ref(),
+                    &kind.as_ref(),
+                    &backend.as_ref(),
+                    &reference,
+                    &SecretVersion::default().as_ref(),
+                    &SecretAlgorithm::default().as_ref(),
+                    &key_pair_pk,
+                ],
+            )
+            .await?;
+        let object: Secret = standard_model::finish_create_from_row(ctx, row).await?;
+
+        Ok(object)
+    }
+
     standard_model_accessor!(name, String, SecretResult);
 
     // Once created, these object fields are to be considered immutable
@@ -242,10 +358,113 @@ impl EncryptedSecret {
     standard_model_accessor_ro!(kind, SecretKind);
     standard_model_accessor_ro!(version, SecretVersion);
     standard_model_accessor_ro!(algorithm, SecretAlgorithm);
+    standard_model_accessor_ro!(backend, SecretBackend);
+
+    /// The location of the secret in its external [`SecretBackend`], if it isn't stored in the
+    /// database.
+    pub fn reference(&self) -> Option<&str> {
+        self.reference.as_deref()
+    }
+
+    /// Replaces the encrypted payload of the secret while keeping its id, so that everything
+    /// referencing the secret uses the new payload from now on.
+    pub async fn update_encrypted_contents(
+        &mut self,
+        ctx: &DalContext,
+        crypted: &[u8],
+        key_pair_pk: KeyPairPk,
+        version: SecretVersion,
+        algorithm: SecretAlgorithm,
+    ) -> SecretResult<()> {
+        Self::ensure_current_key_pair(ctx, key_pair_pk).await?;
+
+        let table = Self::table_name();
+        standard_model::update(
+            ctx,
+            table,
+            "crypted",
+            self.id(),
+            &encode_crypted(crypted),
+            TypeHint::Text,
+        )
+        .await?;
+        standard_model::update(
+            ctx,
+            table,
+            "key_pair_pk",
+            self.id(),
+            &key_pair_pk,
+            TypeHint::Ident,
+        )
+        .await?;
+        standard_model::update(
+            ctx,
+            table,
+            "version",
+            self.id(),
+            &version.as_ref(),
+            TypeHint::Text,
+        )
+        .await?;
+        standard_model::update(
+            ctx,
+            table,
+            "algorithm",
+            self.id(),
+            &algorithm.as_ref(),
+            TypeHint::Text,
+        )
+        .await?;
+        // The payload now lives in the database, even if it used to be an external reference
+        standard_model::update(
+            ctx,
+            table,
+            "backend",
+            self.id(),
+            &SecretBackend::Postgres.as_ref(),
+            TypeHint::Text,
+        )
+        .await?;
+        let updated_at = standard_model::update(
+            ctx,
+            table,
+            "reference",
+            self.id(),
+            &None::<String>,
+            TypeHint::Text,
+        )
+        .await?;
+        // The payload itself never ends up in the history
+        let _history_event = HistoryEvent::new(
+            ctx,
+            Self::history_event_label(vec!["updated"]),
+            Self::history_event_message("updated"),
+            &serde_json::json!({
+                "pk": self.pk,
+                "field": "crypted",
+                "key_pair_pk": key_pair_pk,
+                "version": version,
+                "algorithm": algorithm,
+            }),
+        )
+        .await?;
+        self.timestamp.updated_at = updated_at;
+        self.crypted = crypted.to_vec();
+        self.key_pair_pk = key_pair_pk;
+        self.version = version;
+        self.algorithm = algorithm;
+        self.backend = SecretBackend::Postgres;
+        self.reference = None;
+
+        Ok(())
+    }
 
     /// Decrypts the encrypted secret with its associated [`KeyPair`] and returns a
     /// [`DecryptedSecret`].
     pub async fn decrypt(self, ctx: &DalContext) -> SecretResult<DecryptedSecret> {
+        if self.backend.is_external() {
+            return Err(SecretError::ExternalBackend(self.backend));
+        }
         let key_pair = self.key_pair(ctx).await?;
         self.into_decrypted(key_pair.public_key(), key_pair.secret_key())
     }
@@ -270,6 +489,76 @@ impl EncryptedSecret {
     pub async fn key_pair(&self, ctx: &DalContext) -> SecretResult<KeyPair> {
         Ok(KeyPair::get_by_pk(ctx, self.key_pair_pk).await?)
     }
+
+    /// Re-encrypts every encrypted secret sealed to the `from` [`KeyPair`] so that it is sealed
+    /// to the `to` [`KeyPair`] instead, returning how many secrets were re-encrypted.
+    ///
+    /// This works on the raw rows, so the secrets of every change set (deleted or not) move to the
+    /// new [`KeyPair`] and the old one can be retired.
+    pub async fn reencrypt_for_key_pair(
+        ctx: &DalContext,
+        from: &KeyPair,
+        to: &KeyPair,
+    ) -> SecretResult<usize> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST_FOR_KEY_PAIR, &[&from.pk()])
+            .await?;
+        let encrypted_secrets: Vec<Self> = standard_model::objects_from_rows(rows)?;
+
+        for encrypted_secret in &encrypted_secrets {
+            // Secrets stored in an external backend have nothing to re-encrypt
+            let crypted = if encrypted_secret.backend.is_external() {
+                Vec::new()
+            } else {
+                encrypted_secret.reencrypt(from.public_key(), from.secret_key(), to.public_key())?
+            };
+            ctx.txns()
+                .await?
+                .pg()
+                .execute(
+                    REENCRYPT_BY_PK,
+                    &[
+                        &encrypted_secret.pk,
+                        &encode_crypted(&crypted),
+                        &to.pk(),
+                        &SecretVersion::V1.as_ref(),
+                        &SecretAlgorithm::Sealedbox.as_ref(),
+                    ],
+                )
+                .await?;
+        }
+
+        Ok(encrypted_secrets.len())
+    }
+
+    /// Older key pairs get retired when the key pair of the workspace is rotated, so secrets may
+    /// only be sealed to the current one.
+    async fn ensure_current_key_pair(ctx: &DalContext, key_pair_pk: KeyPairPk) -> SecretResult<()> {
+        if KeyPair::get_current(ctx).await?.pk() != key_pair_pk {
+            return Err(SecretError::KeyPairNotCurrent(key_pair_pk));
+        }
+        Ok(())
+    }
+
+    /// Opens the encrypted payload with the current keys and seals it again to `new_pkey`, using
+    /// the current version and algorithm.
+    fn reencrypt(
+        &self,
+        pkey: &PublicKey,
+        skey: &SecretKey,
+        new_pkey: &PublicKey,
+    ) -> SecretResult<Vec<u8>> {
+        match (self.version, self.algorithm) {
+            (SecretVersion::V1, SecretAlgorithm::Sealedbox) => {
+                let message = sealedbox::open(&self.crypted, pkey, skey)
+                    .map_err(|_| SecretError::DecryptionFailed)?;
+                Ok(sealedbox::seal(&message, new_pkey))
+            }
+        }
+    }
 }
 
 /// A secret that has been decrypted.
@@ -355,6 +644,36 @@ impl Default for SecretAlgorithm {
     }
 }
 
+/// Where the material of a secret is stored.
+#[remain::sorted]
+#[derive(
+    AsRefStr, Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, PartialEq, Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum SecretBackend {
+    /// A file on the hosts running cyclone, relative to their configured secrets directory
+    File,
+    /// A Vault-compatible HTTP API, configured on the hosts running cyclone
+    Http,
+    /// Encrypted in the database, alongside the secret itself
+    Postgres,
+}
+
+impl Default for SecretBackend {
+    fn default() -> Self {
+        Self::Postgres
+    }
+}
+
+impl SecretBackend {
+    /// Whether secrets are stored outside of the database, as a reference which cyclone resolves
+    /// right before executing a function.
+    pub fn is_external(&self) -> bool {
+        !matches!(self, Self::Postgres)
+    }
+}
+
 /// The object type of a secret.
 #[remain::sorted]
 #[derive(
@@ -445,6 +764,8 @@ mod tests {
                 crypted,
                 version: Default::default(),
                 algorithm: Default::default(),
+                backend: Default::default(),
+                reference: None,
                 tenancy: Tenancy::new(wid),
                 timestamp: Timestamp::now(),
                 visibility: Visibility::new_head(false),
@@ -486,6 +807,33 @@ mod tests {
             assert_eq!(SecretKind::DockerHub, decrypted.secret_kind);
             assert_eq!(message, decrypted.message);
         }
+
+        #[test]
+        fn reencrypt() {
+            sodiumoxide::init().expect("crypto failed to init");
+            let (pkey, skey) = box_::gen_keypair();
+            let (new_pkey, new_skey) = box_::gen_keypair();
+
+            let message = serde_json::json!({"username": "Sturgill", "password": "Turtles"});
+            let crypted = crypt(&message, &pkey);
+
+            let mut encrypted = encrypted_secret(
+                "sturgill-simpson",
+                SecretObjectType::Credential,
+                SecretKind::DockerHub,
+                crypted,
+                WorkspacePk::NONE,
+            );
+            encrypted.crypted = encrypted
+                .reencrypt(&pkey, &skey, &new_pkey)
+                .expect("could not reencrypt secret");
+
+            assert!(encrypted.clone().into_decrypted(&pkey, &skey).is_err());
+            let decrypted = encrypted
+                .into_decrypted(&new_pkey, &new_skey)
+                .expect("could not decrypt secret");
+            assert_eq!(message, decrypted.message);
+        }
     }
 
     mod secret_object_type {
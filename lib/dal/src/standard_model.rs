@@ -607,14 +607,18 @@ pub async fn finish_create_from_row<Object: Send + Sync + DeserializeOwned + Sta
     row: PgRow,
 ) -> StandardModelResult<Object> {
     let json: serde_json::Value = row.try_get("object")?;
+    let object: Object = serde_json::from_value(json)?;
     let _history_event = HistoryEvent::new(
         ctx,
         Object::history_event_label(vec!["create"]),
         Object::history_event_message("created"),
-        &serde_json::json![{ "visibility": ctx.visibility() }],
+        &serde_json::json![{
+            "pk": object.pk(),
+            "id": object.id(),
+            "visibility": ctx.visibility(),
+        }],
     )
     .await?;
-    let object: Object = serde_json::from_value(json)?;
     Ok(object)
 }
 
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
+use strum::{AsRefStr, Display, EnumString};
 use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::task::JoinError;
@@ -11,6 +12,7 @@ use crate::{
 };
 
 const USER_GET_BY_PK: &str = include_str!("queries/user/get_by_pk.sql");
+const USER_WORKSPACE_ROLE: &str = include_str!("queries/user/workspace_role.sql");
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -33,12 +35,50 @@ pub enum UserError {
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
     Transactions(#[from] TransactionsError),
+    #[error("unknown workspace role: {0}")]
+    UnknownWorkspaceRole(String),
 }
 
 pub type UserResult<T> = Result<T, UserError>;
 
 pk!(UserPk);
 
+/// What a [`User`] is allowed to do in a [`Workspace`](crate::Workspace) they belong to.
+#[remain::sorted]
+#[derive(
+    AsRefStr, Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, PartialEq, Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum WorkspaceRole {
+    /// Can edit the workspace and review change sets
+    Approver,
+    /// Can edit the workspace
+    Editor,
+    /// Can do everything, including managing who belongs to the workspace
+    Owner,
+    /// Can only look at the workspace
+    Viewer,
+}
+
+impl WorkspaceRole {
+    /// Whether the role allows changing anything in the workspace.
+    pub fn can_edit(&self) -> bool {
+        !matches!(self, Self::Viewer)
+    }
+
+    /// Whether the role allows approving or rejecting change sets.
+    pub fn can_approve(&self) -> bool {
+        matches!(self, Self::Owner | Self::Approver)
+    }
+
+    /// Whether the role allows managing the workspace itself, such as its policies, its keys and
+    /// the roles of its members.
+    pub fn can_manage(&self) -> bool {
+        matches!(self, Self::Owner)
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct User {
     pk: UserPk,
@@ -117,11 +157,82 @@ impl User {
         }
     }
 
-    pub async fn authorize(_ctx: &DalContext, _user_pk: &UserPk) -> UserResult<bool> {
-        // TODO(paulo,theo): implement capabilities through auth0
-        Ok(true)
+    /// Whether the user belongs to the workspace of the [`DalContext`]'s tenancy.
+    pub async fn authorize(ctx: &DalContext, user_pk: &UserPk) -> UserResult<bool> {
+        Ok(Self::role_in_tenancy(ctx, user_pk).await?.is_some())
+    }
+
+    /// The [`WorkspaceRole`] of the user in the workspace of the [`DalContext`]'s tenancy, if they
+    /// belong to it.
+    pub async fn role_in_tenancy(
+        ctx: &DalContext,
+        user_pk: &UserPk,
+    ) -> UserResult<Option<WorkspaceRole>> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(UserError::NoWorkspaceInTenancy)?;
+        Self::workspace_role(ctx, *user_pk, workspace_pk).await
+    }
+
+    /// The [`WorkspaceRole`] of the user in the given workspace, if they belong to it.
+    pub async fn workspace_role(
+        ctx: &DalContext,
+        user_pk: UserPk,
+        workspace_pk: WorkspacePk,
+    ) -> UserResult<Option<WorkspaceRole>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(USER_WORKSPACE_ROLE, &[&user_pk, &workspace_pk])
+            .await?;
+        match row {
+            Some(row) => {
+                let role: String = row.try_get("role")?;
+                let role = role
+                    .parse()
+                    .map_err(|_| UserError::UnknownWorkspaceRole(role))?;
+                Ok(Some(role))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Sets the [`WorkspaceRole`] of the user in the given workspace, associating them with it if
+    /// they don't belong to it yet.
+    pub async fn set_workspace_role(
+        &self,
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+        role: WorkspaceRole,
+    ) -> UserResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "SELECT user_set_workspace_role_v1($1, $2, $3)",
+                &[&self.pk, &workspace_pk, &role.as_ref()],
+            )
+            .await?;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "user.set_workspace_role".to_owned(),
+            "User workspace role set".to_owned(),
+            &serde_json::json![{
+                "user_pk": self.pk,
+                "workspace_pk": workspace_pk,
+                "role": role,
+            }],
+        )
+        .await?;
+
+        Ok(())
     }
 
+    /// Makes the user belong to the given workspace as an [`editor`](WorkspaceRole::Editor),
+    /// keeping their current role if they already belong to it.
     pub async fn associate_workspace(
         &self,
         ctx: &DalContext,
@@ -1,13 +1,15 @@
 use serde::{Deserialize, Serialize};
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
+use si_pkg::PkgPublicKey;
+use strum::{AsRefStr, Display, EnumString};
 use telemetry::prelude::*;
 use thiserror::Error;
 
 use crate::{
     pk, standard_model, standard_model_accessor_ro, DalContext, HistoryActor, HistoryEvent,
     HistoryEventError, KeyPair, KeyPairError, StandardModelError, Tenancy, Timestamp,
-    TransactionsError, User, UserError, UserPk,
+    TransactionsError, User, UserError, UserPk, WorkspaceRole,
 };
 
 const WORKSPACE_GET_BY_PK: &str = include_str!("queries/workspace/get_by_pk.sql");
@@ -18,6 +20,8 @@ const WORKSPACE_FIND_BY_NAME: &str = include_str!("queries/workspace/find_by_nam
 pub enum WorkspaceError {
     #[error(transparent)]
     HistoryEvent(#[from] HistoryEventError),
+    #[error("invalid number of required change set approvals: {0}")]
+    InvalidRequiredApprovals(i32),
     #[error(transparent)]
     KeyPair(#[from] KeyPairError),
     #[error(transparent)]
@@ -38,6 +42,33 @@ pub type WorkspaceResult<T> = Result<T, WorkspaceError>;
 
 pk!(WorkspacePk);
 
+/// Which packages may be installed in a [`Workspace`], based on who signed them.
+#[remain::sorted]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Display,
+    EnumString,
+    Eq,
+    PartialEq,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum PkgTrustPolicy {
+    /// Any package can be installed, signed or not
+    #[default]
+    AllowUnsigned,
+    /// Only packages with a valid signature can be installed, whoever signed them
+    RequireSigned,
+    /// Only packages signed by one of the workspace's trusted keys can be installed
+    RequireTrusted,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct WorkspaceSignup {
     pub key_pair: KeyPair,
@@ -49,6 +80,14 @@ pub struct WorkspaceSignup {
 pub struct Workspace {
     pk: WorkspacePk,
     name: String,
+    /// How many users must approve a [`ChangeSet`](crate::ChangeSet) before it can be applied.
+    #[serde(default)]
+    change_set_required_approvals: i32,
+    #[serde(default)]
+    pkg_trust_policy: PkgTrustPolicy,
+    /// The keys trusted to sign packages when the policy is [`PkgTrustPolicy::RequireTrusted`].
+    #[serde(default)]
+    pkg_trusted_keys: Vec<PkgPublicKey>,
     #[serde(flatten)]
     timestamp: Timestamp,
 }
@@ -125,6 +164,8 @@ impl Workspace {
             None::<&str>,
         )
         .await?;
+        user.set_workspace_role(ctx, workspace.pk, WorkspaceRole::Owner)
+            .await?;
         ctx.update_history_actor(HistoryActor::User(user.pk()));
 
         ctx.import_builtins().await?;
@@ -165,5 +206,82 @@ impl Workspace {
         }
     }
 
+    /// Sets how many users must approve a [`ChangeSet`](crate::ChangeSet) in this workspace
+    /// before it can be applied. Zero disables the approval workflow.
+    #[instrument(skip_all)]
+    pub async fn set_change_set_required_approvals(
+        &mut self,
+        ctx: &DalContext,
+        required_approvals: i32,
+    ) -> WorkspaceResult<()> {
+        if required_approvals < 0 {
+            return Err(WorkspaceError::InvalidRequiredApprovals(required_approvals));
+        }
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM workspace_update_change_set_required_approvals_v1($1, $2)",
+                &[&self.pk, &required_approvals],
+            )
+            .await?;
+        *self = standard_model::object_from_row(row)?;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "workspace.change_set_required_approvals",
+            "Workspace change set approval policy updated",
+            &serde_json::json![{
+                "pk": &self.pk,
+                "change_set_required_approvals": required_approvals,
+            }],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Sets which packages can be installed in this workspace, see [`PkgTrustPolicy`].
+    #[instrument(skip_all)]
+    pub async fn set_pkg_trust_policy(
+        &mut self,
+        ctx: &DalContext,
+        pkg_trust_policy: PkgTrustPolicy,
+        pkg_trusted_keys: Vec<PkgPublicKey>,
+    ) -> WorkspaceResult<()> {
+        let encoded_keys: Vec<String> =
+            pkg_trusted_keys.iter().map(|key| key.to_base64()).collect();
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM workspace_update_pkg_trust_policy_v1($1, $2, $3)",
+                &[&self.pk, &pkg_trust_policy.as_ref(), &encoded_keys],
+            )
+            .await?;
+        *self = standard_model::object_from_row(row)?;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "workspace.pkg_trust_policy",
+            "Workspace package trust policy updated",
+            &serde_json::json![{
+                "pk": &self.pk,
+                "pkg_trust_policy": pkg_trust_policy,
+                "pkg_trusted_keys": encoded_keys,
+            }],
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub fn pkg_trusted_keys(&self) -> &[PkgPublicKey] {
+        &self.pkg_trusted_keys
+    }
+
     standard_model_accessor_ro!(name, String);
+    standard_model_accessor_ro!(change_set_required_approvals, i32);
+    standard_model_accessor_ro!(pkg_trust_policy, PkgTrustPolicy);
 }
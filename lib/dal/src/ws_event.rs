@@ -3,6 +3,7 @@ use si_data_nats::NatsError;
 use si_data_pg::PgError;
 use thiserror::Error;
 
+use crate::change_set::approval::ChangeSetApprovalPayload;
 use crate::component::confirmation::ConfirmationsUpdatedPayload;
 use crate::component::ComponentCreatedPayload;
 use crate::{
@@ -39,6 +40,7 @@ pub type WsEventResult<T> = Result<T, WsEventError>;
 #[allow(clippy::large_enum_variant)]
 pub enum WsPayload {
     ChangeSetApplied(ChangeSetPk),
+    ChangeSetApprovalUpdated(ChangeSetApprovalPayload),
     ChangeSetCanceled(ChangeSetPk),
     ChangeSetCreated(ChangeSetPk),
     ChangeSetWritten(ChangeSetPk),
@@ -1,5 +1,23 @@
-use dal::{ChangeSet, ChangeSetStatus, DalContext, Visibility};
-use dal_test::{helpers::create_change_set, test, DalContextHeadMutRef, DalContextHeadRef};
+use std::collections::HashMap;
+
+use dal::change_set::{
+    approval::ChangeSetApprovalStatus,
+    diff::{ComponentChangeSetDiff, PropValueDiff},
+    rebase::RebaseResolution,
+};
+use dal::change_status::ChangeStatus;
+use dal::{
+    attribute::context::AttributeContextBuilder, component::view::ComponentView,
+    AttributeReadContext, AttributeValue, AttributeValueId, ChangeSet, ChangeSetConflict,
+    ChangeSetError, ChangeSetStatus, Component, ComponentId, DalContext, HistoryActor, Prop,
+    PropId, PropKind, StandardModel, Tenancy, Visibility, Workspace, WorkspaceError,
+};
+use dal_test::{
+    helpers::{component_bag::ComponentBagger, create_change_set, create_user},
+    test,
+    test_harness::{create_schema, create_schema_variant_with_root, create_workspace},
+    DalContextHeadMutRef, DalContextHeadRef,
+};
 
 #[test]
 async fn new(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
@@ -84,3 +102,620 @@ async fn get_by_pk(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
         .expect("change set pk should exist");
     assert_eq!(&change_set, &result);
 }
+
+#[test]
+async fn abandon(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let a_change_set = create_change_set(ctx).await;
+    let mut b_change_set = create_change_set(ctx).await;
+
+    b_change_set
+        .abandon(ctx)
+        .await
+        .expect("cannot abandon change set");
+    assert_eq!(&b_change_set.status, &ChangeSetStatus::Abandoned);
+
+    let open_list = ChangeSet::list_open(ctx)
+        .await
+        .expect("cannot get list of open change sets");
+    assert_eq!(open_list.len(), 1);
+    assert!(
+        open_list.iter().any(|f| f.label == a_change_set.name),
+        "change set has remaining entry"
+    );
+
+    b_change_set
+        .abandon(ctx)
+        .await
+        .expect_err("abandoning an abandoned change set should fail");
+}
+
+#[test]
+async fn abandon_from_another_workspace(ctx: &mut DalContext) {
+    let mut change_set = create_change_set(ctx).await;
+
+    let other_workspace = create_workspace(ctx).await;
+    ctx.update_tenancy(Tenancy::new(*other_workspace.pk()));
+
+    let error = change_set
+        .abandon(ctx)
+        .await
+        .expect_err("abandoning another workspace's change set should fail");
+    assert!(
+        matches!(error, ChangeSetError::ChangeSetNotFound(pk) if pk == change_set.pk),
+        "unexpected error: {error:?}"
+    );
+    assert_eq!(&change_set.status, &ChangeSetStatus::Open);
+}
+
+#[test]
+async fn close(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let mut change_set = create_change_set(ctx).await;
+
+    change_set
+        .close(ctx)
+        .await
+        .expect("cannot close change set");
+    assert_eq!(&change_set.status, &ChangeSetStatus::Closed);
+
+    let result = ChangeSet::get_by_pk(ctx, &change_set.pk)
+        .await
+        .expect("cannot get change set by pk")
+        .expect("change set pk should exist");
+    assert_eq!(&result.status, &ChangeSetStatus::Closed);
+    assert!(ChangeSet::list_open(ctx)
+        .await
+        .expect("cannot get list of open change sets")
+        .is_empty());
+}
+
+#[test]
+async fn rebase(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let mut change_set = create_change_set(ctx).await;
+    assert_eq!(change_set.rebase_base(), change_set.timestamp.created_at);
+
+    let summary = change_set
+        .rebase_summary(ctx)
+        .await
+        .expect("cannot summarize rebase");
+    assert!(summary.conflicts.is_empty());
+
+    change_set
+        .rebase(ctx, &HashMap::new())
+        .await
+        .expect("cannot rebase change set");
+    let rebased_at = change_set.rebased_at.expect("rebased_at should be set");
+    assert_eq!(change_set.rebase_base(), rebased_at);
+
+    let result = ChangeSet::get_by_pk(ctx, &change_set.pk)
+        .await
+        .expect("cannot get change set by pk")
+        .expect("change set pk should exist");
+    assert_eq!(result.rebased_at, Some(rebased_at));
+
+    let summary = change_set
+        .rebase_summary(ctx)
+        .await
+        .expect("cannot summarize rebase");
+    assert_eq!(summary.base, rebased_at);
+}
+
+/// A component on _head_ with a `/root/domain/array_prop` of strings, along with the ids of the
+/// array and element props.
+async fn create_array_component(ctx: &DalContext) -> (ComponentId, PropId, PropId) {
+    let mut schema = create_schema(ctx).await;
+    let (mut schema_variant, root) = create_schema_variant_with_root(ctx, *schema.id()).await;
+    schema
+        .set_default_schema_variant_id(ctx, Some(*schema_variant.id()))
+        .await
+        .expect("cannot set default schema variant");
+    let array_prop = Prop::new(
+        ctx,
+        "array_prop",
+        PropKind::Array,
+        None,
+        *schema_variant.id(),
+        Some(root.domain_prop_id),
+    )
+    .await
+    .expect("could not create prop");
+    let element_prop = Prop::new(
+        ctx,
+        "array_element",
+        PropKind::String,
+        None,
+        *schema_variant.id(),
+        Some(*array_prop.id()),
+    )
+    .await
+    .expect("could not create prop");
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("cannot finalize SchemaVariant");
+
+    let (component, _) =
+        Component::new_for_default_variant_from_schema(ctx, "rebased", *schema.id())
+            .await
+            .expect("cannot create component");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    (*component.id(), *array_prop.id(), *element_prop.id())
+}
+
+async fn insert_array_element(
+    ctx: &DalContext,
+    component_id: ComponentId,
+    array_prop_id: PropId,
+    element_prop_id: PropId,
+    value: &str,
+) -> AttributeValueId {
+    let base_context = AttributeReadContext {
+        prop_id: None,
+        component_id: Some(component_id),
+        ..AttributeReadContext::default()
+    };
+    let array_value = AttributeValue::find_for_context(
+        ctx,
+        AttributeReadContext {
+            prop_id: Some(array_prop_id),
+            ..base_context
+        },
+    )
+    .await
+    .expect("cannot get array AttributeValue")
+    .expect("array AttributeValue not found");
+    let insert_context = AttributeContextBuilder::from(base_context)
+        .set_prop_id(element_prop_id)
+        .to_context()
+        .expect("cannot build write AttributeContext");
+
+    AttributeValue::insert_for_context(
+        ctx,
+        insert_context,
+        *array_value.id(),
+        Some(serde_json::json!(value)),
+        None,
+    )
+    .await
+    .expect("cannot insert array element");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    *array_value.id()
+}
+
+async fn array_elements(ctx: &DalContext, component_id: ComponentId) -> Vec<serde_json::Value> {
+    let view = ComponentView::new(ctx, component_id)
+        .await
+        .expect("cannot get component view");
+    view.properties["domain"]["array_prop"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Creates a change set where `/root/domain/array_prop` of the returned component got an
+/// "ours" element, while it got a "theirs" element on _head_ in the meantime. The `ctx` is left
+/// in the change set.
+async fn create_conflicting_change_set(
+    ctx: &mut DalContext,
+) -> (ChangeSet, ComponentId, AttributeValueId) {
+    let (component_id, array_prop_id, element_prop_id) = create_array_component(ctx).await;
+
+    let change_set = create_change_set(ctx).await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    ctx.update_visibility(Visibility::new_change_set(change_set.pk, false));
+    let array_value_id =
+        insert_array_element(ctx, component_id, array_prop_id, element_prop_id, "ours").await;
+
+    ctx.update_visibility(Visibility::new_head(false));
+    insert_array_element(ctx, component_id, array_prop_id, element_prop_id, "theirs").await;
+
+    ctx.update_visibility(Visibility::new_change_set(change_set.pk, false));
+    (change_set, component_id, array_value_id)
+}
+
+fn resolve_all(
+    conflicts: &[ChangeSetConflict],
+    resolution: RebaseResolution,
+) -> HashMap<AttributeValueId, RebaseResolution> {
+    conflicts
+        .iter()
+        .map(|conflict| (conflict.attribute_value_id, resolution))
+        .collect()
+}
+
+#[test]
+async fn rebase_requires_conflicts_to_be_resolved(
+    DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>,
+) {
+    let (mut change_set, _, array_value_id) = create_conflicting_change_set(ctx).await;
+
+    let summary = change_set
+        .rebase_summary(ctx)
+        .await
+        .expect("cannot summarize rebase");
+    assert!(summary
+        .conflicts
+        .iter()
+        .any(|conflict| conflict.attribute_value_id == array_value_id));
+    assert_eq!(
+        summary.conflicts,
+        change_set
+            .list_conflicts(ctx)
+            .await
+            .expect("cannot list conflicts")
+    );
+
+    let result = change_set.rebase(ctx, &HashMap::new()).await;
+    match result {
+        Err(ChangeSetError::UnresolvedRebaseConflicts(unresolved)) => {
+            assert!(unresolved.contains(&array_value_id));
+        }
+        other => panic!("expected unresolved rebase conflicts, got: {other:?}"),
+    }
+    assert!(change_set.rebased_at.is_none());
+
+    // Leaving out a single conflict is enough to refuse rebasing
+    let mut resolutions = resolve_all(&summary.conflicts, RebaseResolution::Ours);
+    resolutions.remove(&array_value_id);
+    let result = change_set.rebase(ctx, &resolutions).await;
+    match result {
+        Err(ChangeSetError::UnresolvedRebaseConflicts(unresolved)) => {
+            assert_eq!(vec![array_value_id], unresolved);
+        }
+        other => panic!("expected unresolved rebase conflicts, got: {other:?}"),
+    }
+}
+
+#[test]
+async fn rebase_keeping_ours(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let (mut change_set, component_id, _) = create_conflicting_change_set(ctx).await;
+
+    let summary = change_set
+        .rebase_summary(ctx)
+        .await
+        .expect("cannot summarize rebase");
+    change_set
+        .rebase(
+            ctx,
+            &resolve_all(&summary.conflicts, RebaseResolution::Ours),
+        )
+        .await
+        .expect("cannot rebase change set");
+
+    assert!(array_elements(ctx, component_id)
+        .await
+        .contains(&serde_json::json!("ours")));
+    assert!(change_set
+        .list_conflicts(ctx)
+        .await
+        .expect("cannot list conflicts")
+        .is_empty());
+}
+
+#[test]
+async fn rebase_taking_theirs(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let (mut change_set, component_id, _) = create_conflicting_change_set(ctx).await;
+
+    let summary = change_set
+        .rebase_summary(ctx)
+        .await
+        .expect("cannot summarize rebase");
+    change_set
+        .rebase(
+            ctx,
+            &resolve_all(&summary.conflicts, RebaseResolution::Theirs),
+        )
+        .await
+        .expect("cannot rebase change set");
+
+    // The element added under the array in the change set goes away along with the array
+    assert_eq!(
+        vec![serde_json::json!("theirs")],
+        array_elements(ctx, component_id).await
+    );
+    assert!(change_set
+        .list_conflicts(ctx)
+        .await
+        .expect("cannot list conflicts")
+        .is_empty());
+}
+
+#[test]
+async fn apply_without_conflicts(ctx: &mut DalContext) {
+    let mut change_set = ChangeSet::get_by_pk(ctx, &ctx.visibility().change_set_pk)
+        .await
+        .expect("could not perform get by pk")
+        .expect("could not get change set");
+
+    let conflicts = change_set
+        .list_conflicts(ctx)
+        .await
+        .expect("cannot list conflicts");
+    assert!(conflicts.is_empty());
+
+    change_set
+        .apply_raw(ctx, false, false)
+        .await
+        .expect("cannot apply change set");
+    assert_eq!(&change_set.status, &ChangeSetStatus::Applied);
+}
+
+#[test]
+async fn apply_with_conflicts(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let (mut change_set, _, array_value_id) = create_conflicting_change_set(ctx).await;
+
+    let result = change_set.apply(ctx).await;
+    match result {
+        Err(ChangeSetError::Conflicts(conflicts)) => {
+            assert!(conflicts
+                .iter()
+                .any(|conflict| conflict.attribute_value_id == array_value_id));
+        }
+        other => panic!("expected conflicts, got: {other:?}"),
+    }
+    assert_eq!(&change_set.status, &ChangeSetStatus::Open);
+    let result = ChangeSet::get_by_pk(ctx, &change_set.pk)
+        .await
+        .expect("cannot get change set by pk")
+        .expect("change set pk should exist");
+    assert_eq!(&result.status, &ChangeSetStatus::Open);
+}
+
+#[test]
+async fn apply_with_conflicts_forced(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let (mut change_set, component_id, _) = create_conflicting_change_set(ctx).await;
+    assert!(!change_set
+        .list_conflicts(ctx)
+        .await
+        .expect("cannot list conflicts")
+        .is_empty());
+
+    change_set
+        .apply_raw(ctx, false, true)
+        .await
+        .expect("cannot force apply change set");
+    assert_eq!(&change_set.status, &ChangeSetStatus::Applied);
+
+    // The change set's version wins on head
+    ctx.update_visibility(Visibility::new_head(false));
+    assert!(array_elements(ctx, component_id)
+        .await
+        .contains(&serde_json::json!("ours")));
+}
+
+#[test]
+async fn diff(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let change_set = create_change_set(ctx).await;
+    let diff = change_set.diff(ctx).await.expect("cannot diff change set");
+    assert!(diff.components.is_empty());
+    assert!(diff.edges.is_empty());
+    assert!(diff.schema_variants.is_empty());
+    assert!(diff.funcs.is_empty());
+}
+
+#[test]
+async fn diff_with_component_changes(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let mut bagger = ComponentBagger::new();
+    let modified_bag = bagger.create_component(ctx, "modified", "starfield").await;
+    let deleted_bag = bagger.create_component(ctx, "deleted", "starfield").await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let change_set = create_change_set(ctx).await;
+    ctx.update_visibility(Visibility::new_change_set(change_set.pk, false));
+
+    let added_bag = bagger.create_component(ctx, "added", "starfield").await;
+    let freestar_prop = modified_bag
+        .find_prop(ctx, &["root", "domain", "freestar"])
+        .await;
+    modified_bag
+        .update_attribute_value_for_prop(
+            ctx,
+            *freestar_prop.id(),
+            Some(serde_json::json!("twinkle")),
+        )
+        .await;
+    deleted_bag
+        .component(ctx)
+        .await
+        .delete_and_propagate(ctx)
+        .await
+        .expect("cannot delete component");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let diff = change_set.diff(ctx).await.expect("cannot diff change set");
+    let component_diff = |component_id: ComponentId| {
+        diff.components
+            .iter()
+            .find(|component| component.component_id == component_id)
+            .expect("component missing from diff")
+    };
+    let prop_diff = |component: &ComponentChangeSetDiff, path: &str| {
+        component
+            .props
+            .iter()
+            .find(|prop| prop.path == path)
+            .cloned()
+    };
+
+    let added = component_diff(added_bag.component_id);
+    assert_eq!(ChangeStatus::Added, added.status);
+    assert_eq!("added", added.component_name);
+    assert_eq!(
+        Some(PropValueDiff {
+            path: "/root/si/name".to_owned(),
+            before: None,
+            after: Some(serde_json::json!("added")),
+        }),
+        prop_diff(added, "/root/si/name")
+    );
+
+    let modified = component_diff(modified_bag.component_id);
+    assert_eq!(ChangeStatus::Modified, modified.status);
+    assert_eq!(
+        Some(PropValueDiff {
+            path: "/root/domain/freestar".to_owned(),
+            before: None,
+            after: Some(serde_json::json!("twinkle")),
+        }),
+        prop_diff(modified, "/root/domain/freestar")
+    );
+    assert_eq!(None, prop_diff(modified, "/root/si/name"));
+
+    let deleted = component_diff(deleted_bag.component_id);
+    assert_eq!(ChangeStatus::Deleted, deleted.status);
+    assert_eq!(
+        Some(PropValueDiff {
+            path: "/root/si/name".to_owned(),
+            before: Some(serde_json::json!("deleted")),
+            after: None,
+        }),
+        prop_diff(deleted, "/root/si/name")
+    );
+    assert!(deleted.props.iter().all(|prop| prop.after.is_none()));
+}
+
+#[test]
+async fn approval_workflow(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let workspace_pk = ctx
+        .tenancy()
+        .workspace_pk()
+        .expect("no workspace in tenancy");
+    let mut workspace = Workspace::get_by_pk(ctx, &workspace_pk)
+        .await
+        .expect("cannot get workspace")
+        .expect("workspace should exist");
+    workspace
+        .set_change_set_required_approvals(ctx, 1)
+        .await
+        .expect("cannot set required approvals");
+
+    let mut change_set = create_change_set(ctx).await;
+    let result = change_set.apply(ctx).await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetError::ApprovalPolicyNotSatisfied(_))
+    ));
+
+    let user = create_user(ctx).await;
+    ctx.update_history_actor(HistoryActor::User(user.pk()));
+
+    change_set
+        .approve(ctx, None)
+        .await
+        .expect_err("cannot approve before approval is requested");
+    change_set
+        .request_approval(ctx)
+        .await
+        .expect("cannot request approval");
+    assert_eq!(change_set.approval_requested_by, Some(user.pk()));
+    let result = change_set.approve(ctx, None).await;
+    assert!(matches!(result, Err(ChangeSetError::SelfApproval(..))));
+
+    let approver = create_user(ctx).await;
+    ctx.update_history_actor(HistoryActor::User(approver.pk()));
+    let approval = change_set
+        .approve(ctx, Some("looks good".to_string()))
+        .await
+        .expect("cannot approve change set");
+    assert_eq!(approval.status, ChangeSetApprovalStatus::Approved);
+    assert_eq!(approval.user_pk, approver.pk());
+
+    let state = change_set
+        .approval_state(ctx)
+        .await
+        .expect("cannot get approval state");
+    assert_eq!(state.required_approvals, 1);
+    assert!(state.satisfied);
+
+    change_set
+        .apply(ctx)
+        .await
+        .expect("cannot apply approved change set");
+    assert_eq!(&change_set.status, &ChangeSetStatus::Applied);
+}
+
+#[test]
+async fn approvals_are_invalidated_by_edits(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let workspace_pk = ctx
+        .tenancy()
+        .workspace_pk()
+        .expect("no workspace in tenancy");
+    let mut workspace = Workspace::get_by_pk(ctx, &workspace_pk)
+        .await
+        .expect("cannot get workspace")
+        .expect("workspace should exist");
+    workspace
+        .set_change_set_required_approvals(ctx, 1)
+        .await
+        .expect("cannot set required approvals");
+    let (component_id, array_prop_id, element_prop_id) = create_array_component(ctx).await;
+
+    let mut change_set = create_change_set(ctx).await;
+    ctx.update_visibility(Visibility::new_change_set(change_set.pk, false));
+    insert_array_element(ctx, component_id, array_prop_id, element_prop_id, "first").await;
+
+    let requester = create_user(ctx).await;
+    ctx.update_history_actor(HistoryActor::User(requester.pk()));
+    change_set
+        .request_approval(ctx)
+        .await
+        .expect("cannot request approval");
+    let approver = create_user(ctx).await;
+    ctx.update_history_actor(HistoryActor::User(approver.pk()));
+    change_set
+        .approve(ctx, None)
+        .await
+        .expect("cannot approve change set");
+    let state = change_set
+        .approval_state(ctx)
+        .await
+        .expect("cannot get approval state");
+    assert!(state.satisfied);
+
+    insert_array_element(ctx, component_id, array_prop_id, element_prop_id, "second").await;
+    let state = change_set
+        .approval_state(ctx)
+        .await
+        .expect("cannot get approval state");
+    assert!(!state.satisfied);
+    assert!(state.edited_at > Some(state.approvals[0].timestamp.updated_at));
+
+    change_set
+        .approve(ctx, None)
+        .await
+        .expect("cannot approve change set");
+    let state = change_set
+        .approval_state(ctx)
+        .await
+        .expect("cannot get approval state");
+    assert!(state.satisfied);
+}
+
+#[test]
+async fn required_approvals_cannot_be_negative(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let workspace_pk = ctx
+        .tenancy()
+        .workspace_pk()
+        .expect("no workspace in tenancy");
+    let mut workspace = Workspace::get_by_pk(ctx, &workspace_pk)
+        .await
+        .expect("cannot get workspace")
+        .expect("workspace should exist");
+    let result = workspace.set_change_set_required_approvals(ctx, -1).await;
+    assert!(matches!(
+        result,
+        Err(WorkspaceError::InvalidRequiredApprovals(-1))
+    ));
+}
@@ -0,0 +1,650 @@
+use dal::{
+    edge::{EdgeKind, EdgeObjectId, VertexObjectKind},
+    fix::dependency::fix_dependencies,
+    fix::plan::{plan, FixPlanRequest},
+    job::definition::{FixItem, FixesJob},
+    socket::SocketEdgeKind,
+    ActionKind, ActionPrototype, ActionPrototypeContext, ActionPrototypeId, Component, ComponentId,
+    DalContext, Edge, Fix, FixBatch, FixBatchFailurePolicy, FixBatchId, FixCompletionStatus,
+    FixError, Func, FuncBackendKind, FuncBackendResponseType, RootPropChild, SchemaVariantId,
+    Socket, StandardModel,
+};
+use dal_test::helpers::component_bag::{ComponentBag, ComponentBagger};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn dependencies_follow_configuration_edges(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "tail", "fallout").await;
+    let starfield_bag = bagger.create_component(ctx, "head", "starfield").await;
+
+    let output_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationOutput,
+        fallout_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find'")
+    .expect("could not find socket");
+    let input_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationInput,
+        starfield_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find'")
+    .expect("could not find socket");
+
+    Edge::new(
+        ctx,
+        EdgeKind::Configuration,
+        starfield_bag.node_id,
+        VertexObjectKind::Configuration,
+        EdgeObjectId::from(starfield_bag.component_id),
+        *input_socket.id(),
+        fallout_bag.node_id,
+        VertexObjectKind::Configuration,
+        EdgeObjectId::from(fallout_bag.component_id),
+        *output_socket.id(),
+    )
+    .await
+    .expect("cannot create new edge");
+
+    let dependencies = fix_dependencies(
+        ctx,
+        &[
+            (starfield_bag.component_id, ActionKind::Create),
+            (fallout_bag.component_id, ActionKind::Create),
+            (fallout_bag.component_id, ActionKind::Delete),
+            (starfield_bag.component_id, ActionKind::Delete),
+        ],
+    )
+    .await
+    .expect("could not compute fix dependencies");
+
+    assert_eq!(
+        vec![
+            // The dependent is created after the component it gets configuration from...
+            vec![1],
+            vec![],
+            // ...and deleted before it, while actions on a component keep their order.
+            vec![1, 3],
+            vec![0],
+        ],
+        dependencies
+    );
+}
+
+#[test]
+async fn batch_failure_policy(ctx: &DalContext) {
+    let mut batch = FixBatch::new(ctx, "toddhoward@systeminit.com")
+        .await
+        .expect("could not create fix batch");
+    assert_eq!(FixBatchFailurePolicy::ContinueAll, *batch.failure_policy());
+
+    batch
+        .set_failure_policy(ctx, FixBatchFailurePolicy::SkipDependents)
+        .await
+        .expect("could not set failure policy");
+    let batch = FixBatch::get_by_id(ctx, batch.id())
+        .await
+        .expect("could not get fix batch")
+        .expect("fix batch not found");
+    assert_eq!(
+        FixBatchFailurePolicy::SkipDependents,
+        *batch.failure_policy()
+    );
+}
+
+/// Requests the create action of the component, as the confirmations would recommend.
+async fn create_request(ctx: &DalContext, bag: &ComponentBag) -> FixPlanRequest {
+    let action = ActionPrototype::find_for_context_and_kind(
+        ctx,
+        ActionKind::Create,
+        ActionPrototypeContext {
+            schema_variant_id: bag.schema_variant_id,
+        },
+    )
+    .await
+    .expect("could not find action prototypes")
+    .pop()
+    .expect("no create action prototype");
+    let attribute_value = Component::root_prop_child_attribute_value_for_component(
+        ctx,
+        bag.component_id,
+        RootPropChild::Resource,
+    )
+    .await
+    .expect("could not find resource attribute value");
+
+    FixPlanRequest {
+        attribute_value_id: *attribute_value.id(),
+        component_id: bag.component_id,
+        action_prototype_id: *action.id(),
+    }
+}
+
+async fn create_fix(ctx: &DalContext, batch_id: FixBatchId, request: FixPlanRequest) -> FixItem {
+    let fix = Fix::new(
+        ctx,
+        batch_id,
+        request.attribute_value_id,
+        request.component_id,
+        request.action_prototype_id,
+    )
+    .await
+    .expect("could not create fix");
+
+    FixItem {
+        id: *fix.id(),
+        action_prototype_id: request.action_prototype_id,
+        component_id: request.component_id,
+        attribute_value_id: request.attribute_value_id,
+    }
+}
+
+async fn completion_status(ctx: &DalContext, fix_item: &FixItem) -> Option<FixCompletionStatus> {
+    Fix::get_by_id(ctx, &fix_item.id)
+        .await
+        .expect("could not get fix")
+        .expect("fix not found")
+        .completion_status()
+        .copied()
+}
+
+#[test]
+async fn errored_fix_does_not_stop_the_batch(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let mut bags = Vec::new();
+    for name in ["first", "second", "third"] {
+        bags.push(bagger.create_component(ctx, name, "starfield").await);
+    }
+
+    let batch = FixBatch::new(ctx, "toddhoward@systeminit.com")
+        .await
+        .expect("could not create fix batch");
+    // The broken fix goes first, so the other fixes are only started if its error doesn't stop
+    // the batch.
+    let broken_request = FixPlanRequest {
+        component_id: ComponentId::generate(),
+        ..create_request(ctx, &bags[0]).await
+    };
+    let mut fixes = vec![create_fix(ctx, *batch.id(), broken_request).await];
+    for bag in &bags {
+        let request = create_request(ctx, bag).await;
+        fixes.push(create_fix(ctx, *batch.id(), request).await);
+    }
+
+    ctx.enqueue_job(FixesJob::new(ctx, fixes.clone(), *batch.id()))
+        .await
+        .expect("failed to enqueue job");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let broken = Fix::get_by_id(ctx, &fixes[0].id)
+        .await
+        .expect("could not get fix")
+        .expect("fix not found");
+    assert_eq!(
+        Some(&FixCompletionStatus::Error),
+        broken.completion_status()
+    );
+    assert!(broken.completion_message().is_some());
+    for fix_item in &fixes[1..] {
+        assert_eq!(
+            Some(FixCompletionStatus::Success),
+            completion_status(ctx, fix_item).await
+        );
+    }
+
+    let batch = FixBatch::get_by_id(ctx, batch.id())
+        .await
+        .expect("could not get fix batch")
+        .expect("fix batch not found");
+    assert!(batch.finished_at().is_some());
+    assert_eq!(Some(&FixCompletionStatus::Error), batch.completion_status());
+}
+
+/// Makes `head` receive configuration from `tail`. Only configuration edges matter when ordering
+/// fixes, so the frame sockets of both nodes do.
+async fn connect(ctx: &DalContext, tail: &ComponentBag, head: &ComponentBag) {
+    let tail_socket =
+        Socket::find_frame_socket_for_node(ctx, tail.node_id, SocketEdgeKind::ConfigurationOutput)
+            .await
+            .expect("could not find frame socket");
+    let head_socket =
+        Socket::find_frame_socket_for_node(ctx, head.node_id, SocketEdgeKind::ConfigurationInput)
+            .await
+            .expect("could not find frame socket");
+    Edge::new(
+        ctx,
+        EdgeKind::Configuration,
+        head.node_id,
+        VertexObjectKind::Configuration,
+        EdgeObjectId::from(head.component_id),
+        *head_socket.id(),
+        tail.node_id,
+        VertexObjectKind::Configuration,
+        EdgeObjectId::from(tail.component_id),
+        *tail_socket.id(),
+    )
+    .await
+    .expect("cannot create new edge");
+}
+
+#[test]
+async fn plan_orders_a_diamond(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let top = bagger.create_component(ctx, "top", "fallout").await;
+    let left = bagger.create_component(ctx, "left", "fallout").await;
+    let right = bagger.create_component(ctx, "right", "fallout").await;
+    let bottom = bagger.create_component(ctx, "bottom", "fallout").await;
+    connect(ctx, &top, &left).await;
+    connect(ctx, &top, &right).await;
+    connect(ctx, &left, &bottom).await;
+    connect(ctx, &right, &bottom).await;
+
+    // Requested bottom-up, so every step has to be moved after the ones it depends on.
+    let mut requests = Vec::new();
+    for bag in [&bottom, &right, &left, &top] {
+        requests.push(create_request(ctx, bag).await);
+    }
+
+    let steps = plan(ctx, &requests).await.expect("could not plan fixes");
+
+    assert_eq!(
+        vec![
+            ("top".to_string(), vec![]),
+            // Steps that may run at the same time keep the requested order.
+            ("right".to_string(), vec![0]),
+            ("left".to_string(), vec![0]),
+            ("bottom".to_string(), vec![0, 1, 2]),
+        ],
+        steps
+            .iter()
+            .map(|step| (step.component_name.clone(), step.depends_on.clone()))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![
+            top.component_id,
+            right.component_id,
+            left.component_id,
+            bottom.component_id
+        ],
+        steps
+            .iter()
+            .map(|step| step.component_id)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+async fn plan_falls_back_to_the_requested_order_for_a_cycle(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let first = bagger.create_component(ctx, "first", "fallout").await;
+    let second = bagger.create_component(ctx, "second", "fallout").await;
+    let third = bagger.create_component(ctx, "third", "fallout").await;
+    connect(ctx, &first, &second).await;
+    connect(ctx, &second, &first).await;
+    connect(ctx, &first, &third).await;
+
+    let mut requests = Vec::new();
+    for bag in [&third, &second, &first] {
+        requests.push(create_request(ctx, bag).await);
+    }
+
+    let steps = plan(ctx, &requests).await.expect("could not plan fixes");
+
+    // Every step is planned exactly once, "second" and "first" in the order they were requested
+    // since they receive configuration from each other, and "third" after both of them.
+    assert_eq!(
+        vec![
+            ("second".to_string(), vec![]),
+            ("first".to_string(), vec![0]),
+            ("third".to_string(), vec![0, 1]),
+        ],
+        steps
+            .iter()
+            .map(|step| (step.component_name.clone(), step.depends_on.clone()))
+            .collect::<Vec<_>>()
+    );
+}
+
+/// Adds an action to the schema variant that resolves with the given resource status.
+async fn create_action(
+    ctx: &DalContext,
+    schema_variant_id: SchemaVariantId,
+    kind: ActionKind,
+    status: &str,
+) -> ActionPrototypeId {
+    let mut func = Func::new(
+        ctx,
+        format!("test:{kind}Action{status}"),
+        FuncBackendKind::JsAction,
+        FuncBackendResponseType::Action,
+    )
+    .await
+    .expect("could not create func");
+    func.set_handler(ctx, Some("run"))
+        .await
+        .expect("could not set handler");
+    func.set_code_plaintext(
+        ctx,
+        Some(&format!(
+            "async function run() {{ return {{ status: \"{status}\" }}; }}"
+        )),
+    )
+    .await
+    .expect("could not set code");
+
+    *ActionPrototype::new(
+        ctx,
+        *func.id(),
+        kind,
+        ActionPrototypeContext { schema_variant_id },
+    )
+    .await
+    .expect("could not create action prototype")
+    .id()
+}
+
+/// Runs the requests as a batch with the given failure policy, returning the completion status of
+/// every fix, in the requested order, and of the batch.
+async fn run_batch(
+    ctx: &DalContext,
+    failure_policy: FixBatchFailurePolicy,
+    requests: Vec<FixPlanRequest>,
+) -> (
+    Vec<Option<FixCompletionStatus>>,
+    Option<FixCompletionStatus>,
+) {
+    let mut batch = FixBatch::new(ctx, "toddhoward@systeminit.com")
+        .await
+        .expect("could not create fix batch");
+    batch
+        .set_failure_policy(ctx, failure_policy)
+        .await
+        .expect("could not set failure policy");
+    let mut fixes = Vec::new();
+    for request in requests {
+        fixes.push(create_fix(ctx, *batch.id(), request).await);
+    }
+
+    ctx.enqueue_job(FixesJob::new(ctx, fixes.clone(), *batch.id()))
+        .await
+        .expect("failed to enqueue job");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let mut statuses = Vec::new();
+    for fix_item in &fixes {
+        statuses.push(completion_status(ctx, fix_item).await);
+    }
+    let batch = FixBatch::get_by_id(ctx, batch.id())
+        .await
+        .expect("could not get fix batch")
+        .expect("fix batch not found");
+    assert!(batch.finished_at().is_some());
+
+    (statuses, batch.completion_status().copied())
+}
+
+/// A failing fix, a direct and a transitive dependent of it, and an unrelated fix:
+///
+/// 0. "failing" runs an action that fails
+/// 1. "dependent" is created after 0, as it receives configuration from "failing"
+/// 2. "dependent" is deleted after 1, and only after 1
+/// 3. "unrelated" is created whenever
+async fn failure_scenario(ctx: &DalContext) -> Vec<FixPlanRequest> {
+    let mut bagger = ComponentBagger::new();
+    let failing = bagger.create_component(ctx, "failing", "fallout").await;
+    let dependent = bagger.create_component(ctx, "dependent", "fallout").await;
+    let unrelated = bagger.create_component(ctx, "unrelated", "fallout").await;
+    connect(ctx, &failing, &dependent).await;
+
+    let fail = create_action(ctx, failing.schema_variant_id, ActionKind::Other, "error").await;
+    let delete = create_action(ctx, dependent.schema_variant_id, ActionKind::Delete, "ok").await;
+    let create_dependent = create_request(ctx, &dependent).await;
+    let requests = vec![
+        FixPlanRequest {
+            action_prototype_id: fail,
+            ..create_request(ctx, &failing).await
+        },
+        create_dependent,
+        FixPlanRequest {
+            action_prototype_id: delete,
+            ..create_dependent
+        },
+        create_request(ctx, &unrelated).await,
+    ];
+
+    let dependencies = fix_dependencies(
+        ctx,
+        &[
+            (failing.component_id, ActionKind::Other),
+            (dependent.component_id, ActionKind::Create),
+            (dependent.component_id, ActionKind::Delete),
+            (unrelated.component_id, ActionKind::Create),
+        ],
+    )
+    .await
+    .expect("could not compute fix dependencies");
+    assert_eq!(vec![vec![], vec![0], vec![1], vec![]], dependencies);
+
+    requests
+}
+
+#[test]
+async fn skip_dependents_skips_transitive_dependents(ctx: &DalContext) {
+    let requests = failure_scenario(ctx).await;
+
+    let (statuses, batch_status) =
+        run_batch(ctx, FixBatchFailurePolicy::SkipDependents, requests).await;
+
+    assert_eq!(
+        vec![
+            Some(FixCompletionStatus::Failure),
+            Some(FixCompletionStatus::Skipped),
+            Some(FixCompletionStatus::Skipped),
+            Some(FixCompletionStatus::Success),
+        ],
+        statuses
+    );
+    assert_eq!(Some(FixCompletionStatus::Failure), batch_status);
+}
+
+#[test]
+async fn continue_all_runs_dependents(ctx: &DalContext) {
+    let requests = failure_scenario(ctx).await;
+
+    let (statuses, batch_status) =
+        run_batch(ctx, FixBatchFailurePolicy::ContinueAll, requests).await;
+
+    assert_eq!(
+        vec![
+            Some(FixCompletionStatus::Failure),
+            Some(FixCompletionStatus::Success),
+            Some(FixCompletionStatus::Success),
+            Some(FixCompletionStatus::Success),
+        ],
+        statuses
+    );
+    assert_eq!(Some(FixCompletionStatus::Failure), batch_status);
+}
+
+#[test]
+async fn abort_remaining_skips_unrelated_fixes(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let unrelated = bagger.create_component(ctx, "unrelated", "fallout").await;
+    let delete = create_action(ctx, unrelated.schema_variant_id, ActionKind::Delete, "ok").await;
+    let create_unrelated = create_request(ctx, &unrelated).await;
+    let requests = vec![
+        // Errors as soon as it starts, since the component doesn't exist, which is long before
+        // the unrelated create action is done running.
+        FixPlanRequest {
+            component_id: ComponentId::generate(),
+            ..create_unrelated
+        },
+        create_unrelated,
+        FixPlanRequest {
+            action_prototype_id: delete,
+            ..create_unrelated
+        },
+    ];
+
+    let (statuses, batch_status) =
+        run_batch(ctx, FixBatchFailurePolicy::AbortRemaining, requests).await;
+
+    assert_eq!(
+        vec![
+            Some(FixCompletionStatus::Error),
+            // Already running when the batch was aborted.
+            Some(FixCompletionStatus::Success),
+            Some(FixCompletionStatus::Skipped),
+        ],
+        statuses
+    );
+    assert_eq!(Some(FixCompletionStatus::Error), batch_status);
+}
+
+#[test]
+async fn cancel_before_the_fix_starts(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let bag = bagger.create_component(ctx, "cancelled", "fallout").await;
+    let batch = FixBatch::new(ctx, "toddhoward@systeminit.com")
+        .await
+        .expect("could not create fix batch");
+    let request = create_request(ctx, &bag).await;
+    let fix_item = create_fix(ctx, *batch.id(), request).await;
+
+    // Nothing is executing yet, so only the fix itself can remember the cancellation.
+    let mut fix = Fix::get_by_id(ctx, &fix_item.id)
+        .await
+        .expect("could not get fix")
+        .expect("fix not found");
+    fix.cancel(ctx).await.expect("could not cancel fix");
+    assert!(fix.cancel_requested_at().is_some());
+
+    ctx.enqueue_job(FixesJob::new(ctx, vec![fix_item.clone()], *batch.id()))
+        .await
+        .expect("failed to enqueue job");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let mut fix = Fix::get_by_id(ctx, &fix_item.id)
+        .await
+        .expect("could not get fix")
+        .expect("fix not found");
+    assert_eq!(
+        Some(&FixCompletionStatus::Cancelled),
+        fix.completion_status()
+    );
+    assert!(fix.resource().is_none());
+
+    let error = fix
+        .cancel(ctx)
+        .await
+        .expect_err("cancelling a finished fix should fail");
+    assert!(
+        matches!(error, FixError::CancelFinished(id) if id == fix_item.id),
+        "unexpected error: {error:?}"
+    );
+}
+
+#[test]
+async fn independent_fixes_run_concurrently_up_to_the_limit(ctx: &DalContext) {
+    let limit = ctx.fix_concurrency_limit();
+    let mut bagger = ComponentBagger::new();
+    let mut requests = Vec::new();
+    for index in 0..=limit {
+        let bag = bagger
+            .create_component(ctx, &format!("sleepy-{index}"), "fallout")
+            .await;
+        let mut func = Func::new(
+            ctx,
+            format!("test:sleepyAction{index}"),
+            FuncBackendKind::JsAction,
+            FuncBackendResponseType::Action,
+        )
+        .await
+        .expect("could not create func");
+        func.set_handler(ctx, Some("run"))
+            .await
+            .expect("could not set handler");
+        func.set_code_plaintext(
+            ctx,
+            Some(
+                "async function run() { await new Promise((resolve) => setTimeout(resolve, 2000)); return { status: \"ok\" }; }",
+            ),
+        )
+        .await
+        .expect("could not set code");
+        let action = ActionPrototype::new(
+            ctx,
+            *func.id(),
+            ActionKind::Other,
+            ActionPrototypeContext {
+                schema_variant_id: bag.schema_variant_id,
+            },
+        )
+        .await
+        .expect("could not create action prototype");
+        requests.push(FixPlanRequest {
+            action_prototype_id: *action.id(),
+            ..create_request(ctx, &bag).await
+        });
+    }
+
+    let mut batch = FixBatch::new(ctx, "toddhoward@systeminit.com")
+        .await
+        .expect("could not create fix batch");
+    batch
+        .set_failure_policy(ctx, FixBatchFailurePolicy::ContinueAll)
+        .await
+        .expect("could not set failure policy");
+    let mut fixes = Vec::new();
+    for request in requests {
+        fixes.push(create_fix(ctx, *batch.id(), request).await);
+    }
+    ctx.enqueue_job(FixesJob::new(ctx, fixes.clone(), *batch.id()))
+        .await
+        .expect("failed to enqueue job");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    // Every fix's run, from being stamped started to being stamped finished.
+    let mut runs = Vec::new();
+    for fix_item in &fixes {
+        let fix = Fix::get_by_id(ctx, &fix_item.id)
+            .await
+            .expect("could not get fix")
+            .expect("fix not found");
+        assert_eq!(Some(&FixCompletionStatus::Success), fix.completion_status());
+        let stamp = |stamp: Option<&str>| {
+            chrono::DateTime::parse_from_rfc3339(stamp.expect("fix not stamped"))
+                .expect("could not parse stamp")
+        };
+        runs.push((stamp(fix.started_at()), stamp(fix.finished_at())));
+    }
+
+    // The most fixes running at once is when the latest of them has just started.
+    let most_running_at_once = runs
+        .iter()
+        .map(|(started_at, _)| {
+            runs.iter()
+                .filter(|(other_started_at, other_finished_at)| {
+                    other_started_at <= started_at && started_at < other_finished_at
+                })
+                .count()
+        })
+        .max();
+    assert_eq!(Some(limit), most_running_at_once);
+}
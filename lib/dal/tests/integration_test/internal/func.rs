@@ -1,7 +1,10 @@
 use dal::{
     func::{
         argument::{FuncArgument, FuncArgumentKind},
-        backend::string::FuncBackendStringArgs,
+        backend::{
+            js_action::FuncBackendJsAction, string::FuncBackendStringArgs, FuncDispatch,
+            FuncDispatchContext,
+        },
         binding::FuncBinding,
         binding_return_value::FuncBindingReturnValue,
         execution::FuncExecution,
@@ -228,3 +231,37 @@ async fn duplicate(ctx: &DalContext) {
         new_func.handler()  // actual
     );
 }
+
+#[test]
+async fn timeout_is_sent_with_the_request(ctx: &DalContext) {
+    let mut func = Func::new(
+        ctx,
+        "slowpoke",
+        FuncBackendKind::JsAction,
+        FuncBackendResponseType::Action,
+    )
+    .await
+    .expect("could not create func");
+    func.set_handler(ctx, Some("run"))
+        .await
+        .expect("could not set handler");
+    func.set_code_plaintext(ctx, Some("function run() { return {}; }"))
+        .await
+        .expect("could not set code");
+
+    let (context, _rx) = FuncDispatchContext::new(ctx);
+    let executor = FuncBackendJsAction::create(context, &func, &serde_json::json!({}))
+        .expect("could not create executor");
+    assert_eq!(None, executor.request.timeout_secs);
+
+    func.set_timeout_secs(ctx, Some(5))
+        .await
+        .expect("could not set timeout");
+    let (context, _rx) = FuncDispatchContext::new(ctx);
+    let executor = FuncBackendJsAction::create(context, &func, &serde_json::json!({}))
+        .expect("could not create executor");
+    assert_eq!(Some(5), executor.request.timeout_secs);
+
+    let duplicate = func.duplicate(ctx).await.expect("could not duplicate func");
+    assert_eq!(func.timeout_secs(), duplicate.timeout_secs());
+}
@@ -1,5 +1,11 @@
-use dal::{DalContext, HistoryActor, HistoryEvent};
-use dal_test::test;
+use dal::{
+    Component, ComponentId, DalContext, HistoryActor, HistoryEvent, HistoryEventFilter,
+    StandardModel, UserPk,
+};
+use dal_test::{
+    test,
+    test_harness::{create_schema, create_schema_variant_with_root},
+};
 
 #[test]
 async fn new(ctx: &DalContext) {
@@ -17,3 +23,165 @@ async fn new(ctx: &DalContext) {
     assert_eq!(&history_event.data, &serde_json::json!({}));
     assert_eq!(&history_event.tenancy, ctx.tenancy());
 }
+
+#[test]
+async fn list_filtered(ctx: &DalContext) {
+    let component_id = ComponentId::generate();
+    let older = HistoryEvent::new(
+        ctx,
+        "audit.component.updated",
+        "component updated",
+        &serde_json::json!({ "component_id": component_id }),
+    )
+    .await
+    .expect("cannot create a new history event");
+    let newer = HistoryEvent::new(
+        ctx,
+        "audit.secret.updated",
+        "secret updated",
+        &serde_json::json!({}),
+    )
+    .await
+    .expect("cannot create a new history event");
+    assert_eq!(Some(component_id), older.component_id);
+    assert_eq!(Some(ctx.visibility().change_set_pk), newer.change_set_pk);
+
+    let filter = HistoryEventFilter {
+        label_prefix: Some("audit.".to_owned()),
+        ..Default::default()
+    };
+    let events = HistoryEvent::list(ctx, &filter, 0, None)
+        .await
+        .expect("cannot list history events");
+    assert_eq!(vec![newer.pk, older.pk], pks(&events));
+    assert_eq!(
+        2,
+        HistoryEvent::count(ctx, &filter)
+            .await
+            .expect("cannot count history events")
+    );
+
+    let events = HistoryEvent::list(ctx, &filter, 1, Some(1))
+        .await
+        .expect("cannot list history events");
+    assert_eq!(vec![older.pk], pks(&events));
+
+    let events = HistoryEvent::list(
+        ctx,
+        &HistoryEventFilter {
+            component_id: Some(component_id),
+            ..Default::default()
+        },
+        0,
+        None,
+    )
+    .await
+    .expect("cannot list history events");
+    assert_eq!(vec![older.pk], pks(&events));
+
+    let events = HistoryEvent::list(
+        ctx,
+        &HistoryEventFilter {
+            actor: Some(HistoryActor::SystemInit),
+            change_set_pk: Some(ctx.visibility().change_set_pk),
+            since: Some(older.timestamp.created_at),
+            until: Some(newer.timestamp.created_at),
+            ..filter.clone()
+        },
+        0,
+        None,
+    )
+    .await
+    .expect("cannot list history events");
+    assert_eq!(vec![older.pk], pks(&events));
+
+    let events = HistoryEvent::list(
+        ctx,
+        &HistoryEventFilter {
+            actor: Some(HistoryActor::User(UserPk::generate())),
+            ..filter
+        },
+        0,
+        None,
+    )
+    .await
+    .expect("cannot list history events");
+    assert!(events.is_empty());
+}
+
+#[test]
+async fn list_before_pages_through_events(ctx: &DalContext) {
+    let mut created = Vec::new();
+    for _ in 0..5 {
+        created.push(
+            HistoryEvent::new(ctx, "audit.keyset", "paged through", &serde_json::json!({}))
+                .await
+                .expect("cannot create a new history event"),
+        );
+    }
+    created.reverse();
+
+    let filter = HistoryEventFilter {
+        label_prefix: Some("audit.keyset".to_owned()),
+        ..Default::default()
+    };
+    let mut listed = Vec::new();
+    let mut before = None;
+    loop {
+        let events = HistoryEvent::list_before(ctx, &filter, before.as_ref(), 2)
+            .await
+            .expect("cannot list history events");
+        assert!(events.len() <= 2);
+        listed.extend(pks(&events));
+        before = match events.into_iter().last() {
+            Some(event) => Some(event),
+            None => break,
+        };
+    }
+    assert_eq!(pks(&created), listed);
+}
+
+#[test]
+async fn only_user_attribute_value_updates_are_recorded(ctx: &mut DalContext) {
+    let mut schema = create_schema(ctx).await;
+    let (mut schema_variant, _) = create_schema_variant_with_root(ctx, *schema.id()).await;
+    schema
+        .set_default_schema_variant_id(ctx, Some(*schema_variant.id()))
+        .await
+        .expect("cannot set default schema variant");
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("cannot finalize SchemaVariant");
+    let (component, _) = Component::new(ctx, "sturgill", *schema_variant.id())
+        .await
+        .expect("Unable to create component");
+
+    let filter = HistoryEventFilter {
+        label_prefix: Some("attribute_value.update_for_context".to_owned()),
+        component_id: Some(*component.id()),
+        ..Default::default()
+    };
+    let events = HistoryEvent::list(ctx, &filter, 0, None)
+        .await
+        .expect("cannot list history events");
+    assert!(events.is_empty(), "system edits are not recorded");
+
+    let user_pk = UserPk::generate();
+    ctx.update_history_actor(HistoryActor::User(user_pk));
+    component
+        .set_name(ctx, Some("simpson"))
+        .await
+        .expect("cannot set name");
+
+    let events = HistoryEvent::list(ctx, &filter, 0, None)
+        .await
+        .expect("cannot list history events");
+    assert_eq!(1, events.len());
+    assert_eq!(HistoryActor::User(user_pk), events[0].actor);
+    assert!(events[0].data.get("value").is_none());
+}
+
+fn pks(events: &[HistoryEvent]) -> Vec<dal::history_event::HistoryEventPk> {
+    events.iter().map(|event| event.pk).collect()
+}
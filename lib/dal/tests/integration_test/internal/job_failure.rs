@@ -0,0 +1,80 @@
+use dal::job::consumer::JobInfo;
+use dal::job_failure::JobFailurePk;
+use dal::{DalContext, DependentValuesUpdate, JobFailure, JobFailureError, StandardModel};
+use dal_test::test;
+
+#[test]
+async fn list_unsolved_and_mark_solved(ctx: &DalContext) {
+    let job_info = JobInfo::new(DependentValuesUpdate::new(
+        ctx.access_builder(),
+        *ctx.visibility(),
+        vec![],
+    ))
+    .expect("could not create job info");
+
+    let mut failure = JobFailure::new(
+        ctx,
+        "DependentValuesUpdate",
+        "veritech timed out",
+        Some(&job_info),
+    )
+    .await
+    .expect("could not create job failure");
+    let _without_job_info = JobFailure::new(ctx, "FixesJob", "boom", None)
+        .await
+        .expect("could not create job failure");
+
+    let recorded = failure
+        .job_info()
+        .expect("could not deserialize job info")
+        .expect("job info not recorded");
+    assert_eq!(job_info.id, recorded.id);
+    assert_eq!(job_info.kind, recorded.kind);
+    assert_eq!(job_info.arg, recorded.arg);
+
+    let unsolved = JobFailure::list_unsolved(ctx)
+        .await
+        .expect("could not list unsolved job failures");
+    assert_eq!(2, unsolved.len());
+    assert!(unsolved.contains(&failure));
+
+    failure
+        .mark_solved(ctx)
+        .await
+        .expect("could not mark job failure as solved");
+    assert!(failure.solved());
+
+    let unsolved = JobFailure::list_unsolved(ctx)
+        .await
+        .expect("could not list unsolved job failures");
+    assert_eq!(1, unsolved.len());
+    assert_eq!("FixesJob", unsolved[0].kind());
+}
+
+#[test]
+async fn replay_requires_job_info(ctx: &DalContext) {
+    let mut failure = JobFailure::new(ctx, "FixesJob", "boom", None)
+        .await
+        .expect("could not create job failure");
+
+    let result = failure.replay(ctx).await;
+    assert!(matches!(result, Err(JobFailureError::MissingJobInfo(pk)) if pk == *failure.pk()));
+    assert!(!failure.solved());
+}
+
+#[test]
+async fn find_by_pk(ctx: &DalContext) {
+    let failure = JobFailure::new(ctx, "FixesJob", "boom", None)
+        .await
+        .expect("could not create job failure");
+
+    let found = JobFailure::find_by_pk(ctx, *failure.pk())
+        .await
+        .expect("could not find job failure");
+    assert_eq!(Some(failure), found);
+
+    let missing = JobFailure::find_by_pk(ctx, JobFailurePk::generate())
+        .await
+        .expect("could not find job failure");
+    assert_eq!(None, missing);
+}
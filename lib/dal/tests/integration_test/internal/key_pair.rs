@@ -1,7 +1,12 @@
-use dal::{key_pair::PublicKey, DalContext, KeyPair, Tenancy};
+use dal::{
+    key_pair::PublicKey, DalContext, EncryptedSecret, KeyPair, SecretError, SecretKind,
+    SecretObjectType, StandardModel, Tenancy,
+};
 use dal_test::{
     test,
-    test_harness::{create_key_pair, create_workspace},
+    test_harness::{
+        create_key_pair, create_secret_with_message, create_workspace, encrypt_message,
+    },
 };
 
 #[test]
@@ -44,3 +49,110 @@ async fn public_key_get_current(ctx: &mut DalContext) {
     assert_eq!(second_key_pair.pk(), *pk.pk());
     assert_eq!(second_key_pair.public_key(), pk.public_key());
 }
+
+#[test]
+async fn rotate(ctx: &mut DalContext) {
+    let workspace = create_workspace(ctx).await;
+    ctx.update_tenancy(Tenancy::new(*workspace.pk()));
+
+    let old_key_pair = create_key_pair(ctx).await;
+    let message = serde_json::json!({"song": "Long White Line"});
+    let secret = create_secret_with_message(ctx, old_key_pair.pk(), &message).await;
+
+    let new_key_pair = KeyPair::rotate(ctx).await.expect("cannot rotate key pair");
+    assert_ne!(old_key_pair.pk(), new_key_pair.pk());
+
+    let pk = PublicKey::get_current(ctx)
+        .await
+        .expect("cannot get public key");
+    assert_eq!(new_key_pair.pk(), *pk.pk());
+    assert!(KeyPair::get_by_pk(ctx, old_key_pair.pk()).await.is_err());
+
+    let encrypted_secret = EncryptedSecret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret for tenancy and/or visibility");
+    assert_eq!(
+        new_key_pair.pk(),
+        encrypted_secret
+            .key_pair(ctx)
+            .await
+            .expect("failed to fetch key pair")
+            .pk()
+    );
+    let decrypted = encrypted_secret
+        .decrypt(ctx)
+        .await
+        .expect("failed to decrypt encrypted secret");
+    let decrypted_value =
+        serde_json::to_value(&decrypted).expect("failed to serial decrypted into Value");
+    assert_eq!(decrypted_value["message"], message);
+}
+
+#[test]
+async fn rotate_reencrypts_secrets_of_every_key_pair(ctx: &mut DalContext) {
+    let workspace = create_workspace(ctx).await;
+    ctx.update_tenancy(Tenancy::new(*workspace.pk()));
+
+    let first_key_pair = create_key_pair(ctx).await;
+    let first_message = serde_json::json!({"song": "Way Down Yonder"});
+    let first_secret = create_secret_with_message(ctx, first_key_pair.pk(), &first_message).await;
+    let second_key_pair = create_key_pair(ctx).await;
+    let second_message = serde_json::json!({"song": "Yard Sale"});
+    let second_secret =
+        create_secret_with_message(ctx, second_key_pair.pk(), &second_message).await;
+
+    let new_key_pair = KeyPair::rotate(ctx).await.expect("cannot rotate key pair");
+    assert!(KeyPair::get_by_pk(ctx, first_key_pair.pk()).await.is_err());
+    assert!(KeyPair::get_by_pk(ctx, second_key_pair.pk()).await.is_err());
+
+    for (secret, message) in [
+        (first_secret, first_message),
+        (second_secret, second_message),
+    ] {
+        let encrypted_secret = EncryptedSecret::get_by_id(ctx, secret.id())
+            .await
+            .expect("failed to fetch encrypted secret")
+            .expect("failed to find encrypted secret for tenancy and/or visibility");
+        assert_eq!(
+            new_key_pair.pk(),
+            encrypted_secret
+                .key_pair(ctx)
+                .await
+                .expect("failed to fetch key pair")
+                .pk()
+        );
+        let decrypted = encrypted_secret
+            .decrypt(ctx)
+            .await
+            .expect("failed to decrypt encrypted secret");
+        let decrypted_value =
+            serde_json::to_value(&decrypted).expect("failed to serial decrypted into Value");
+        assert_eq!(decrypted_value["message"], message);
+    }
+}
+
+#[test]
+async fn new_secret_refuses_stale_key_pair(ctx: &mut DalContext) {
+    let workspace = create_workspace(ctx).await;
+    ctx.update_tenancy(Tenancy::new(*workspace.pk()));
+
+    let old_key_pair = create_key_pair(ctx).await;
+    let _new_key_pair = create_key_pair(ctx).await;
+
+    let result = EncryptedSecret::new(
+        ctx,
+        "stale",
+        SecretObjectType::Credential,
+        SecretKind::DockerHub,
+        &encrypt_message(ctx, old_key_pair.pk(), &serde_json::json!({})).await,
+        old_key_pair.pk(),
+        Default::default(),
+        Default::default(),
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(SecretError::KeyPairNotCurrent(key_pair_pk)) if key_pair_pk == old_key_pair.pk()
+    ));
+}
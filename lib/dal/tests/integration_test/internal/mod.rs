@@ -4,10 +4,12 @@ mod change_set;
 mod component;
 mod diagram;
 mod edge;
+mod fix;
 mod func;
 mod func_execution;
 mod graph;
 mod history_event;
+mod job_failure;
 mod key_pair;
 mod node;
 mod node_menu;
@@ -3,13 +3,14 @@ use dal::func::intrinsics::IntrinsicFunc;
 use dal::{
     func::backend::validation::FuncBackendValidationArgs, installed_pkg::*, pkg::*,
     schema::variant::leaves::LeafKind, validation::Validation, DalContext, ExternalProvider, Func,
-    InternalProvider, Schema, SchemaVariant, StandardModel, ValidationPrototype,
+    InternalProvider, PkgTrustPolicy, Schema, SchemaVariant, StandardModel, ValidationPrototype,
+    Workspace,
 };
 use dal_test::test;
 use si_pkg::{
-    FuncSpec, FuncSpecBackendKind, FuncSpecBackendResponseType, LeafFunctionSpec,
-    LeafInputLocation as PkgLeafInputLocation, LeafKind as PkgLeafKind, PkgSpec, PropSpec,
-    PropSpecKind, SchemaSpec, SchemaVariantSpec, SiPkg, SocketSpec, SocketSpecArity,
+    DependencySpec, FuncSpec, FuncSpecBackendKind, FuncSpecBackendResponseType, LeafFunctionSpec,
+    LeafInputLocation as PkgLeafInputLocation, LeafKind as PkgLeafKind, PkgSigningKey, PkgSpec,
+    PropSpec, PropSpecKind, SchemaSpec, SchemaVariantSpec, SiPkg, SocketSpec, SocketSpecArity,
     SocketSpecKind, ValidationSpec, ValidationSpecKind,
 };
 
@@ -416,3 +417,250 @@ async fn test_install_pkg(ctx: &DalContext) {
         .expect("func is there");
     assert_eq!(func.name(), "groucho");
 }
+
+#[test]
+async fn workspace_pkg_trust_policy(ctx: &DalContext) {
+    let workspace_pk = ctx
+        .tenancy()
+        .workspace_pk()
+        .expect("no workspace in tenancy");
+    let mut workspace = Workspace::get_by_pk(ctx, &workspace_pk)
+        .await
+        .expect("cannot get workspace")
+        .expect("workspace should exist");
+    assert_eq!(&PkgTrustPolicy::AllowUnsigned, workspace.pkg_trust_policy());
+
+    let trusted_key = PkgSigningKey::generate().expect("able to generate signing key");
+    let untrusted_key = PkgSigningKey::generate().expect("able to generate signing key");
+    workspace
+        .set_pkg_trust_policy(
+            ctx,
+            PkgTrustPolicy::RequireTrusted,
+            vec![trusted_key.public_key()],
+        )
+        .await
+        .expect("able to set trust policy");
+    assert_eq!(&[trusted_key.public_key()], workspace.pkg_trusted_keys());
+
+    let build_pkg = |name: &str| {
+        let spec = PkgSpec::builder()
+            .name(name)
+            .version("0.1")
+            .created_by("Pointsman")
+            .build()
+            .expect("able to build package spec");
+        SiPkg::load_from_spec(spec).expect("able to load pkg from spec")
+    };
+
+    let unsigned_pkg = build_pkg("Unsigned");
+    let result = import_pkg_from_pkg(ctx, &unsigned_pkg, "unsigned", None).await;
+    assert!(matches!(
+        result,
+        Err(PkgError::TrustPolicyViolation(
+            PkgTrustPolicy::RequireTrusted,
+            si_pkg::SiPkgError::Unsigned
+        ))
+    ));
+
+    let mut untrusted_pkg = build_pkg("Untrusted");
+    untrusted_pkg
+        .sign(&untrusted_key)
+        .expect("able to sign package");
+    let result = import_pkg_from_pkg(ctx, &untrusted_pkg, "untrusted", None).await;
+    assert!(matches!(
+        result,
+        Err(PkgError::TrustPolicyViolation(
+            PkgTrustPolicy::RequireTrusted,
+            si_pkg::SiPkgError::UntrustedSigners(_)
+        ))
+    ));
+
+    let mut trusted_pkg = build_pkg("Trusted");
+    trusted_pkg
+        .sign(&trusted_key)
+        .expect("able to sign package");
+    import_pkg_from_pkg(ctx, &trusted_pkg, "trusted", None)
+        .await
+        .expect("able to install trusted pkg");
+
+    // any valid signature is enough when trusted keys aren't required
+    workspace
+        .set_pkg_trust_policy(ctx, PkgTrustPolicy::RequireSigned, vec![])
+        .await
+        .expect("able to set trust policy");
+    import_pkg_from_pkg(ctx, &untrusted_pkg, "untrusted", None)
+        .await
+        .expect("able to install signed pkg");
+
+    // packages built by SI itself can skip the policy
+    import_pkg_from_pkg(
+        ctx,
+        &unsigned_pkg,
+        "unsigned",
+        Some(ImportOptions {
+            skip_trust_policy: true,
+            ..Default::default()
+        }),
+    )
+    .await
+    .expect("able to install unsigned pkg when skipping the trust policy");
+}
+
+#[test]
+async fn install_pkg_with_dependencies(ctx: &DalContext) {
+    let build_pkg = |name: &str, version: &str, dependencies: Vec<DependencySpec>| {
+        let spec = PkgSpec::builder()
+            .name(name)
+            .version(version)
+            .created_by("Pointsman")
+            .dependencies(dependencies)
+            .build()
+            .expect("able to build package spec");
+        SiPkg::load_from_spec(spec).expect("able to load pkg from spec")
+    };
+
+    let base_pkg = build_pkg("Base", "0.2", vec![]);
+    import_pkg_from_pkg(ctx, &base_pkg, "base", None)
+        .await
+        .expect("able to install base pkg");
+
+    let installed_base = InstalledPkg::list_for_pkg_name(ctx, "Base")
+        .await
+        .expect("able to list installed pkgs")
+        .pop()
+        .expect("base pkg should be installed");
+    assert_eq!(Some("0.2"), installed_base.pkg_version().as_deref());
+
+    let dependent_pkg = build_pkg(
+        "Dependent",
+        "0.1",
+        vec![DependencySpec::builder()
+            .name("Base")
+            .try_version_req(">=0.1, <1.0")
+            .expect("able to parse version requirement")
+            .build()
+            .expect("able to build dependency spec")],
+    );
+    import_pkg_from_pkg(ctx, &dependent_pkg, "dependent", None)
+        .await
+        .expect("able to install pkg with a satisfied dependency");
+
+    let pinned_pkg = build_pkg(
+        "Pinned",
+        "0.1",
+        vec![DependencySpec::builder()
+            .name("Base")
+            .hash(base_pkg.hash().expect("able to get hash"))
+            .build()
+            .expect("able to build dependency spec")],
+    );
+    import_pkg_from_pkg(ctx, &pinned_pkg, "pinned", None)
+        .await
+        .expect("able to install pkg with a pinned dependency");
+
+    let unsatisfiable_pkg = build_pkg(
+        "Unsatisfiable",
+        "0.1",
+        vec![DependencySpec::builder()
+            .name("Base")
+            .try_version_req(">=1.0")
+            .expect("able to parse version requirement")
+            .build()
+            .expect("able to build dependency spec")],
+    );
+    let result = import_pkg_from_pkg(ctx, &unsatisfiable_pkg, "unsatisfiable", None).await;
+    assert!(matches!(
+        result,
+        Err(PkgError::UnsatisfiableDependency(name, version_req))
+            if name == "Base" && version_req == ">=1.0"
+    ));
+}
+
+#[test]
+async fn install_pkg_using_dependency_funcs(ctx: &DalContext) {
+    let qualification_code = "function qualification(_input) { return { result: 'success' }; }";
+    let qualification_func_spec = FuncSpec::builder()
+        .name("si:qualificationFromBase")
+        .display_name("from base")
+        .description("it comes from the base package")
+        .handler("qualification")
+        .code_plaintext(qualification_code)
+        .backend_kind(FuncSpecBackendKind::JsAttribute)
+        .response_type(FuncSpecBackendResponseType::Qualification)
+        .hidden(false)
+        .build()
+        .expect("build qual func spec");
+
+    let base_spec = PkgSpec::builder()
+        .name("Base")
+        .version("0.2")
+        .created_by("Pointsman")
+        .func(qualification_func_spec.clone())
+        .build()
+        .expect("able to build package spec");
+    let base_pkg = SiPkg::load_from_spec(base_spec).expect("able to load pkg from spec");
+    import_pkg_from_pkg(ctx, &base_pkg, "base", None)
+        .await
+        .expect("able to install base pkg");
+
+    let scaffold_func_spec = FuncSpec::builder()
+        .name("si:scaffoldFunc")
+        .code_plaintext("function createAsset() { return new AssetBuilder().build(); }")
+        .handler("createAsset")
+        .backend_kind(FuncSpecBackendKind::JsSchemaVariantDefinition)
+        .response_type(FuncSpecBackendResponseType::SchemaVariantDefinition)
+        .build()
+        .expect("could not build schema variant definition spec");
+
+    // The dependent package only knows the qualification by its unique id
+    let schema_spec = SchemaSpec::builder()
+        .name("Enzian")
+        .category("Rockets")
+        .ui_hidden(false)
+        .variant(
+            SchemaVariantSpec::builder()
+                .name("Schwarzgerat")
+                .color("baddad")
+                .func_unique_id(scaffold_func_spec.unique_id)
+                .leaf_function(
+                    LeafFunctionSpec::builder()
+                        .func_unique_id(qualification_func_spec.unique_id)
+                        .leaf_kind(PkgLeafKind::Qualification)
+                        .inputs(vec![PkgLeafInputLocation::Domain])
+                        .build()
+                        .expect("could not build qual spec"),
+                )
+                .build()
+                .expect("able to make schema variant spec"),
+        )
+        .build()
+        .expect("able to make schema spec");
+    let dependent_spec = PkgSpec::builder()
+        .name("Dependent")
+        .version("0.1")
+        .created_by("Pointsman")
+        .func(scaffold_func_spec)
+        .schema(schema_spec)
+        .dependency(
+            DependencySpec::builder()
+                .name("Base")
+                .try_version_req(">=0.1, <1.0")
+                .expect("able to parse version requirement")
+                .build()
+                .expect("able to build dependency spec"),
+        )
+        .build()
+        .expect("able to build package spec");
+    let dependent_pkg = SiPkg::load_from_spec(dependent_spec).expect("able to load pkg from spec");
+
+    let (_, schema_variant_ids) = import_pkg_from_pkg(ctx, &dependent_pkg, "dependent", None)
+        .await
+        .expect("able to install pkg using a func of its dependency");
+    assert_eq!(1, schema_variant_ids.len());
+
+    // The func of the dependency is used, not installed again
+    let funcs = Func::find_by_attr(ctx, "name", &"si:qualificationFromBase")
+        .await
+        .expect("able to find funcs");
+    assert_eq!(1, funcs.len());
+}
@@ -1,10 +1,15 @@
 use dal::{
-    DalContext, EncryptedSecret, Secret, SecretAlgorithm, SecretKind, SecretObjectType,
-    SecretVersion, StandardModel, WorkspaceSignup,
+    generate_name, property_editor::schema::WidgetKind, AttributeContext, AttributeValue,
+    ChangeSet, Component, ComponentId, DalContext, EncryptedSecret, Prop, PropKind, Secret,
+    SecretAlgorithm, SecretBackend, SecretError, SecretId, SecretKind, SecretObjectType,
+    SecretVersion, StandardModel, Visibility, WorkspaceSignup,
 };
 use dal_test::{
     test,
-    test_harness::{create_secret, generate_fake_name},
+    test_harness::{
+        create_key_pair, create_schema, create_schema_variant_with_root, create_secret,
+        encrypt_message, generate_fake_name,
+    },
 };
 
 #[test]
@@ -35,6 +40,50 @@ async fn new_encrypted_secret(ctx: &DalContext, nw: &WorkspaceSignup) {
     assert_eq!(key_pair.pk(), nw.key_pair.pk());
 }
 
+#[test]
+async fn new_secret_reference(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let name = generate_fake_name();
+
+    let secret = EncryptedSecret::new_reference(
+        ctx,
+        &name,
+        SecretObjectType::Credential,
+        SecretKind::AwsAccessKey,
+        SecretBackend::Http,
+        "secret/data/aws/prod",
+        nw.key_pair.pk(),
+    )
+    .await
+    .expect("failed to create secret reference");
+    assert_eq!(secret.name(), name);
+    assert_eq!(secret.backend(), &SecretBackend::Http);
+
+    let encrypted_secret = EncryptedSecret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret in current tenancy and visibility");
+    assert_eq!(encrypted_secret.reference(), Some("secret/data/aws/prod"));
+    match encrypted_secret.decrypt(ctx).await {
+        Err(SecretError::ExternalBackend(SecretBackend::Http)) => {}
+        other => panic!("expected secret to only be resolvable by cyclone, got: {other:?}"),
+    }
+
+    let result = EncryptedSecret::new_reference(
+        ctx,
+        &name,
+        SecretObjectType::Credential,
+        SecretKind::AwsAccessKey,
+        SecretBackend::Postgres,
+        "secret/data/aws/prod",
+        nw.key_pair.pk(),
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(SecretError::NotAReferenceBackend(SecretBackend::Postgres))
+    ));
+}
+
 #[test]
 async fn secret_get_by_id(ctx: &DalContext, nw: &WorkspaceSignup) {
     let og_secret = create_secret(ctx, nw.key_pair.pk()).await;
@@ -117,3 +166,259 @@ async fn encrypt_decrypt_round_trip(ctx: &DalContext, nw: &WorkspaceSignup) {
         serde_json::to_value(&decrypted).expect("failed to serial decrypted into Value");
     assert_eq!(decrypted_value["message"], message);
 }
+
+#[test]
+async fn secret_update_encrypted_contents(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let secret = create_secret(ctx, nw.key_pair.pk()).await;
+
+    let message = serde_json::json!({"song": "Drowns the Whiskey"});
+    let crypted = encrypt_message(ctx, nw.key_pair.pk(), &message).await;
+    EncryptedSecret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret for tenancy and/or visibility")
+        .update_encrypted_contents(
+            ctx,
+            &crypted,
+            nw.key_pair.pk(),
+            SecretVersion::V1,
+            SecretAlgorithm::Sealedbox,
+        )
+        .await
+        .expect("failed to update encrypted contents");
+
+    let decrypted = EncryptedSecret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret for tenancy and/or visibility")
+        .decrypt(ctx)
+        .await
+        .expect("failed to decrypt encrypted secret");
+    assert_eq!(decrypted.name(), secret.name());
+
+    let decrypted_value =
+        serde_json::to_value(&decrypted).expect("failed to serial decrypted into Value");
+    assert_eq!(decrypted_value["message"], message);
+}
+
+#[test]
+async fn secret_update_encrypted_contents_refuses_stale_key_pair(
+    ctx: &DalContext,
+    nw: &WorkspaceSignup,
+) {
+    let secret = create_secret(ctx, nw.key_pair.pk()).await;
+    let mut encrypted_secret = EncryptedSecret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret for tenancy and/or visibility");
+
+    // A newer key pair makes the one the secret was created with stale
+    let message = serde_json::json!({"song": "Whitehouse Road"});
+    let crypted = encrypt_message(ctx, nw.key_pair.pk(), &message).await;
+    let _key_pair = create_key_pair(ctx).await;
+
+    let result = encrypted_secret
+        .update_encrypted_contents(
+            ctx,
+            &crypted,
+            nw.key_pair.pk(),
+            SecretVersion::V1,
+            SecretAlgorithm::Sealedbox,
+        )
+        .await;
+    match result {
+        Err(SecretError::KeyPairNotCurrent(key_pair_pk)) => {
+            assert_eq!(key_pair_pk, nw.key_pair.pk());
+        }
+        other => panic!("expected key pair to be stale, got: {other:?}"),
+    }
+}
+
+#[test]
+async fn secret_delete_refuses_while_referenced(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let mut secret = create_secret(ctx, nw.key_pair.pk()).await;
+
+    let mut schema = create_schema(ctx).await;
+    let (mut schema_variant, root) = create_schema_variant_with_root(ctx, *schema.id()).await;
+    schema
+        .set_default_schema_variant_id(ctx, Some(*schema_variant.id()))
+        .await
+        .expect("cannot set default schema variant");
+    let secret_prop = Prop::new(
+        ctx,
+        "credential",
+        PropKind::String,
+        Some((WidgetKind::SecretSelect, None)),
+        *schema_variant.id(),
+        Some(root.domain_prop_id),
+    )
+    .await
+    .expect("could not create prop");
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("cannot finalize SchemaVariant");
+
+    let (component, _) = Component::new(ctx, "tennessee", *schema_variant.id())
+        .await
+        .expect("Unable to create component");
+
+    let mut base_attribute_context = AttributeContext::builder();
+    base_attribute_context.set_component_id(*component.id());
+    let domain_context = base_attribute_context
+        .clone()
+        .set_prop_id(root.domain_prop_id)
+        .to_context()
+        .expect("cannot create domain AttributeContext");
+    let domain_value = AttributeValue::find_for_context(ctx, domain_context.into())
+        .await
+        .expect("could not fetch domain AttributeValue")
+        .expect("could not find domain AttributeValue");
+    let secret_context = base_attribute_context
+        .clone()
+        .set_prop_id(*secret_prop.id())
+        .to_context()
+        .expect("cannot create secret AttributeContext");
+    let secret_value = AttributeValue::find_for_context(ctx, secret_context.into())
+        .await
+        .expect("could not fetch secret AttributeValue")
+        .expect("could not find secret AttributeValue");
+    let (_, secret_value_id) = AttributeValue::update_for_context(
+        ctx,
+        *secret_value.id(),
+        Some(*domain_value.id()),
+        secret_context,
+        Some(serde_json::json!(secret.id().to_string())),
+        None,
+    )
+    .await
+    .expect("could not select secret");
+
+    assert_eq!(
+        vec![*component.id()],
+        secret
+            .list_referencing_components(ctx)
+            .await
+            .expect("could not list referencing components")
+    );
+    match secret.delete(ctx).await {
+        Err(SecretError::SecretInUse(id, component_ids)) => {
+            assert_eq!(id, *secret.id());
+            assert_eq!(component_ids, vec![*component.id()]);
+        }
+        other => panic!("expected secret to be in use, got: {other:?}"),
+    }
+
+    AttributeValue::update_for_context(
+        ctx,
+        secret_value_id,
+        Some(*domain_value.id()),
+        secret_context,
+        None,
+        None,
+    )
+    .await
+    .expect("could not unselect secret");
+    secret.delete(ctx).await.expect("could not delete secret");
+
+    assert!(Secret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to get secret")
+        .is_none());
+}
+
+#[test]
+async fn secret_delete_refuses_while_referenced_in_another_change_set(
+    ctx: &mut DalContext,
+    nw: &WorkspaceSignup,
+) {
+    // The secret lives on head, so that every change set can use it
+    ctx.update_to_head();
+    let secret = create_secret(ctx, nw.key_pair.pk()).await;
+
+    let referencing_change_set = ChangeSet::new(ctx, generate_name(), None)
+        .await
+        .expect("could not create change set");
+    ctx.update_visibility(Visibility::new(referencing_change_set.pk, None));
+    let component_id = select_secret(ctx, *secret.id()).await;
+
+    let other_change_set = ChangeSet::new(ctx, generate_name(), None)
+        .await
+        .expect("could not create change set");
+    for visibility in [
+        Visibility::new(other_change_set.pk, None),
+        Visibility::new_head(false),
+    ] {
+        ctx.update_visibility(visibility);
+        let mut secret = Secret::get_by_id(ctx, secret.id())
+            .await
+            .expect("failed to get secret")
+            .expect("failed to find secret in current tenancy and visibility");
+        match secret.delete(ctx).await {
+            Err(SecretError::SecretInUse(_, component_ids)) => {
+                assert_eq!(component_ids, vec![component_id]);
+            }
+            other => panic!("expected secret to be in use, got: {other:?}"),
+        }
+    }
+}
+
+/// Creates a component with a secret prop and selects the secret in it.
+async fn select_secret(ctx: &DalContext, secret_id: SecretId) -> ComponentId {
+    let mut schema = create_schema(ctx).await;
+    let (mut schema_variant, root) = create_schema_variant_with_root(ctx, *schema.id()).await;
+    schema
+        .set_default_schema_variant_id(ctx, Some(*schema_variant.id()))
+        .await
+        .expect("cannot set default schema variant");
+    let secret_prop = Prop::new(
+        ctx,
+        "credential",
+        PropKind::String,
+        Some((WidgetKind::SecretSelect, None)),
+        *schema_variant.id(),
+        Some(root.domain_prop_id),
+    )
+    .await
+    .expect("could not create prop");
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("cannot finalize SchemaVariant");
+
+    let (component, _) = Component::new(ctx, "knoxville", *schema_variant.id())
+        .await
+        .expect("Unable to create component");
+
+    let mut base_attribute_context = AttributeContext::builder();
+    base_attribute_context.set_component_id(*component.id());
+    let domain_context = base_attribute_context
+        .clone()
+        .set_prop_id(root.domain_prop_id)
+        .to_context()
+        .expect("cannot create domain AttributeContext");
+    let domain_value = AttributeValue::find_for_context(ctx, domain_context.into())
+        .await
+        .expect("could not fetch domain AttributeValue")
+        .expect("could not find domain AttributeValue");
+    let secret_context = base_attribute_context
+        .set_prop_id(*secret_prop.id())
+        .to_context()
+        .expect("cannot create secret AttributeContext");
+    let secret_value = AttributeValue::find_for_context(ctx, secret_context.into())
+        .await
+        .expect("could not fetch secret AttributeValue")
+        .expect("could not find secret AttributeValue");
+    AttributeValue::update_for_context(
+        ctx,
+        *secret_value.id(),
+        Some(*domain_value.id()),
+        secret_context,
+        Some(serde_json::json!(secret_id.to_string())),
+        None,
+    )
+    .await
+    .expect("could not select secret");
+
+    *component.id()
+}
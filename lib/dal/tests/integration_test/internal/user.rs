@@ -1,4 +1,4 @@
-use dal::{DalContext, User, UserPk, WorkspaceSignup};
+use dal::{DalContext, User, UserPk, WorkspaceRole, WorkspaceSignup};
 use dal_test::test;
 
 #[test]
@@ -21,21 +21,69 @@ async fn authorize(ctx: &DalContext, nw: &WorkspaceSignup) {
         .expect("admin group user should be authorized");
     assert!(worked, "authorized admin group user returns true");
 
-    // TODO(theo,paulo): re-enable that when capabilities are back
-    /*
-    let user_no_group = User::new(
+    let user_no_workspace = User::new(
         ctx,
         UserPk::generate(),
         "funky",
         "bobotclown@systeminit.com",
+        None::<String>,
     )
     .await
     .expect("cannot create user");
 
-    let f = User::authorize(ctx, &user_no_group.pk()).await;
+    let authorized = User::authorize(ctx, &user_no_workspace.pk())
+        .await
+        .expect("cannot authorize user");
     assert!(
-        f.is_err(),
-        "user that is not in the admin group should fail"
+        !authorized,
+        "user that does not belong to the workspace is not authorized"
+    );
+}
+
+#[test]
+async fn workspace_roles(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let workspace_pk = *nw.workspace.pk();
+    assert_eq!(
+        Some(WorkspaceRole::Owner),
+        User::workspace_role(ctx, nw.user.pk(), workspace_pk)
+            .await
+            .expect("cannot get workspace role")
+    );
+
+    let user = User::new(
+        ctx,
+        UserPk::generate(),
+        "funky",
+        "bobotclown@systeminit.com",
+        None::<String>,
+    )
+    .await
+    .expect("cannot create user");
+    user.associate_workspace(ctx, workspace_pk)
+        .await
+        .expect("cannot associate workspace");
+    assert_eq!(
+        Some(WorkspaceRole::Editor),
+        User::role_in_tenancy(ctx, &user.pk())
+            .await
+            .expect("cannot get workspace role")
     );
-    */
+
+    user.set_workspace_role(ctx, workspace_pk, WorkspaceRole::Viewer)
+        .await
+        .expect("cannot set workspace role");
+    // Associating again doesn't reset the role
+    user.associate_workspace(ctx, workspace_pk)
+        .await
+        .expect("cannot associate workspace");
+    let role = User::role_in_tenancy(ctx, &user.pk())
+        .await
+        .expect("cannot get workspace role")
+        .expect("user does not belong to the workspace");
+    assert_eq!(WorkspaceRole::Viewer, role);
+    assert!(!role.can_edit());
+    assert!(!role.can_approve());
+    assert!(User::authorize(ctx, &user.pk())
+        .await
+        .expect("cannot authorize user"));
 }
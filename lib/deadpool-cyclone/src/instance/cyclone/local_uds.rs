@@ -14,8 +14,8 @@ use cyclone_core::{
     process::{self, ShutdownError},
     ActionRunRequest, ActionRunResultSuccess, CanonicalCommand, ReconciliationRequest,
     ReconciliationResultSuccess, ResolverFunctionRequest, ResolverFunctionResultSuccess,
-    SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess, ValidationRequest,
-    ValidationResultSuccess,
+    ResourceLimits, SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess,
+    ValidationRequest, ValidationResultSuccess,
 };
 use derive_builder::Builder;
 use futures::StreamExt;
@@ -291,6 +291,19 @@ pub struct LocalUdsInstanceSpec {
     #[builder(setter(into), default = "Some(1)")]
     limit_requests: Option<u32>,
 
+    /// Sets the function execution timeout for a spawned Cyclone server.
+    #[builder(setter(into, strip_option), default)]
+    execution_timeout: Option<Duration>,
+
+    /// Sets the resource limits applied to function executions of a spawned Cyclone server.
+    #[builder(default)]
+    resource_limits: ResourceLimits,
+
+    /// Sets the environment variables passed through to function executions of a spawned Cyclone
+    /// server. When unset, executions inherit the whole environment.
+    #[builder(setter(into, strip_option), default)]
+    env_allow_list: Option<Vec<String>>,
+
     /// Enables the `ping` execution endpoint for a spawned Cyclone server.
     #[builder(private, setter(name = "_ping"), default = "false")]
     ping: bool,
@@ -375,6 +388,27 @@ impl LocalUdsInstanceSpec {
             cmd.arg("--watch-timeout")
                 .arg(timeout.as_secs().to_string());
         }
+        if let Some(timeout) = self.execution_timeout {
+            cmd.arg("--execution-timeout")
+                .arg(timeout.as_secs().to_string());
+        }
+        if let Some(bytes) = self.resource_limits.memory_bytes {
+            cmd.arg("--limit-memory-bytes").arg(bytes.to_string());
+        }
+        if let Some(secs) = self.resource_limits.cpu_time_secs {
+            cmd.arg("--limit-cpu-time").arg(secs.to_string());
+        }
+        if let Some(files) = self.resource_limits.open_files {
+            cmd.arg("--limit-open-files").arg(files.to_string());
+        }
+        if let Some(bytes) = self.resource_limits.max_output_bytes {
+            cmd.arg("--max-output-bytes").arg(bytes.to_string());
+        }
+        if let Some(env_allow_list) = &self.env_allow_list {
+            for name in env_allow_list {
+                cmd.arg("--env-allow").arg(name);
+            }
+        }
         if self.ping {
             cmd.arg("--enable-ping");
         }
@@ -28,8 +28,8 @@ pub use cyclone_core::{
     ActionRunRequest, ActionRunResultSuccess, ComponentView, FunctionResult, FunctionResultFailure,
     FunctionResultFailureError, OutputStream, ProgressMessage, ReconciliationRequest,
     ReconciliationResultSuccess, ResolverFunctionRequest, ResolverFunctionResultSuccess,
-    ResourceStatus, SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess,
-    ValidationRequest, ValidationResultSuccess,
+    ResourceLimits, ResourceStatus, SchemaVariantDefinitionRequest,
+    SchemaVariantDefinitionResultSuccess, ValidationRequest, ValidationResultSuccess,
 };
 
 /// [`Instance`] implementations.
@@ -1,7 +1,10 @@
 use ulid::Ulid;
 use url::Url;
 
-use crate::{IndexClientResult, ModuleDetailsResponse};
+use crate::{
+    IndexClientError, IndexClientResult, ListModuleVersionsResponse, ListModulesRequest,
+    ListModulesResponse, ModuleDetailsResponse,
+};
 
 #[derive(Debug, Clone)]
 pub struct IndexClient {
@@ -38,6 +41,24 @@ impl IndexClient {
         Ok(upload_response.json::<ModuleDetailsResponse>().await?)
     }
 
+    /// Lists one page of the modules matching the request, see
+    /// [`ListModulesResponse::next_cursor`] to get the following ones.
+    pub async fn list_modules(
+        &self,
+        request: &ListModulesRequest,
+    ) -> IndexClientResult<ListModulesResponse> {
+        let list_url = self.base_url.join("modules")?;
+        let response = reqwest::Client::new()
+            .get(list_url)
+            .query(request)
+            .bearer_auth(&self.auth_token)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json::<ListModulesResponse>().await?)
+    }
+
     pub async fn download_module(&self, module_id: Ulid) -> IndexClientResult<Vec<u8>> {
         let download_url = dbg!(self
             .base_url
@@ -56,4 +77,50 @@ impl IndexClient {
 
         Ok(bytes.to_vec())
     }
+
+    pub async fn list_module_versions(
+        &self,
+        module_id: Ulid,
+    ) -> IndexClientResult<ListModuleVersionsResponse> {
+        let versions_url = self
+            .base_url
+            .join("modules/")?
+            .join(&format!("{module_id}/"))?
+            .join("versions")?;
+        let response = reqwest::Client::new()
+            .get(versions_url)
+            .bearer_auth(&self.auth_token)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json::<ListModuleVersionsResponse>().await?)
+    }
+
+    /// Downloads a specific version of a module, which stays available after newer versions are
+    /// uploaded.
+    pub async fn download_module_version(
+        &self,
+        module_id: Ulid,
+        version: &str,
+    ) -> IndexClientResult<Vec<u8>> {
+        let module_id = module_id.to_string();
+        let mut download_url = self.base_url.join("modules/")?;
+        download_url
+            .path_segments_mut()
+            .map_err(|_| IndexClientError::InvalidBaseUrl(self.base_url.clone()))?
+            .pop_if_empty()
+            // The version is pushed as a segment to get it percent-encoded
+            .extend([module_id.as_str(), "versions", version, "download"]);
+        let response = reqwest::Client::new()
+            .get(download_url)
+            .bearer_auth(&self.auth_token)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let bytes = response.bytes().await?;
+
+        Ok(bytes.to_vec())
+    }
 }
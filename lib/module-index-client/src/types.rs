@@ -5,6 +5,8 @@ use thiserror::Error;
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum IndexClientError {
+    #[error("Invalid base url: {0}")]
+    InvalidBaseUrl(url::Url),
     #[error("Request error: {0}")]
     InvalidHeaderValue(#[from] reqwest::header::InvalidHeaderValue),
     #[error("Request error: {0}")]
@@ -23,7 +25,7 @@ pub struct ModuleDetailsResponse {
     pub id: String,
     pub name: String,
     pub description: Option<String>,
-    pub owner_user_id: String,
+    pub owner_user_id: Option<String>,
     pub owner_display_name: Option<String>,
     pub metadata: serde_json::Value,
     pub latest_hash: String,
@@ -31,6 +33,77 @@ pub struct ModuleDetailsResponse {
     pub created_at: DateTime<Utc>,
 }
 
+/// The filters, ordering and page of a module listing. Every filter left empty matches all
+/// modules.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListModulesRequest {
+    /// Only modules whose name contains this
+    pub name: Option<String>,
+    /// Only modules whose owner display name contains this, or owned by this user id
+    pub owner: Option<String>,
+    /// Only modules with a schema whose name contains this
+    pub schema_name: Option<String>,
+    /// Only modules with a func whose name contains this
+    pub func_name: Option<String>,
+    /// Only modules with a schema in this category
+    pub category: Option<String>,
+    /// Only modules created at or after this time
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only modules created before this time
+    pub created_before: Option<DateTime<Utc>>,
+    pub sort_by: Option<ModuleSortBy>,
+    pub sort_direction: Option<SortDirection>,
+    /// The maximum number of modules to return
+    pub limit: Option<u64>,
+    /// Returns the modules after the ones of a previous page, see
+    /// [`ListModulesResponse::next_cursor`]
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ModuleSortBy {
+    CreatedAt,
+    #[default]
+    Name,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListModulesResponse {
+    pub modules: Vec<ModuleDetailsResponse>,
+    /// The cursor of the next page, if there are more modules
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleVersionResponse {
+    pub id: String,
+    pub module_id: String,
+    pub version: String,
+    pub hash: String,
+    pub owner_display_name: Option<String>,
+    pub metadata: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListModuleVersionsResponse {
+    /// Most recently uploaded first
+    pub versions: Vec<ModuleVersionResponse>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FuncMetadata {
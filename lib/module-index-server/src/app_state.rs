@@ -2,13 +2,12 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use axum::extract::FromRef;
-use s3::creds::Credentials as AwsCredentials;
 use sea_orm::DatabaseConnection;
 pub use si_posthog::PosthogClient;
 
 use tokio::sync::{broadcast, mpsc, Mutex};
 
-use crate::{jwt_key::JwtPublicSigningKey, s3::S3Config};
+use crate::{jwt_key::JwtPublicSigningKey, storage::ModuleStorage};
 
 #[remain::sorted]
 #[derive(Debug, Eq, PartialEq)]
@@ -23,9 +22,9 @@ pub struct AppState {
     pg_pool: DatabaseConnection,
     jwt_public_signing_key: JwtPublicSigningKey,
     posthog_client: PosthogClient,
-    aws_creds: AwsCredentials,
-    s3_config: S3Config,
+    storage: Arc<dyn ModuleStorage>,
     restrict_listing: bool,
+    require_signed_modules: bool,
     token_emails: Arc<Mutex<HashMap<String, String>>>,
 
     shutdown_broadcast: ShutdownBroadcast,
@@ -42,9 +41,9 @@ impl AppState {
         pg_pool: DatabaseConnection,
         jwt_public_signing_key: JwtPublicSigningKey,
         posthog_client: PosthogClient,
-        aws_creds: AwsCredentials,
-        s3_config: S3Config,
+        storage: Arc<dyn ModuleStorage>,
         restrict_listing: bool,
+        require_signed_modules: bool,
         shutdown_broadcast_tx: broadcast::Sender<()>,
         tmp_shutdown_tx: mpsc::Sender<ShutdownSource>,
     ) -> Self {
@@ -52,9 +51,9 @@ impl AppState {
             pg_pool,
             jwt_public_signing_key,
             posthog_client,
-            aws_creds,
-            s3_config,
+            storage,
             restrict_listing,
+            require_signed_modules,
             shutdown_broadcast: ShutdownBroadcast(shutdown_broadcast_tx),
             token_emails: Arc::new(Mutex::new(HashMap::new())),
             _tmp_shutdown_tx: Arc::new(tmp_shutdown_tx),
@@ -76,13 +75,9 @@ impl AppState {
         &self.posthog_client
     }
 
-    /// Gets a reference to the aws creds.
-    pub fn aws_creds(&self) -> &AwsCredentials {
-        &self.aws_creds
-    }
-    /// Gets a reference to the s3 config (bucket, region, etc)
-    pub fn s3_config(&self) -> &S3Config {
-        &self.s3_config
+    /// Gets a reference to the storage modules are kept in.
+    pub fn storage(&self) -> &Arc<dyn ModuleStorage> {
+        &self.storage
     }
 
     /// Clones the ArcMutex that holds a hashmap between auth tokens and emails
@@ -93,4 +88,8 @@ impl AppState {
     pub fn restrict_listing(&self) -> bool {
         self.restrict_listing
     }
+
+    pub fn require_signed_modules(&self) -> bool {
+        self.require_signed_modules
+    }
 }
@@ -0,0 +1,158 @@
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set,
+};
+use serde_json::Value;
+use si_pkg::SiPkg;
+use telemetry::prelude::*;
+
+use crate::{
+    models::{module_version, si_module},
+    routes::upsert_module_route::module_categories,
+    storage::ModuleStorage,
+};
+
+/// Records the categories of modules and module versions uploaded before categories were, reading
+/// them from the stored modules. Modules which can't be fetched from storage are logged and tried
+/// again next time, whereas modules which can't be parsed are logged and recorded without
+/// categories, so they are only ever downloaded once.
+///
+/// Returns how many modules and module versions were backfilled.
+pub async fn backfill_module_categories(
+    db: &DatabaseConnection,
+    storage: &dyn ModuleStorage,
+) -> Result<usize, DbErr> {
+    let mut backfilled = 0;
+
+    let modules = si_module::Entity::find()
+        .filter(Expr::cust("metadata -> 'categories' IS NULL"))
+        .all(db)
+        .await?;
+    for module in modules {
+        if let Some(metadata) =
+            with_stored_categories(storage, &module.latest_hash, &module.metadata).await
+        {
+            let mut module: si_module::ActiveModel = module.into();
+            module.metadata = Set(metadata);
+            module.update(db).await?;
+            backfilled += 1;
+        }
+    }
+
+    let versions = module_version::Entity::find()
+        .filter(Expr::cust("metadata -> 'categories' IS NULL"))
+        .all(db)
+        .await?;
+    for version in versions {
+        if let Some(metadata) =
+            with_stored_categories(storage, &version.hash, &version.metadata).await
+        {
+            let mut version: module_version::ActiveModel = version.into();
+            version.metadata = Set(metadata);
+            version.update(db).await?;
+            backfilled += 1;
+        }
+    }
+
+    Ok(backfilled)
+}
+
+async fn with_stored_categories(
+    storage: &dyn ModuleStorage,
+    hash: &str,
+    metadata: &Value,
+) -> Option<Value> {
+    let bytes = match storage.get(&format!("{hash}.sipkg")).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            warn!(error = %err, hash, "unable to read stored module to backfill its categories");
+            return None;
+        }
+    };
+    let categories = match SiPkg::load_from_bytes(bytes)
+        .and_then(|module| module_categories(&module))
+    {
+        Ok(categories) => categories,
+        Err(err) => {
+            warn!(error = %err, hash, "unable to parse stored module, recording it without categories");
+            Vec::new()
+        }
+    };
+
+    let mut metadata = metadata.clone();
+    metadata
+        .as_object_mut()?
+        .insert("categories".to_owned(), categories.into());
+    Some(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::{DbBackend, MockDatabase};
+    use si_pkg::{PkgSpec, SchemaSpec};
+
+    use super::*;
+    use crate::{routes::test_helpers, storage::FilesystemStorage};
+
+    #[tokio::test]
+    async fn backfills_categories_from_stored_modules() {
+        let spec = PkgSpec::builder()
+            .name("ufo")
+            .version("1.0.0")
+            .created_by("Fox Mulder")
+            .schema(
+                SchemaSpec::builder()
+                    .name("saucer")
+                    .category("Crafts")
+                    .build()
+                    .expect("unable to build schema spec"),
+            )
+            .build()
+            .expect("unable to build package spec");
+        let pkg = SiPkg::load_from_spec(spec).expect("unable to load package");
+        let hash = pkg.hash().expect("unable to hash package").to_string();
+
+        let root = tempfile::TempDir::new().expect("unable to create storage dir");
+        let storage = FilesystemStorage::new(root.path());
+        storage
+            .put(
+                &format!("{hash}.sipkg"),
+                &pkg.write_to_bytes().expect("unable to write package"),
+            )
+            .await
+            .expect("unable to store module");
+
+        storage
+            .put("garbage.sipkg", b"not a module")
+            .await
+            .expect("unable to store garbage");
+
+        let mut module = test_helpers::module("ufo");
+        module.latest_hash = hash.clone();
+        let stored_version = test_helpers::module_version(&module, "1.0.0", &hash);
+        let missing_version = test_helpers::module_version(&module, "0.1.0", "missing");
+        let garbage_version = test_helpers::module_version(&module, "0.0.1", "garbage");
+
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results(vec![vec![module.clone()], vec![module]])
+            .append_query_results(vec![
+                vec![
+                    stored_version.clone(),
+                    missing_version,
+                    garbage_version.clone(),
+                ],
+                vec![stored_version],
+                vec![garbage_version],
+            ])
+            .into_connection();
+
+        let backfilled = backfill_module_categories(&db, &storage)
+            .await
+            .expect("unable to backfill categories");
+
+        // The missing module is tried again next time, the unparsable one never is
+        assert_eq!(3, backfilled);
+        let log = format!("{:?}", db.into_transaction_log());
+        assert!(log.contains("Crafts"));
+        assert!(!log.contains("missing"));
+    }
+}
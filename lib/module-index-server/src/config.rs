@@ -12,7 +12,10 @@ use thiserror::Error;
 pub use si_settings::{StandardConfig, StandardConfigFile};
 use ulid::Ulid;
 
-use crate::s3::S3Config;
+use crate::{
+    s3::S3Config,
+    storage::{FilesystemConfig, StorageKind},
+};
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -58,6 +61,15 @@ pub struct Config {
     #[builder(default = "false")]
     restrict_listing: bool,
 
+    #[builder(default = "false")]
+    require_signed_modules: bool,
+
+    #[builder(default)]
+    storage: StorageKind,
+
+    #[builder(default)]
+    filesystem: FilesystemConfig,
+
     s3: S3Config,
 }
 
@@ -94,6 +106,17 @@ impl Config {
         &self.posthog
     }
 
+    /// Gets the kind of storage modules are kept in
+    pub fn storage(&self) -> StorageKind {
+        self.storage
+    }
+
+    /// Gets a config's filesystem storage details
+    #[must_use]
+    pub fn filesystem(&self) -> &FilesystemConfig {
+        &self.filesystem
+    }
+
     /// Gets a config's s3 details
     #[must_use]
     pub fn s3(&self) -> &S3Config {
@@ -104,6 +127,11 @@ impl Config {
     pub fn restrict_listing(&self) -> bool {
         self.restrict_listing
     }
+
+    /// Whether to reject uploads of modules which aren't signed, instead of flagging them
+    pub fn require_signed_modules(&self) -> bool {
+        self.require_signed_modules
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -119,9 +147,15 @@ pub struct ConfigFile {
     #[serde(default)]
     pub posthog: PosthogConfig,
     #[serde(default)]
+    pub storage: StorageKind,
+    #[serde(default)]
+    pub filesystem: FilesystemConfig,
+    #[serde(default)]
     pub s3: S3Config,
     #[serde(default)]
     pub restrict_listing: bool,
+    #[serde(default)]
+    pub require_signed_modules: bool,
 }
 
 impl Default for ConfigFile {
@@ -138,8 +172,11 @@ impl Default for ConfigFile {
             instance_id: random_instance_id(),
             jwt_signing_public_key_path: default_jwt_signing_public_key_path(),
             posthog: Default::default(),
+            storage: Default::default(),
+            filesystem: Default::default(),
             s3: Default::default(),
             restrict_listing: Default::default(),
+            require_signed_modules: Default::default(),
         }
     }
 }
@@ -160,8 +197,11 @@ impl TryFrom<ConfigFile> for Config {
         config.instance_id(value.instance_id);
         config.jwt_signing_public_key_path(value.jwt_signing_public_key_path.try_into()?);
         config.posthog(value.posthog);
+        config.storage(value.storage);
+        config.filesystem(value.filesystem);
         config.s3(value.s3);
         config.restrict_listing(value.restrict_listing);
+        config.require_signed_modules(value.require_signed_modules);
         config.build().map_err(Into::into)
     }
 }
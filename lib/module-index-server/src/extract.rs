@@ -1,15 +1,17 @@
-use std::fmt;
+use std::{fmt, sync::Arc};
 
 use axum::{async_trait, extract::FromRequestParts, http::request::Parts, Json};
 use hyper::StatusCode;
-use s3::{Bucket as S3Bucket, Region as AwsRegion};
 use sea_orm::{DatabaseTransaction, TransactionTrait};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use ulid::Ulid;
 
 use super::app_state::AppState;
-use crate::jwt_key::{JwtKeyError, JwtPublicSigningKey};
+use crate::{
+    jwt_key::{JwtKeyError, JwtPublicSigningKey},
+    storage::ModuleStorage,
+};
 
 pub struct PosthogClient(pub super::app_state::PosthogClient);
 
@@ -25,46 +27,17 @@ impl FromRequestParts<AppState> for PosthogClient {
     }
 }
 
-pub struct ExtractedS3Bucket(pub S3Bucket);
+pub struct ExtractedStorage(pub Arc<dyn ModuleStorage>);
 
 #[async_trait]
-impl FromRequestParts<AppState> for ExtractedS3Bucket {
+impl FromRequestParts<AppState> for ExtractedStorage {
     type Rejection = (StatusCode, Json<serde_json::Value>);
 
     async fn from_request_parts(
         _parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        let region = match state.s3_config().region.parse::<AwsRegion>() {
-            Ok(region) => region,
-            Err(err) => {
-                return Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({
-                        "error": {
-                            "message": err.to_string(),
-                            "code": 42,
-                            "statusCode": 500
-                        }
-                    })),
-                ))
-            }
-        };
-
-        let bucket = S3Bucket::new(&state.s3_config().bucket, region, state.aws_creds().clone())
-            .map_err(|err| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({
-                        "error": {
-                            "message": err.to_string(),
-                            "code": 42,
-                            "statusCode": 500
-                        }
-                    })),
-                )
-            })?;
-        Ok(ExtractedS3Bucket(bucket))
+        Ok(Self(state.storage().clone()))
     }
 }
 
@@ -1,4 +1,5 @@
 mod app_state;
+mod backfill;
 mod config;
 mod extract;
 mod jwt_key;
@@ -6,6 +7,7 @@ mod models;
 mod routes;
 mod s3;
 pub mod server;
+mod storage;
 mod whoami;
 
 pub use crate::{
@@ -13,5 +15,10 @@ pub use crate::{
         detect_and_configure_development, Config, ConfigBuilder, ConfigError, ConfigFile,
         StandardConfig, StandardConfigFile,
     },
+    s3::{S3Config, S3Storage},
     server::{Server, ServerError},
+    storage::{
+        FilesystemConfig, FilesystemStorage, ModuleDownload, ModuleStorage, StorageError,
+        StorageKind, StorageResult,
+    },
 };
@@ -0,0 +1,21 @@
+CREATE TABLE module_versions
+(
+    id                          ident primary key default ident_create_v1(),
+    module_id                   ident                    NOT NULL REFERENCES modules (id),
+    version                     text                     NOT NULL,
+    hash                        char(64)                 NOT NULL,
+    owner_display_name          text,
+    metadata                    json,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP()
+);
+CREATE UNIQUE INDEX ON module_versions (module_id, version);
+
+-- Every module uploaded so far is the only known version of itself
+INSERT INTO module_versions (module_id, version, hash, owner_display_name, metadata, created_at)
+SELECT id,
+       COALESCE(metadata ->> 'version', latest_hash),
+       latest_hash,
+       owner_display_name,
+       metadata,
+       COALESCE(latest_hash_created_at, created_at)
+FROM modules;
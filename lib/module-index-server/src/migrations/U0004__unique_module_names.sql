@@ -0,0 +1,37 @@
+-- Uploads used to create a new module every time, so several modules may share a name. They are
+-- folded into the most recently uploaded one, which is what the index has been returning.
+CREATE TEMPORARY TABLE module_keepers AS
+SELECT id,
+       first_value(id) OVER (PARTITION BY name
+           ORDER BY COALESCE(latest_hash_created_at, created_at) DESC, id DESC) AS keeper_id
+FROM modules;
+
+-- When a version was uploaded under several of them, the most recent upload wins
+DELETE
+FROM module_versions
+WHERE id IN (SELECT id
+             FROM (SELECT module_versions.id,
+                          row_number() OVER (PARTITION BY module_keepers.keeper_id, module_versions.version
+                              ORDER BY module_versions.created_at DESC, module_versions.id DESC) AS rank
+                   FROM module_versions
+                            JOIN module_keepers ON module_keepers.id = module_versions.module_id) AS ranked
+             WHERE rank > 1);
+
+UPDATE module_versions
+SET module_id = module_keepers.keeper_id
+FROM module_keepers
+WHERE module_keepers.id = module_versions.module_id
+  AND module_keepers.id <> module_keepers.keeper_id;
+
+DELETE
+FROM modules
+WHERE id IN (SELECT id FROM module_keepers WHERE id <> keeper_id);
+
+DROP TABLE module_keepers;
+
+CREATE UNIQUE INDEX ON modules (name);
+
+-- Uploads used to record a made up owner, which nobody can authenticate as. Those modules are left
+-- without an owner, which keeps them read-only until an admin assigns them one.
+ALTER TABLE modules ALTER COLUMN owner_user_id DROP NOT NULL;
+UPDATE modules SET owner_user_id = NULL;
@@ -1 +1,2 @@
+pub mod module_version;
 pub mod si_module;
@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::si_module::ModuleId;
+
+/// A version of a module, kept around so that older versions can still be downloaded after a
+/// newer one has been uploaded.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[sea_orm(table_name = "module_versions")]
+pub struct Model {
+    #[sea_orm(primary_key, column_type = r##"custom("ident")"##)]
+    pub id: String,
+    #[sea_orm(column_type = r##"custom("ident")"##)]
+    pub module_id: ModuleId,
+    #[sea_orm(column_type = "Text")]
+    pub version: String,
+    pub hash: String,
+    pub owner_display_name: Option<String>,
+    pub metadata: Json,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl TryInto<module_index_client::ModuleVersionResponse> for Model {
+    type Error = serde_json::Error;
+
+    fn try_into(self) -> Result<module_index_client::ModuleVersionResponse, Self::Error> {
+        serde_json::from_value(serde_json::to_value(self)?)
+    }
+}
@@ -16,7 +16,8 @@ pub struct Model {
     pub name: String,
     #[sea_orm(column_type = "Text")]
     pub description: Option<String>,
-    pub owner_user_id: String,
+    /// `None` for modules uploaded before owners were recorded
+    pub owner_user_id: Option<String>,
     pub owner_display_name: Option<String>,
     pub metadata: Json,
     pub latest_hash: String,
@@ -11,7 +11,9 @@ use thiserror::Error;
 use tower_http::cors::CorsLayer;
 
 mod download_module_route;
+mod download_module_version_route;
 mod get_module_details_route;
+mod list_module_versions_route;
 mod list_modules_route;
 pub(crate) mod upsert_module_route;
 
@@ -32,6 +34,14 @@ pub fn routes(state: AppState) -> Router {
             "/modules/:module_id/download",
             get(download_module_route::download_module_route),
         )
+        .route(
+            "/modules/:module_id/versions",
+            get(list_module_versions_route::list_module_versions_route),
+        )
+        .route(
+            "/modules/:module_id/versions/:version/download",
+            get(download_module_version_route::download_module_version_route),
+        )
         .layer(CorsLayer::permissive());
 
     router.with_state(state)
@@ -66,3 +76,61 @@ impl IntoResponse for AppError {
         (status, body).into_response()
     }
 }
+
+#[cfg(test)]
+pub(crate) mod test_helpers {
+    use chrono::{DateTime, FixedOffset, Offset, Utc};
+    use ulid::Ulid;
+
+    use crate::{
+        extract::{Authorization, UserClaim},
+        models::{
+            module_version,
+            si_module::{self, ModuleId},
+        },
+    };
+
+    pub fn authorization() -> Authorization {
+        Authorization {
+            user_claim: UserClaim {
+                user_pk: Ulid::new(),
+                workspace_pk: Ulid::new(),
+            },
+            auth_token: "Bearer token".to_owned(),
+        }
+    }
+
+    pub fn module(name: &str) -> si_module::Model {
+        si_module::Model {
+            id: ModuleId(Ulid::new()),
+            name: name.to_owned(),
+            description: None,
+            owner_user_id: Some(Ulid::new().to_string()),
+            owner_display_name: None,
+            metadata: serde_json::json!({}),
+            latest_hash: "latest".to_owned(),
+            latest_hash_created_at: now(),
+            created_at: now(),
+        }
+    }
+
+    pub fn module_version(
+        module: &si_module::Model,
+        version: &str,
+        hash: &str,
+    ) -> module_version::Model {
+        module_version::Model {
+            id: Ulid::new().to_string(),
+            module_id: module.id,
+            version: version.to_owned(),
+            hash: hash.to_owned(),
+            owner_display_name: None,
+            metadata: serde_json::json!({ "version": version }),
+            created_at: now(),
+        }
+    }
+
+    fn now() -> DateTime<FixedOffset> {
+        DateTime::<FixedOffset>::from_utc(Utc::now().naive_utc(), Utc.fix())
+    }
+}
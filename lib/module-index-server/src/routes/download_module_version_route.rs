@@ -0,0 +1,137 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use hyper::StatusCode;
+use sea_orm::{ColumnTrait, DbErr, EntityTrait, QueryFilter};
+use thiserror::Error;
+
+use crate::{
+    extract::{Authorization, DbConnection, ExtractedStorage},
+    models::{module_version, si_module::ModuleId},
+    storage::{ModuleDownload, StorageError},
+};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum DownloadModuleVersionError {
+    #[error("db error: {0}")]
+    DbErr(#[from] DbErr),
+    #[error(r#"Version "{1}" of module "{0}" not found"#)]
+    NotFound(ModuleId, String),
+    #[error("storage error: {0}")]
+    Storage(#[from] StorageError),
+}
+
+// TODO: figure out how to not keep this serialization logic here
+impl IntoResponse for DownloadModuleVersionError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            Self::NotFound(..) | Self::Storage(StorageError::NotFound(_)) => {
+                (StatusCode::NOT_FOUND, self.to_string())
+            }
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        (status, body).into_response()
+    }
+}
+
+pub async fn download_module_version_route(
+    Path((module_id, version)): Path<(ModuleId, String)>,
+    Authorization { .. }: Authorization,
+    ExtractedStorage(storage): ExtractedStorage,
+    DbConnection(txn): DbConnection,
+) -> Result<ModuleDownload, DownloadModuleVersionError> {
+    let module_version = match module_version::Entity::find()
+        .filter(module_version::Column::ModuleId.eq(module_id))
+        .filter(module_version::Column::Version.eq(version.as_str()))
+        .one(&txn)
+        .await?
+    {
+        Some(module_version) => module_version,
+        _ => return Err(DownloadModuleVersionError::NotFound(module_id, version)),
+    };
+
+    Ok(storage
+        .download(&format!("{}.sipkg", module_version.hash))
+        .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use sea_orm::{DatabaseBackend, MockDatabase, TransactionTrait};
+
+    use super::*;
+    use crate::{
+        routes::test_helpers,
+        storage::{FilesystemStorage, ModuleStorage},
+    };
+
+    #[tokio::test]
+    async fn downloads_the_requested_version() {
+        let root = tempfile::TempDir::new().expect("unable to create storage dir");
+        let storage = Arc::new(FilesystemStorage::new(root.path()));
+        storage
+            .put("one.sipkg", b"version one")
+            .await
+            .expect("unable to store module");
+        storage
+            .put("two.sipkg", b"version two")
+            .await
+            .expect("unable to store module");
+
+        let module = test_helpers::module("ufo");
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![test_helpers::module_version(
+                &module, "1.0.0", "one",
+            )]])
+            .into_connection();
+        let txn = db.begin().await.expect("unable to begin txn");
+
+        let download = download_module_version_route(
+            Path((module.id, "1.0.0".to_owned())),
+            test_helpers::authorization(),
+            ExtractedStorage(storage),
+            DbConnection(txn),
+        )
+        .await
+        .expect("unable to download version");
+
+        assert!(matches!(download, ModuleDownload::Bytes(bytes) if bytes == b"version one"));
+    }
+
+    #[tokio::test]
+    async fn unknown_version_is_not_found() {
+        let root = tempfile::TempDir::new().expect("unable to create storage dir");
+        let module_id = test_helpers::module("ufo").id;
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![Vec::<module_version::Model>::new()])
+            .into_connection();
+        let txn = db.begin().await.expect("unable to begin txn");
+
+        let err = download_module_version_route(
+            Path((module_id, "3.0.0".to_owned())),
+            test_helpers::authorization(),
+            ExtractedStorage(Arc::new(FilesystemStorage::new(root.path()))),
+            DbConnection(txn),
+        )
+        .await
+        .expect_err("unknown version was found");
+
+        match &err {
+            DownloadModuleVersionError::NotFound(id, version) => {
+                assert_eq!((module_id, "3.0.0"), (*id, version.as_str()));
+            }
+            other => panic!("expected version to not be found, got: {other:?}"),
+        }
+        assert_eq!(StatusCode::NOT_FOUND, err.into_response().status());
+    }
+}
@@ -0,0 +1,130 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use hyper::StatusCode;
+use module_index_client::ListModuleVersionsResponse;
+use sea_orm::{ColumnTrait, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use thiserror::Error;
+
+use crate::{
+    extract::{Authorization, DbConnection},
+    models::{
+        module_version,
+        si_module::{self, ModuleId},
+    },
+};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ListModuleVersionsError {
+    #[error("db error: {0}")]
+    DbErr(#[from] DbErr),
+    #[error(r#"Module "{0}" not found"#)]
+    NotFound(ModuleId),
+    #[error("JSON serialization/deserialization error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+}
+
+// TODO: figure out how to not keep this serialization logic here
+impl IntoResponse for ListModuleVersionsError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            Self::NotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        (status, body).into_response()
+    }
+}
+
+pub async fn list_module_versions_route(
+    Path(module_id): Path<ModuleId>,
+    Authorization { .. }: Authorization,
+    DbConnection(txn): DbConnection,
+) -> Result<Json<ListModuleVersionsResponse>, ListModuleVersionsError> {
+    if si_module::Entity::find_by_id(module_id)
+        .one(&txn)
+        .await?
+        .is_none()
+    {
+        return Err(ListModuleVersionsError::NotFound(module_id));
+    }
+
+    let versions = module_version::Entity::find()
+        .filter(module_version::Column::ModuleId.eq(module_id))
+        .order_by_desc(module_version::Column::CreatedAt)
+        .all(&txn)
+        .await?
+        .into_iter()
+        .map(TryInto::try_into)
+        .collect::<Result<_, _>>()?;
+
+    Ok(Json(ListModuleVersionsResponse { versions }))
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::{DatabaseBackend, MockDatabase, TransactionTrait};
+
+    use super::*;
+    use crate::routes::test_helpers;
+
+    #[tokio::test]
+    async fn lists_versions_most_recent_first() {
+        let module = test_helpers::module("ufo");
+        let versions = vec![
+            test_helpers::module_version(&module, "2.0.0", "two"),
+            test_helpers::module_version(&module, "1.0.0", "one"),
+        ];
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![module.clone()]])
+            .append_query_results(vec![versions])
+            .into_connection();
+        let txn = db.begin().await.expect("unable to begin txn");
+
+        let Json(response) = list_module_versions_route(
+            Path(module.id),
+            test_helpers::authorization(),
+            DbConnection(txn),
+        )
+        .await
+        .expect("unable to list versions");
+
+        let versions: Vec<(&str, &str)> = response
+            .versions
+            .iter()
+            .map(|version| (version.version.as_str(), version.hash.as_str()))
+            .collect();
+        assert_eq!(vec![("2.0.0", "two"), ("1.0.0", "one")], versions);
+        assert!(response
+            .versions
+            .iter()
+            .all(|version| version.module_id == module.id.to_string()));
+    }
+
+    #[tokio::test]
+    async fn unknown_module_is_not_found() {
+        let module_id = test_helpers::module("ufo").id;
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![Vec::<si_module::Model>::new()])
+            .into_connection();
+        let txn = db.begin().await.expect("unable to begin txn");
+
+        let err = list_module_versions_route(
+            Path(module_id),
+            test_helpers::authorization(),
+            DbConnection(txn),
+        )
+        .await
+        .expect_err("unknown module was found");
+
+        assert!(matches!(err, ListModuleVersionsError::NotFound(id) if id == module_id));
+        assert_eq!(StatusCode::NOT_FOUND, err.into_response().status());
+    }
+}
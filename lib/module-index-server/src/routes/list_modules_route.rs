@@ -3,23 +3,38 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
+use base64::{engine::general_purpose, Engine};
+use chrono::{DateTime, FixedOffset};
 use hyper::StatusCode;
-use sea_orm::{ColumnTrait, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use module_index_client::{ListModulesRequest, ModuleSortBy, SortDirection};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, Condition, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::{
     app_state::AppState,
     extract::{Authorization, DbConnection},
-    models::si_module,
+    models::si_module::{self, ModuleId},
     whoami::{is_systeminit_auth_token, WhoamiError},
 };
 
+const DEFAULT_LIMIT: u64 = 50;
+const MAX_LIMIT: u64 = 200;
+
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum ListModulesError {
     #[error("db error: {0}")]
     DbErr(#[from] DbErr),
+    #[error("invalid cursor")]
+    InvalidCursor,
+    #[error("invalid limit {0}, must be between 1 and {1}")]
+    InvalidLimit(u64, u64),
+    #[error("JSON serialization/deserialization error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
     #[error("whoami error: {0}")]
     Whoami(#[from] WhoamiError),
 }
@@ -27,7 +42,12 @@ pub enum ListModulesError {
 // TODO: figure out how to not keep this serialization logic here
 impl IntoResponse for ListModulesError {
     fn into_response(self) -> Response {
-        let (status, error_message) = (StatusCode::INTERNAL_SERVER_ERROR, self.to_string());
+        let (status, error_message) = match self {
+            Self::InvalidCursor | Self::InvalidLimit(..) => {
+                (StatusCode::BAD_REQUEST, self.to_string())
+            }
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
 
         let body = Json(
             serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
@@ -39,14 +59,34 @@ impl IntoResponse for ListModulesError {
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct ListModulesRequest {
-    pub name: Option<String>,
+pub struct ListModulesResponse {
+    modules: Vec<si_module::Model>,
+    next_cursor: Option<String>,
 }
 
+/// Where the previous page ended. It's tied to the ordering it was created for, since the same
+/// position means something else in another ordering.
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct ListModulesResponse {
-    modules: Vec<si_module::Model>,
+struct ListModulesCursor {
+    sort_by: ModuleSortBy,
+    sort_direction: SortDirection,
+    id: ModuleId,
+    name: String,
+    created_at: DateTime<FixedOffset>,
+}
+
+impl ListModulesCursor {
+    fn encode(&self) -> Result<String, ListModulesError> {
+        Ok(general_purpose::URL_SAFE_NO_PAD.encode(serde_json::to_vec(self)?))
+    }
+
+    fn decode(cursor: &str) -> Result<Self, ListModulesError> {
+        let bytes = general_purpose::URL_SAFE_NO_PAD
+            .decode(cursor)
+            .map_err(|_| ListModulesError::InvalidCursor)?;
+        serde_json::from_slice(&bytes).map_err(|_| ListModulesError::InvalidCursor)
+    }
 }
 
 pub async fn list_module_route(
@@ -58,25 +98,239 @@ pub async fn list_module_route(
     Query(request): Query<ListModulesRequest>,
     State(state): State<AppState>,
 ) -> Result<Json<ListModulesResponse>, ListModulesError> {
-    let query = si_module::Entity::find();
-
-    if dbg!(state.restrict_listing())
-        && !dbg!(is_systeminit_auth_token(&auth_token, state.token_emails()).await?)
+    if state.restrict_listing()
+        && !is_systeminit_auth_token(&auth_token, state.token_emails()).await?
     {
-        return Ok(Json(ListModulesResponse { modules: vec![] }));
+        return Ok(Json(ListModulesResponse {
+            modules: vec![],
+            next_cursor: None,
+        }));
+    }
+
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(ListModulesError::InvalidLimit(limit, MAX_LIMIT));
+    }
+    let sort_by = request.sort_by.unwrap_or_default();
+    let sort_direction = request.sort_direction.unwrap_or_default();
+
+    let mut query = si_module::Entity::find().filter(filter_condition(&request));
+
+    if let Some(cursor) = &request.cursor {
+        let cursor = ListModulesCursor::decode(cursor)?;
+        if cursor.sort_by != sort_by || cursor.sort_direction != sort_direction {
+            return Err(ListModulesError::InvalidCursor);
+        }
+        query = query.filter(after_cursor_condition(&cursor));
     }
 
-    // filters
-    let query = if let Some(name_filter) = request.name {
-        query.filter(si_module::Column::Name.contains(&name_filter))
+    // ordering, with the id breaking ties so that pages never overlap
+    let sort_column = sort_column(sort_by);
+    let query = match sort_direction {
+        SortDirection::Asc => query
+            .order_by_asc(sort_column)
+            .order_by_asc(si_module::Column::Id),
+        SortDirection::Desc => query
+            .order_by_desc(sort_column)
+            .order_by_desc(si_module::Column::Id),
+    };
+
+    // fetch one more module than asked for to know whether there is a next page
+    let mut modules: Vec<si_module::Model> = query.limit(limit + 1).all(&txn).await?;
+    let next_cursor = if modules.len() as u64 > limit {
+        modules.truncate(limit as usize);
+        modules
+            .last()
+            .map(|last| {
+                ListModulesCursor {
+                    sort_by,
+                    sort_direction,
+                    id: last.id,
+                    name: last.name.clone(),
+                    created_at: last.created_at,
+                }
+                .encode()
+            })
+            .transpose()?
     } else {
-        query
+        None
+    };
+
+    Ok(Json(ListModulesResponse {
+        modules,
+        next_cursor,
+    }))
+}
+
+fn sort_column(sort_by: ModuleSortBy) -> si_module::Column {
+    match sort_by {
+        ModuleSortBy::CreatedAt => si_module::Column::CreatedAt,
+        ModuleSortBy::Name => si_module::Column::Name,
+    }
+}
+
+fn filter_condition(request: &ListModulesRequest) -> Condition {
+    let mut condition = Condition::all();
+
+    if let Some(name) = &request.name {
+        condition = condition.add(si_module::Column::Name.contains(name));
+    }
+    if let Some(owner) = &request.owner {
+        condition = condition.add(
+            Condition::any()
+                .add(si_module::Column::OwnerDisplayName.contains(owner))
+                .add(si_module::Column::OwnerUserId.eq(owner.as_str())),
+        );
+    }
+    // the schemas, funcs and categories of a module are in the metadata stored by the upsert
+    if let Some(schema_name) = &request.schema_name {
+        condition = condition.add(Expr::cust_with_values(
+            "EXISTS (SELECT 1 FROM json_array_elements_text(metadata -> 'schemas') AS schema_name \
+             WHERE schema_name ILIKE $1)",
+            [like_pattern(schema_name)],
+        ));
+    }
+    if let Some(func_name) = &request.func_name {
+        condition = condition.add(Expr::cust_with_values(
+            "EXISTS (SELECT 1 FROM json_array_elements(metadata -> 'funcs') AS func \
+             WHERE func ->> 'name' ILIKE $1)",
+            [like_pattern(func_name)],
+        ));
+    }
+    if let Some(category) = &request.category {
+        condition = condition.add(Expr::cust_with_values(
+            "EXISTS (SELECT 1 FROM json_array_elements_text(metadata -> 'categories') AS category \
+             WHERE category = $1)",
+            [category.clone()],
+        ));
+    }
+    if let Some(created_after) = request.created_after {
+        condition = condition.add(si_module::Column::CreatedAt.gte(created_after));
+    }
+    if let Some(created_before) = request.created_before {
+        condition = condition.add(si_module::Column::CreatedAt.lt(created_before));
+    }
+
+    condition
+}
+
+fn after_cursor_condition(cursor: &ListModulesCursor) -> Condition {
+    let (column, value): (si_module::Column, sea_orm::Value) = match cursor.sort_by {
+        ModuleSortBy::CreatedAt => (si_module::Column::CreatedAt, cursor.created_at.into()),
+        ModuleSortBy::Name => (si_module::Column::Name, cursor.name.clone().into()),
+    };
+
+    let (past_value, past_id) = match cursor.sort_direction {
+        SortDirection::Asc => (
+            column.gt(value.clone()),
+            si_module::Column::Id.gt(cursor.id),
+        ),
+        SortDirection::Desc => (
+            column.lt(value.clone()),
+            si_module::Column::Id.lt(cursor.id),
+        ),
     };
 
-    // ordering
-    let query = query.order_by_asc(si_module::Column::Name);
+    Condition::any()
+        .add(past_value)
+        .add(Condition::all().add(column.eq(value)).add(past_id))
+}
+
+/// Matches values containing the given text, escaping the characters `LIKE` treats specially.
+fn like_pattern(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{escaped}%")
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::{DbBackend, QueryTrait, Values};
+
+    use super::*;
+    use crate::routes::test_helpers;
+
+    fn cursor(sort_by: ModuleSortBy, sort_direction: SortDirection) -> ListModulesCursor {
+        let module = test_helpers::module("ufo");
+        ListModulesCursor {
+            sort_by,
+            sort_direction,
+            id: module.id,
+            name: module.name,
+            created_at: module.created_at,
+        }
+    }
+
+    fn after_cursor_statement(cursor: &ListModulesCursor) -> sea_orm::Statement {
+        si_module::Entity::find()
+            .filter(after_cursor_condition(cursor))
+            .build(DbBackend::Postgres)
+    }
+
+    #[test]
+    fn cursor_round_trip() {
+        let cursor = cursor(ModuleSortBy::CreatedAt, SortDirection::Desc);
+
+        let decoded = ListModulesCursor::decode(&cursor.encode().expect("unable to encode cursor"))
+            .expect("unable to decode cursor");
+
+        assert_eq!(cursor.sort_by, decoded.sort_by);
+        assert_eq!(cursor.sort_direction, decoded.sort_direction);
+        assert_eq!(cursor.id, decoded.id);
+        assert_eq!(cursor.name, decoded.name);
+        assert_eq!(cursor.created_at, decoded.created_at);
+    }
+
+    #[test]
+    fn invalid_cursors_are_refused() {
+        let not_json = general_purpose::URL_SAFE_NO_PAD.encode(b"not a cursor");
+
+        for invalid in ["not base64!", not_json.as_str()] {
+            assert!(matches!(
+                ListModulesCursor::decode(invalid),
+                Err(ListModulesError::InvalidCursor)
+            ));
+        }
+    }
 
-    let modules: Vec<si_module::Model> = query.all(&txn).await?;
+    #[test]
+    fn like_pattern_escapes_wildcards() {
+        assert_eq!("%aws%", like_pattern("aws"));
+        assert_eq!(r"%50\%\_off\\%", like_pattern(r"50%_off\"));
+    }
+
+    #[test]
+    fn after_cursor_ascending() {
+        let cursor = cursor(ModuleSortBy::Name, SortDirection::Asc);
+        let statement = after_cursor_statement(&cursor);
+
+        assert!(statement.sql.ends_with(
+            r#"WHERE "modules"."name" > $1 OR ("modules"."name" = $2 AND "modules"."id" > $3)"#
+        ));
+        assert_eq!(
+            Some(Values(vec!["ufo".into(), "ufo".into(), cursor.id.into()])),
+            statement.values
+        );
+    }
+
+    #[test]
+    fn after_cursor_descending() {
+        let cursor = cursor(ModuleSortBy::CreatedAt, SortDirection::Desc);
+        let statement = after_cursor_statement(&cursor);
 
-    Ok(Json(ListModulesResponse { modules }))
+        assert!(statement.sql.ends_with(concat!(
+            r#"WHERE "modules"."created_at" < $1 "#,
+            r#"OR ("modules"."created_at" = $2 AND "modules"."id" < $3)"#
+        )));
+        assert_eq!(
+            Some(Values(vec![
+                cursor.created_at.into(),
+                cursor.created_at.into(),
+                cursor.id.into()
+            ])),
+            statement.values
+        );
+    }
 }
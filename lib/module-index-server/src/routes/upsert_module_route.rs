@@ -1,22 +1,24 @@
 use axum::{
-    extract::Multipart,
+    extract::{Multipart, State},
     response::{IntoResponse, Response},
     Json,
 };
 use chrono::{DateTime, FixedOffset, Offset, Utc};
 use hyper::StatusCode;
 use module_index_client::{FuncMetadata, ModuleDetailsResponse};
-use s3::error::S3Error;
-use sea_orm::{ActiveModelTrait, DbErr, Set};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DbErr, EntityTrait, QueryFilter, RuntimeErr, Set, SqlxError,
+};
 use serde::{Deserialize, Serialize};
-use si_pkg::{SiPkg, SiPkgError};
+use si_pkg::{PkgPublicKey, SiPkg, SiPkgError, SignatureError};
 use telemetry::prelude::*;
 use thiserror::Error;
-use ulid::Ulid;
 
 use crate::{
-    extract::{Authorization, DbConnection, ExtractedS3Bucket},
-    models::si_module,
+    app_state::AppState,
+    extract::{Authorization, DbConnection, ExtractedStorage},
+    models::{module_version, si_module},
+    storage::StorageError,
 };
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -30,22 +32,37 @@ pub struct UpsertModuleRequest {
 pub enum UpsertModuleError {
     #[error("db error: {0}")]
     DbErr(#[from] DbErr),
+    #[error("module signature is invalid: {0}")]
+    InvalidSignature(#[source] SiPkgError),
     #[error("file upload error: {0}")]
     IoError(#[from] std::io::Error),
-    #[error("s3 error: {0}")]
-    S3Error(#[from] S3Error),
+    #[error(r#"module "{0}" may only be uploaded by its owner"#)]
+    NotModuleOwner(String),
     #[error("JSON serialization/deserialization error: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error("module parsing error: {0}")]
     SiPkgError(#[from] SiPkgError),
+    #[error("storage error: {0}")]
+    Storage(#[from] StorageError),
+    #[error("module must be signed")]
+    UnsignedModule,
     #[error("upload is required")]
     UploadRequiredError,
+    #[error(r#"version "{1}" of module "{0}" was already uploaded with different contents"#)]
+    VersionAlreadyExists(String, String),
 }
 
 // TODO: figure out how to not keep this serialization logic here
 impl IntoResponse for UpsertModuleError {
     fn into_response(self) -> Response {
-        let (status, error_message) = (StatusCode::INTERNAL_SERVER_ERROR, self.to_string());
+        let (status, error_message) = match self {
+            Self::InvalidSignature(_) | Self::UnsignedModule => {
+                (StatusCode::BAD_REQUEST, self.to_string())
+            }
+            Self::NotModuleOwner(_) => (StatusCode::FORBIDDEN, self.to_string()),
+            Self::VersionAlreadyExists(..) => (StatusCode::CONFLICT, self.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
 
         let body = Json(
             serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
@@ -57,9 +74,10 @@ impl IntoResponse for UpsertModuleError {
 
 // #[debug_handler]
 pub async fn upsert_module_route(
-    Authorization { .. }: Authorization,
-    ExtractedS3Bucket(s3_bucket): ExtractedS3Bucket,
+    Authorization { user_claim, .. }: Authorization,
+    ExtractedStorage(storage): ExtractedStorage,
     DbConnection(txn): DbConnection,
+    State(state): State<AppState>,
     mut multipart: Multipart,
 ) -> Result<Json<ModuleDetailsResponse>, UpsertModuleError> {
     info!("Upsert module");
@@ -72,15 +90,28 @@ pub async fn upsert_module_route(
     info!("Got part data");
 
     // SiPkg using old term "package" but we are dealing with a "module"
-    let loaded_module = dbg!(SiPkg::load_from_bytes(data.to_vec()))?;
-    let module_metadata = dbg!(loaded_module.metadata())?;
+    let loaded_module = SiPkg::load_from_bytes(data.to_vec())?;
+    let module_metadata = loaded_module.metadata()?;
+
+    // signatures are checked against the module contents here, but whether the signers are
+    // trusted is up to whoever installs the module
+    if let Some(invalid_signer) = loaded_module.invalid_signers()?.first() {
+        return Err(UpsertModuleError::InvalidSignature(
+            SignatureError::InvalidSignature(*invalid_signer).into(),
+        ));
+    }
+    let signers = loaded_module.signers()?;
+    if signers.is_empty() {
+        if state.require_signed_modules() {
+            return Err(UpsertModuleError::UnsignedModule);
+        }
+        warn!(name = module_metadata.name(), "accepting unsigned module");
+    }
 
     let version = module_metadata.version().to_owned();
-    let schemas: Vec<String> = loaded_module
-        .schemas()?
-        .iter()
-        .map(|s| s.name().to_owned())
-        .collect();
+    let pkg_schemas = loaded_module.schemas()?;
+    let schemas: Vec<String> = pkg_schemas.iter().map(|s| s.name().to_owned()).collect();
+    let categories = module_categories(&loaded_module)?;
     let funcs: Vec<FuncMetadata> = loaded_module
         .funcs()?
         .iter()
@@ -91,37 +122,132 @@ pub async fn upsert_module_route(
         })
         .collect();
 
-    let new_module = si_module::ActiveModel {
-        name: Set(module_metadata.name().to_owned()),
-        description: Set(Some(module_metadata.description().to_owned())),
-        // owner_user_id: Set(claim.user_pk.to_string()),
-        owner_user_id: Set(Ulid::new().to_string()),
+    let hash = module_metadata.hash().to_string();
+    let metadata = serde_json::to_value(ExtraMetadata {
+        version: version.clone(),
+        schemas,
+        funcs,
+        categories,
+        signers,
+    })?;
+    // maybe use db's `CLOCK_TIMESTAMP()`?
+    let now = DateTime::<FixedOffset>::from_utc(Utc::now().naive_utc(), Utc.fix());
+
+    let existing_module = si_module::Entity::find()
+        .filter(si_module::Column::Name.eq(module_metadata.name()))
+        .one(&txn)
+        .await?;
+
+    // Versions are immutable so that workspaces can pin them: uploading the same contents again
+    // is a no-op whereas uploading different contents under the same version is refused
+    let user_pk = user_claim.user_pk.to_string();
+    if let Some(existing_module) = &existing_module {
+        if !may_upload(existing_module, &user_pk) {
+            return Err(UpsertModuleError::NotModuleOwner(
+                existing_module.name.clone(),
+            ));
+        }
+        let existing_version = module_version::Entity::find()
+            .filter(module_version::Column::ModuleId.eq(existing_module.id))
+            .filter(module_version::Column::Version.eq(version.as_str()))
+            .one(&txn)
+            .await?;
+        if let Some(existing_version) = existing_version {
+            if existing_version.hash != hash {
+                return Err(UpsertModuleError::VersionAlreadyExists(
+                    existing_module.name.clone(),
+                    version,
+                ));
+            }
+            return Ok(Json(existing_module.clone().try_into()?));
+        }
+    }
+
+    storage.put(&format!("{hash}.sipkg"), &data).await?;
+
+    let module: si_module::Model = match existing_module {
+        Some(existing_module) => {
+            let mut module: si_module::ActiveModel = existing_module.into();
+            module.description = Set(Some(module_metadata.description().to_owned()));
+            module.owner_display_name = Set(Some(module_metadata.created_by().to_owned()));
+            module.latest_hash = Set(hash.clone());
+            module.latest_hash_created_at = Set(now);
+            module.metadata = Set(metadata.clone());
+            module.update(&txn).await?
+        }
+        None => {
+            let new_module = si_module::ActiveModel {
+                name: Set(module_metadata.name().to_owned()),
+                description: Set(Some(module_metadata.description().to_owned())),
+                owner_user_id: Set(Some(user_pk)),
+                owner_display_name: Set(Some(module_metadata.created_by().to_owned())),
+                latest_hash: Set(hash.clone()),
+                latest_hash_created_at: Set(now),
+                metadata: Set(metadata.clone()),
+                ..Default::default() // all other attributes are `NotSet`
+            };
+            // Another upload of the same module may have inserted it in the meantime
+            new_module.insert(&txn).await.map_err(|err| {
+                version_already_exists_on_conflict(err, module_metadata.name(), &version)
+            })?
+        }
+    };
+
+    let new_version = module_version::ActiveModel {
+        module_id: Set(module.id),
+        version: Set(version.clone()),
+        hash: Set(hash),
         owner_display_name: Set(Some(module_metadata.created_by().to_owned())),
-        latest_hash: Set(module_metadata.hash().to_string()),
-        // maybe use db's `CLOCK_TIMESTAMP()`?
-        latest_hash_created_at: Set(DateTime::<FixedOffset>::from_utc(
-            Utc::now().naive_utc(),
-            Utc.fix(),
-        )),
-        metadata: Set(serde_json::to_value(ExtraMetadata {
-            version,
-            schemas,
-            funcs,
-        })?),
+        metadata: Set(metadata),
+        created_at: Set(now),
         ..Default::default() // all other attributes are `NotSet`
     };
+    new_version
+        .insert(&txn)
+        .await
+        .map_err(|err| version_already_exists_on_conflict(err, &module.name, &version))?;
 
-    // TODO: put below
-    // upload to s3
-    s3_bucket
-        .put_object(format!("{}.sipkg", module_metadata.hash()), &data)
-        .await?;
+    txn.commit().await?;
 
-    let new_module: si_module::Model = new_module.insert(&txn).await?;
+    Ok(Json(module.try_into()?))
+}
 
-    txn.commit().await?;
+/// Whether a user may upload new versions of a module. Modules uploaded before owners were recorded
+/// have none and stay read-only until an admin assigns them one.
+fn may_upload(module: &si_module::Model, user_pk: &str) -> bool {
+    module
+        .owner_user_id
+        .as_deref()
+        .is_some_and(|owner| owner == user_pk)
+}
+
+/// The categories of the schemas in a module, sorted and without duplicates.
+pub(crate) fn module_categories(module: &SiPkg) -> Result<Vec<String>, SiPkgError> {
+    let mut categories: Vec<String> = module
+        .schemas()?
+        .iter()
+        .map(|s| s.category().to_owned())
+        .collect();
+    categories.sort();
+    categories.dedup();
+    Ok(categories)
+}
 
-    Ok(dbg!(Json(new_module.try_into()?)))
+/// Turns the database refusing a duplicate module or version, which happens when the same version
+/// is uploaded concurrently, into [`UpsertModuleError::VersionAlreadyExists`].
+fn version_already_exists_on_conflict(err: DbErr, name: &str, version: &str) -> UpsertModuleError {
+    let is_unique_violation = match &err {
+        DbErr::Exec(RuntimeErr::SqlxError(SqlxError::Database(db_err)))
+        | DbErr::Query(RuntimeErr::SqlxError(SqlxError::Database(db_err))) => {
+            db_err.code().as_deref() == Some("23505")
+        }
+        _ => false,
+    };
+    if is_unique_violation {
+        UpsertModuleError::VersionAlreadyExists(name.to_owned(), version.to_owned())
+    } else {
+        err.into()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -129,4 +255,29 @@ pub struct ExtraMetadata {
     pub version: String,
     pub schemas: Vec<String>,
     pub funcs: Vec<FuncMetadata>,
+    // modules uploaded before categories were recorded don't have them
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// The keys which signed the module, unsigned modules have none.
+    #[serde(default)]
+    pub signers: Vec<PkgPublicKey>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::test_helpers;
+
+    #[test]
+    fn only_owners_may_upload() {
+        let owner = ulid::Ulid::new().to_string();
+        let mut module = test_helpers::module("ufo");
+        module.owner_user_id = Some(owner.clone());
+        assert!(may_upload(&module, &owner));
+        assert!(!may_upload(&module, &ulid::Ulid::new().to_string()));
+
+        // Modules without an owner are read-only
+        module.owner_user_id = None;
+        assert!(!may_upload(&module, &owner));
+    }
 }
@@ -1,5 +1,12 @@
+use axum::async_trait;
+use s3::{creds::error::CredentialsError, Bucket, Region};
 use serde::{Deserialize, Serialize};
 
+use crate::storage::{ModuleDownload, ModuleStorage, StorageError, StorageResult};
+
+/// How long presigned download urls stay valid.
+const PRESIGNED_URL_EXPIRY_SECONDS: u32 = 60 * 5;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default)]
 pub struct S3Config {
@@ -8,6 +15,11 @@ pub struct S3Config {
     pub region: String,
     pub bucket: String,
     pub path_prefix: String,
+    /// The endpoint of an S3-compatible object store (i.e. MinIO), AWS S3 when unset
+    pub endpoint: Option<String>,
+    /// Whether to address buckets as `{endpoint}/{bucket}` rather than `{bucket}.{endpoint}`,
+    /// which most S3-compatible object stores require
+    pub path_style: bool,
 }
 
 impl Default for S3Config {
@@ -19,6 +31,79 @@ impl Default for S3Config {
             bucket: String::from("modules-index-dev"),
             // TODO? is this right?
             path_prefix: String::from("dev"),
+            endpoint: None,
+            path_style: false,
+        }
+    }
+}
+
+/// Keeps modules in an S3 bucket, downloaded by clients through presigned urls.
+#[derive(Clone, Debug)]
+pub struct S3Storage {
+    bucket: Bucket,
+}
+
+impl S3Storage {
+    pub fn new(config: &S3Config) -> StorageResult<Self> {
+        // try to load aws creds from a few different places
+        let credentials = match (&config.access_key_id, &config.secret_access_key) {
+            (Some(aws_key), Some(aws_secret)) => {
+                s3::creds::Credentials::new(Some(aws_key), Some(aws_secret), None, None, None)?
+            }
+            (None, None) => match s3::creds::Credentials::from_env() {
+                Ok(creds) => creds,
+                Err(CredentialsError::MissingEnvVar(_, _)) => {
+                    s3::creds::Credentials::from_profile(None)?
+                }
+                Err(err) => return Err(err.into()),
+            },
+            _ => return Err(StorageError::AwsConfigError),
+        };
+
+        let region = match &config.endpoint {
+            Some(endpoint) => Region::Custom {
+                region: config.region.clone(),
+                endpoint: endpoint.clone(),
+            },
+            None => config
+                .region
+                .parse::<Region>()
+                .map_err(|_| StorageError::InvalidRegion(config.region.clone()))?,
+        };
+
+        let bucket = Bucket::new(&config.bucket, region, credentials)?;
+        let bucket = if config.path_style {
+            bucket.with_path_style()
+        } else {
+            bucket
+        };
+
+        Ok(Self { bucket })
+    }
+}
+
+#[async_trait]
+impl ModuleStorage for S3Storage {
+    async fn put(&self, key: &str, bytes: &[u8]) -> StorageResult<()> {
+        self.bucket.put_object(key, bytes).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> StorageResult<Vec<u8>> {
+        let response = self.bucket.get_object(key).await?;
+        match response.status_code() {
+            200..=299 => Ok(response.to_vec()),
+            404 => Err(StorageError::NotFound(key.to_owned())),
+            status_code => {
+                Err(::s3::error::S3Error::Http(status_code, response.to_string()).into())
+            }
         }
     }
+
+    async fn download(&self, key: &str) -> StorageResult<ModuleDownload> {
+        let url = self
+            .bucket
+            .presign_get(key, PRESIGNED_URL_EXPIRY_SECONDS, None)?;
+        Ok(ModuleDownload::Redirect(url))
+    }
 }
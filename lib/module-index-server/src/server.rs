@@ -1,11 +1,10 @@
-use std::{io, net::SocketAddr, path::Path, time::Duration};
+use std::{io, net::SocketAddr, path::Path, sync::Arc, time::Duration};
 
 use super::routes;
 
 use axum::routing::IntoMakeService;
 use axum::Router;
 use hyper::server::{accept::Accept, conn::AddrIncoming};
-use s3::creds::{error::CredentialsError, Credentials as AwsCredentials};
 use sea_orm::{ConnectOptions, Database, DatabaseConnection, DbErr};
 use si_data_pg::{PgPool, PgPoolConfig, PgPoolError};
 use si_posthog::{PosthogClient, PosthogConfig};
@@ -20,8 +19,9 @@ use tower_http::trace::{DefaultMakeSpan, TraceLayer};
 
 use crate::{
     app_state::{AppState, ShutdownSource},
+    backfill,
     jwt_key::{JwtKeyError, JwtPublicSigningKey},
-    s3::S3Config,
+    storage::{storage_for_config, ModuleStorage, StorageError},
     Config,
 };
 
@@ -34,10 +34,6 @@ mod embedded_migrations {
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ServerError {
-    #[error("bad aws config")]
-    AwsConfigError,
-    #[error("aws creds error: {0}")]
-    CredentialsError(#[from] CredentialsError),
     #[error("db error: {0}")]
     DbErr(#[from] DbErr),
     #[error("hyper server error")]
@@ -52,6 +48,8 @@ pub enum ServerError {
     SerdeJson(#[from] serde_json::Error),
     #[error("failed to setup signal handler")]
     Signal(#[source] io::Error),
+    #[error("storage error: {0}")]
+    Storage(#[from] StorageError),
 }
 
 impl From<PgPoolError> for ServerError {
@@ -79,28 +77,15 @@ impl Server<(), ()> {
     ) -> Result<(Server<AddrIncoming, SocketAddr>, broadcast::Receiver<()>)> {
         // socket_addr
 
-        // try to load aws creds from a few different places
-        let aws_creds = match (&config.s3().access_key_id, &config.s3().secret_access_key) {
-            (Some(aws_key), Some(aws_secret)) => {
-                AwsCredentials::new(Some(aws_key), Some(aws_secret), None, None, None)?
-            }
-            (None, None) => match AwsCredentials::from_env() {
-                Ok(creds) => creds,
-                Err(CredentialsError::MissingEnvVar(_, _)) => AwsCredentials::from_profile(None)?,
-                Err(err) => return Err(err.into()),
-            },
-            _ => {
-                return Err(ServerError::AwsConfigError);
-            }
-        };
+        let storage = storage_for_config(config.storage(), config.filesystem(), config.s3())?;
 
         let (service, shutdown_rx, shutdown_broadcast_rx) = build_service(
             pg_pool,
             jwt_public_signing_key,
             posthog_client,
-            aws_creds,
-            config.s3().clone(),
+            storage,
             config.restrict_listing(),
+            config.require_signed_modules(),
         )?;
 
         info!(
@@ -165,6 +150,23 @@ impl Server<(), ()> {
             .await?)
     }
 
+    /// Records the categories of modules uploaded before categories were, reading them from the
+    /// stored modules. This runs in the background so the server doesn't wait on it to start.
+    #[instrument(name = "module-index.init.spawn_backfill_module_categories", skip_all)]
+    pub fn spawn_backfill_module_categories(config: &Config, db: DatabaseConnection) -> Result<()> {
+        let storage = storage_for_config(config.storage(), config.filesystem(), config.s3())?;
+
+        drop(tokio::spawn(async move {
+            match backfill::backfill_module_categories(&db, storage.as_ref()).await {
+                Ok(0) => {}
+                Ok(backfilled) => info!(backfilled, "backfilled module categories"),
+                Err(err) => error!(error = %err, "unable to backfill module categories"),
+            }
+        }));
+
+        Ok(())
+    }
+
     #[instrument(name = "sdf.init.load_jwt_public_signing_key", skip_all)]
     pub async fn load_jwt_public_signing_key(
         path: impl AsRef<Path>,
@@ -213,9 +215,9 @@ pub fn build_service(
     pg_pool: DatabaseConnection,
     jwt_public_signing_key: JwtPublicSigningKey,
     posthog_client: PosthogClient,
-    aws_creds: AwsCredentials,
-    s3_config: S3Config,
+    storage: Arc<dyn ModuleStorage>,
     restrict_listing: bool,
+    require_signed_modules: bool,
 ) -> Result<(Router, oneshot::Receiver<()>, broadcast::Receiver<()>)> {
     let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
     let (shutdown_broadcast_tx, shutdown_broadcast_rx) = broadcast::channel(1);
@@ -224,9 +226,9 @@ pub fn build_service(
         pg_pool,
         jwt_public_signing_key,
         posthog_client,
-        aws_creds,
-        s3_config,
+        storage,
         restrict_listing,
+        require_signed_modules,
         shutdown_broadcast_tx.clone(),
         shutdown_tx,
     );
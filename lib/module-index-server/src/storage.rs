@@ -0,0 +1,199 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use axum::{
+    async_trait,
+    response::{IntoResponse, Redirect, Response},
+};
+use hyper::header;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::s3::{S3Config, S3Storage};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("bad aws config")]
+    AwsConfigError,
+    #[error("aws creds error: {0}")]
+    CredentialsError(#[from] ::s3::creds::error::CredentialsError),
+    #[error("invalid storage key: {0}")]
+    InvalidKey(String),
+    #[error("invalid s3 region: {0}")]
+    InvalidRegion(String),
+    #[error("file storage error on {1}: {0}")]
+    Io(#[source] io::Error, PathBuf),
+    #[error(r#"stored module "{0}" not found"#)]
+    NotFound(String),
+    #[error("s3 error: {0}")]
+    S3Error(#[from] ::s3::error::S3Error),
+}
+
+pub type StorageResult<T> = Result<T, StorageError>;
+
+/// Where the bytes of uploaded modules are kept, keyed by their hash.
+#[async_trait]
+pub trait ModuleStorage: std::fmt::Debug + Send + Sync {
+    /// Stores the bytes of a module under the given key, replacing anything stored there before.
+    async fn put(&self, key: &str, bytes: &[u8]) -> StorageResult<()>;
+
+    /// Reads the bytes of the module stored under the given key.
+    async fn get(&self, key: &str) -> StorageResult<Vec<u8>>;
+
+    /// Returns how the module stored under the given key can be downloaded.
+    async fn download(&self, key: &str) -> StorageResult<ModuleDownload>;
+}
+
+/// How a stored module is handed to clients.
+#[derive(Debug)]
+pub enum ModuleDownload {
+    /// The module is served directly by the module index
+    Bytes(Vec<u8>),
+    /// The module is downloaded from another location, i.e. a presigned S3 url
+    Redirect(String),
+}
+
+impl IntoResponse for ModuleDownload {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Bytes(bytes) => {
+                ([(header::CONTENT_TYPE, "application/octet-stream")], bytes).into_response()
+            }
+            Self::Redirect(url) => Redirect::temporary(&url).into_response(),
+        }
+    }
+}
+
+/// The kind of storage modules are kept in.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageKind {
+    /// A directory on the local filesystem, see [`FilesystemConfig`]
+    Filesystem,
+    /// AWS S3 or any S3-compatible object store, see [`S3Config`]
+    S3,
+}
+
+impl Default for StorageKind {
+    fn default() -> Self {
+        Self::S3
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilesystemConfig {
+    pub root: PathBuf,
+}
+
+impl Default for FilesystemConfig {
+    fn default() -> Self {
+        Self {
+            root: PathBuf::from("/var/lib/si/module-index"),
+        }
+    }
+}
+
+/// Builds the storage of the given kind.
+pub fn storage_for_config(
+    kind: StorageKind,
+    filesystem_config: &FilesystemConfig,
+    s3_config: &S3Config,
+) -> StorageResult<Arc<dyn ModuleStorage>> {
+    Ok(match kind {
+        StorageKind::Filesystem => Arc::new(FilesystemStorage::new(&filesystem_config.root)),
+        StorageKind::S3 => Arc::new(S3Storage::new(s3_config)?),
+    })
+}
+
+/// Keeps modules as files in a directory, for tests and self-hosted installs without an object
+/// store.
+#[derive(Clone, Debug)]
+pub struct FilesystemStorage {
+    root: PathBuf,
+}
+
+impl FilesystemStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path_for_key(&self, key: &str) -> StorageResult<PathBuf> {
+        let is_single_file_name = Path::new(key).file_name().map_or(false, |name| name == key);
+        if !is_single_file_name {
+            return Err(StorageError::InvalidKey(key.to_owned()));
+        }
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl ModuleStorage for FilesystemStorage {
+    async fn put(&self, key: &str, bytes: &[u8]) -> StorageResult<()> {
+        let path = self.path_for_key(key)?;
+        tokio::fs::create_dir_all(&self.root)
+            .await
+            .map_err(|err| StorageError::Io(err, self.root.clone()))?;
+
+        // Write next to the destination first so a module is never read half written
+        let tmp_path = path.with_extension("partial");
+        tokio::fs::write(&tmp_path, bytes)
+            .await
+            .map_err(|err| StorageError::Io(err, tmp_path.clone()))?;
+        tokio::fs::rename(&tmp_path, &path)
+            .await
+            .map_err(|err| StorageError::Io(err, path))?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> StorageResult<Vec<u8>> {
+        let path = self.path_for_key(key)?;
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => Ok(bytes),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                Err(StorageError::NotFound(key.to_owned()))
+            }
+            Err(err) => Err(StorageError::Io(err, path)),
+        }
+    }
+
+    async fn download(&self, key: &str) -> StorageResult<ModuleDownload> {
+        Ok(ModuleDownload::Bytes(self.get(key).await?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn filesystem_storage_round_trip() {
+        let root = tempfile::TempDir::new().expect("unable to create storage dir");
+        let storage = FilesystemStorage::new(root.path());
+
+        storage
+            .put("abc.sipkg", b"module bytes")
+            .await
+            .expect("unable to store module");
+        let download = storage
+            .download("abc.sipkg")
+            .await
+            .expect("unable to download module");
+
+        assert!(matches!(download, ModuleDownload::Bytes(bytes) if bytes == b"module bytes"));
+        assert!(matches!(
+            storage.download("missing.sipkg").await,
+            Err(StorageError::NotFound(_))
+        ));
+        assert!(matches!(
+            storage.put("../escape.sipkg", b"").await,
+            Err(StorageError::InvalidKey(_))
+        ));
+    }
+}
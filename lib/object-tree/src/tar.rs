@@ -12,3 +12,7 @@ fn object_path(hash: &Hash) -> PathBuf {
 fn ref_path(name: impl AsRef<Path>) -> PathBuf {
     Path::new("refs").join(name)
 }
+
+fn signature_path(name: impl AsRef<Path>) -> PathBuf {
+    Path::new("signatures").join(name)
+}
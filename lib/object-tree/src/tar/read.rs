@@ -6,7 +6,7 @@ use thiserror::Error;
 use crate::{
     graph::{GraphError, HashedNodeWithEntries, NodeWithEntries, ObjectTree, ReadBytes},
     hash::{Hash, HashParseError},
-    tar::{object_path, ref_path},
+    tar::{object_path, ref_path, signature_path},
 };
 
 /// Errors that can occur when reading a module bundle from a tar file
@@ -16,6 +16,9 @@ pub enum TarReadError {
     /// [`String`]
     #[error("Error parsing hash: {0}")]
     Hash(#[from] HashParseError),
+    /// When the contents of a node don't hash to the [`struct@Hash`] it is stored under
+    #[error("node {0} does not match its contents, which hash to {1}")]
+    HashMismatch(Hash, Hash),
     /// When an error occurs while reading bytes
     #[error("io error when reading: {0}")]
     IoRead(#[from] std::io::Error),
@@ -43,8 +46,25 @@ impl<T> ObjectTree<T> {
     /// - An I/O error occurs while reading from a file
     /// - An expected file does not exist or cannot be opened
     /// - A node file fails to be correctly parsed
+    /// - The contents of a node file don't match the hash it is stored under
     /// - The resulting tree structure has no root node or multiple root nodes
     pub fn read_from_tar<N>(tar_data: Vec<u8>) -> Result<ObjectTree<N>, TarReadError>
+    where
+        N: ReadBytes,
+    {
+        let (tree, _signatures) = Self::read_from_tar_with_signatures(tar_data)?;
+        Ok(tree)
+    }
+
+    /// Reads and returns an [`ObjectTree`] along with the detached signatures stored next to it,
+    /// sorted by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` for the same reasons as [`ObjectTree::read_from_tar`].
+    pub fn read_from_tar_with_signatures<N>(
+        tar_data: Vec<u8>,
+    ) -> Result<(ObjectTree<N>, Vec<(String, Vec<u8>)>), TarReadError>
     where
         N: ReadBytes,
     {
@@ -90,10 +110,9 @@ impl<T> ObjectTree<T> {
             }
         }
 
-        match root_idx {
-            Some(root_idx) => Ok(ObjectTree::new(graph, root_idx)),
-            None => Err(TarReadError::ReadTree(GraphError::MissingRootNode)),
-        }
+        let root_idx = root_idx.ok_or(TarReadError::ReadTree(GraphError::MissingRootNode))?;
+
+        Ok((ObjectTree::new(graph, root_idx), get_signatures(tar_data)))
     }
 }
 
@@ -109,6 +128,13 @@ where
         .get(&dst_path)
         .ok_or_else(|| TarReadError::NodeNotFound(dst_path))?;
 
+    // Nodes are hashed over the exact bytes they are stored as, so checking every node as it is
+    // read guarantees the whole tree matches its root hash
+    let computed = Hash::new(buf);
+    if computed != hash {
+        return Err(TarReadError::HashMismatch(hash, computed));
+    }
+
     let node_with_entries: NodeWithEntries<N> =
         NodeWithEntries::from_bytes(buf.clone()).map_err(TarReadError::NodeWithEntriesParse)?;
 
@@ -129,3 +155,17 @@ fn get_root_ref(tar_data: &mut HashMap<PathBuf, Vec<u8>>) -> Result<Hash, TarRea
 
     Hash::from_str(&buf).map_err(Into::into)
 }
+
+fn get_signatures(tar_data: HashMap<PathBuf, Vec<u8>>) -> Vec<(String, Vec<u8>)> {
+    let signatures_path = signature_path("");
+    let mut signatures: Vec<(String, Vec<u8>)> = tar_data
+        .into_iter()
+        .filter_map(|(path, data)| {
+            let name = path.strip_prefix(&signatures_path).ok()?.to_str()?;
+            (!name.is_empty()).then(|| (name.to_owned(), data))
+        })
+        .collect();
+    signatures.sort_by(|a, b| a.0.cmp(&b.0));
+
+    signatures
+}
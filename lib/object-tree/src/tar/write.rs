@@ -6,7 +6,7 @@ use thiserror::Error;
 
 use crate::{
     graph::{HashedNodeWithEntries, NodeEntry},
-    tar::{object_path, ref_path},
+    tar::{object_path, ref_path, signature_path},
     GraphError, NameStr, ObjectTree, WriteBytes,
 };
 
@@ -33,6 +33,20 @@ pub struct TarWriter {
 impl TarWriter {
     /// Return a [`TarWriter`] populated from the provided [`ObjectTree`]
     pub fn new<T>(tree: &ObjectTree<T>) -> Result<Self, TarWriterError>
+    where
+        T: Clone + NameStr + WriteBytes + Send + Sync + 'static,
+    {
+        Self::new_with_signatures(tree, &[])
+    }
+
+    /// Return a [`TarWriter`] populated from the provided [`ObjectTree`] and detached signatures.
+    ///
+    /// Each signature is stored by name alongside the tree rather than in it, so attaching
+    /// signatures never changes the hash of any node.
+    pub fn new_with_signatures<T>(
+        tree: &ObjectTree<T>,
+        signatures: &[(String, Vec<u8>)],
+    ) -> Result<Self, TarWriterError>
     where
         T: Clone + NameStr + WriteBytes + Send + Sync + 'static,
     {
@@ -85,6 +99,9 @@ impl TarWriter {
             ref_path("root"),
             root_node.hash().to_string().as_bytes(),
         )?;
+        for (name, signature) in signatures {
+            write_tar_entry(&mut tar_builder, signature_path(name), signature)?;
+        }
         tar_builder.finish()?;
 
         Ok(Self {
@@ -13,6 +13,8 @@ pub use dal::CycloneKeyPair;
 pub use si_settings::{StandardConfig, StandardConfigFile};
 use ulid::Ulid;
 
+use crate::retry::RetryPolicies;
+
 const DEFAULT_CONCURRENCY_LIMIT: usize = 5;
 
 #[remain::sorted]
@@ -49,8 +51,14 @@ pub struct Config {
     #[builder(default = "default_concurrency_limit()")]
     concurrency: usize,
 
+    #[builder(default = "default_fix_concurrency_limit()")]
+    fix_concurrency: usize,
+
     #[builder(default = "random_instance_id()")]
     instance_id: String,
+
+    #[builder(default)]
+    retry_policies: RetryPolicies,
 }
 
 impl StandardConfig for Config {
@@ -86,10 +94,20 @@ impl Config {
         self.concurrency
     }
 
+    /// Gets the config's limit on the number of fixes in a batch that may run at the same time.
+    pub fn fix_concurrency(&self) -> usize {
+        self.fix_concurrency
+    }
+
     /// Gets the config's instance ID.
     pub fn instance_id(&self) -> &str {
         self.instance_id.as_ref()
     }
+
+    /// Gets a reference to the config's job retry policies.
+    pub fn retry_policies(&self) -> &RetryPolicies {
+        &self.retry_policies
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -102,8 +120,12 @@ pub struct ConfigFile {
     cyclone_encryption_key_path: String,
     #[serde(default = "default_concurrency_limit")]
     concurrency_limit: usize,
+    #[serde(default = "default_fix_concurrency_limit")]
+    fix_concurrency_limit: usize,
     #[serde(default = "random_instance_id")]
     instance_id: String,
+    #[serde(default)]
+    retry: RetryPolicies,
 }
 
 impl Default for ConfigFile {
@@ -113,7 +135,9 @@ impl Default for ConfigFile {
             nats: Default::default(),
             cyclone_encryption_key_path: default_cyclone_encryption_key_path(),
             concurrency_limit: default_concurrency_limit(),
+            fix_concurrency_limit: default_fix_concurrency_limit(),
             instance_id: random_instance_id(),
+            retry: Default::default(),
         }
     }
 }
@@ -133,7 +157,9 @@ impl TryFrom<ConfigFile> for Config {
         config.nats(value.nats);
         config.cyclone_encryption_key_path(value.cyclone_encryption_key_path.try_into()?);
         config.concurrency(value.concurrency_limit);
+        config.fix_concurrency(value.fix_concurrency_limit);
         config.instance_id(value.instance_id);
+        config.retry_policies(value.retry);
         config.build().map_err(Into::into)
     }
 }
@@ -150,6 +176,10 @@ fn default_concurrency_limit() -> usize {
     DEFAULT_CONCURRENCY_LIMIT
 }
 
+fn default_fix_concurrency_limit() -> usize {
+    dal::DEFAULT_FIX_CONCURRENCY_LIMIT
+}
+
 #[allow(clippy::disallowed_methods)] // Used to determine if running in development
 pub fn detect_and_configure_development(config: &mut ConfigFile) -> Result<()> {
     if env::var("BUCK_RUN_BUILD_ID").is_ok() || env::var("BUCK_BUILD_ID").is_ok() {
@@ -1,4 +1,5 @@
 mod config;
+pub mod retry;
 pub mod server;
 
 pub use crate::{
@@ -6,16 +7,37 @@ pub use crate::{
         detect_and_configure_development, Config, ConfigBuilder, ConfigError, ConfigFile,
         StandardConfig, StandardConfigFile,
     },
-    server::{Server, ServerError},
+    retry::{RetryPolicies, RetryPolicy},
+    server::{DeadLetter, Server, ServerError},
 };
 
 const NATS_JOBS_DEFAULT_SUBJECT: &str = "pinga-jobs";
 const NATS_JOBS_DEFAULT_QUEUE: &str = "pinga";
+const NATS_DEAD_LETTER_DEFAULT_SUBJECT: &str = "pinga-jobs-dead-letter";
+const NATS_DEAD_LETTER_DEFAULT_STREAM: &str = "PINGA_JOBS_DEAD_LETTER";
 
 pub fn nats_jobs_subject(prefix: Option<&str>) -> String {
     nats_subject(prefix, NATS_JOBS_DEFAULT_SUBJECT)
 }
 
+/// Gets the subject where jobs that failed on every attempt are published, along with their
+/// [`JobInfo`](dal::job::consumer::JobInfo), so they can be inspected and re-enqueued.
+pub fn nats_dead_letter_subject(prefix: Option<&str>) -> String {
+    nats_subject(prefix, NATS_DEAD_LETTER_DEFAULT_SUBJECT)
+}
+
+/// Gets the name of the JetStream stream that stores the messages published on the
+/// [dead-letter subject](nats_dead_letter_subject). Every subject prefix gets its own stream.
+pub fn nats_dead_letter_stream(prefix: Option<&str>) -> String {
+    match prefix {
+        Some(prefix) => format!(
+            "{}_{NATS_DEAD_LETTER_DEFAULT_STREAM}",
+            prefix.replace(|c: char| !c.is_ascii_alphanumeric() && c != '-', "_")
+        ),
+        None => NATS_DEAD_LETTER_DEFAULT_STREAM.to_string(),
+    }
+}
+
 pub fn nats_subject(prefix: Option<&str>, suffix: impl AsRef<str>) -> String {
     let suffix = suffix.as_ref();
     match prefix {
@@ -23,3 +45,21 @@ pub fn nats_subject(prefix: Option<&str>, suffix: impl AsRef<str>) -> String {
         None => suffix.to_string(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dead_letter_stream_names_are_valid_per_prefix() {
+        assert_eq!("PINGA_JOBS_DEAD_LETTER", nats_dead_letter_stream(None));
+        assert_eq!(
+            "test_01GWEAN_PINGA_JOBS_DEAD_LETTER",
+            nats_dead_letter_stream(Some("test.01GWEAN"))
+        );
+        assert_eq!(
+            "test_01GWEAN.pinga-jobs-dead-letter",
+            nats_dead_letter_subject(Some("test_01GWEAN"))
+        );
+    }
+}
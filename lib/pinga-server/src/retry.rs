@@ -0,0 +1,179 @@
+//! Retry policies for jobs that fail with a transient error (e.g. a veritech timeout or a NATS
+//! hiccup).
+
+use std::{collections::HashMap, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_INITIAL_BACKOFF_MS: u64 = 500;
+const DEFAULT_MAX_BACKOFF_MS: u64 = 30_000;
+
+/// Job kinds that are attempted once unless configured otherwise: they run actions against the
+/// outside world, which can't safely be repeated after a failure partway through.
+const SINGLE_ATTEMPT_KINDS: &[&str] = &["FixesJob"];
+
+/// How many times a job is attempted and how long to wait between attempts. The wait doubles after
+/// every failed attempt, up to `max_backoff_ms`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct RetryPolicy {
+    #[serde(default = "default_max_attempts")]
+    max_attempts: u32,
+    #[serde(default = "default_initial_backoff_ms")]
+    initial_backoff_ms: u64,
+    #[serde(default = "default_max_backoff_ms")]
+    max_backoff_ms: u64,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32, initial_backoff_ms: u64, max_backoff_ms: u64) -> Self {
+        Self {
+            max_attempts,
+            initial_backoff_ms,
+            max_backoff_ms,
+        }
+    }
+
+    /// Gets the total number of attempts, including the first one. A value of `1` disables
+    /// retries.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts.max(1)
+    }
+
+    /// Gets how long to wait before trying again after the given (1-based) attempt failed, or
+    /// `None` if the job should not be tried again.
+    pub fn retry_after(&self, attempt: u32, transient: bool) -> Option<Duration> {
+        if transient && attempt < self.max_attempts() {
+            Some(self.backoff(attempt))
+        } else {
+            None
+        }
+    }
+
+    /// Gets how long to wait after the given (1-based) failed attempt before trying again.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(32);
+        let backoff_ms = self
+            .initial_backoff_ms
+            .saturating_mul(2_u64.saturating_pow(exponent))
+            .min(self.max_backoff_ms);
+        Duration::from_millis(backoff_ms)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+        }
+    }
+}
+
+/// The [`RetryPolicy`] for every job kind, with a fallback for kinds that are not listed. Kinds
+/// that can't safely be repeated (e.g. `FixesJob`) are only attempted once unless listed.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct RetryPolicies {
+    #[serde(default)]
+    default: RetryPolicy,
+    /// Policies keyed by job kind (e.g. `DependentValuesUpdate`).
+    #[serde(default)]
+    kinds: HashMap<String, RetryPolicy>,
+}
+
+impl RetryPolicies {
+    pub fn new(default: RetryPolicy, kinds: HashMap<String, RetryPolicy>) -> Self {
+        Self { default, kinds }
+    }
+
+    /// Gets the [`RetryPolicy`] for a given job kind.
+    pub fn for_kind(&self, kind: &str) -> RetryPolicy {
+        match self.kinds.get(kind) {
+            Some(policy) => policy.clone(),
+            None if SINGLE_ATTEMPT_KINDS.contains(&kind) => RetryPolicy {
+                max_attempts: 1,
+                ..self.default.clone()
+            },
+            None => self.default.clone(),
+        }
+    }
+}
+
+fn default_max_attempts() -> u32 {
+    DEFAULT_MAX_ATTEMPTS
+}
+
+fn default_initial_backoff_ms() -> u64 {
+    DEFAULT_INITIAL_BACKOFF_MS
+}
+
+fn default_max_backoff_ms() -> u64 {
+    DEFAULT_MAX_BACKOFF_MS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_until_capped() {
+        let policy = RetryPolicy::new(5, 100, 500);
+
+        assert_eq!(Duration::from_millis(100), policy.backoff(1));
+        assert_eq!(Duration::from_millis(200), policy.backoff(2));
+        assert_eq!(Duration::from_millis(400), policy.backoff(3));
+        assert_eq!(Duration::from_millis(500), policy.backoff(4));
+        assert_eq!(Duration::from_millis(500), policy.backoff(100));
+    }
+
+    #[test]
+    fn for_kind_falls_back_to_default() {
+        let fixes = RetryPolicy::new(1, 0, 0);
+        let policies = RetryPolicies::new(
+            RetryPolicy::default(),
+            HashMap::from([("FixesJob".to_string(), fixes.clone())]),
+        );
+
+        assert_eq!(fixes, policies.for_kind("FixesJob"));
+        assert_eq!(
+            RetryPolicy::default(),
+            policies.for_kind("DependentValuesUpdate")
+        );
+    }
+
+    #[test]
+    fn fixes_are_attempted_once_by_default() {
+        let policies = RetryPolicies::default();
+
+        assert_eq!(1, policies.for_kind("FixesJob").max_attempts());
+        assert_eq!(
+            DEFAULT_MAX_ATTEMPTS,
+            policies.for_kind("RefreshJob").max_attempts()
+        );
+
+        let fixes = RetryPolicy::new(2, 0, 0);
+        let policies = RetryPolicies::new(
+            RetryPolicy::default(),
+            HashMap::from([("FixesJob".to_string(), fixes.clone())]),
+        );
+        assert_eq!(fixes, policies.for_kind("FixesJob"));
+    }
+
+    #[test]
+    fn retry_after_stops_at_max_attempts() {
+        let policy = RetryPolicy::new(3, 100, 500);
+
+        assert_eq!(
+            Some(Duration::from_millis(100)),
+            policy.retry_after(1, true)
+        );
+        assert_eq!(
+            Some(Duration::from_millis(200)),
+            policy.retry_after(2, true)
+        );
+        assert_eq!(None, policy.retry_after(3, true));
+        assert_eq!(None, policy.retry_after(1, false));
+        assert_eq!(None, RetryPolicy::new(0, 100, 500).retry_after(1, true));
+    }
+}
@@ -1,4 +1,4 @@
-use std::{io, path::Path, sync::Arc};
+use std::{io, path::Path, sync::Arc, time::Duration};
 
 use dal::{
     job::{
@@ -11,6 +11,7 @@ use dal::{
 };
 use futures::{FutureExt, Stream, StreamExt};
 use nats_subscriber::{Request, SubscriberError, Subscription};
+use serde::{Deserialize, Serialize};
 use si_data_nats::{NatsClient, NatsConfig, NatsError};
 use si_data_pg::{PgPool, PgPoolConfig, PgPoolError};
 use stream_cancel::StreamExt as StreamCancelStreamExt;
@@ -27,7 +28,10 @@ use tokio::{
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use veritech_client::{Client as VeritechClient, EncryptionKey, EncryptionKeyError};
 
-use crate::{nats_jobs_subject, Config, NATS_JOBS_DEFAULT_QUEUE};
+use crate::{
+    nats_dead_letter_stream, nats_dead_letter_subject, nats_jobs_subject, retry::RetryPolicies,
+    Config, NATS_JOBS_DEFAULT_QUEUE,
+};
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -110,6 +114,8 @@ impl Server {
         Self::from_services(
             config.instance_id().to_string(),
             config.concurrency(),
+            config.fix_concurrency(),
+            config.retry_policies().clone(),
             encryption_key,
             nats,
             pg_pool,
@@ -123,6 +129,8 @@ impl Server {
     pub fn from_services(
         instance_id: impl Into<String>,
         concurrency_limit: usize,
+        fix_concurrency_limit: usize,
+        retry_policies: RetryPolicies,
         encryption_key: Arc<EncryptionKey>,
         nats: NatsClient,
         pg_pool: PgPool,
@@ -141,6 +149,10 @@ impl Server {
         let metadata = ServerMetadata {
             job_instance: instance_id.into(),
             job_invoked_provider: "si",
+            retry_policies,
+            dead_letter_stream: nats_dead_letter_stream(nats.metadata().subject_prefix()),
+            dead_letter_subject: nats_dead_letter_subject(nats.metadata().subject_prefix()),
+            fix_concurrency_limit,
         };
 
         let graceful_shutdown_rx =
@@ -170,7 +182,8 @@ impl Server {
         )));
 
         // Run "the main loop" which pulls message from a subscription off NATS and forwards each
-        // request to an unbounded channel
+        // request to an unbounded channel. Jobs that are retried are sent back on the same channel
+        // once their backoff has elapsed.
         receive_job_requests_task(
             tx,
             self.metadata,
@@ -231,6 +244,36 @@ impl Server {
 pub struct ServerMetadata {
     job_instance: String,
     job_invoked_provider: &'static str,
+    retry_policies: RetryPolicies,
+    dead_letter_stream: String,
+    dead_letter_subject: String,
+    fix_concurrency_limit: usize,
+}
+
+/// A job that failed on its final attempt, as stored in the dead-letter JetStream stream.
+/// Publishing [`Self::job`] back on the jobs subject re-enqueues it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DeadLetter {
+    pub job: JobInfo,
+    pub error: String,
+    pub attempts: u32,
+    pub instance: String,
+}
+
+impl DeadLetter {
+    pub fn new(
+        job: JobInfo,
+        error: impl Into<String>,
+        attempts: u32,
+        instance: impl Into<String>,
+    ) -> Self {
+        Self {
+            job,
+            error: error.into(),
+            attempts,
+            instance: instance.into(),
+        }
+    }
 }
 
 pub struct PingaShutdownHandle {
@@ -262,6 +305,10 @@ pub struct JobItem {
     messaging_destination: Arc<String>,
     ctx_builder: DalContextBuilder,
     request: Result<Request<JobInfo>>,
+    /// Which (1-based) attempt at running the job this is.
+    attempt: u32,
+    /// Where to send the job when it is retried.
+    retry_tx: UnboundedSender<JobItem>,
 }
 
 pub struct Subscriber;
@@ -274,6 +321,7 @@ impl Subscriber {
         veritech: veritech_client::Client,
         job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
         encryption_key: Arc<veritech_client::EncryptionKey>,
+        retry_tx: UnboundedSender<JobItem>,
     ) -> Result<impl Stream<Item = JobItem>> {
         let subject = nats_jobs_subject(nats.metadata().subject_prefix());
         debug!(
@@ -289,7 +337,8 @@ impl Subscriber {
             encryption_key,
             None,
             None,
-        );
+        )
+        .with_fix_concurrency_limit(metadata.fix_concurrency_limit);
 
         // Make non blocking context here, and update it for each job
         // Since the any blocking job should block on its child jobs
@@ -306,6 +355,8 @@ impl Subscriber {
                 messaging_destination: messaging_destination.clone(),
                 ctx_builder: ctx_builder.clone(),
                 request: request.map_err(Into::into),
+                attempt: 1,
+                retry_tx: retry_tx.clone(),
             }))
     }
 }
@@ -355,6 +406,7 @@ async fn receive_job_requests(
         veritech,
         job_processor,
         encryption_key,
+        tx.clone(),
     )
     .await?
     .take_until_if(Box::pin(shutdown_watch_rx.changed().map(|_| true)));
@@ -384,6 +436,8 @@ async fn process_job_requests_task(rx: UnboundedReceiver<JobItem>, concurrency_l
                         job.messaging_destination,
                         job.ctx_builder,
                         request,
+                        job.attempt,
+                        job.retry_tx,
                     ));
                     if let Err(err) = join_handle.await {
                         // NOTE(fnichol): This likely happens when there is contention or
@@ -429,6 +483,8 @@ async fn execute_job_task(
     messaging_destination: Arc<String>,
     ctx_builder: DalContextBuilder,
     request: Request<JobInfo>,
+    attempt: u32,
+    retry_tx: UnboundedSender<JobItem>,
 ) {
     let span = Span::current();
     let id = request.payload.id.clone();
@@ -444,15 +500,30 @@ async fn execute_job_task(
     );
 
     let maybe_reply_channel = request.reply_mailbox.clone();
-    let reply_message = match execute_job(
-        &metadata,
-        messaging_destination,
-        ctx_builder.clone(),
-        request,
-    )
-    .await
-    {
-        Ok(_) => {
+    let result = execute_job(&metadata, ctx_builder.clone(), &request.payload, attempt).await;
+    let reply_message = match result {
+        Ok(Some(backoff)) => {
+            span.record_ok();
+            // The job is retried from a detached task so the backoff doesn't hold on to one of
+            // the server's concurrency slots. The caller of a blocking job is only replied to
+            // once the job finishes or fails for good.
+            drop(task::spawn(async move {
+                tokio::time::sleep(backoff).await;
+                let retry = JobItem {
+                    metadata,
+                    messaging_destination,
+                    ctx_builder,
+                    request: Ok(request),
+                    attempt: attempt + 1,
+                    retry_tx: retry_tx.clone(),
+                };
+                if let Err(_job) = retry_tx.send(retry) {
+                    error!("process_job_requests rx has already closed, job will not be retried");
+                }
+            }));
+            return;
+        }
+        Ok(None) => {
             span.record_ok();
             Ok(())
         }
@@ -483,13 +554,15 @@ async fn execute_job_task(
     }
 }
 
+/// Makes a single attempt at running a job. Returns how long to wait before trying again if the
+/// attempt failed in a way that is worth retrying, otherwise the failure is dead-lettered and
+/// recorded.
 async fn execute_job(
-    _metadata: &Arc<ServerMetadata>,
-    _messaging_destination: Arc<String>,
+    metadata: &ServerMetadata,
     mut ctx_builder: DalContextBuilder,
-    request: Request<JobInfo>,
-) -> Result<()> {
-    let (job_info, _) = request.into_parts();
+    job_info: &JobInfo,
+    attempt: u32,
+) -> Result<Option<Duration>> {
     if job_info.blocking {
         ctx_builder.set_blocking();
     }
@@ -524,21 +597,69 @@ async fn execute_job(
             kind => return Err(ServerError::UnknownJobKind(kind.to_owned())),
         };
 
-    info!("Processing job");
+    info!(attempt, "Processing job");
 
-    if let Err(err) = job.run_job(ctx_builder.clone()).await {
-        // The missing part is this, should we execute subsequent jobs if the one they depend on fail or not?
-        record_job_failure(ctx_builder, job, err).await?;
+    match job.run_job(ctx_builder.clone()).await {
+        Ok(()) => {
+            info!("Finished processing job");
+            Ok(None)
+        }
+        Err(err) => {
+            let retry_policy = metadata.retry_policies.for_kind(&job_info.kind);
+            if let Some(backoff) = retry_policy.retry_after(attempt, err.is_transient()) {
+                warn!(
+                    error = ?err,
+                    attempt,
+                    backoff_ms = backoff.as_millis() as u64,
+                    "job execution failed with a transient error, retrying"
+                );
+                return Ok(Some(backoff));
+            }
+
+            publish_dead_letter(&ctx_builder, metadata, job_info, &err, attempt).await;
+            // The missing part is this, should we execute subsequent jobs if the one they depend on fail or not?
+            record_job_failure(ctx_builder, job, job_info, err).await?;
+            Ok(None)
+        }
     }
+}
 
-    info!("Finished processing job");
+async fn publish_dead_letter(
+    ctx_builder: &DalContextBuilder,
+    metadata: &ServerMetadata,
+    job_info: &JobInfo,
+    err: &JobConsumerError,
+    attempts: u32,
+) {
+    let dead_letter = DeadLetter::new(
+        job_info.clone(),
+        err.to_string(),
+        attempts,
+        &metadata.job_instance,
+    );
 
-    Ok(())
+    match serde_json::to_vec(&dead_letter) {
+        Ok(message) => {
+            if let Err(err) = ctx_builder
+                .nats_conn()
+                .jetstream_publish(
+                    &metadata.dead_letter_stream,
+                    &metadata.dead_letter_subject,
+                    message,
+                )
+                .await
+            {
+                error!(error = ?err, "unable to publish job to the dead-letter stream");
+            }
+        }
+        Err(err) => error!(error = ?err, "unable to serialize dead-letter message"),
+    }
 }
 
 async fn record_job_failure(
     ctx_builder: DalContextBuilder,
     job: Box<dyn JobConsumer + Send + Sync>,
+    job_info: &JobInfo,
     err: JobConsumerError,
 ) -> Result<()> {
     warn!(error = ?err, "job execution failed, recording a job failure to the database");
@@ -547,7 +668,7 @@ async fn record_job_failure(
     let visibility = job.visibility();
     let ctx = ctx_builder.build(access_builder.build(visibility)).await?;
 
-    JobFailure::new(&ctx, job.type_name(), err.to_string()).await?;
+    JobFailure::new(&ctx, job.type_name(), err.to_string(), Some(job_info)).await?;
 
     ctx.commit().await?;
 
@@ -604,3 +725,33 @@ fn prepare_graceful_shutdown(
 
     Ok(graceful_shutdown_rx)
 }
+
+#[cfg(test)]
+mod tests {
+    use dal::{AccessBuilder, HistoryActor, Tenancy, Visibility};
+
+    use super::*;
+
+    #[test]
+    fn dead_letter_keeps_the_job_replayable() {
+        let job = JobInfo::new(DependentValuesUpdate::new(
+            AccessBuilder::new(Tenancy::new_empty(), HistoryActor::SystemInit),
+            Visibility::new_head(false),
+            vec![],
+        ))
+        .expect("could not create job info");
+
+        let dead_letter = DeadLetter::new(job.clone(), "veritech timed out", 3, "pinga-1");
+        let message = serde_json::to_vec(&dead_letter).expect("could not serialize dead letter");
+        let received: DeadLetter =
+            serde_json::from_slice(&message).expect("could not deserialize dead letter");
+
+        assert_eq!("veritech timed out", received.error);
+        assert_eq!(3, received.attempts);
+        assert_eq!("pinga-1", received.instance);
+        assert_eq!(job.id, received.job.id);
+        assert_eq!(job.kind, received.job.kind);
+        assert_eq!(job.arg, received.job.arg);
+        DependentValuesUpdate::try_from(received.job).expect("could not rebuild job");
+    }
+}
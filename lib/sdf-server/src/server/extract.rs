@@ -14,6 +14,11 @@ use hyper::StatusCode;
 
 use super::state::AppState;
 
+/// The [`AccessBuilder`](context::AccessBuilder) of the requesting user.
+///
+/// Requests that aren't read-only, i.e. anything but `GET`, `HEAD` or `OPTIONS`, are rejected
+/// for users whose [`WorkspaceRole`](dal::WorkspaceRole) doesn't allow editing the workspace. Use
+/// [`ReadAccessBuilder`] for handlers that only read but can't be reached with `GET`.
 pub struct AccessBuilder(pub context::AccessBuilder);
 
 #[async_trait]
@@ -24,16 +29,66 @@ impl FromRequestParts<AppState> for AccessBuilder {
         parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        let Authorization(claim) = Authorization::from_request_parts(parts, state).await?;
-        let Tenancy(tenancy) = tenancy_from_claim(&claim).await?;
+        let WorkspaceRole(claim, role) = WorkspaceRole::from_request_parts(parts, state).await?;
+        if !parts.method.is_safe() && !role.can_edit() {
+            return Err(forbidden_error());
+        }
 
-        Ok(Self(context::AccessBuilder::new(
-            tenancy,
-            dal::HistoryActor::from(claim.user_pk),
-        )))
+        Ok(Self(access_builder_from_claim(&claim).await?))
     }
 }
 
+/// The [`AccessBuilder`](context::AccessBuilder) of the requesting user, for handlers that manage
+/// the workspace itself (its policies, keys and members). Only users whose
+/// [`WorkspaceRole`](dal::WorkspaceRole) allows managing the workspace get through, whatever the
+/// HTTP method.
+pub struct OwnerAccessBuilder(pub context::AccessBuilder);
+
+#[async_trait]
+impl FromRequestParts<AppState> for OwnerAccessBuilder {
+    type Rejection = (StatusCode, Json<serde_json::Value>);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let WorkspaceRole(claim, role) = WorkspaceRole::from_request_parts(parts, state).await?;
+        if !role.can_manage() {
+            return Err(forbidden_error());
+        }
+
+        Ok(Self(access_builder_from_claim(&claim).await?))
+    }
+}
+
+/// The [`AccessBuilder`](context::AccessBuilder) of the requesting user, for handlers that don't
+/// change anything, whatever their [`WorkspaceRole`](dal::WorkspaceRole) and the HTTP method.
+pub struct ReadAccessBuilder(pub context::AccessBuilder);
+
+#[async_trait]
+impl FromRequestParts<AppState> for ReadAccessBuilder {
+    type Rejection = (StatusCode, Json<serde_json::Value>);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let Authorization(claim) = Authorization::from_request_parts(parts, state).await?;
+        Ok(Self(access_builder_from_claim(&claim).await?))
+    }
+}
+
+async fn access_builder_from_claim(
+    claim: &UserClaim,
+) -> Result<context::AccessBuilder, (StatusCode, Json<serde_json::Value>)> {
+    let Tenancy(tenancy) = tenancy_from_claim(claim).await?;
+
+    Ok(context::AccessBuilder::new(
+        tenancy,
+        dal::HistoryActor::from(claim.user_pk),
+    ))
+}
+
 pub struct RawAccessToken(pub String);
 
 #[async_trait]
@@ -121,27 +176,33 @@ impl FromRequestParts<AppState> for Authorization {
         parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        let HandlerContext(builder) = HandlerContext::from_request_parts(parts, state).await?;
-        let mut ctx = builder.build_default().await.map_err(internal_error)?;
-        let jwt_public_signing_key = state.jwt_public_signing_key().clone();
+        let WorkspaceRole(claim, _role) = WorkspaceRole::from_request_parts(parts, state).await?;
+        Ok(Self(claim))
+    }
+}
+
+/// The [`WorkspaceRole`](dal::WorkspaceRole) of the requesting user in the workspace of their
+/// claim. Users that don't belong to that workspace are unauthorized.
+pub struct WorkspaceRole(pub UserClaim, pub dal::WorkspaceRole);
 
+#[async_trait]
+impl FromRequestParts<AppState> for WorkspaceRole {
+    type Rejection = (StatusCode, Json<serde_json::Value>);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
         let headers = &parts.headers;
         let authorization_header_value = headers
             .get("Authorization")
             .ok_or_else(unauthorized_error)?;
         let authorization = authorization_header_value
             .to_str()
-            .map_err(internal_error)?;
-        let claim = UserClaim::from_bearer_token(jwt_public_signing_key, authorization)
-            .await
-            .map_err(|_| unauthorized_error())?;
-        ctx.update_tenancy(dal::Tenancy::new(claim.workspace_pk));
+            .map_err(internal_error)?
+            .to_owned();
 
-        User::authorize(&ctx, &claim.user_pk)
-            .await
-            .map_err(|_| unauthorized_error())?;
-
-        Ok(Self(claim))
+        authorize_bearer_token(parts, state, authorization).await
     }
 }
 
@@ -155,28 +216,39 @@ impl FromRequestParts<AppState> for WsAuthorization {
         parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        let HandlerContext(builder) = HandlerContext::from_request_parts(parts, state).await?;
-        let mut ctx = builder.build_default().await.map_err(internal_error)?;
-        let jwt_public_signing_key = state.jwt_public_signing_key().clone();
-
         let query: Query<HashMap<String, String>> = Query::from_request_parts(parts, state)
             .await
             .map_err(|_| unauthorized_error())?;
-        let authorization = query.get("token").ok_or_else(unauthorized_error)?;
-
-        let claim = UserClaim::from_bearer_token(jwt_public_signing_key, authorization)
-            .await
-            .map_err(|_| unauthorized_error())?;
-        ctx.update_tenancy(dal::Tenancy::new(claim.workspace_pk));
-
-        User::authorize(&ctx, &claim.user_pk)
-            .await
-            .map_err(|_| unauthorized_error())?;
+        let authorization = query.get("token").ok_or_else(unauthorized_error)?.clone();
 
+        let WorkspaceRole(claim, _role) =
+            authorize_bearer_token(parts, state, authorization).await?;
         Ok(Self(claim))
     }
 }
 
+async fn authorize_bearer_token(
+    parts: &mut Parts,
+    state: &AppState,
+    authorization: String,
+) -> Result<WorkspaceRole, (StatusCode, Json<serde_json::Value>)> {
+    let HandlerContext(builder) = HandlerContext::from_request_parts(parts, state).await?;
+    let mut ctx = builder.build_default().await.map_err(internal_error)?;
+    let jwt_public_signing_key = state.jwt_public_signing_key().clone();
+
+    let claim = UserClaim::from_bearer_token(jwt_public_signing_key, authorization)
+        .await
+        .map_err(|_| unauthorized_error())?;
+    ctx.update_tenancy(dal::Tenancy::new(claim.workspace_pk));
+
+    let role = User::role_in_tenancy(&ctx, &claim.user_pk)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(unauthorized_error)?;
+
+    Ok(WorkspaceRole(claim, role))
+}
+
 pub struct Tenancy(pub dal::Tenancy);
 
 #[async_trait]
@@ -225,3 +297,17 @@ fn unauthorized_error() -> (StatusCode, Json<serde_json::Value>) {
         })),
     )
 }
+
+fn forbidden_error() -> (StatusCode, Json<serde_json::Value>) {
+    let status_code = StatusCode::FORBIDDEN;
+    (
+        status_code,
+        Json(serde_json::json!({
+            "error": {
+                "message": "forbidden",
+                "statusCode": status_code.as_u16(),
+                "code": 42,
+            },
+        })),
+    )
+}
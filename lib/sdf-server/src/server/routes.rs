@@ -32,6 +32,8 @@ pub fn routes(state: AppState) -> Router {
         )
         .nest("/api/fix", crate::server::service::fix::routes())
         .nest("/api/func", crate::server::service::func::routes())
+        .nest("/api/history", crate::server::service::history::routes())
+        .nest("/api/job", crate::server::service::job::routes())
         .nest("/api/pkg", crate::server::service::pkg::routes())
         .nest("/api/provider", crate::server::service::provider::routes())
         .nest(
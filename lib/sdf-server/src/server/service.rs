@@ -3,6 +3,8 @@ pub mod component;
 pub mod diagram;
 pub mod fix;
 pub mod func;
+pub mod history;
+pub mod job;
 pub mod pkg;
 pub mod provider;
 pub mod qualification;
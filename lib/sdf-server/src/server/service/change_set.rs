@@ -7,7 +7,7 @@ use axum::{
 use dal::{
     change_status::ChangeStatusError, ChangeSetError as DalChangeSetError,
     ComponentError as DalComponentError, FixError, StandardModelError, TransactionsError,
-    UserError, UserPk,
+    UserError, UserPk, WorkspaceError, WorkspaceRole,
 };
 use module_index_client::IndexClientError;
 use telemetry::prelude::*;
@@ -15,17 +15,25 @@ use thiserror::Error;
 
 use crate::{server::state::AppState, service::pkg::PkgError};
 
+pub mod abandon_change_set;
 pub mod apply_change_set;
 pub mod apply_change_set2;
 pub mod create_change_set;
+pub mod get_approval_state;
 pub mod get_change_set;
+pub mod get_diff;
 pub mod get_stats;
 pub mod list_open_change_sets;
+pub mod request_approval;
+pub mod review_change_set;
+pub mod update_approval_policy;
 pub mod update_selected_change_set;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ChangeSetError {
+    #[error("the {0} role can't review change sets")]
+    CannotReview(WorkspaceRole),
     #[error(transparent)]
     ChangeSet(#[from] DalChangeSetError),
     #[error("change set not found")]
@@ -58,6 +66,10 @@ pub enum ChangeSetError {
     UrlParse(#[from] url::ParseError),
     #[error(transparent)]
     User(#[from] UserError),
+    #[error(transparent)]
+    Workspace(#[from] WorkspaceError),
+    #[error("workspace not found")]
+    WorkspaceNotFound,
 }
 
 pub type ChangeSetResult<T> = std::result::Result<T, ChangeSetError>;
@@ -65,7 +77,22 @@ pub type ChangeSetResult<T> = std::result::Result<T, ChangeSetError>;
 impl IntoResponse for ChangeSetError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
-            ChangeSetError::ChangeSetNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            ChangeSetError::ChangeSetNotFound
+            | ChangeSetError::ChangeSet(DalChangeSetError::ChangeSetNotFound(_))
+            | ChangeSetError::WorkspaceNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            ChangeSetError::CannotReview(_)
+            | ChangeSetError::ChangeSet(DalChangeSetError::SelfApproval(..)) => {
+                (StatusCode::FORBIDDEN, self.to_string())
+            }
+            ChangeSetError::Workspace(WorkspaceError::InvalidRequiredApprovals(_)) => {
+                (StatusCode::BAD_REQUEST, self.to_string())
+            }
+            ChangeSetError::ChangeSet(DalChangeSetError::ApprovalNotRequested(..))
+            | ChangeSetError::ChangeSet(DalChangeSetError::ApprovalPolicyNotSatisfied(..))
+            | ChangeSetError::ChangeSet(DalChangeSetError::Conflicts(..))
+            | ChangeSetError::ChangeSet(DalChangeSetError::NotOpen(..)) => {
+                (StatusCode::CONFLICT, self.to_string())
+            }
             _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
 
@@ -89,14 +116,39 @@ pub fn routes() -> Router<AppState> {
         )
         .route("/get_change_set", get(get_change_set::get_change_set))
         .route("/get_stats", get(get_stats::get_stats))
+        .route("/get_diff", get(get_diff::get_diff))
         .route(
             "/apply_change_set",
             post(apply_change_set::apply_change_set),
         )
+        .route(
+            "/abandon_change_set",
+            post(abandon_change_set::abandon_change_set),
+        )
         .route(
             "/apply_change_set2",
             post(apply_change_set2::apply_change_set),
         )
+        .route(
+            "/request_approval",
+            post(request_approval::request_approval),
+        )
+        .route(
+            "/approve_change_set",
+            post(review_change_set::approve_change_set),
+        )
+        .route(
+            "/reject_change_set",
+            post(review_change_set::reject_change_set),
+        )
+        .route(
+            "/get_approval_state",
+            get(get_approval_state::get_approval_state),
+        )
+        .route(
+            "/update_approval_policy",
+            post(update_approval_policy::update_approval_policy),
+        )
         .route(
             "/update_selected_change_set",
             post(update_selected_change_set::update_selected_change_set),
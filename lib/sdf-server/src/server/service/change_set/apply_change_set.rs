@@ -11,6 +11,9 @@ use serde::{Deserialize, Serialize};
 #[serde(rename_all = "camelCase")]
 pub struct ApplyChangeSetRequest {
     pub change_set_pk: ChangeSetPk,
+    /// Apply even if head has moved under values edited in the change set.
+    #[serde(default)]
+    pub force: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -31,7 +34,7 @@ pub async fn apply_change_set(
     let mut change_set = ChangeSet::get_by_pk(&ctx, &request.change_set_pk)
         .await?
         .ok_or(ChangeSetError::ChangeSetNotFound)?;
-    change_set.apply(&mut ctx).await?;
+    change_set.apply_raw(&mut ctx, true, request.force).await?;
 
     track(
         &posthog_client,
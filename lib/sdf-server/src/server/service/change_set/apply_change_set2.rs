@@ -25,6 +25,9 @@ pub struct FixRunRequest {
 pub struct ApplyChangeSetRequest {
     pub change_set_pk: ChangeSetPk,
     pub list: Vec<FixRunRequest>,
+    /// Apply even if head has moved under values edited in the change set.
+    #[serde(default)]
+    pub force: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -45,7 +48,7 @@ pub async fn apply_change_set(
     let mut change_set = ChangeSet::get_by_pk(&ctx, &request.change_set_pk)
         .await?
         .ok_or(ChangeSetError::ChangeSetNotFound)?;
-    change_set.apply_raw(&mut ctx, false).await?;
+    change_set.apply_raw(&mut ctx, false, request.force).await?;
 
     track(
         &posthog_client,
@@ -0,0 +1,34 @@
+use super::{ChangeSetError, ChangeSetResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use axum::extract::Query;
+use axum::Json;
+use dal::change_set::approval::ChangeSetApprovalState;
+use dal::{ChangeSet, ChangeSetPk};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetApprovalStateRequest {
+    pub pk: ChangeSetPk,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetApprovalStateResponse {
+    pub approval_state: ChangeSetApprovalState,
+}
+
+pub async fn get_approval_state(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<GetApprovalStateRequest>,
+) -> ChangeSetResult<Json<GetApprovalStateResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let change_set = ChangeSet::get_by_pk(&ctx, &request.pk)
+        .await?
+        .ok_or(ChangeSetError::ChangeSetNotFound)?;
+    let approval_state = change_set.approval_state(&ctx).await?;
+
+    Ok(Json(GetApprovalStateResponse { approval_state }))
+}
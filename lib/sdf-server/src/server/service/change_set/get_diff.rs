@@ -0,0 +1,35 @@
+use super::{ChangeSetError, ChangeSetResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use axum::extract::Query;
+use axum::Json;
+use dal::change_set::diff::ChangeSetDiff;
+use dal::{ChangeSet, ChangeSetPk};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDiffRequest {
+    pub pk: ChangeSetPk,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDiffResponse {
+    pub diff: ChangeSetDiff,
+}
+
+/// Gather everything the change set would add, delete or modify on head if it were applied.
+pub async fn get_diff(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<GetDiffRequest>,
+) -> ChangeSetResult<Json<GetDiffResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let change_set = ChangeSet::get_by_pk(&ctx, &request.pk)
+        .await?
+        .ok_or(ChangeSetError::ChangeSetNotFound)?;
+    let diff = change_set.diff(&ctx).await?;
+
+    Ok(Json(GetDiffResponse { diff }))
+}
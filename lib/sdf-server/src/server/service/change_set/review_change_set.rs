@@ -0,0 +1,101 @@
+use super::ChangeSetResult;
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient, WorkspaceRole};
+use crate::server::service::change_set::ChangeSetError;
+use crate::server::state;
+use crate::server::tracking::track;
+use axum::extract::OriginalUri;
+use axum::http::Uri;
+use axum::Json;
+use dal::change_set::approval::{ChangeSetApproval, ChangeSetApprovalStatus};
+use dal::{ChangeSet, ChangeSetPk, DalContext};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewChangeSetRequest {
+    pub change_set_pk: ChangeSetPk,
+    pub note: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewChangeSetResponse {
+    pub approval: ChangeSetApproval,
+}
+
+pub async fn approve_change_set(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    WorkspaceRole(_claim, role): WorkspaceRole,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<ReviewChangeSetRequest>,
+) -> ChangeSetResult<Json<ReviewChangeSetResponse>> {
+    if !role.can_approve() {
+        return Err(ChangeSetError::CannotReview(role));
+    }
+
+    let ctx = builder.build_head(access_builder).await?;
+    review_change_set(
+        ctx,
+        &posthog_client,
+        &original_uri,
+        request,
+        ChangeSetApprovalStatus::Approved,
+    )
+    .await
+}
+
+pub async fn reject_change_set(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    WorkspaceRole(_claim, role): WorkspaceRole,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<ReviewChangeSetRequest>,
+) -> ChangeSetResult<Json<ReviewChangeSetResponse>> {
+    if !role.can_approve() {
+        return Err(ChangeSetError::CannotReview(role));
+    }
+
+    let ctx = builder.build_head(access_builder).await?;
+    review_change_set(
+        ctx,
+        &posthog_client,
+        &original_uri,
+        request,
+        ChangeSetApprovalStatus::Rejected,
+    )
+    .await
+}
+
+async fn review_change_set(
+    ctx: DalContext,
+    posthog_client: &state::PosthogClient,
+    original_uri: &Uri,
+    request: ReviewChangeSetRequest,
+    status: ChangeSetApprovalStatus,
+) -> ChangeSetResult<Json<ReviewChangeSetResponse>> {
+    let change_set = ChangeSet::get_by_pk(&ctx, &request.change_set_pk)
+        .await?
+        .ok_or(ChangeSetError::ChangeSetNotFound)?;
+    let approval = match status {
+        ChangeSetApprovalStatus::Approved => change_set.approve(&ctx, request.note).await?,
+        ChangeSetApprovalStatus::Rejected => change_set.reject(&ctx, request.note).await?,
+    };
+
+    track(
+        posthog_client,
+        &ctx,
+        original_uri,
+        "review_change_set",
+        serde_json::json!({
+            "change_set": request.change_set_pk,
+            "status": status,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(ReviewChangeSetResponse { approval }))
+}
@@ -0,0 +1,56 @@
+use super::ChangeSetResult;
+use crate::server::extract::{HandlerContext, OwnerAccessBuilder, PosthogClient};
+use crate::server::service::change_set::ChangeSetError;
+use crate::server::tracking::track;
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::Workspace;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateApprovalPolicyRequest {
+    pub required_approvals: i32,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateApprovalPolicyResponse {
+    pub workspace: Workspace,
+}
+
+/// Set how many approvals a change set needs in the current workspace before it can be applied.
+pub async fn update_approval_policy(
+    HandlerContext(builder): HandlerContext,
+    OwnerAccessBuilder(access_builder): OwnerAccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<UpdateApprovalPolicyRequest>,
+) -> ChangeSetResult<Json<UpdateApprovalPolicyResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let workspace_pk = ctx
+        .tenancy()
+        .workspace_pk()
+        .ok_or(ChangeSetError::WorkspaceNotFound)?;
+    let mut workspace = Workspace::get_by_pk(&ctx, &workspace_pk)
+        .await?
+        .ok_or(ChangeSetError::WorkspaceNotFound)?;
+    workspace
+        .set_change_set_required_approvals(&ctx, request.required_approvals)
+        .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "update_change_set_approval_policy",
+        serde_json::json!({
+            "required_approvals": request.required_approvals,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(UpdateApprovalPolicyResponse { workspace }))
+}
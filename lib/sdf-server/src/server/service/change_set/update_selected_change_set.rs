@@ -3,7 +3,7 @@ use dal::{ChangeSet, ChangeSetPk};
 use serde::{Deserialize, Serialize};
 
 use super::{ChangeSetError, ChangeSetResult};
-use crate::server::extract::{AccessBuilder, Authorization, HandlerContext};
+use crate::server::extract::{Authorization, HandlerContext, ReadAccessBuilder};
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -19,7 +19,7 @@ pub struct UpdateSelectedChangeSetResponse {
 
 pub async fn update_selected_change_set(
     HandlerContext(builder): HandlerContext,
-    AccessBuilder(access_builder): AccessBuilder,
+    ReadAccessBuilder(access_builder): ReadAccessBuilder,
     Authorization(_claim): Authorization,
     Json(request): Json<UpdateSelectedChangeSetRequest>,
 ) -> ChangeSetResult<Json<UpdateSelectedChangeSetResponse>> {
@@ -4,7 +4,7 @@ use dal::Visibility;
 use serde::{Deserialize, Serialize};
 
 use super::DiagramResult;
-use crate::server::extract::{AccessBuilder, HandlerContext};
+use crate::server::extract::{HandlerContext, ReadAccessBuilder};
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -17,7 +17,7 @@ pub type GetNodeAddMenuResponse = serde_json::Value;
 
 pub async fn get_node_add_menu(
     HandlerContext(builder): HandlerContext,
-    AccessBuilder(request_ctx): AccessBuilder,
+    ReadAccessBuilder(request_ctx): ReadAccessBuilder,
     Json(request): Json<GetNodeAddMenuRequest>,
 ) -> DiagramResult<Json<GetNodeAddMenuResponse>> {
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
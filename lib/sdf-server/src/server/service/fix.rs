@@ -9,14 +9,16 @@ use thiserror::Error;
 use dal::fix::FixError as DalFixError;
 use dal::schema::SchemaError as DalSchemaError;
 use dal::{
-    ComponentError, ComponentId, FixResolverError, FuncBindingReturnValueError, StandardModelError,
-    TransactionsError, UserError, UserPk,
+    ComponentError, ComponentId, FixId, FixResolverError, FuncBindingReturnValueError,
+    StandardModelError, TransactionsError, UserError, UserPk,
 };
 
 use crate::server::state::AppState;
 
+pub mod cancel;
 pub mod confirmations;
 pub mod list;
+pub mod plan;
 pub mod run;
 
 #[remain::sorted]
@@ -30,6 +32,8 @@ pub enum FixError {
     DalFix(#[from] DalFixError),
     #[error(transparent)]
     DalSchema(#[from] DalSchemaError),
+    #[error("fix {0} not found")]
+    FixNotFound(FixId),
     #[error(transparent)]
     FixResolver(#[from] FixResolverError),
     #[error(transparent)]
@@ -54,7 +58,16 @@ pub type FixResult<T> = std::result::Result<T, FixError>;
 
 impl IntoResponse for FixError {
     fn into_response(self) -> Response {
-        let (status, error_message) = (StatusCode::INTERNAL_SERVER_ERROR, self.to_string());
+        let (status, error_message) = match self {
+            FixError::FixNotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            FixError::DalFix(DalFixError::CancelFinished(_)) => {
+                (StatusCode::CONFLICT, self.to_string())
+            }
+            FixError::DalFix(
+                DalFixError::MissingActionPrototype(_) | DalFixError::MissingComponent(_),
+            ) => (StatusCode::NOT_FOUND, self.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
 
         let body = Json(
             serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
@@ -66,7 +79,9 @@ impl IntoResponse for FixError {
 
 pub fn routes() -> Router<AppState> {
     Router::new()
+        .route("/cancel", post(cancel::cancel))
         .route("/confirmations", get(confirmations::confirmations))
         .route("/list", get(list::list))
+        .route("/plan", post(plan::plan))
         .route("/run", post(run::run))
 }
@@ -0,0 +1,46 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+
+use super::{FixError, FixResult};
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+use dal::{Fix, FixId, StandardModel, Visibility};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelFixRequest {
+    pub id: FixId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub async fn cancel(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<CancelFixRequest>,
+) -> FixResult<Json<()>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut fix = Fix::get_by_id(&ctx, &request.id)
+        .await?
+        .ok_or(FixError::FixNotFound(request.id))?;
+    fix.cancel(&ctx).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "cancel_fix",
+        serde_json::json!({
+            "fix_id": request.id,
+            "component_id": fix.component_id(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(()))
+}
@@ -0,0 +1,48 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::fix::plan::{FixPlan, FixPlanRequest};
+use dal::Visibility;
+use serde::{Deserialize, Serialize};
+
+use super::FixResult;
+use crate::server::extract::{HandlerContext, PosthogClient, ReadAccessBuilder};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FixesPlanRequest {
+    pub list: Vec<FixPlanRequest>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FixesPlanResponse {
+    pub steps: FixPlan,
+}
+
+/// Previews the actions `/fix/run` would run for the same list, without running anything.
+pub async fn plan(
+    HandlerContext(builder): HandlerContext,
+    ReadAccessBuilder(request_ctx): ReadAccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<FixesPlanRequest>,
+) -> FixResult<Json<FixesPlanResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let steps = dal::fix::plan::plan(&ctx, &request.list).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "plan_fix",
+        serde_json::json!({
+            "number_of_fixes_in_plan": steps.len(),
+        }),
+    );
+
+    Ok(Json(FixesPlanResponse { steps }))
+}
@@ -5,24 +5,18 @@ use serde::{Deserialize, Serialize};
 use super::{FixError, FixResult};
 use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
 use crate::server::tracking::track;
+use dal::fix::plan::FixPlanRequest;
 use dal::job::definition::{FixItem, FixesJob};
 use dal::{
-    ActionPrototypeId, AttributeValueId, ComponentId, Fix, FixBatch, FixBatchId, HistoryActor,
-    StandardModel, User, Visibility,
+    Fix, FixBatch, FixBatchFailurePolicy, FixBatchId, HistoryActor, StandardModel, User, Visibility,
 };
 
-#[derive(Deserialize, Serialize, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct FixRunRequest {
-    pub attribute_value_id: AttributeValueId,
-    pub component_id: ComponentId,
-    pub action_prototype_id: ActionPrototypeId,
-}
-
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct FixesRunRequest {
-    pub list: Vec<FixRunRequest>,
+    pub list: Vec<FixPlanRequest>,
+    #[serde(default)]
+    pub failure_policy: FixBatchFailurePolicy,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
@@ -49,7 +43,10 @@ pub async fn run(
 
         HistoryActor::SystemInit => return Err(FixError::InvalidUserSystemInit),
     };
-    let batch = FixBatch::new(&ctx, user.email()).await?;
+    let mut batch = FixBatch::new(&ctx, user.email()).await?;
+    batch
+        .set_failure_policy(&ctx, request.failure_policy)
+        .await?;
     let mut fixes = Vec::with_capacity(request.list.len());
 
     for fix_run_request in request.list {
@@ -78,6 +75,7 @@ pub async fn run(
         serde_json::json!({
             "fix_batch_id": batch.id(),
             "number_of_fixes_in_batch": fixes.len(),
+            "failure_policy": request.failure_policy,
             "fixes_applied": fixes,
         }),
     );
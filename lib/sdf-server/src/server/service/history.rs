@@ -0,0 +1,84 @@
+use axum::{
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use dal::{
+    ChangeSetPk, ComponentId, HistoryActor, HistoryEventError, HistoryEventFilter,
+    TransactionsError, UserPk,
+};
+use hyper::StatusCode;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::server::state::AppState;
+
+pub mod export_history;
+pub mod list_history;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum HistoryError {
+    #[error(transparent)]
+    ContextTransaction(#[from] TransactionsError),
+    #[error(transparent)]
+    HistoryEvent(#[from] HistoryEventError),
+    #[error("invalid page {0}, it is past the last possible page")]
+    InvalidPage(i64),
+    #[error("invalid page size {0}, must be between 1 and {1}")]
+    InvalidPageSize(i64, i64),
+    #[error("json serialization error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+}
+
+pub type HistoryResult<T> = std::result::Result<T, HistoryError>;
+
+/// The criteria shared by the history routes to narrow down the events they return.
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryFilterRequest {
+    /// Only events recorded by this user
+    pub user_pk: Option<UserPk>,
+    pub label_prefix: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub change_set_pk: Option<ChangeSetPk>,
+    pub component_id: Option<ComponentId>,
+}
+
+impl From<HistoryFilterRequest> for HistoryEventFilter {
+    fn from(request: HistoryFilterRequest) -> Self {
+        Self {
+            actor: request.user_pk.map(HistoryActor::User),
+            label_prefix: request.label_prefix,
+            since: request.since,
+            until: request.until,
+            change_set_pk: request.change_set_pk,
+            component_id: request.component_id,
+        }
+    }
+}
+
+impl IntoResponse for HistoryError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            HistoryError::InvalidPage(_) | HistoryError::InvalidPageSize(..) => {
+                (StatusCode::BAD_REQUEST, self.to_string())
+            }
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        (status, body).into_response()
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/list", get(list_history::list_history))
+        .route("/export", get(export_history::export_history))
+}
@@ -0,0 +1,73 @@
+use axum::{
+    body::StreamBody,
+    extract::Query,
+    http::header,
+    response::{IntoResponse, Response},
+};
+use dal::{DalContext, HistoryEvent, HistoryEventFilter, Visibility};
+use serde::{Deserialize, Serialize};
+
+use super::{HistoryError, HistoryFilterRequest, HistoryResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+const EXPORT_BATCH_SIZE: i64 = 1000;
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportHistoryRequest {
+    #[serde(flatten)]
+    pub filter: HistoryFilterRequest,
+}
+
+/// Exports every event matching the filter as newline delimited JSON, most recent first.
+pub async fn export_history(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ExportHistoryRequest>,
+) -> HistoryResult<Response> {
+    let ctx = builder
+        .build(request_ctx.build(Visibility::new_head(false)))
+        .await?;
+
+    let filter = HistoryEventFilter::from(request.filter);
+    // Events are fetched and sent one batch at a time, so the whole history never sits in memory
+    let batches = futures::stream::try_unfold(
+        Some((ctx, filter, None)),
+        |state: Option<(DalContext, HistoryEventFilter, Option<HistoryEvent>)>| async move {
+            let (ctx, filter, before) = match state {
+                Some(state) => state,
+                None => return Ok(None),
+            };
+            let events =
+                HistoryEvent::list_before(&ctx, &filter, before.as_ref(), EXPORT_BATCH_SIZE)
+                    .await?;
+            let mut body = Vec::new();
+            for event in &events {
+                serde_json::to_writer(&mut body, event)?;
+                body.push(b'\n');
+            }
+
+            let next = if (events.len() as i64) < EXPORT_BATCH_SIZE {
+                None
+            } else {
+                events
+                    .into_iter()
+                    .last()
+                    .map(|last| (ctx, filter, Some(last)))
+            };
+            Ok::<_, HistoryError>(Some((body, next)))
+        },
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"history.ndjson\"",
+            ),
+        ],
+        StreamBody::new(batches),
+    )
+        .into_response())
+}
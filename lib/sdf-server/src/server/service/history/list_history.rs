@@ -0,0 +1,59 @@
+use axum::{extract::Query, Json};
+use dal::{HistoryEvent, HistoryEventFilter, Visibility};
+use serde::{Deserialize, Serialize};
+
+use super::{HistoryError, HistoryFilterRequest, HistoryResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 500;
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListHistoryRequest {
+    #[serde(flatten)]
+    pub filter: HistoryFilterRequest,
+    /// The page to return, starting at 1
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListHistoryResponse {
+    pub events: Vec<HistoryEvent>,
+    pub page: i64,
+    pub page_size: i64,
+    pub total: i64,
+}
+
+pub async fn list_history(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ListHistoryRequest>,
+) -> HistoryResult<Json<ListHistoryResponse>> {
+    let ctx = builder
+        .build(request_ctx.build(Visibility::new_head(false)))
+        .await?;
+
+    let page_size = request.page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+    if !(1..=MAX_PAGE_SIZE).contains(&page_size) {
+        return Err(HistoryError::InvalidPageSize(page_size, MAX_PAGE_SIZE));
+    }
+    let page = request.page.unwrap_or(1).max(1);
+
+    let offset = (page - 1)
+        .checked_mul(page_size)
+        .ok_or(HistoryError::InvalidPage(page))?;
+
+    let filter = HistoryEventFilter::from(request.filter);
+    let events = HistoryEvent::list(&ctx, &filter, offset, Some(page_size)).await?;
+    let total = HistoryEvent::count(&ctx, &filter).await?;
+
+    Ok(Json(ListHistoryResponse {
+        events,
+        page,
+        page_size,
+        total,
+    }))
+}
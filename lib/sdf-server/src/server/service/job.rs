@@ -0,0 +1,66 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Json;
+use axum::Router;
+use dal::job_failure::JobFailurePk;
+use dal::{DalContext, JobFailure, JobFailureError, StandardModelError, TransactionsError};
+use thiserror::Error;
+
+use crate::server::state::AppState;
+
+pub mod list_failures;
+pub mod replay_failure;
+pub mod solve_failure;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum JobError {
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error(transparent)]
+    JobFailure(#[from] JobFailureError),
+    #[error("job failure not found: {0}")]
+    JobFailureNotFound(JobFailurePk),
+    #[error(transparent)]
+    StandardModel(#[from] StandardModelError),
+}
+
+pub type JobResult<T> = std::result::Result<T, JobError>;
+
+impl IntoResponse for JobError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            JobError::JobFailureNotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            JobError::JobFailure(JobFailureError::MissingJobInfo(_))
+            | JobError::JobFailure(JobFailureError::UnknownJobKind(_)) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, self.to_string())
+            }
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(serde_json::json!({
+            "error": {
+                "message": error_message,
+                "code": 42,
+                "statusCode": status.as_u16()
+            }
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+/// Fetches a [`JobFailure`] from the workspace of the caller.
+async fn get_job_failure(ctx: &DalContext, pk: JobFailurePk) -> JobResult<JobFailure> {
+    JobFailure::find_by_pk(ctx, pk)
+        .await?
+        .ok_or(JobError::JobFailureNotFound(pk))
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/list_failures", get(list_failures::list_failures))
+        .route("/solve_failure", post(solve_failure::solve_failure))
+        .route("/replay_failure", post(replay_failure::replay_failure))
+}
@@ -0,0 +1,23 @@
+use axum::Json;
+use dal::JobFailure;
+use serde::{Deserialize, Serialize};
+
+use super::JobResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListFailuresResponse {
+    pub failures: Vec<JobFailure>,
+}
+
+pub async fn list_failures(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+) -> JobResult<Json<ListFailuresResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let failures = JobFailure::list_unsolved(&ctx).await?;
+
+    Ok(Json(ListFailuresResponse { failures }))
+}
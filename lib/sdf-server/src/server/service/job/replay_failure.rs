@@ -0,0 +1,49 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::job_failure::JobFailurePk;
+use dal::JobFailure;
+use serde::{Deserialize, Serialize};
+
+use super::{get_job_failure, JobResult};
+use crate::server::extract::{HandlerContext, OwnerAccessBuilder, PosthogClient};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayFailureRequest {
+    pub job_failure_pk: JobFailurePk,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayFailureResponse {
+    pub failure: JobFailure,
+}
+
+pub async fn replay_failure(
+    HandlerContext(builder): HandlerContext,
+    OwnerAccessBuilder(access_builder): OwnerAccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<ReplayFailureRequest>,
+) -> JobResult<Json<ReplayFailureResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut failure = get_job_failure(&ctx, request.job_failure_pk).await?;
+    failure.replay(&ctx).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "replay_job_failure",
+        serde_json::json!({
+            "job_failure_pk": request.job_failure_pk,
+            "job_kind": failure.kind(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(ReplayFailureResponse { failure }))
+}
@@ -0,0 +1,49 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::job_failure::JobFailurePk;
+use dal::JobFailure;
+use serde::{Deserialize, Serialize};
+
+use super::{get_job_failure, JobResult};
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SolveFailureRequest {
+    pub job_failure_pk: JobFailurePk,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SolveFailureResponse {
+    pub failure: JobFailure,
+}
+
+pub async fn solve_failure(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<SolveFailureRequest>,
+) -> JobResult<Json<SolveFailureResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut failure = get_job_failure(&ctx, request.job_failure_pk).await?;
+    failure.mark_solved(&ctx).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "solve_job_failure",
+        serde_json::json!({
+            "job_failure_pk": request.job_failure_pk,
+            "job_kind": failure.kind(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(SolveFailureResponse { failure }))
+}
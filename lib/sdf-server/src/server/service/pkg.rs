@@ -7,7 +7,7 @@ use axum::{
 use convert_case::{Case, Casing};
 use dal::{
     installed_pkg::InstalledPkgError, pkg::PkgError as DalPkgError, DalContextBuilder,
-    StandardModelError, TenancyError, TransactionsError, UserError, WsEventError,
+    StandardModelError, TenancyError, TransactionsError, UserError, WorkspaceError, WsEventError,
 };
 use serde::{Deserialize, Serialize};
 use si_pkg::{SiPkg, SiPkgError};
@@ -24,6 +24,7 @@ pub mod get_pkg;
 pub mod install_pkg;
 pub mod list_pkgs;
 pub mod remote_module_spec;
+pub mod update_trust_policy;
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -79,6 +80,10 @@ pub enum PkgError {
     Url(#[from] url::ParseError),
     #[error("transparent")]
     User(#[from] UserError),
+    #[error(transparent)]
+    Workspace(#[from] WorkspaceError),
+    #[error("workspace not found")]
+    WorkspaceNotFound,
     #[error("could not publish websocket event: {0}")]
     WsEvent(#[from] WsEventError),
 }
@@ -185,4 +190,8 @@ pub fn routes() -> Router<AppState> {
             "/remote_module_spec",
             get(remote_module_spec::remote_module_spec),
         )
+        .route(
+            "/update_trust_policy",
+            post(update_trust_policy::update_trust_policy),
+        )
 }
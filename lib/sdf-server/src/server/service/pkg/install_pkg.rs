@@ -7,7 +7,10 @@ use crate::{
 };
 use axum::extract::OriginalUri;
 use axum::Json;
-use dal::{pkg::import_pkg_from_pkg, Visibility, WsEvent};
+use dal::{
+    pkg::{import_pkg_from_pkg, ImportOptions},
+    Visibility, WsEvent,
+};
 use module_index_client::IndexClient;
 use serde::{Deserialize, Serialize};
 use si_pkg::SiPkg;
@@ -47,7 +50,16 @@ pub async fn install_pkg(
 
     let pkg = SiPkg::load_from_bytes(pkg_data)?;
     let pkg_name = pkg.metadata()?.name().to_owned();
-    import_pkg_from_pkg(&ctx, &pkg, &pkg_name, None).await?;
+    import_pkg_from_pkg(
+        &ctx,
+        &pkg,
+        &pkg_name,
+        Some(ImportOptions {
+            module_index_auth_token: Some(raw_access_token),
+            ..Default::default()
+        }),
+    )
+    .await?;
 
     track(
         &posthog_client,
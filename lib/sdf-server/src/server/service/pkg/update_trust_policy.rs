@@ -0,0 +1,58 @@
+use super::{PkgError, PkgResult};
+use crate::server::extract::{HandlerContext, OwnerAccessBuilder, PosthogClient};
+use crate::server::tracking::track;
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::{PkgTrustPolicy, Workspace};
+use serde::{Deserialize, Serialize};
+use si_pkg::PkgPublicKey;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateTrustPolicyRequest {
+    pub pkg_trust_policy: PkgTrustPolicy,
+    #[serde(default)]
+    pub pkg_trusted_keys: Vec<PkgPublicKey>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateTrustPolicyResponse {
+    pub workspace: Workspace,
+}
+
+/// Set which packages can be installed in the current workspace.
+pub async fn update_trust_policy(
+    HandlerContext(builder): HandlerContext,
+    OwnerAccessBuilder(access_builder): OwnerAccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<UpdateTrustPolicyRequest>,
+) -> PkgResult<Json<UpdateTrustPolicyResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let workspace_pk = ctx
+        .tenancy()
+        .workspace_pk()
+        .ok_or(PkgError::WorkspaceNotFound)?;
+    let mut workspace = Workspace::get_by_pk(&ctx, &workspace_pk)
+        .await?
+        .ok_or(PkgError::WorkspaceNotFound)?;
+    workspace
+        .set_pkg_trust_policy(&ctx, request.pkg_trust_policy, request.pkg_trusted_keys)
+        .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "update_pkg_trust_policy",
+        serde_json::json!({
+            "pkg_trust_policy": request.pkg_trust_policy,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(UpdateTrustPolicyResponse { workspace }))
+}
@@ -4,15 +4,20 @@ use axum::routing::{get, post};
 use axum::Json;
 use axum::Router;
 use dal::{
-    KeyPairError, StandardModelError, TransactionsError, UserError, WorkspacePk, WsEventError,
+    KeyPairError, SecretId, StandardModelError, TransactionsError, UserError, WorkspacePk,
+    WsEventError,
 };
 use thiserror::Error;
 
 use crate::server::state::AppState;
 
 pub mod create_secret;
+pub mod create_secret_reference;
+pub mod delete_secret;
 pub mod get_public_key;
 pub mod list_secrets;
+pub mod rotate_key_pair;
+pub mod update_secret;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -27,6 +32,8 @@ pub enum SecretError {
     Pg(#[from] si_data_pg::PgError),
     #[error(transparent)]
     Secret(#[from] dal::SecretError),
+    #[error("secret not found: {0}")]
+    SecretNotFound(SecretId),
     #[error(transparent)]
     StandardModel(#[from] StandardModelError),
     #[error(transparent)]
@@ -41,8 +48,17 @@ pub type SecretResult<T> = std::result::Result<T, SecretError>;
 
 impl IntoResponse for SecretError {
     fn into_response(self) -> Response {
-        let (status, error_message) = (StatusCode::INTERNAL_SERVER_ERROR, self.to_string());
-        //SecretError::SecretNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+        let (status, error_message) = match self {
+            SecretError::SecretNotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            SecretError::Secret(dal::SecretError::KeyPairNotCurrent(_))
+            | SecretError::Secret(dal::SecretError::NotAReferenceBackend(_)) => {
+                (StatusCode::BAD_REQUEST, self.to_string())
+            }
+            SecretError::Secret(dal::SecretError::SecretInUse(_, _)) => {
+                (StatusCode::CONFLICT, self.to_string())
+            }
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
 
         let body = Json(serde_json::json!({
             "error": {
@@ -60,5 +76,12 @@ pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/get_public_key", get(get_public_key::get_public_key))
         .route("/create_secret", post(create_secret::create_secret))
+        .route(
+            "/create_secret_reference",
+            post(create_secret_reference::create_secret_reference),
+        )
         .route("/list_secrets", get(list_secrets::list_secrets))
+        .route("/update_secret", post(update_secret::update_secret))
+        .route("/delete_secret", post(delete_secret::delete_secret))
+        .route("/rotate_key_pair", post(rotate_key_pair::rotate_key_pair))
 }
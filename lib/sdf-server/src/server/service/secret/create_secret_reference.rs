@@ -0,0 +1,58 @@
+use axum::Json;
+use dal::{
+    EncryptedSecret, KeyPair, Secret, SecretBackend, SecretKind, SecretObjectType, Visibility,
+    WsEvent,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+use super::SecretResult;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSecretReferenceRequest {
+    pub name: String,
+    pub object_type: SecretObjectType,
+    pub kind: SecretKind,
+    pub backend: SecretBackend,
+    /// Where the secret can be found in the backend, i.e. a file path or a Vault secret path.
+    pub reference: String,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSecretReferenceResponse {
+    pub secret: Secret,
+}
+
+pub async fn create_secret_reference(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_tx): AccessBuilder,
+    Json(request): Json<CreateSecretReferenceRequest>,
+) -> SecretResult<Json<CreateSecretReferenceResponse>> {
+    let ctx = builder.build(request_tx.build(request.visibility)).await?;
+
+    let key_pair = KeyPair::get_current(&ctx).await?;
+    let secret = EncryptedSecret::new_reference(
+        &ctx,
+        request.name,
+        request.object_type,
+        request.kind,
+        request.backend,
+        request.reference,
+        key_pair.pk(),
+    )
+    .await?;
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(CreateSecretReferenceResponse { secret }))
+}
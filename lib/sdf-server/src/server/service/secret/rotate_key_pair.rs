@@ -0,0 +1,21 @@
+use axum::Json;
+use dal::{KeyPair, PublicKey};
+
+use super::SecretResult;
+use crate::server::extract::{HandlerContext, OwnerAccessBuilder};
+
+pub type RotateKeyPairResponse = PublicKey;
+
+pub async fn rotate_key_pair(
+    HandlerContext(builder): HandlerContext,
+    OwnerAccessBuilder(access_builder): OwnerAccessBuilder,
+) -> SecretResult<Json<RotateKeyPairResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    KeyPair::rotate(&ctx).await?;
+    let response: RotateKeyPairResponse = PublicKey::get_current(&ctx).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(response))
+}
@@ -0,0 +1,81 @@
+use axum::Json;
+use dal::{
+    key_pair::KeyPairPk, EncryptedSecret, Secret, SecretAlgorithm, SecretId, SecretVersion,
+    StandardModel, Visibility, WsEvent,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+use super::{SecretError, SecretResult};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSecretContents {
+    pub crypted: Vec<u8>,
+    pub key_pair_pk: KeyPairPk,
+    pub version: SecretVersion,
+    pub algorithm: SecretAlgorithm,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSecretRequest {
+    pub id: SecretId,
+    pub name: Option<String>,
+    /// The new encrypted payload, kept under the same id so that components referencing the
+    /// secret pick it up.
+    pub contents: Option<UpdateSecretContents>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSecretResponse {
+    pub secret: Secret,
+}
+
+pub async fn update_secret(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_tx): AccessBuilder,
+    Json(request): Json<UpdateSecretRequest>,
+) -> SecretResult<Json<UpdateSecretResponse>> {
+    let ctx = builder.build(request_tx.build(request.visibility)).await?;
+
+    let mut secret = Secret::get_by_id(&ctx, &request.id)
+        .await?
+        .ok_or(SecretError::SecretNotFound(request.id))?;
+
+    if let Some(name) = request.name {
+        secret.set_name(&ctx, name).await?;
+    }
+
+    if let Some(contents) = request.contents {
+        let mut encrypted_secret = EncryptedSecret::get_by_id(&ctx, &request.id)
+            .await?
+            .ok_or(SecretError::SecretNotFound(request.id))?;
+        encrypted_secret
+            .update_encrypted_contents(
+                &ctx,
+                &contents.crypted,
+                contents.key_pair_pk,
+                contents.version,
+                contents.algorithm,
+            )
+            .await?;
+
+        secret = Secret::get_by_id(&ctx, &request.id)
+            .await?
+            .ok_or(SecretError::SecretNotFound(request.id))?;
+    }
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(UpdateSecretResponse { secret }))
+}
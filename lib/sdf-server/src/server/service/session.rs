@@ -14,12 +14,15 @@ use crate::server::state::AppState;
 pub mod auth_connect;
 pub mod load_workspace;
 pub mod restore_authentication;
+pub mod set_workspace_role;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum SessionError {
     #[error("auth api error: {0}")]
     AuthApiError(String),
+    #[error("workspace owners cannot change their own role")]
+    CannotChangeOwnRole,
     #[error(transparent)]
     ContextTransactions(#[from] TransactionsError),
     #[error("Invalid user: {0}")]
@@ -42,6 +45,8 @@ pub enum SessionError {
     StandardModel(#[from] StandardModelError),
     #[error("user error: {0}")]
     User(#[from] UserError),
+    #[error("user not found: {0}")]
+    UserNotFound(UserPk),
     #[error(transparent)]
     Workspace(#[from] WorkspaceError),
 }
@@ -52,6 +57,8 @@ impl IntoResponse for SessionError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
             SessionError::LoginFailed => (StatusCode::CONFLICT, self.to_string()),
+            SessionError::CannotChangeOwnRole => (StatusCode::BAD_REQUEST, self.to_string()),
+            SessionError::UserNotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
             _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
 
@@ -71,4 +78,8 @@ pub fn routes() -> Router<AppState> {
             get(restore_authentication::restore_authentication),
         )
         .route("/load_workspace", get(load_workspace::load_workspace))
+        .route(
+            "/set_workspace_role",
+            post(set_workspace_role::set_workspace_role),
+        )
 }
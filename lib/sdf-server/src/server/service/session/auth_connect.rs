@@ -1,7 +1,7 @@
 use super::{SessionError, SessionResult};
 use crate::server::extract::HandlerContext;
 use axum::Json;
-use dal::{HistoryActor, KeyPair, Tenancy, User, UserPk, Workspace, WorkspacePk};
+use dal::{HistoryActor, KeyPair, Tenancy, User, UserPk, Workspace, WorkspacePk, WorkspaceRole};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
@@ -43,8 +43,8 @@ pub struct AuthApiUser {
 pub struct AuthApiWorkspace {
     pub id: WorkspacePk,
     pub display_name: String,
-    // dont need to do anything with these for now
     pub creator_user_id: UserPk,
+    // dont need to do anything with these for now
     pub instance_url: String,
     pub instance_env_type: String,
 }
@@ -124,8 +124,19 @@ pub async fn auth_connect(
         }
     };
 
-    // ensure workspace is associated to user
-    user.associate_workspace(&ctx, *workspace.pk()).await?;
+    // ensure workspace is associated to user, the creator of the workspace owning it. Existing
+    // members keep the role they were given, so logging in again never changes it
+    if User::workspace_role(&ctx, user.pk(), *workspace.pk())
+        .await?
+        .is_none()
+    {
+        if res_body.workspace.creator_user_id == user.pk() {
+            user.set_workspace_role(&ctx, *workspace.pk(), WorkspaceRole::Owner)
+                .await?;
+        } else {
+            user.associate_workspace(&ctx, *workspace.pk()).await?;
+        }
+    }
 
     ctx.commit().await?;
 
@@ -0,0 +1,45 @@
+use axum::Json;
+use dal::{User, UserPk, WorkspaceRole};
+use serde::{Deserialize, Serialize};
+
+use super::{SessionError, SessionResult};
+use crate::server::extract::{Authorization, HandlerContext, OwnerAccessBuilder};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetWorkspaceRoleRequest {
+    pub user_pk: UserPk,
+    pub role: WorkspaceRole,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetWorkspaceRoleResponse {
+    pub success: bool,
+}
+
+/// Sets the [`WorkspaceRole`] of a user in the workspace of the caller, making them a member of
+/// it if they weren't already. Owners can't change their own role, so a workspace always keeps at
+/// least one owner.
+pub async fn set_workspace_role(
+    HandlerContext(builder): HandlerContext,
+    OwnerAccessBuilder(access_builder): OwnerAccessBuilder,
+    Authorization(claim): Authorization,
+    Json(request): Json<SetWorkspaceRoleRequest>,
+) -> SessionResult<Json<SetWorkspaceRoleResponse>> {
+    if request.user_pk == claim.user_pk {
+        return Err(SessionError::CannotChangeOwnRole);
+    }
+
+    let ctx = builder.build_head(access_builder).await?;
+
+    let user = User::get_by_pk(&ctx, request.user_pk)
+        .await?
+        .ok_or(SessionError::UserNotFound(request.user_pk))?;
+    user.set_workspace_role(&ctx, claim.workspace_pk, request.role)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(SetWorkspaceRoleResponse { success: true }))
+}
@@ -130,6 +130,8 @@ pub async fn exec_variant_def(
                 asset_func.clone(),
             )])),
             no_record: true,
+            skip_trust_policy: true,
+            module_index_auth_token: None,
         }),
     )
     .await?;
@@ -1,17 +1,27 @@
-use axum::{http::Method, Router};
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use dal::{ChangeSetStatus, WorkspaceRole, WorkspaceSignup};
 use dal_test::{
     sdf_test, test_harness::create_change_set as dal_create_change_set, AuthTokenRef,
     DalContextHead,
 };
 use sdf_server::service::change_set::{
+    abandon_change_set::{AbandonChangeSetRequest, AbandonChangeSetResponse},
     apply_change_set::{ApplyChangeSetRequest, ApplyChangeSetResponse},
     create_change_set::{CreateChangeSetRequest, CreateChangeSetResponse},
     get_change_set::{GetChangeSetRequest, GetChangeSetResponse},
+    get_diff::{GetDiffRequest, GetDiffResponse},
     list_open_change_sets::ListOpenChangeSetsResponse,
+    request_approval::{RequestApprovalRequest, RequestApprovalResponse},
+    review_change_set::ReviewChangeSetRequest,
+    update_approval_policy::UpdateApprovalPolicyRequest,
 };
 
 use crate::service_tests::{
-    api_request_auth_empty, api_request_auth_json_body, api_request_auth_query,
+    api_request_auth_empty, api_request_auth_json_body, api_request_auth_json_body_status,
+    api_request_auth_query,
 };
 
 #[sdf_test]
@@ -76,6 +86,7 @@ async fn apply_change_set(
     ctx.commit().await.expect("cannot commit txn");
     let request = ApplyChangeSetRequest {
         change_set_pk: change_set.pk,
+        force: false,
     };
 
     let _response: ApplyChangeSetResponse = api_request_auth_json_body(
@@ -87,3 +98,134 @@ async fn apply_change_set(
     )
     .await;
 }
+
+#[sdf_test]
+async fn abandon_change_set(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let change_set = dal_create_change_set(&ctx).await;
+    ctx.commit().await.expect("cannot commit txn");
+    let request = AbandonChangeSetRequest {
+        change_set_pk: change_set.pk,
+    };
+
+    let response: AbandonChangeSetResponse = api_request_auth_json_body(
+        app,
+        Method::POST,
+        "/api/change_set/abandon_change_set",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(&response.change_set.status, &ChangeSetStatus::Abandoned);
+}
+
+#[sdf_test]
+async fn get_diff(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let change_set = dal_create_change_set(&ctx).await;
+    ctx.commit().await.expect("cannot commit txn");
+    let request = GetDiffRequest { pk: change_set.pk };
+
+    let response: GetDiffResponse =
+        api_request_auth_query(app, "/api/change_set/get_diff", auth_token, &request).await;
+    assert!(response.diff.components.is_empty());
+    assert!(response.diff.edges.is_empty());
+}
+
+#[sdf_test]
+async fn viewers_cannot_create_change_sets(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+    nw: WorkspaceSignup,
+) {
+    nw.user
+        .set_workspace_role(&ctx, *nw.workspace.pk(), WorkspaceRole::Viewer)
+        .await
+        .expect("cannot set workspace role");
+    ctx.commit().await.expect("cannot commit txn");
+
+    let response: ListOpenChangeSetsResponse = api_request_auth_empty(
+        app.clone(),
+        Method::GET,
+        "/api/change_set/list_open_change_sets",
+        auth_token,
+    )
+    .await;
+    assert!(response.list.is_empty());
+
+    let request = CreateChangeSetRequest {
+        change_set_name: "mastodon".to_string(),
+    };
+    let status = api_request_auth_json_body_status(
+        app,
+        Method::POST,
+        "/api/change_set/create_change_set",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(StatusCode::FORBIDDEN, status);
+}
+
+#[sdf_test]
+async fn update_approval_policy_rejects_negative_approvals(
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let request = UpdateApprovalPolicyRequest {
+        required_approvals: -1,
+    };
+    let status = api_request_auth_json_body_status(
+        app,
+        Method::POST,
+        "/api/change_set/update_approval_policy",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(StatusCode::BAD_REQUEST, status);
+}
+
+#[sdf_test]
+async fn cannot_approve_own_change_set(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let change_set = dal_create_change_set(&ctx).await;
+    ctx.commit().await.expect("cannot commit txn");
+
+    let request = RequestApprovalRequest {
+        change_set_pk: change_set.pk,
+    };
+    let response: RequestApprovalResponse = api_request_auth_json_body(
+        app.clone(),
+        Method::POST,
+        "/api/change_set/request_approval",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert!(response.change_set.approval_requested_by.is_some());
+
+    let request = ReviewChangeSetRequest {
+        change_set_pk: change_set.pk,
+        note: None,
+    };
+    let status = api_request_auth_json_body_status(
+        app,
+        Method::POST,
+        "/api/change_set/approve_change_set",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(StatusCode::FORBIDDEN, status);
+}
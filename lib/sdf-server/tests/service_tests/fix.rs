@@ -0,0 +1,222 @@
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use dal::{
+    edge::{EdgeKind, EdgeObjectId, VertexObjectKind},
+    fix::plan::FixPlanRequest,
+    socket::SocketEdgeKind,
+    ActionKind, ActionPrototype, ActionPrototypeContext, ActionPrototypeId, Component, DalContext,
+    Edge, Fix, FixBatch, FixCompletionStatus, RootPropChild, Socket, StandardModel, Visibility,
+};
+use dal_test::{
+    helpers::component_bag::{ComponentBag, ComponentBagger},
+    sdf_test, AuthTokenRef, DalContextHead,
+};
+use pretty_assertions_sorted::assert_eq;
+use sdf_server::service::fix::{
+    cancel::CancelFixRequest,
+    plan::{FixesPlanRequest, FixesPlanResponse},
+};
+
+use crate::service_tests::{api_request_auth_json_body, api_request_auth_json_body_status};
+
+async fn create_request(ctx: &DalContext, bag: &ComponentBag) -> FixPlanRequest {
+    let action = ActionPrototype::find_for_context_and_kind(
+        ctx,
+        ActionKind::Create,
+        ActionPrototypeContext {
+            schema_variant_id: bag.schema_variant_id,
+        },
+    )
+    .await
+    .expect("could not find action prototypes")
+    .pop()
+    .expect("no create action prototype");
+    let attribute_value = Component::root_prop_child_attribute_value_for_component(
+        ctx,
+        bag.component_id,
+        RootPropChild::Resource,
+    )
+    .await
+    .expect("could not find resource attribute value");
+
+    FixPlanRequest {
+        attribute_value_id: *attribute_value.id(),
+        component_id: bag.component_id,
+        action_prototype_id: *action.id(),
+    }
+}
+
+#[sdf_test]
+async fn plan(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(&ctx, "tail", "fallout").await;
+    let starfield_bag = bagger.create_component(&ctx, "head", "starfield").await;
+    let output_socket = Socket::find_by_name_for_edge_kind_and_node(
+        &ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationOutput,
+        fallout_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    let input_socket = Socket::find_by_name_for_edge_kind_and_node(
+        &ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationInput,
+        starfield_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    Edge::new(
+        &ctx,
+        EdgeKind::Configuration,
+        starfield_bag.node_id,
+        VertexObjectKind::Configuration,
+        EdgeObjectId::from(starfield_bag.component_id),
+        *input_socket.id(),
+        fallout_bag.node_id,
+        VertexObjectKind::Configuration,
+        EdgeObjectId::from(fallout_bag.component_id),
+        *output_socket.id(),
+    )
+    .await
+    .expect("cannot create new edge");
+
+    let head_request = create_request(&ctx, &starfield_bag).await;
+    let tail_request = create_request(&ctx, &fallout_bag).await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let request = FixesPlanRequest {
+        list: vec![head_request, tail_request],
+        visibility: Visibility::new_head(false),
+    };
+    let response: FixesPlanResponse =
+        api_request_auth_json_body(app, Method::POST, "/api/fix/plan", auth_token, &request).await;
+
+    assert_eq!(
+        vec![
+            (tail_request, "tail".to_string(), vec![]),
+            (head_request, "head".to_string(), vec![0]),
+        ],
+        response
+            .steps
+            .into_iter()
+            .map(|step| {
+                assert_eq!(ActionKind::Create, step.action_kind);
+                (
+                    FixPlanRequest {
+                        attribute_value_id: step.attribute_value_id,
+                        component_id: step.component_id,
+                        action_prototype_id: step.action_prototype_id,
+                    },
+                    step.component_name,
+                    step.depends_on,
+                )
+            })
+            .collect::<Vec<_>>()
+    );
+}
+
+#[sdf_test]
+async fn plan_with_unknown_action_is_not_found(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let mut bagger = ComponentBagger::new();
+    let bag = bagger.create_component(&ctx, "tail", "fallout").await;
+    let known = create_request(&ctx, &bag).await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let request = FixesPlanRequest {
+        list: vec![
+            known,
+            FixPlanRequest {
+                action_prototype_id: ActionPrototypeId::generate(),
+                ..known
+            },
+        ],
+        visibility: Visibility::new_head(false),
+    };
+    let status =
+        api_request_auth_json_body_status(app, Method::POST, "/api/fix/plan", auth_token, &request)
+            .await;
+
+    assert_eq!(StatusCode::NOT_FOUND, status);
+}
+
+#[sdf_test]
+async fn cancel(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let mut bagger = ComponentBagger::new();
+    let bag = bagger.create_component(&ctx, "cancelled", "fallout").await;
+    let request = create_request(&ctx, &bag).await;
+    let batch = FixBatch::new(&ctx, "toddhoward@systeminit.com")
+        .await
+        .expect("could not create fix batch");
+    let fix = Fix::new(
+        &ctx,
+        *batch.id(),
+        request.attribute_value_id,
+        request.component_id,
+        request.action_prototype_id,
+    )
+    .await
+    .expect("could not create fix");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let request = CancelFixRequest {
+        id: *fix.id(),
+        visibility: Visibility::new_head(false),
+    };
+    let _response: () = api_request_auth_json_body(
+        app.clone(),
+        Method::POST,
+        "/api/fix/cancel",
+        auth_token,
+        &request,
+    )
+    .await;
+
+    // The fix hasn't started, so the cancellation is recorded for the job to honor.
+    let mut fix = Fix::get_by_id(&ctx, fix.id())
+        .await
+        .expect("could not get fix")
+        .expect("fix not found");
+    assert!(fix.cancel_requested_at().is_some());
+
+    fix.stamp_started(&ctx).await.expect("could not start fix");
+    fix.stamp_finished(&ctx, FixCompletionStatus::Success, None, None)
+        .await
+        .expect("could not finish fix");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let status = api_request_auth_json_body_status(
+        app,
+        Method::POST,
+        "/api/fix/cancel",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(StatusCode::CONFLICT, status);
+}
@@ -0,0 +1,104 @@
+use axum::{
+    body::{Body, Bytes},
+    http::{self, Method, Request, StatusCode},
+    Router,
+};
+use dal::{HistoryActor, HistoryEvent, WorkspaceSignup};
+use dal_test::{sdf_test, test_harness::create_change_set, AuthTokenRef, DalContextHead};
+use sdf_server::service::history::{
+    list_history::{ListHistoryRequest, ListHistoryResponse},
+    HistoryFilterRequest,
+};
+use tower::ServiceExt;
+
+use crate::service_tests::api_request_auth_query;
+
+#[sdf_test]
+async fn list_history(
+    DalContextHead(mut ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+    nw: WorkspaceSignup,
+) {
+    ctx.update_history_actor(HistoryActor::User(nw.user.pk()));
+    for _ in 0..3 {
+        create_change_set(&ctx).await;
+    }
+    ctx.commit().await.expect("cannot commit txn");
+
+    let request = ListHistoryRequest {
+        filter: HistoryFilterRequest {
+            user_pk: Some(nw.user.pk()),
+            label_prefix: Some("change_set.create".to_owned()),
+            ..Default::default()
+        },
+        page: Some(2),
+        page_size: Some(2),
+    };
+    let response: ListHistoryResponse =
+        api_request_auth_query(app, "/api/history/list", auth_token, &request).await;
+    assert_eq!(3, response.total);
+    assert_eq!(1, response.events.len());
+    assert_eq!(
+        HistoryActor::User(nw.user.pk()),
+        response.events[0].actor,
+        "event recorded by the user"
+    );
+}
+
+#[sdf_test]
+async fn list_history_past_the_last_page(app: Router, AuthTokenRef(auth_token): AuthTokenRef<'_>) {
+    let (status, _) = get(
+        app,
+        format!("/api/history/list?page={}&pageSize=500", i64::MAX),
+        auth_token,
+    )
+    .await;
+    assert_eq!(StatusCode::BAD_REQUEST, status);
+}
+
+#[sdf_test]
+async fn export_history(
+    DalContextHead(mut ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+    nw: WorkspaceSignup,
+) {
+    ctx.update_history_actor(HistoryActor::User(nw.user.pk()));
+    for _ in 0..3 {
+        create_change_set(&ctx).await;
+    }
+    ctx.commit().await.expect("cannot commit txn");
+
+    let (status, body) = get(
+        app,
+        "/api/history/export?labelPrefix=change_set.create",
+        auth_token,
+    )
+    .await;
+    assert_eq!(StatusCode::OK, status);
+    let events: Vec<HistoryEvent> = body
+        .split(|byte| *byte == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_slice(line).expect("line is not a history event"))
+        .collect();
+    assert_eq!(3, events.len());
+    assert!(events
+        .windows(2)
+        .all(|pair| pair[0].timestamp.created_at >= pair[1].timestamp.created_at));
+}
+
+async fn get(app: Router, uri: impl AsRef<str>, auth_token: &str) -> (StatusCode, Bytes) {
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri(uri.as_ref())
+        .header(http::header::AUTHORIZATION, format!("Bearer {auth_token}"))
+        .body(Body::empty())
+        .expect("cannot create api request");
+    let response = app.oneshot(request).await.expect("cannot send request");
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .expect("cannot read body");
+    (status, body)
+}
@@ -0,0 +1,124 @@
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use dal::{job_failure::JobFailurePk, JobFailure, WorkspaceRole, WorkspaceSignup};
+use dal_test::{sdf_test, AuthTokenRef, DalContextHead};
+use sdf_server::service::job::{
+    list_failures::ListFailuresResponse,
+    replay_failure::ReplayFailureRequest,
+    solve_failure::{SolveFailureRequest, SolveFailureResponse},
+};
+
+use crate::service_tests::{
+    api_request_auth_empty, api_request_auth_json_body, api_request_auth_json_body_status,
+};
+
+#[sdf_test]
+async fn list_and_solve_failures(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let failure = JobFailure::new(&ctx, "DependentValuesUpdate", "veritech timed out", None)
+        .await
+        .expect("could not create job failure");
+    ctx.commit().await.expect("cannot commit txn");
+
+    let response: ListFailuresResponse = api_request_auth_empty(
+        app.clone(),
+        Method::GET,
+        "/api/job/list_failures",
+        auth_token,
+    )
+    .await;
+    assert_eq!(vec![failure.clone()], response.failures);
+
+    let request = SolveFailureRequest {
+        job_failure_pk: *failure.pk(),
+    };
+    let response: SolveFailureResponse = api_request_auth_json_body(
+        app.clone(),
+        Method::POST,
+        "/api/job/solve_failure",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert!(response.failure.solved());
+
+    let response: ListFailuresResponse =
+        api_request_auth_empty(app, Method::GET, "/api/job/list_failures", auth_token).await;
+    assert!(response.failures.is_empty());
+}
+
+#[sdf_test]
+async fn missing_failures_are_not_found(app: Router, AuthTokenRef(auth_token): AuthTokenRef<'_>) {
+    let request = SolveFailureRequest {
+        job_failure_pk: JobFailurePk::generate(),
+    };
+    let status = api_request_auth_json_body_status(
+        app.clone(),
+        Method::POST,
+        "/api/job/solve_failure",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(StatusCode::NOT_FOUND, status);
+
+    let request = ReplayFailureRequest {
+        job_failure_pk: JobFailurePk::generate(),
+    };
+    let status = api_request_auth_json_body_status(
+        app,
+        Method::POST,
+        "/api/job/replay_failure",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(StatusCode::NOT_FOUND, status);
+}
+
+#[sdf_test]
+async fn replay_failure(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+    nw: WorkspaceSignup,
+) {
+    let failure = JobFailure::new(&ctx, "FixesJob", "boom", None)
+        .await
+        .expect("could not create job failure");
+    ctx.commit().await.expect("cannot commit txn");
+    let request = ReplayFailureRequest {
+        job_failure_pk: *failure.pk(),
+    };
+
+    // Without the job that failed there is nothing to replay
+    let status = api_request_auth_json_body_status(
+        app.clone(),
+        Method::POST,
+        "/api/job/replay_failure",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, status);
+
+    nw.user
+        .set_workspace_role(&ctx, *nw.workspace.pk(), WorkspaceRole::Editor)
+        .await
+        .expect("cannot set workspace role");
+    ctx.commit().await.expect("cannot commit txn");
+    let status = api_request_auth_json_body_status(
+        app,
+        Method::POST,
+        "/api/job/replay_failure",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(StatusCode::FORBIDDEN, status);
+}
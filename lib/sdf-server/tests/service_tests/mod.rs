@@ -8,6 +8,10 @@ use tower::ServiceExt;
 
 mod change_set;
 mod component;
+mod fix;
+mod history;
+mod job;
+mod pkg;
 mod scenario;
 mod schema;
 mod secret;
@@ -161,3 +165,27 @@ pub async fn api_request_auth_no_response<Req: Serialize>(
 
     assert_eq!(body, "", "response is not empty");
 }
+
+pub async fn api_request_auth_json_body_status<Req: Serialize>(
+    app: Router,
+    method: Method,
+    uri: impl AsRef<str>,
+    auth_token: impl AsRef<str>,
+    request: &Req,
+) -> StatusCode {
+    let auth_token = auth_token.as_ref();
+    let uri = uri.as_ref();
+    let api_request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .header(http::header::AUTHORIZATION, format!("Bearer {auth_token}"));
+
+    let api_request = api_request
+        .body(Body::from(
+            serde_json::to_vec(&serde_json::json!(&request)).expect("cannot turn request to json"),
+        ))
+        .expect("cannot create api request");
+    let response = app.oneshot(api_request).await.expect("cannot send request");
+    response.status()
+}
@@ -0,0 +1,49 @@
+use axum::{http::Method, Router};
+use dal::{PkgTrustPolicy, Workspace, WorkspaceSignup};
+use dal_test::{sdf_test, AuthTokenRef, DalContextHead};
+use sdf_server::service::pkg::update_trust_policy::{
+    UpdateTrustPolicyRequest, UpdateTrustPolicyResponse,
+};
+use si_pkg::PkgSigningKey;
+
+use crate::service_tests::api_request_auth_json_body;
+
+#[sdf_test]
+async fn update_trust_policy(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+    nw: WorkspaceSignup,
+) {
+    ctx.commit().await.expect("cannot commit txn");
+
+    let trusted_key = PkgSigningKey::generate()
+        .expect("cannot generate signing key")
+        .public_key();
+    let request = UpdateTrustPolicyRequest {
+        pkg_trust_policy: PkgTrustPolicy::RequireTrusted,
+        pkg_trusted_keys: vec![trusted_key],
+    };
+    let response: UpdateTrustPolicyResponse = api_request_auth_json_body(
+        app,
+        Method::POST,
+        "/api/pkg/update_trust_policy",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(
+        PkgTrustPolicy::RequireTrusted,
+        *response.workspace.pkg_trust_policy()
+    );
+    assert_eq!(&[trusted_key], response.workspace.pkg_trusted_keys());
+
+    let workspace = Workspace::get_by_pk(&ctx, nw.workspace.pk())
+        .await
+        .expect("cannot get workspace")
+        .expect("workspace not found");
+    assert_eq!(
+        PkgTrustPolicy::RequireTrusted,
+        *workspace.pkg_trust_policy()
+    );
+}
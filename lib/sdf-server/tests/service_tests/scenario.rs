@@ -16,6 +16,7 @@ mod model_flow_fedora_coreos_ignition;
 use axum::http::Method;
 use axum::Router;
 use dal::component::confirmation::view::{ConfirmationView, RecommendationView};
+use dal::fix::plan::FixPlanRequest;
 use dal::schema::variant::definition::SchemaVariantDefinitionId;
 use dal::{
     property_editor::values::PropertyEditorValue, socket::SocketEdgeKind, AttributeValue,
@@ -56,7 +57,7 @@ use sdf_server::service::{
     fix::{
         confirmations::{ConfirmationsRequest, ConfirmationsResponse},
         list::{BatchHistoryView, ListFixesRequest, ListFixesResponse},
-        run::{FixRunRequest, FixesRunRequest, FixesRunResponse},
+        run::{FixesRunRequest, FixesRunResponse},
     },
 };
 use serde::{de::DeserializeOwned, Serialize};
@@ -561,6 +562,7 @@ impl ScenarioHarness {
         assert!(!ctx.visibility().is_head());
         let request = ApplyChangeSetRequest {
             change_set_pk: ctx.visibility().change_set_pk,
+            force: false,
         };
         let _response: ApplyChangeSetResponse = self
             .query_post("/api/change_set/apply_change_set", &request)
@@ -593,10 +595,11 @@ impl ScenarioHarness {
     pub async fn run_fixes(
         &self,
         visibility: &Visibility,
-        fixes: Vec<FixRunRequest>,
+        fixes: Vec<FixPlanRequest>,
     ) -> FixBatchId {
         let request = dbg!(FixesRunRequest {
             list: fixes,
+            failure_policy: Default::default(),
             visibility: *visibility,
         });
         let response: FixesRunResponse = dbg!(self.query_post("/api/fix/run", &request).await);
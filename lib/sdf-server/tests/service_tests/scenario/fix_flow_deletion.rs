@@ -3,14 +3,12 @@ use std::collections::HashSet;
 use axum::Router;
 use dal::{
     component::confirmation::view::{ConfirmationStatus, RecommendationView},
+    fix::plan::FixPlanRequest,
     ActionKind, ComponentId, FixCompletionStatus,
 };
 use dal_test::{sdf_test, AuthToken, DalContextHead};
 use pretty_assertions_sorted::assert_eq;
-use sdf_server::service::{
-    dev::{CREATE_CONFIRMATION_NAME, DELETE_CONFIRMATION_NAME},
-    fix::run::FixRunRequest,
-};
+use sdf_server::service::dev::{CREATE_CONFIRMATION_NAME, DELETE_CONFIRMATION_NAME};
 
 use crate::service_tests::scenario::ScenarioHarness;
 
@@ -147,7 +145,7 @@ async fn fix_flow_deletion(
             ActionKind::Create,         // expected
             recommendation.action_kind  // actual
         );
-        fix_requests.push(FixRunRequest {
+        fix_requests.push(FixPlanRequest {
             attribute_value_id: recommendation.confirmation_attribute_value_id,
             component_id: recommendation.component_id,
             action_prototype_id: recommendation.action_prototype_id,
@@ -353,7 +351,7 @@ async fn fix_flow_deletion(
             ActionKind::Delete,         // expected
             recommendation.action_kind  // actual
         );
-        fix_requests.push(FixRunRequest {
+        fix_requests.push(FixPlanRequest {
             attribute_value_id: recommendation.confirmation_attribute_value_id,
             component_id: recommendation.component_id,
             action_prototype_id: recommendation.action_prototype_id,
@@ -1,8 +1,7 @@
 use axum::Router;
-use dal::FixCompletionStatus;
+use dal::{fix::plan::FixPlanRequest, FixCompletionStatus};
 use dal_test::{sdf_test, AuthToken, DalContextHead};
 use pretty_assertions_sorted::assert_eq;
-use sdf_server::service::fix::run::FixRunRequest;
 
 use crate::service_tests::scenario::ScenarioHarness;
 
@@ -132,7 +131,7 @@ async fn model_and_fix_flow_aws_key_pair(
     let fix_batch_id = harness
         .run_fixes(
             ctx.visibility(),
-            vec![FixRunRequest {
+            vec![FixPlanRequest {
                 attribute_value_id: recommendation.confirmation_attribute_value_id,
                 component_id: recommendation.component_id,
                 action_prototype_id: recommendation.action_prototype_id,
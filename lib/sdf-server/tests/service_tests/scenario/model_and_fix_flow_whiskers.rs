@@ -2,12 +2,11 @@ use std::collections::HashSet;
 
 use axum::Router;
 use dal::{
-    component::confirmation::view::ConfirmationStatus, qualification::QualificationSubCheckStatus,
-    ActionKind, Component, FixCompletionStatus,
+    component::confirmation::view::ConfirmationStatus, fix::plan::FixPlanRequest,
+    qualification::QualificationSubCheckStatus, ActionKind, Component, FixCompletionStatus,
 };
 use dal_test::{sdf_test, AuthToken, DalContextHead};
 use pretty_assertions_sorted::assert_eq;
-use sdf_server::service::fix::run::FixRunRequest;
 
 use crate::service_tests::scenario::ScenarioHarness;
 
@@ -553,7 +552,7 @@ async fn model_and_fix_flow_whiskers(
     // Select the recommendations we want.
     for recommendation in recommendations {
         if failing_targets.contains(&recommendation.confirmation_attribute_value_id) {
-            fix_requests.push(FixRunRequest {
+            fix_requests.push(FixPlanRequest {
                 attribute_value_id: recommendation.confirmation_attribute_value_id,
                 component_id: recommendation.component_id,
                 action_prototype_id: recommendation.action_prototype_id,
@@ -646,13 +645,13 @@ async fn model_and_fix_flow_whiskers(
     for delete_recommendation in delete_recommendations {
         if delete_recommendation.action_kind == ActionKind::Delete {
             if delete_recommendation.name == "Delete Security Group" {
-                delete_sg_requests.push(FixRunRequest {
+                delete_sg_requests.push(FixPlanRequest {
                     attribute_value_id: delete_recommendation.confirmation_attribute_value_id,
                     component_id: delete_recommendation.component_id,
                     action_prototype_id: delete_recommendation.action_prototype_id,
                 })
             } else {
-                delete_requests.push(FixRunRequest {
+                delete_requests.push(FixPlanRequest {
                     attribute_value_id: delete_recommendation.confirmation_attribute_value_id,
                     component_id: delete_recommendation.component_id,
                     action_prototype_id: delete_recommendation.action_prototype_id,
@@ -1,13 +1,22 @@
 use axum::Router;
 use dal::{
-    EncryptedSecret, SecretAlgorithm, SecretKind, SecretObjectType, SecretVersion, StandardModel,
-    Visibility, WorkspaceSignup,
+    EncryptedSecret, PublicKey, SecretAlgorithm, SecretKind, SecretObjectType, SecretVersion,
+    StandardModel, Visibility, WorkspaceSignup,
 };
-use dal_test::{sdf_test, test_harness::encrypt_message, AuthTokenRef, DalContextHead};
-use hyper::Method;
+use dal_test::{
+    sdf_test,
+    test_harness::{create_secret, create_secret_with_message, encrypt_message},
+    AuthTokenRef, DalContextHead,
+};
+use hyper::{Method, StatusCode};
 use sdf_server::service::secret::create_secret::{CreateSecretRequest, CreateSecretResponse};
+use sdf_server::service::secret::update_secret::{
+    UpdateSecretContents, UpdateSecretRequest, UpdateSecretResponse,
+};
 
-use crate::service_tests::api_request_auth_json_body;
+use crate::service_tests::{
+    api_request_auth_empty, api_request_auth_json_body, api_request_auth_json_body_status,
+};
 
 #[sdf_test]
 async fn create_secret(
@@ -62,3 +71,138 @@ async fn create_secret(
         serde_json::to_value(&decrypted_secret).expect("failed to serial decrypted into Value");
     assert_eq!(decrypted_value["message"], message);
 }
+
+#[sdf_test]
+async fn update_secret(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+    nw: WorkspaceSignup,
+) {
+    let visibility = Visibility::new_head(false);
+    let secret = create_secret(&ctx, nw.key_pair.pk()).await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let message = serde_json::json!({"artist":"Rush"});
+    let request = UpdateSecretRequest {
+        id: *secret.id(),
+        name: Some("red-barchetta".to_string()),
+        contents: Some(UpdateSecretContents {
+            crypted: encrypt_message(&ctx, nw.key_pair.pk(), &message).await,
+            key_pair_pk: nw.key_pair.pk(),
+            version: SecretVersion::V1,
+            algorithm: SecretAlgorithm::Sealedbox,
+        }),
+        visibility,
+    };
+
+    let response: UpdateSecretResponse = api_request_auth_json_body(
+        app,
+        Method::POST,
+        "/api/secret/update_secret",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(response.secret.id(), secret.id());
+    assert_eq!(response.secret.name(), "red-barchetta");
+
+    let decrypted_secret = EncryptedSecret::get_by_id(&ctx, secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret in tenancy and/or visibility")
+        .decrypt(&ctx)
+        .await
+        .expect("failed to decrypt secret");
+    let decrypted_value =
+        serde_json::to_value(&decrypted_secret).expect("failed to serial decrypted into Value");
+    assert_eq!(decrypted_value["message"], message);
+}
+
+#[sdf_test]
+async fn rotate_key_pair(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+    nw: WorkspaceSignup,
+) {
+    let message = serde_json::json!({"artist":"Silverstein"});
+    let secret = create_secret_with_message(&ctx, nw.key_pair.pk(), &message).await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let public_key: PublicKey = api_request_auth_empty(
+        app.clone(),
+        Method::POST,
+        "/api/secret/rotate_key_pair",
+        auth_token,
+    )
+    .await;
+    assert_ne!(*public_key.pk(), nw.key_pair.pk());
+
+    let encrypted_secret = EncryptedSecret::get_by_id(&ctx, secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret in tenancy and/or visibility");
+    assert_eq!(
+        *public_key.pk(),
+        encrypted_secret
+            .key_pair(&ctx)
+            .await
+            .expect("failed to fetch key pair")
+            .pk()
+    );
+    let decrypted_value = serde_json::to_value(
+        &encrypted_secret
+            .decrypt(&ctx)
+            .await
+            .expect("failed to decrypt secret"),
+    )
+    .expect("failed to serial decrypted into Value");
+    assert_eq!(decrypted_value["message"], message);
+
+    // The key pair the secret was sealed to before the rotation can't seal new secrets
+    let stale_request = CreateSecretRequest {
+        name: "my-own-reflection".to_string(),
+        object_type: SecretObjectType::Credential,
+        kind: SecretKind::DockerHub,
+        crypted: b"stale".to_vec(),
+        key_pair_pk: nw.key_pair.pk(),
+        version: SecretVersion::V1,
+        algorithm: SecretAlgorithm::Sealedbox,
+        visibility: Visibility::new_head(false),
+    };
+    let status = api_request_auth_json_body_status(
+        app.clone(),
+        Method::POST,
+        "/api/secret/create_secret",
+        auth_token,
+        &stale_request,
+    )
+    .await;
+    assert_eq!(StatusCode::BAD_REQUEST, status);
+
+    let stale_request = UpdateSecretRequest {
+        id: *secret.id(),
+        name: None,
+        contents: Some(UpdateSecretContents {
+            crypted: b"stale".to_vec(),
+            key_pair_pk: nw.key_pair.pk(),
+            version: SecretVersion::V1,
+            algorithm: SecretAlgorithm::Sealedbox,
+        }),
+        visibility: Visibility::new_head(false),
+    };
+    let status = api_request_auth_json_body_status(
+        app,
+        Method::POST,
+        "/api/secret/update_secret",
+        auth_token,
+        &stale_request,
+    )
+    .await;
+    assert_eq!(StatusCode::BAD_REQUEST, status);
+}
@@ -1,11 +1,22 @@
-use axum::{http::Method, Router};
-use dal::WorkspaceSignup;
-use dal_test::{sdf_test, AuthTokenRef, DalContextHead};
-use sdf_server::service::session::{
-    load_workspace::LoadWorkspaceResponse, restore_authentication::RestoreAuthenticationResponse,
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use dal::{PkgTrustPolicy, User, WorkspaceRole, WorkspaceSignup};
+use dal_test::{helpers::create_user, sdf_test, AuthTokenRef, DalContextHead};
+use sdf_server::service::{
+    change_set::update_approval_policy::UpdateApprovalPolicyRequest,
+    pkg::update_trust_policy::UpdateTrustPolicyRequest,
+    session::{
+        load_workspace::LoadWorkspaceResponse,
+        restore_authentication::RestoreAuthenticationResponse,
+        set_workspace_role::{SetWorkspaceRoleRequest, SetWorkspaceRoleResponse},
+    },
 };
 
-use crate::service_tests::api_request_auth_empty;
+use crate::service_tests::{
+    api_request_auth_empty, api_request_auth_json_body, api_request_auth_json_body_status,
+};
 
 #[sdf_test]
 async fn restore_authentication(
@@ -52,3 +63,115 @@ async fn load_workspace(
         api_request_auth_empty(app, Method::GET, "/api/session/load_workspace", auth_token).await;
     assert_eq!(nw.workspace, response.workspace);
 }
+
+#[sdf_test]
+async fn set_workspace_role(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+    nw: WorkspaceSignup,
+) {
+    let member = create_user(&ctx).await;
+    ctx.commit().await.expect("cannot commit txn");
+
+    let request = SetWorkspaceRoleRequest {
+        user_pk: member.pk(),
+        role: WorkspaceRole::Approver,
+    };
+    let response: SetWorkspaceRoleResponse = api_request_auth_json_body(
+        app.clone(),
+        Method::POST,
+        "/api/session/set_workspace_role",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert!(response.success);
+    assert_eq!(
+        Some(WorkspaceRole::Approver),
+        User::workspace_role(&ctx, member.pk(), *nw.workspace.pk())
+            .await
+            .expect("cannot get workspace role")
+    );
+
+    // Owners can't lock themselves out
+    let request = SetWorkspaceRoleRequest {
+        user_pk: nw.user.pk(),
+        role: WorkspaceRole::Viewer,
+    };
+    let status = api_request_auth_json_body_status(
+        app,
+        Method::POST,
+        "/api/session/set_workspace_role",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(StatusCode::BAD_REQUEST, status);
+}
+
+#[sdf_test]
+async fn only_owners_can_manage_the_workspace(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+    nw: WorkspaceSignup,
+) {
+    let member = create_user(&ctx).await;
+    nw.user
+        .set_workspace_role(&ctx, *nw.workspace.pk(), WorkspaceRole::Editor)
+        .await
+        .expect("cannot set workspace role");
+    ctx.commit().await.expect("cannot commit txn");
+
+    let request = SetWorkspaceRoleRequest {
+        user_pk: member.pk(),
+        role: WorkspaceRole::Owner,
+    };
+    let status = api_request_auth_json_body_status(
+        app.clone(),
+        Method::POST,
+        "/api/session/set_workspace_role",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(StatusCode::FORBIDDEN, status);
+
+    let request = UpdateApprovalPolicyRequest {
+        required_approvals: 0,
+    };
+    let status = api_request_auth_json_body_status(
+        app.clone(),
+        Method::POST,
+        "/api/change_set/update_approval_policy",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(StatusCode::FORBIDDEN, status);
+
+    let request = UpdateTrustPolicyRequest {
+        pkg_trust_policy: PkgTrustPolicy::AllowUnsigned,
+        pkg_trusted_keys: Vec::new(),
+    };
+    let status = api_request_auth_json_body_status(
+        app.clone(),
+        Method::POST,
+        "/api/pkg/update_trust_policy",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(StatusCode::FORBIDDEN, status);
+
+    let status = api_request_auth_json_body_status(
+        app,
+        Method::POST,
+        "/api/secret/rotate_key_pair",
+        auth_token,
+        &serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(StatusCode::FORBIDDEN, status);
+}
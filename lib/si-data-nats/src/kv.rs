@@ -0,0 +1,78 @@
+use std::fmt;
+
+use telemetry::prelude::*;
+use tokio::task::spawn_blocking;
+
+use crate::{Error, Result};
+
+/// A handle on a JetStream key/value bucket.
+///
+/// The underlying client is blocking, so every operation is run on the blocking thread pool.
+#[derive(Clone)]
+pub struct KeyValueStore {
+    inner: nats::kv::Store,
+    bucket: String,
+}
+
+impl KeyValueStore {
+    pub(crate) fn new(inner: nats::kv::Store, bucket: String) -> Self {
+        Self { inner, bucket }
+    }
+
+    /// Gets the name of the bucket.
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    /// Gets the latest value for a key, if any.
+    #[instrument(name = "key_value_store.get", skip(self), level = "debug")]
+    pub async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let inner = self.inner.clone();
+        let key = key.to_owned();
+        spawn_blocking(move || inner.get(&key))
+            .await
+            .map_err(Error::Async)?
+            .map_err(Error::Nats)
+    }
+
+    /// Stores a new value for a key, returning the revision of the bucket that holds it.
+    #[instrument(name = "key_value_store.put", skip(self, value), level = "debug")]
+    pub async fn put(&self, key: &str, value: impl Into<Vec<u8>>) -> Result<u64> {
+        let inner = self.inner.clone();
+        let key = key.to_owned();
+        let value = value.into();
+        spawn_blocking(move || inner.put(&key, value))
+            .await
+            .map_err(Error::Async)?
+            .map_err(Error::Nats)
+    }
+
+    /// Deletes a key.
+    #[instrument(name = "key_value_store.delete", skip(self), level = "debug")]
+    pub async fn delete(&self, key: &str) -> Result<()> {
+        let inner = self.inner.clone();
+        let key = key.to_owned();
+        spawn_blocking(move || inner.delete(&key))
+            .await
+            .map_err(Error::Async)?
+            .map_err(Error::Nats)
+    }
+
+    /// Lists every key that currently holds a value.
+    #[instrument(name = "key_value_store.keys", skip(self), level = "debug")]
+    pub async fn keys(&self) -> Result<Vec<String>> {
+        let inner = self.inner.clone();
+        spawn_blocking(move || inner.keys().map(Iterator::collect))
+            .await
+            .map_err(Error::Async)?
+            .map_err(Error::Nats)
+    }
+}
+
+impl fmt::Debug for KeyValueStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyValueStore")
+            .field("bucket", &self.bucket)
+            .finish_non_exhaustive()
+    }
+}
@@ -19,10 +19,12 @@ use tokio::{
 };
 
 pub mod jetstream;
+mod kv;
 mod message;
 mod options;
 mod subscription;
 
+pub use kv::KeyValueStore;
 pub use message::Message;
 pub use nats::{header::HeaderMap, rustls};
 pub use options::Options;
@@ -902,6 +904,101 @@ impl Client {
         self.inner.max_payload()
     }
 
+    /// Gets a handle on a JetStream key/value bucket, creating the bucket if it does not yet
+    /// exist.
+    ///
+    /// Bucket names may only contain alphanumeric characters, `-` and `_`.
+    #[instrument(
+        name = "client.key_value_store",
+        skip_all,
+        level = "debug",
+        fields(
+            messaging.protocol = %self.metadata.messaging_protocol,
+            messaging.system = %self.metadata.messaging_system,
+            messaging.url = %self.metadata.messaging_url,
+            net.transport = %self.metadata.net_transport,
+            otel.kind = %FormattedSpanKind(SpanKind::Client),
+            otel.status_code = Empty,
+            otel.status_message = Empty,
+        )
+    )]
+    pub async fn key_value_store(&self, bucket: impl Into<String>) -> Result<KeyValueStore> {
+        let span = Span::current();
+
+        let bucket = bucket.into();
+        let inner = self.inner.clone();
+        let bucket_name = bucket.clone();
+        let store = spawn_blocking(move || {
+            let jetstream = nats::jetstream::new(inner);
+            match jetstream.key_value(&bucket_name) {
+                Ok(store) => Ok(store),
+                Err(_) => jetstream.create_key_value(&nats::kv::Config {
+                    bucket: bucket_name,
+                    ..Default::default()
+                }),
+            }
+        })
+        .await
+        .map_err(|err| span.record_err(Error::Async(err)))?
+        .map_err(|err| span.record_err(Error::Nats(err)))?;
+
+        span.record_ok();
+        Ok(KeyValueStore::new(store, bucket))
+    }
+
+    /// Publishes a message to a JetStream stream, creating the stream (bound to only the given
+    /// subject) if it does not yet exist, and waits for the server to acknowledge it was stored.
+    ///
+    /// Stream names may only contain alphanumeric characters, `-` and `_`.
+    #[instrument(
+        name = "client.jetstream_publish",
+        skip_all,
+        level = "debug",
+        fields(
+            messaging.destination = Empty,
+            messaging.destination_kind = "topic",
+            messaging.operation = "send",
+            messaging.protocol = %self.metadata.messaging_protocol,
+            messaging.system = %self.metadata.messaging_system,
+            messaging.url = %self.metadata.messaging_url,
+            net.transport = %self.metadata.net_transport,
+            otel.kind = %FormattedSpanKind(SpanKind::Producer),
+            otel.status_code = Empty,
+            otel.status_message = Empty,
+        )
+    )]
+    pub async fn jetstream_publish(
+        &self,
+        stream: impl Into<String>,
+        subject: impl Into<String>,
+        msg: impl Into<Vec<u8>>,
+    ) -> Result<()> {
+        let span = Span::current();
+
+        let stream = stream.into();
+        let subject = subject.into();
+        let msg = msg.into();
+        span.record("messaging.destination", subject.as_str());
+        let inner = self.inner.clone();
+        spawn_blocking(move || {
+            let jetstream = nats::jetstream::new(inner);
+            if jetstream.stream_info(&stream).is_err() {
+                jetstream.add_stream(nats::jetstream::StreamConfig {
+                    name: stream,
+                    subjects: vec![subject.clone()],
+                    ..Default::default()
+                })?;
+            }
+            jetstream.publish(&subject, msg).map(|_ack| ())
+        })
+        .await
+        .map_err(|err| span.record_err(Error::Async(err)))?
+        .map_err(|err| span.record_err(Error::Nats(err)))?;
+
+        span.record_ok();
+        Ok(())
+    }
+
     /// Gets a reference to the client's metadata.
     pub fn metadata(&self) -> &ConnectionMetadata {
         self.metadata.as_ref()
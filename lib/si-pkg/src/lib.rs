@@ -1,30 +1,34 @@
 pub(crate) mod node;
 mod pkg;
+mod signature;
 mod spec;
 
 pub use pkg::{
-    SiPkg, SiPkgActionFunc, SiPkgAttrFuncInput, SiPkgAttrFuncInputView, SiPkgError, SiPkgFunc,
-    SiPkgFuncDescription, SiPkgLeafFunction, SiPkgMapKeyFunc, SiPkgMetadata, SiPkgProp,
-    SiPkgSchema, SiPkgSchemaVariant, SiPkgSocket, SiPkgValidation,
+    SiPkg, SiPkgActionFunc, SiPkgAttrFuncInput, SiPkgAttrFuncInputView, SiPkgDependency,
+    SiPkgError, SiPkgFunc, SiPkgFuncDescription, SiPkgLeafFunction, SiPkgMapKeyFunc, SiPkgMetadata,
+    SiPkgProp, SiPkgSchema, SiPkgSchemaVariant, SiPkgSocket, SiPkgValidation,
 };
+pub use signature::{PkgPublicKey, PkgSignature, PkgSigningKey, SignatureError, SignatureResult};
 pub use spec::{
-    ActionFuncSpec, ActionFuncSpecBuilder, ActionFuncSpecKind, AttrFuncInputSpec,
-    AttrFuncInputSpecKind, FuncArgumentKind, FuncArgumentSpec, FuncArgumentSpecBuilder,
-    FuncDescriptionSpec, FuncDescriptionSpecBuilder, FuncSpec, FuncSpecBackendKind,
-    FuncSpecBackendResponseType, FuncUniqueId, LeafFunctionSpec, LeafFunctionSpecBuilder,
-    LeafInputLocation, LeafKind, MapKeyFuncSpec, MapKeyFuncSpecBuilder, PkgSpec, PkgSpecBuilder,
-    PropSpec, PropSpecBuilder, PropSpecKind, PropSpecWidgetKind, SchemaSpec, SchemaSpecBuilder,
-    SchemaVariantSpec, SchemaVariantSpecBuilder, SchemaVariantSpecComponentType,
-    SchemaVariantSpecPropRoot, SiPropFuncSpec, SiPropFuncSpecBuilder, SiPropFuncSpecKind,
-    SocketSpec, SocketSpecArity, SocketSpecKind, SpecError, ValidationSpec, ValidationSpecKind,
+    compare_versions, ActionFuncSpec, ActionFuncSpecBuilder, ActionFuncSpecKind, AttrFuncInputSpec,
+    AttrFuncInputSpecKind, DependencySpec, DependencySpecBuilder, FuncArgumentKind,
+    FuncArgumentSpec, FuncArgumentSpecBuilder, FuncDescriptionSpec, FuncDescriptionSpecBuilder,
+    FuncSpec, FuncSpecBackendKind, FuncSpecBackendResponseType, FuncUniqueId, LeafFunctionSpec,
+    LeafFunctionSpecBuilder, LeafInputLocation, LeafKind, MapKeyFuncSpec, MapKeyFuncSpecBuilder,
+    PkgSpec, PkgSpecBuilder, PropSpec, PropSpecBuilder, PropSpecKind, PropSpecWidgetKind,
+    SchemaSpec, SchemaSpecBuilder, SchemaVariantSpec, SchemaVariantSpecBuilder,
+    SchemaVariantSpecComponentType, SchemaVariantSpecPropRoot, SiPropFuncSpec,
+    SiPropFuncSpecBuilder, SiPropFuncSpecKind, SocketSpec, SocketSpecArity, SocketSpecKind,
+    SpecError, ValidationSpec, ValidationSpecKind, VersionReq,
 };
 
 #[cfg(test)]
 mod tests {
+    use object_tree::{ObjectTree, TarWriter};
     use petgraph::dot::Dot;
     use tokio::sync::Mutex;
 
-    use crate::spec::PkgSpec;
+    use crate::{node::PkgNode, spec::PkgSpec};
 
     use super::*;
 
@@ -142,4 +146,259 @@ mod tests {
 
         let _ = dbg!(props.lock().await);
     }
+
+    #[tokio::test]
+    async fn pkg_signatures_round_trip() {
+        let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        let mut pkg = SiPkg::load_from_spec(spec).expect("failed to load spec");
+        let hash = pkg.hash().expect("get hash");
+
+        let signing_key = PkgSigningKey::generate().expect("generate signing key");
+        let other_key = PkgSigningKey::generate().expect("generate signing key");
+        assert!(matches!(pkg.verify(&[]), Err(SiPkgError::Unsigned)));
+
+        pkg.sign(&signing_key).expect("sign pkg");
+        // signing again with the same key replaces the signature
+        pkg.sign(&signing_key).expect("sign pkg");
+
+        let pkg_data = pkg.write_to_bytes().expect("failed to serialize pkg");
+        let read_pkg = SiPkg::load_from_bytes(pkg_data).expect("failed to load pkg from bytes");
+
+        assert_eq!(hash, read_pkg.hash().expect("get hash"));
+        assert_eq!(
+            vec![signing_key.public_key()],
+            read_pkg.signers().expect("get signers")
+        );
+        assert_eq!(
+            signing_key.public_key(),
+            read_pkg
+                .verify(&[other_key.public_key(), signing_key.public_key()])
+                .expect("verify pkg")
+        );
+        assert!(matches!(
+            read_pkg.verify(&[other_key.public_key()]),
+            Err(SiPkgError::UntrustedSigners(_))
+        ));
+
+        let public_key: PkgPublicKey = signing_key
+            .public_key()
+            .to_string()
+            .parse()
+            .expect("parse public key");
+        assert_eq!(signing_key.public_key(), public_key);
+    }
+
+    #[tokio::test]
+    async fn pkg_signature_over_other_pkg_is_rejected() {
+        let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        let pkg = SiPkg::load_from_spec(spec).expect("failed to load spec");
+        let signing_key = PkgSigningKey::generate().expect("generate signing key");
+
+        let mut other_spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        other_spec.description = "tampered".to_owned();
+        let mut other_pkg = SiPkg::load_from_spec(other_spec).expect("failed to load spec");
+        other_pkg.sign(&signing_key).expect("sign pkg");
+
+        // graft the signature of one package onto another
+        let (_, signatures): (ObjectTree<PkgNode>, _) =
+            ObjectTree::<PkgNode>::read_from_tar_with_signatures(
+                other_pkg.write_to_bytes().expect("failed to serialize pkg"),
+            )
+            .expect("failed to read signatures");
+        let tree: ObjectTree<PkgNode> = ObjectTree::<PkgNode>::read_from_tar(
+            pkg.write_to_bytes().expect("failed to serialize pkg"),
+        )
+        .expect("failed to read tree");
+        let grafted_data = TarWriter::new_with_signatures(&tree, &signatures)
+            .expect("failed to write tar")
+            .bytes();
+        let pkg = SiPkg::load_from_bytes(grafted_data).expect("failed to load pkg from bytes");
+
+        assert!(matches!(
+            pkg.verify(&[signing_key.public_key()]),
+            Err(SiPkgError::Signature(SignatureError::InvalidSignature(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn pkg_with_an_invalid_extra_signature_is_verified() {
+        let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        let mut pkg = SiPkg::load_from_spec(spec).expect("failed to load spec");
+        let signing_key = PkgSigningKey::generate().expect("generate signing key");
+        pkg.sign(&signing_key).expect("sign pkg");
+
+        let mut other_spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        other_spec.description = "tampered".to_owned();
+        let mut other_pkg = SiPkg::load_from_spec(other_spec).expect("failed to load spec");
+        let other_key = PkgSigningKey::generate().expect("generate signing key");
+        other_pkg.sign(&other_key).expect("sign pkg");
+
+        // add the signature of another package next to the valid one
+        let (tree, mut signatures): (ObjectTree<PkgNode>, _) =
+            ObjectTree::<PkgNode>::read_from_tar_with_signatures(
+                pkg.write_to_bytes().expect("failed to serialize pkg"),
+            )
+            .expect("failed to read pkg");
+        let (_, other_signatures): (ObjectTree<PkgNode>, _) =
+            ObjectTree::<PkgNode>::read_from_tar_with_signatures(
+                other_pkg.write_to_bytes().expect("failed to serialize pkg"),
+            )
+            .expect("failed to read signatures");
+        signatures.extend(other_signatures);
+        let data = TarWriter::new_with_signatures(&tree, &signatures)
+            .expect("failed to write tar")
+            .bytes();
+        let pkg = SiPkg::load_from_bytes(data).expect("failed to load pkg from bytes");
+
+        assert_eq!(
+            vec![signing_key.public_key()],
+            pkg.signers().expect("get signers")
+        );
+        assert_eq!(
+            vec![other_key.public_key()],
+            pkg.invalid_signers().expect("get invalid signers")
+        );
+        assert_eq!(
+            signing_key.public_key(),
+            pkg.verify(&[signing_key.public_key(), other_key.public_key()])
+                .expect("verify pkg")
+        );
+        assert!(matches!(
+            pkg.verify(&[other_key.public_key()]),
+            Err(SiPkgError::UntrustedSigners(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn pkg_with_tampered_object_is_rejected() {
+        use std::io::Read;
+
+        let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        let mut pkg = SiPkg::load_from_spec(spec).expect("failed to load spec");
+        let signing_key = PkgSigningKey::generate().expect("generate signing key");
+        pkg.sign(&signing_key).expect("sign pkg");
+
+        // change the contents of an object, keeping the hash it is stored under
+        let data = pkg.write_to_bytes().expect("failed to serialize pkg");
+        let mut archive = tar::Archive::new(data.as_slice());
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut tampered = false;
+        for entry in archive.entries().expect("failed to read tar") {
+            let mut entry = entry.expect("failed to read tar entry");
+            let mut header = entry.header().clone();
+            let mut bytes = Vec::new();
+            entry
+                .read_to_end(&mut bytes)
+                .expect("failed to read tar entry");
+
+            let text = String::from_utf8_lossy(&bytes);
+            if text.contains("it returns true") {
+                bytes = text
+                    .replace("it returns true", "it returns TRUE")
+                    .into_bytes();
+                tampered = true;
+            }
+            header.set_cksum();
+            builder
+                .append(&header, bytes.as_slice())
+                .expect("failed to write tar entry");
+        }
+        assert!(tampered, "no object was tampered with");
+        let tampered_data = builder.into_inner().expect("failed to write tar");
+
+        assert!(matches!(
+            SiPkg::load_from_bytes(tampered_data),
+            Err(SiPkgError::TarRead(
+                object_tree::TarReadError::HashMismatch(..)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn pkg_dependencies_round_trip() {
+        let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        let hash_without_dependencies = SiPkg::load_from_spec(spec.clone())
+            .expect("failed to load spec")
+            .hash()
+            .expect("get hash");
+
+        let dependency_hash = object_tree::Hash::new(b"mi casa es su casa");
+        let mut spec_with_dependencies = spec;
+        spec_with_dependencies.dependencies = vec![
+            DependencySpec::builder()
+                .name("si-intrinsics")
+                .try_version_req(">=2023-05-01, <2024")
+                .expect("parse version requirement")
+                .build()
+                .expect("build dependency"),
+            DependencySpec::builder()
+                .name("si-aws")
+                .hash(dependency_hash)
+                .build()
+                .expect("build dependency"),
+        ];
+        let pkg = SiPkg::load_from_spec(spec_with_dependencies).expect("failed to load spec");
+        assert_ne!(hash_without_dependencies, pkg.hash().expect("get hash"));
+
+        let read_pkg = SiPkg::load_from_bytes(pkg.write_to_bytes().expect("serialize pkg"))
+            .expect("failed to load pkg from bytes");
+        let dependencies = read_pkg.dependencies().expect("get dependencies");
+        assert_eq!(2, dependencies.len());
+
+        let intrinsics = dependencies
+            .iter()
+            .find(|dependency| dependency.name() == "si-intrinsics")
+            .expect("has intrinsics dependency");
+        assert_eq!("si-intrinsics", intrinsics.name());
+        assert_eq!(">=2023-05-01, <2024", intrinsics.version_req().to_string());
+        assert!(intrinsics.is_satisfied_by("si-intrinsics", "2023-05-24", "any"));
+        assert!(!intrinsics.is_satisfied_by("si-intrinsics", "2024-01-01", "any"));
+        assert!(!intrinsics.is_satisfied_by("si-aws", "2023-05-24", "any"));
+
+        let aws = dependencies
+            .iter()
+            .find(|dependency| dependency.name() == "si-aws")
+            .expect("has aws dependency");
+        assert_eq!(VersionReq::any(), *aws.version_req());
+        assert!(aws.is_satisfied_by("si-aws", "0.1", &dependency_hash.to_string()));
+        assert!(!aws.is_satisfied_by("si-aws", "0.1", "another hash"));
+
+        let read_spec = read_pkg.to_spec().await.expect("convert to spec");
+        assert_eq!(2, read_spec.dependencies.len());
+
+        let without_dependencies: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        let pkg = SiPkg::load_from_spec(without_dependencies).expect("failed to load spec");
+        assert!(pkg.dependencies().expect("get dependencies").is_empty());
+    }
+
+    #[test]
+    fn version_requirements() {
+        assert_eq!(
+            std::cmp::Ordering::Greater,
+            compare_versions("1.10.0", "1.9.2")
+        );
+        assert_eq!(std::cmp::Ordering::Equal, compare_versions("1.0", "1.0.0"));
+        assert_eq!(
+            std::cmp::Ordering::Less,
+            compare_versions("2023-05-23", "2023-05-24")
+        );
+
+        let any: VersionReq = "*".parse().expect("parse version requirement");
+        assert!(any.matches("whatever"));
+
+        let exact: VersionReq = "0.1".parse().expect("parse version requirement");
+        assert_eq!(VersionReq::exact("0.1"), exact);
+        assert!(exact.matches("0.1.0"));
+        assert!(!exact.matches("0.2"));
+
+        let range: VersionReq = "> 1.0, <=2".parse().expect("parse version requirement");
+        assert_eq!(">1.0, <=2", range.to_string());
+        assert!(!range.matches("1.0"));
+        assert!(range.matches("1.5"));
+        assert!(range.matches("2.0.0"));
+        assert!(!range.matches("2.0.1"));
+
+        assert!(">=".parse::<VersionReq>().is_err());
+        assert!("1.0,".parse::<VersionReq>().is_err());
+    }
 }
@@ -0,0 +1,83 @@
+use std::{
+    io::{BufRead, Write},
+    str::FromStr,
+};
+
+use object_tree::{
+    read_key_value_line, write_key_value_line, GraphError, Hash, NameStr, NodeChild, NodeKind,
+    NodeWithChildren, ReadBytes, WriteBytes,
+};
+
+use crate::spec::{DependencySpec, VersionReq};
+
+use super::PkgNode;
+
+const KEY_NAME_STR: &str = "name";
+const KEY_VERSION_REQ_STR: &str = "version_req";
+const KEY_HASH_STR: &str = "hash";
+
+#[derive(Clone, Debug)]
+pub struct DependencyNode {
+    pub name: String,
+    pub version_req: VersionReq,
+    pub hash: Option<Hash>,
+}
+
+impl NameStr for DependencyNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl WriteBytes for DependencyNode {
+    fn write_bytes<W: Write>(&self, writer: &mut W) -> Result<(), GraphError> {
+        write_key_value_line(writer, KEY_NAME_STR, self.name())?;
+        write_key_value_line(writer, KEY_VERSION_REQ_STR, &self.version_req)?;
+        write_key_value_line(
+            writer,
+            KEY_HASH_STR,
+            self.hash.map(|hash| hash.to_string()).unwrap_or_default(),
+        )?;
+
+        Ok(())
+    }
+}
+
+impl ReadBytes for DependencyNode {
+    fn read_bytes<R: BufRead>(reader: &mut R) -> Result<Self, GraphError>
+    where
+        Self: std::marker::Sized,
+    {
+        let name = read_key_value_line(reader, KEY_NAME_STR)?;
+        let version_req_str = read_key_value_line(reader, KEY_VERSION_REQ_STR)?;
+        let version_req = VersionReq::from_str(&version_req_str).map_err(GraphError::parse)?;
+        let hash_str = read_key_value_line(reader, KEY_HASH_STR)?;
+        let hash = if hash_str.is_empty() {
+            None
+        } else {
+            Some(Hash::from_str(&hash_str).map_err(GraphError::parse)?)
+        };
+
+        Ok(Self {
+            name,
+            version_req,
+            hash,
+        })
+    }
+}
+
+impl NodeChild for DependencySpec {
+    type NodeType = PkgNode;
+
+    fn as_node_with_children(&self) -> NodeWithChildren<Self::NodeType> {
+        NodeWithChildren::new(
+            NodeKind::Leaf,
+            Self::NodeType::Dependency(DependencyNode {
+                name: self.name.to_owned(),
+                version_req: self.version_req.to_owned(),
+                hash: self.hash,
+            }),
+            vec![],
+        )
+    }
+}
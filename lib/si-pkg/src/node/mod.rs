@@ -7,6 +7,7 @@ use object_tree::{
 mod action_func;
 mod attr_func_input;
 mod category;
+mod dependency;
 mod func;
 mod func_argument;
 mod func_description;
@@ -26,6 +27,7 @@ pub(crate) use self::{
     action_func::ActionFuncNode,
     attr_func_input::AttrFuncInputNode,
     category::CategoryNode,
+    dependency::DependencyNode,
     func::FuncNode,
     func_argument::FuncArgumentNode,
     func_description::FuncDescriptionNode,
@@ -45,6 +47,7 @@ pub(crate) use self::{
 const NODE_KIND_ACTION_FUNC: &str = "action_func";
 const NODE_KIND_ATTR_FUNC_INPUT: &str = "attr_func_input";
 const NODE_KIND_CATEGORY: &str = "category";
+const NODE_KIND_DEPENDENCY: &str = "dependency";
 const NODE_KIND_FUNC: &str = "func";
 const NODE_KIND_FUNC_ARGUMENT: &str = "func_argument";
 const NODE_KIND_FUNC_DESCRIPTION: &str = "func_description";
@@ -68,6 +71,7 @@ pub enum PkgNode {
     ActionFunc(ActionFuncNode),
     AttrFuncInput(AttrFuncInputNode),
     Category(CategoryNode),
+    Dependency(DependencyNode),
     Func(FuncNode),
     FuncArgument(FuncArgumentNode),
     FuncDescription(FuncDescriptionNode),
@@ -88,6 +92,7 @@ impl PkgNode {
     pub const ACTION_FUNC_KIND_STR: &str = NODE_KIND_ACTION_FUNC;
     pub const ATTR_FUNC_INPUT_KIND_STR: &str = NODE_KIND_ATTR_FUNC_INPUT;
     pub const CATEGORY_KIND_STR: &str = NODE_KIND_CATEGORY;
+    pub const DEPENDENCY_KIND_STR: &str = NODE_KIND_DEPENDENCY;
     pub const FUNC_KIND_STR: &str = NODE_KIND_FUNC;
     pub const FUNC_ARGUMENT_KIND_STR: &str = NODE_KIND_FUNC_ARGUMENT;
     pub const FUNC_DESCRIPTION_KIND_STR: &str = NODE_KIND_FUNC_DESCRIPTION;
@@ -108,6 +113,7 @@ impl PkgNode {
             Self::AttrFuncInput(_) => NODE_KIND_ATTR_FUNC_INPUT,
             Self::Category(_) => NODE_KIND_CATEGORY,
             Self::ActionFunc(_) => NODE_KIND_ACTION_FUNC,
+            Self::Dependency(_) => NODE_KIND_DEPENDENCY,
             Self::Func(_) => NODE_KIND_FUNC,
             Self::FuncArgument(_) => NODE_KIND_FUNC_ARGUMENT,
             Self::FuncDescription(_) => NODE_KIND_FUNC_DESCRIPTION,
@@ -132,6 +138,7 @@ impl NameStr for PkgNode {
             Self::AttrFuncInput(node) => node.name(),
             Self::Category(node) => node.name(),
             Self::ActionFunc(_) => NODE_KIND_ACTION_FUNC,
+            Self::Dependency(node) => node.name(),
             Self::Func(node) => node.name(),
             Self::FuncArgument(node) => node.name(),
             Self::FuncDescription(_) => NODE_KIND_FUNC_DESCRIPTION,
@@ -158,6 +165,7 @@ impl WriteBytes for PkgNode {
             Self::AttrFuncInput(node) => node.write_bytes(writer)?,
             Self::Category(node) => node.write_bytes(writer)?,
             Self::ActionFunc(node) => node.write_bytes(writer)?,
+            Self::Dependency(node) => node.write_bytes(writer)?,
             Self::Func(node) => node.write_bytes(writer)?,
             Self::FuncArgument(node) => node.write_bytes(writer)?,
             Self::FuncDescription(node) => node.write_bytes(writer)?,
@@ -191,6 +199,7 @@ impl ReadBytes for PkgNode {
                 Self::AttrFuncInput(AttrFuncInputNode::read_bytes(reader)?)
             }
             NODE_KIND_CATEGORY => Self::Category(CategoryNode::read_bytes(reader)?),
+            NODE_KIND_DEPENDENCY => Self::Dependency(DependencyNode::read_bytes(reader)?),
             NODE_KIND_FUNC => Self::Func(FuncNode::read_bytes(reader)?),
             NODE_KIND_FUNC_ARGUMENT => Self::FuncArgument(FuncArgumentNode::read_bytes(reader)?),
             NODE_KIND_FUNC_DESCRIPTION => {
@@ -71,6 +71,20 @@ impl NodeChild for PkgSpec {
     type NodeType = PkgNode;
 
     fn as_node_with_children(&self) -> NodeWithChildren<Self::NodeType> {
+        let mut children = vec![
+            Box::new(PackageCategory::Schemas(self.schemas.clone()))
+                as Box<dyn NodeChild<NodeType = Self::NodeType>>,
+            Box::new(PackageCategory::Funcs(self.funcs.clone()))
+                as Box<dyn NodeChild<NodeType = Self::NodeType>>,
+        ];
+        // The category is left out when there are no dependencies, so that packages which don't
+        // have any keep the same hash they had before dependencies could be declared
+        if !self.dependencies.is_empty() {
+            children.push(Box::new(PackageCategory::Dependencies(
+                self.dependencies.clone(),
+            )));
+        }
+
         NodeWithChildren::new(
             NodeKind::Tree,
             Self::NodeType::Package(PackageNode {
@@ -80,12 +94,7 @@ impl NodeChild for PkgSpec {
                 created_at: self.created_at,
                 created_by: self.created_by.clone(),
             }),
-            vec![
-                Box::new(PackageCategory::Schemas(self.schemas.clone()))
-                    as Box<dyn NodeChild<NodeType = Self::NodeType>>,
-                Box::new(PackageCategory::Funcs(self.funcs.clone()))
-                    as Box<dyn NodeChild<NodeType = Self::NodeType>>,
-            ],
+            children,
         )
     }
 }
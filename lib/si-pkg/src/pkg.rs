@@ -11,6 +11,7 @@ use thiserror::Error;
 
 mod action_func;
 mod attr_func_input;
+mod dependency;
 mod func;
 mod func_description;
 mod leaf_function;
@@ -23,13 +24,15 @@ mod validation;
 mod variant;
 
 pub use {
-    action_func::*, attr_func_input::*, func::*, func_description::*, leaf_function::*,
-    map_key_func::*, prop::*, schema::*, si_prop_func::*, socket::*, validation::*, variant::*,
+    action_func::*, attr_func_input::*, dependency::*, func::*, func_description::*,
+    leaf_function::*, map_key_func::*, prop::*, schema::*, si_prop_func::*, socket::*,
+    validation::*, variant::*,
 };
 
 use crate::{
     node::{CategoryNode, PkgNode},
-    spec::{FuncSpec, PkgSpec, SchemaVariantSpecPropRoot, SpecError},
+    signature::{PkgPublicKey, PkgSignature, PkgSigningKey, SignatureError},
+    spec::{DependencySpec, FuncSpec, PkgSpec, SchemaVariantSpecPropRoot, SpecError},
 };
 
 #[remain::sorted]
@@ -58,11 +61,17 @@ pub enum SiPkgError {
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
+    Signature(#[from] SignatureError),
+    #[error(transparent)]
     Spec(#[from] SpecError),
     #[error(transparent)]
     TarRead(#[from] TarReadError),
     #[error("unexpected pkg node type; expected={0}, actual={1}")]
     UnexpectedPkgNodeType(&'static str, &'static str),
+    #[error("package is not signed")]
+    Unsigned,
+    #[error("package is not signed by a trusted key, signed by: {0:?}")]
+    UntrustedSigners(Vec<PkgPublicKey>),
     #[error("Validation spec missing required field: {0}")]
     ValidationMissingField(String),
     #[error("error while visiting prop: {0}")]
@@ -92,6 +101,7 @@ impl From<Infallible> for SiPkgError {
 #[derive(Clone, Debug)]
 pub struct SiPkg {
     tree: Arc<ObjectTree<PkgNode>>,
+    signatures: Vec<PkgSignature>,
 }
 
 impl SiPkg {
@@ -101,10 +111,18 @@ impl SiPkg {
     }
 
     pub fn load_from_bytes(bytes: Vec<u8>) -> PkgResult<Self> {
-        let tree: ObjectTree<PkgNode> = ObjectTree::<PkgNode>::read_from_tar(bytes)?;
+        let (tree, signature_entries): (ObjectTree<PkgNode>, _) =
+            ObjectTree::<PkgNode>::read_from_tar_with_signatures(bytes)?;
+
+        let mut signatures = Vec::with_capacity(signature_entries.len());
+        for (name, signature) in signature_entries {
+            let public_key = PkgPublicKey::from_signature_name(&name)?;
+            signatures.push(PkgSignature::from_bytes(public_key, &signature)?);
+        }
 
         Ok(Self {
             tree: Arc::new(tree),
+            signatures,
         })
     }
 
@@ -118,11 +136,83 @@ impl SiPkg {
 
         Ok(Self {
             tree: Arc::new(tree),
+            signatures: vec![],
         })
     }
 
     pub fn write_to_bytes(&self) -> PkgResult<Vec<u8>> {
-        Ok(TarWriter::new(&self.tree)?.bytes())
+        let signatures: Vec<(String, Vec<u8>)> = self
+            .signatures
+            .iter()
+            .map(|signature| {
+                (
+                    signature.public_key().signature_name(),
+                    signature.to_bytes(),
+                )
+            })
+            .collect();
+
+        Ok(TarWriter::new_with_signatures(&self.tree, &signatures)?.bytes())
+    }
+
+    /// Signs the root hash of the package, replacing any previous signature made with the same
+    /// key. The signature is detached from the package contents, so the hash doesn't change.
+    pub fn sign(&mut self, signing_key: &PkgSigningKey) -> PkgResult<()> {
+        let signature = signing_key.sign(self.hash()?);
+        self.signatures
+            .retain(|existing| existing.public_key() != signature.public_key());
+        self.signatures.push(signature);
+
+        Ok(())
+    }
+
+    pub fn signatures(&self) -> &[PkgSignature] {
+        &self.signatures
+    }
+
+    /// Returns the keys whose signature matches the package contents. Signatures which don't are
+    /// left out, see [`Self::invalid_signers`].
+    pub fn signers(&self) -> PkgResult<Vec<PkgPublicKey>> {
+        Ok(self.check_signatures()?.0)
+    }
+
+    /// Returns the keys whose signature doesn't match the package contents.
+    pub fn invalid_signers(&self) -> PkgResult<Vec<PkgPublicKey>> {
+        Ok(self.check_signatures()?.1)
+    }
+
+    /// Verifies that the package was signed by at least one of the trusted keys, returning the
+    /// first one found. Signatures which don't match the package contents are ignored, unless
+    /// there are no others.
+    pub fn verify(&self, trusted_keys: &[PkgPublicKey]) -> PkgResult<PkgPublicKey> {
+        let (signers, invalid_signers) = self.check_signatures()?;
+        if let Some(signer) = signers.iter().find(|signer| trusted_keys.contains(signer)) {
+            return Ok(*signer);
+        }
+
+        if signers.is_empty() {
+            return Err(match invalid_signers.first() {
+                Some(invalid_signer) => SignatureError::InvalidSignature(*invalid_signer).into(),
+                None => SiPkgError::Unsigned,
+            });
+        }
+        Err(SiPkgError::UntrustedSigners(signers))
+    }
+
+    /// Splits the keys which signed the package into those whose signature matches the package
+    /// contents and those whose signature doesn't.
+    fn check_signatures(&self) -> PkgResult<(Vec<PkgPublicKey>, Vec<PkgPublicKey>)> {
+        let hash = self.hash()?;
+        let mut signers = Vec::with_capacity(self.signatures.len());
+        let mut invalid_signers = Vec::new();
+        for signature in &self.signatures {
+            match signature.verify(hash) {
+                Ok(()) => signers.push(signature.public_key()),
+                Err(_) => invalid_signers.push(signature.public_key()),
+            }
+        }
+
+        Ok((signers, invalid_signers))
     }
 
     pub fn metadata(&self) -> PkgResult<SiPkgMetadata> {
@@ -170,6 +260,19 @@ impl SiPkg {
         Ok(schemas)
     }
 
+    /// The packages which must be installed along with this one.
+    pub fn dependencies(&self) -> PkgResult<Vec<SiPkgDependency>> {
+        let (graph, root_idx) = self.as_petgraph();
+
+        let node_idxs = dependency_node_idxs(graph, root_idx)?;
+        let mut dependencies = Vec::with_capacity(node_idxs.len());
+        for node_idx in node_idxs {
+            dependencies.push(SiPkgDependency::from_graph(graph, node_idx)?);
+        }
+
+        Ok(dependencies)
+    }
+
     pub fn schema_by_name(&self, name: impl AsRef<str>) -> PkgResult<SiPkgSchema> {
         let (graph, root_idx) = self.as_petgraph();
 
@@ -210,6 +313,10 @@ impl SiPkg {
             builder.schema(schema.to_spec().await?);
         }
 
+        for dependency in self.dependencies()? {
+            builder.dependency(DependencySpec::try_from(dependency)?);
+        }
+
         Ok(builder.build()?)
     }
 }
@@ -262,6 +369,17 @@ fn schema_node_idxs(
     category_node_idxs(CategoryNode::Schemas, graph, root_idx)
 }
 
+fn dependency_node_idxs(
+    graph: &Graph<HashedNode<PkgNode>, ()>,
+    root_idx: NodeIndex,
+) -> PkgResult<Vec<NodeIndex>> {
+    // packages without dependencies don't have the category at all
+    match category_node_idxs(CategoryNode::Dependencies, graph, root_idx) {
+        Err(SiPkgError::CategoryNotFound(_)) => Ok(vec![]),
+        result => result,
+    }
+}
+
 fn func_node_idxs(
     graph: &Graph<HashedNode<PkgNode>, ()>,
     root_idx: NodeIndex,
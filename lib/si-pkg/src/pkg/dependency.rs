@@ -0,0 +1,86 @@
+use object_tree::{Hash, HashedNode};
+use petgraph::prelude::*;
+
+use super::{PkgResult, SiPkgError, Source};
+
+use crate::{
+    node::PkgNode,
+    spec::{DependencySpec, VersionReq},
+};
+
+#[derive(Clone, Debug)]
+pub struct SiPkgDependency<'a> {
+    name: String,
+    version_req: VersionReq,
+    pinned_hash: Option<Hash>,
+    hash: Hash,
+    source: Source<'a>,
+}
+
+impl<'a> SiPkgDependency<'a> {
+    pub fn from_graph(
+        graph: &'a Graph<HashedNode<PkgNode>, ()>,
+        node_idx: NodeIndex,
+    ) -> PkgResult<Self> {
+        let hashed_node = &graph[node_idx];
+        let node = match hashed_node.inner() {
+            PkgNode::Dependency(node) => node.clone(),
+            unexpected => {
+                return Err(SiPkgError::UnexpectedPkgNodeType(
+                    PkgNode::DEPENDENCY_KIND_STR,
+                    unexpected.node_kind_str(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            name: node.name,
+            version_req: node.version_req,
+            pinned_hash: node.hash,
+            hash: hashed_node.hash(),
+            source: Source::new(graph, node_idx),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    pub fn version_req(&self) -> &VersionReq {
+        &self.version_req
+    }
+
+    /// The root hash the dependency is pinned to, if any.
+    pub fn pinned_hash(&self) -> Option<Hash> {
+        self.pinned_hash
+    }
+
+    /// Whether the package with this name, version and root hash satisfies the dependency.
+    pub fn is_satisfied_by(&self, name: &str, version: &str, root_hash: &str) -> bool {
+        self.name == name
+            && self.version_req.matches(version)
+            && self
+                .pinned_hash
+                .map_or(true, |pinned_hash| pinned_hash.to_string() == root_hash)
+    }
+
+    pub fn hash(&self) -> Hash {
+        self.hash
+    }
+
+    pub fn source(&self) -> &Source<'a> {
+        &self.source
+    }
+}
+
+impl<'a> TryFrom<SiPkgDependency<'a>> for DependencySpec {
+    type Error = SiPkgError;
+
+    fn try_from(value: SiPkgDependency<'a>) -> Result<Self, Self::Error> {
+        Ok(DependencySpec::builder()
+            .name(value.name)
+            .version_req(value.version_req)
+            .hash(value.pinned_hash)
+            .build()?)
+    }
+}
@@ -0,0 +1,184 @@
+use std::{fmt, str::FromStr};
+
+use base64::{engine::general_purpose, Engine};
+use object_tree::Hash;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sodiumoxide::crypto::sign;
+use thiserror::Error;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum SignatureError {
+    #[error("failed to decode base64 string: {0}")]
+    Base64Decode(#[from] base64::DecodeError),
+    #[error("invalid public key")]
+    InvalidPublicKey,
+    #[error("invalid signature for public key {0}")]
+    InvalidSignature(PkgPublicKey),
+    #[error("invalid signing key")]
+    InvalidSigningKey,
+    #[error("failed to initialize sodiumoxide")]
+    SodiumOxideInit,
+}
+
+pub type SignatureResult<T> = Result<T, SignatureError>;
+
+/// The ed25519 key used to sign packages. Only its [`PkgPublicKey`] should ever be shared.
+#[derive(Clone)]
+pub struct PkgSigningKey(sign::SecretKey);
+
+impl PkgSigningKey {
+    pub fn generate() -> SignatureResult<Self> {
+        sodiumoxide::init().map_err(|()| SignatureError::SodiumOxideInit)?;
+        let (_public_key, secret_key) = sign::gen_keypair();
+
+        Ok(Self(secret_key))
+    }
+
+    /// Loads a signing key from its base64 encoding, as returned by
+    /// [`PkgSigningKey::to_base64`].
+    pub fn from_base64(encoded: impl AsRef<[u8]>) -> SignatureResult<Self> {
+        let bytes = general_purpose::STANDARD.decode(encoded)?;
+        let secret_key =
+            sign::SecretKey::from_slice(&bytes).ok_or(SignatureError::InvalidSigningKey)?;
+
+        Ok(Self(secret_key))
+    }
+
+    pub fn to_base64(&self) -> String {
+        general_purpose::STANDARD.encode(self.0.as_ref())
+    }
+
+    pub fn public_key(&self) -> PkgPublicKey {
+        PkgPublicKey(self.0.public_key())
+    }
+
+    pub(crate) fn sign(&self, hash: Hash) -> PkgSignature {
+        PkgSignature {
+            public_key: self.public_key(),
+            signature: sign::sign_detached(hash.to_string().as_bytes(), &self.0),
+        }
+    }
+}
+
+impl fmt::Debug for PkgSigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PkgSigningKey").field(&"...").finish()
+    }
+}
+
+/// The public half of a [`PkgSigningKey`], used to check who signed a package. It's represented
+/// as a base64 string when displayed or serialized.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PkgPublicKey(sign::PublicKey);
+
+impl PkgPublicKey {
+    pub fn from_base64(encoded: impl AsRef<[u8]>) -> SignatureResult<Self> {
+        let bytes = general_purpose::STANDARD.decode(encoded)?;
+        let public_key =
+            sign::PublicKey::from_slice(&bytes).ok_or(SignatureError::InvalidPublicKey)?;
+
+        Ok(Self(public_key))
+    }
+
+    pub fn to_base64(&self) -> String {
+        general_purpose::STANDARD.encode(self.0.as_ref())
+    }
+
+    /// The name of the signature made with this key in a package archive. It's the URL safe
+    /// encoding of the key, since the standard one can contain path separators.
+    pub(crate) fn signature_name(&self) -> String {
+        general_purpose::URL_SAFE_NO_PAD.encode(self.0.as_ref())
+    }
+
+    pub(crate) fn from_signature_name(name: &str) -> SignatureResult<Self> {
+        let bytes = general_purpose::URL_SAFE_NO_PAD.decode(name)?;
+        let public_key =
+            sign::PublicKey::from_slice(&bytes).ok_or(SignatureError::InvalidPublicKey)?;
+
+        Ok(Self(public_key))
+    }
+}
+
+impl fmt::Display for PkgPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_base64())
+    }
+}
+
+impl fmt::Debug for PkgPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PkgPublicKey")
+            .field(&self.to_base64())
+            .finish()
+    }
+}
+
+impl FromStr for PkgPublicKey {
+    type Err = SignatureError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_base64(s)
+    }
+}
+
+impl Serialize for PkgPublicKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_base64())
+    }
+}
+
+impl<'de> Deserialize<'de> for PkgPublicKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let encoded = String::deserialize(deserializer)?;
+        Self::from_base64(encoded).map_err(serde::de::Error::custom)
+    }
+}
+
+/// A detached ed25519 signature over the root [`Hash`] of a package. Since every node hash
+/// covers its children, signing the root hash signs the whole package.
+#[derive(Clone, Debug)]
+pub struct PkgSignature {
+    public_key: PkgPublicKey,
+    signature: sign::Signature,
+}
+
+impl PkgSignature {
+    pub(crate) fn from_bytes(public_key: PkgPublicKey, bytes: &[u8]) -> SignatureResult<Self> {
+        let signature = sign::Signature::try_from(bytes)
+            .map_err(|_| SignatureError::InvalidSignature(public_key))?;
+
+        Ok(Self {
+            public_key,
+            signature,
+        })
+    }
+
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        self.signature.to_bytes().to_vec()
+    }
+
+    pub fn public_key(&self) -> PkgPublicKey {
+        self.public_key
+    }
+
+    /// Checks that this signature was made over the given hash with the private half of its
+    /// public key.
+    pub fn verify(&self, hash: Hash) -> SignatureResult<()> {
+        if sign::verify_detached(
+            &self.signature,
+            hash.to_string().as_bytes(),
+            &self.public_key.0,
+        ) {
+            Ok(())
+        } else {
+            Err(SignatureError::InvalidSignature(self.public_key))
+        }
+    }
+}
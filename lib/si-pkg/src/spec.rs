@@ -5,6 +5,7 @@ use thiserror::Error;
 
 mod action_func;
 mod attr_func_input;
+mod dependency;
 mod func;
 mod func_description;
 mod leaf_function;
@@ -17,8 +18,9 @@ mod validation;
 mod variant;
 
 pub use {
-    action_func::*, attr_func_input::*, func::*, func_description::*, leaf_function::*,
-    map_key_func::*, prop::*, schema::*, si_prop_func::*, socket::*, validation::*, variant::*,
+    action_func::*, attr_func_input::*, dependency::*, func::*, func_description::*,
+    leaf_function::*, map_key_func::*, prop::*, schema::*, si_prop_func::*, socket::*,
+    validation::*, variant::*,
 };
 
 #[derive(Builder, Clone, Debug, Deserialize, Serialize)]
@@ -42,6 +44,10 @@ pub struct PkgSpec {
 
     #[builder(setter(each(name = "func", into)), default)]
     pub funcs: Vec<FuncSpec>,
+
+    #[builder(setter(each(name = "dependency", into)), default)]
+    #[serde(default)]
+    pub dependencies: Vec<DependencySpec>,
 }
 
 impl PkgSpec {
@@ -95,6 +101,8 @@ impl TryFrom<PkgSpecBuilder> for PkgSpec {
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum SpecError {
+    #[error("invalid version requirement: {0}")]
+    InvalidVersionReq(String),
     #[error("Can't convert {0} to LeafInputLocation")]
     LeafInputLocationConversionError(String),
     /// Uninitialized field
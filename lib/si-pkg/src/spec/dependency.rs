@@ -0,0 +1,192 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use derive_builder::Builder;
+use object_tree::Hash;
+use serde::{Deserialize, Serialize};
+
+use super::SpecError;
+
+/// Another package which must be installed for this one to work, usually because it provides
+/// functions this package uses.
+#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError"))]
+pub struct DependencySpec {
+    #[builder(setter(into))]
+    pub name: String,
+    #[builder(try_setter, setter(into), default)]
+    pub version_req: VersionReq,
+    /// Pins the dependency to the package with this root hash.
+    #[builder(setter(into), default)]
+    pub hash: Option<Hash>,
+}
+
+impl DependencySpec {
+    pub fn builder() -> DependencySpecBuilder {
+        DependencySpecBuilder::default()
+    }
+}
+
+/// The versions of a package which satisfy a dependency on it.
+///
+/// A requirement is a comma separated list of comparisons which must all hold, such as
+/// `>=2023-05-01, <2024-01-01`, or `*` for any version. A version without an operator must match
+/// exactly. Versions are compared with [`compare_versions`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct VersionReq {
+    comparators: Vec<Comparator>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Comparator {
+    op: Op,
+    version: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+}
+
+impl Op {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Exact => "=",
+            Self::Greater => ">",
+            Self::GreaterEq => ">=",
+            Self::Less => "<",
+            Self::LessEq => "<=",
+        }
+    }
+}
+
+impl VersionReq {
+    /// A requirement satisfied by every version.
+    pub fn any() -> Self {
+        Self::default()
+    }
+
+    /// A requirement satisfied by this version only.
+    pub fn exact(version: impl Into<String>) -> Self {
+        Self {
+            comparators: vec![Comparator {
+                op: Op::Exact,
+                version: version.into(),
+            }],
+        }
+    }
+
+    pub fn matches(&self, version: &str) -> bool {
+        self.comparators.iter().all(|comparator| {
+            let ordering = compare_versions(version, &comparator.version);
+            match comparator.op {
+                Op::Exact => ordering == Ordering::Equal,
+                Op::Greater => ordering == Ordering::Greater,
+                Op::GreaterEq => ordering != Ordering::Less,
+                Op::Less => ordering == Ordering::Less,
+                Op::LessEq => ordering != Ordering::Greater,
+            }
+        })
+    }
+}
+
+impl FromStr for VersionReq {
+    type Err = SpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "*" {
+            return Ok(Self::any());
+        }
+
+        let mut comparators = Vec::new();
+        for comparison in s.split(',').map(str::trim) {
+            // longer operators first, since `>` is a prefix of `>=`
+            let (op, version) = [Op::GreaterEq, Op::LessEq, Op::Greater, Op::Less, Op::Exact]
+                .into_iter()
+                .find_map(|op| {
+                    comparison
+                        .strip_prefix(op.as_str())
+                        .map(|version| (op, version))
+                })
+                .unwrap_or((Op::Exact, comparison));
+
+            let version = version.trim();
+            if version.is_empty() {
+                return Err(SpecError::InvalidVersionReq(s.to_owned()));
+            }
+            comparators.push(Comparator {
+                op,
+                version: version.to_owned(),
+            });
+        }
+
+        Ok(Self { comparators })
+    }
+}
+
+impl TryFrom<String> for VersionReq {
+    type Error = SpecError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl TryFrom<&str> for VersionReq {
+    type Error = SpecError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<VersionReq> for String {
+    fn from(value: VersionReq) -> Self {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.comparators.is_empty() {
+            return f.write_str("*");
+        }
+
+        let comparisons: Vec<String> = self
+            .comparators
+            .iter()
+            .map(|comparator| format!("{}{}", comparator.op.as_str(), comparator.version))
+            .collect();
+        f.write_str(&comparisons.join(", "))
+    }
+}
+
+/// Compares two package versions part by part, the parts being separated by `.` or `-`. Numeric
+/// parts are compared as numbers and the others as strings, which orders both `1.10.0` after
+/// `1.9.2` and date versions like `2023-05-24`. Missing parts count as `0`, so `1.0` equals
+/// `1.0.0`.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let mut a_parts = a.split(['.', '-']);
+    let mut b_parts = b.split(['.', '-']);
+
+    loop {
+        let (a_part, b_part) = match (a_parts.next(), b_parts.next()) {
+            (None, None) => return Ordering::Equal,
+            (a_part, b_part) => (a_part.unwrap_or("0"), b_part.unwrap_or("0")),
+        };
+
+        let ordering = match (a_part.parse::<u64>(), b_part.parse::<u64>()) {
+            (Ok(a_number), Ok(b_number)) => a_number.cmp(&b_number),
+            _ => a_part.cmp(b_part),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
@@ -6,18 +6,19 @@ use thiserror::Error;
 use tokio::sync::mpsc;
 
 use veritech_core::{
-    nats_action_run_subject, nats_reconciliation_subject, nats_resolver_function_subject,
-    nats_schema_variant_definition_subject, nats_subject, nats_validation_subject,
-    reply_mailbox_for_output, reply_mailbox_for_result, FINAL_MESSAGE_HEADER_KEY,
+    nats_action_run_subject, nats_cancel_execution_subject, nats_reconciliation_subject,
+    nats_resolver_function_subject, nats_schema_variant_definition_subject, nats_subject,
+    nats_validation_subject, reply_mailbox_for_output, reply_mailbox_for_result,
+    FINAL_MESSAGE_HEADER_KEY,
 };
 
 pub use cyclone_core::{
     ActionRunRequest, ActionRunResultSuccess, ComponentKind, ComponentView, EncryptionKey,
-    EncryptionKeyError, FunctionResult, FunctionResultFailure, OutputStream, ReconciliationRequest,
-    ReconciliationResultSuccess, ResolverFunctionComponent, ResolverFunctionRequest,
-    ResolverFunctionResponseType, ResolverFunctionResultSuccess, ResourceStatus,
-    SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess, SensitiveContainer,
-    ValidationRequest, ValidationResultSuccess,
+    EncryptionKeyError, FunctionResult, FunctionResultFailure, FunctionResultFailureError,
+    OutputStream, ReconciliationRequest, ReconciliationResultSuccess, ResolverFunctionComponent,
+    ResolverFunctionRequest, ResolverFunctionResponseType, ResolverFunctionResultSuccess,
+    ResourceStatus, SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess,
+    SensitiveContainer, ValidationRequest, ValidationResultSuccess,
 };
 use si_data_nats::NatsClient;
 
@@ -199,6 +200,26 @@ impl Client {
         .await
     }
 
+    /// Asks veritech to stop the in-flight execution with the given id. Whoever is waiting on the
+    /// execution's result receives a [`FunctionResultFailure`] whose error is cancelled (see
+    /// [`FunctionResultFailureError::is_cancelled`]).
+    #[instrument(name = "client.cancel_execution", skip(self))]
+    pub async fn cancel_execution(&self, execution_id: &str) -> ClientResult<()> {
+        let subject = self.cancel_execution_subject(execution_id);
+        trace!(
+            messaging.destination = &subject.as_str(),
+            "publishing cancel message"
+        );
+        self.nats.publish(subject, Vec::new()).await?;
+        Ok(())
+    }
+
+    /// The subject [`Self::cancel_execution`] publishes on, for callers that need to publish the
+    /// request themselves (e.g. only once a transaction commits). The message body is ignored.
+    pub fn cancel_execution_subject(&self, execution_id: &str) -> String {
+        nats_cancel_execution_subject(self.nats_subject_prefix(), execution_id)
+    }
+
     async fn execute_request<R, S>(
         &self,
         subject: impl Into<String>,
@@ -12,6 +12,7 @@
 )]
 
 const NATS_ACTION_RUN_DEFAULT_SUBJECT: &str = "veritech.fn.actionrun";
+const NATS_CANCEL_EXECUTION_DEFAULT_SUBJECT: &str = "veritech.cancel";
 const NATS_CONCILIATION_DEFAULT_SUBJECT: &str = "veritech.fn.reconciliation";
 const NATS_RESOLVER_FUNCTION_DEFAULT_SUBJECT: &str = "veritech.fn.resolverfunction";
 const NATS_SCHEMA_VARIANT_DEFINITION_DEFAULT_SUBJECT: &str = "veritech.fn.schemavariantdefinition";
@@ -47,6 +48,15 @@ pub fn nats_schema_variant_definition_subject(prefix: Option<&str>) -> String {
     nats_subject(prefix, NATS_SCHEMA_VARIANT_DEFINITION_DEFAULT_SUBJECT)
 }
 
+/// The subject on which a request to cancel the execution with the given id is published. The
+/// execution id must be a valid NATS subject token (e.g. no `.` or whitespace).
+pub fn nats_cancel_execution_subject(prefix: Option<&str>, execution_id: &str) -> String {
+    nats_subject(
+        prefix,
+        format!("{NATS_CANCEL_EXECUTION_DEFAULT_SUBJECT}.{execution_id}"),
+    )
+}
+
 pub fn nats_subject(prefix: Option<&str>, suffix: impl AsRef<str>) -> String {
     let suffix = suffix.as_ref();
     match prefix {
@@ -11,7 +11,7 @@ use deadpool_cyclone::{
         LocalHttpInstance, LocalHttpInstanceSpec, LocalHttpSocketStrategy, LocalUdsInstance,
         LocalUdsInstanceSpec, LocalUdsSocketStrategy,
     },
-    Instance,
+    Instance, ResourceLimits,
 };
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
@@ -168,8 +168,14 @@ pub enum CycloneConfig {
         socket_strategy: LocalHttpSocketStrategy,
         #[serde(default)]
         watch_timeout: Option<Duration>,
+        #[serde(default)]
+        execution_timeout: Option<Duration>,
         #[serde(default = "default_limit_requests")]
         limit_requets: Option<u32>,
+        #[serde(default)]
+        resource_limits: ResourceLimits,
+        #[serde(default)]
+        env_allow_list: Option<Vec<String>>,
         #[serde(default = "default_enable_endpoint")]
         ping: bool,
         #[serde(default = "default_enable_endpoint")]
@@ -188,8 +194,14 @@ pub enum CycloneConfig {
         socket_strategy: LocalUdsSocketStrategy,
         #[serde(default)]
         watch_timeout: Option<Duration>,
+        #[serde(default)]
+        execution_timeout: Option<Duration>,
         #[serde(default = "default_limit_requests")]
         limit_requets: Option<u32>,
+        #[serde(default)]
+        resource_limits: ResourceLimits,
+        #[serde(default)]
+        env_allow_list: Option<Vec<String>>,
         #[serde(default = "default_enable_endpoint")]
         ping: bool,
         #[serde(default = "default_enable_endpoint")]
@@ -207,7 +219,10 @@ impl CycloneConfig {
             lang_server_cmd_path: default_lang_server_cmd_path(),
             socket_strategy: Default::default(),
             watch_timeout: Default::default(),
+            execution_timeout: Default::default(),
             limit_requets: default_limit_requests(),
+            resource_limits: Default::default(),
+            env_allow_list: Default::default(),
             ping: default_enable_endpoint(),
             resolver: default_enable_endpoint(),
             action: default_enable_endpoint(),
@@ -221,7 +236,10 @@ impl CycloneConfig {
             lang_server_cmd_path: default_lang_server_cmd_path(),
             socket_strategy: Default::default(),
             watch_timeout: Default::default(),
+            execution_timeout: Default::default(),
             limit_requets: default_limit_requests(),
+            resource_limits: Default::default(),
+            env_allow_list: Default::default(),
             ping: default_enable_endpoint(),
             resolver: default_enable_endpoint(),
             action: default_enable_endpoint(),
@@ -309,6 +327,19 @@ impl CycloneConfig {
         };
     }
 
+    /// Sets how long a function may run before the Cyclone server terminates it, unless its
+    /// request sets a timeout of its own.
+    pub fn set_execution_timeout(&mut self, value: impl Into<Option<Duration>>) {
+        match self {
+            CycloneConfig::LocalUds {
+                execution_timeout, ..
+            } => *execution_timeout = value.into(),
+            CycloneConfig::LocalHttp {
+                execution_timeout, ..
+            } => *execution_timeout = value.into(),
+        };
+    }
+
     pub fn set_ping(&mut self, value: bool) {
         match self {
             CycloneConfig::LocalUds { ping, .. } => *ping = value,
@@ -348,7 +379,10 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 lang_server_cmd_path,
                 socket_strategy,
                 watch_timeout,
+                execution_timeout,
                 limit_requets,
+                resource_limits,
+                env_allow_list,
                 ping,
                 resolver,
                 action,
@@ -365,7 +399,14 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 if let Some(watch_timeout) = watch_timeout {
                     builder.watch_timeout(watch_timeout);
                 }
+                if let Some(execution_timeout) = execution_timeout {
+                    builder.execution_timeout(execution_timeout);
+                }
                 builder.limit_requests(limit_requets);
+                builder.resource_limits(resource_limits);
+                if let Some(env_allow_list) = env_allow_list {
+                    builder.env_allow_list(env_allow_list);
+                }
                 if ping {
                     builder.ping();
                 }
@@ -386,7 +427,10 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 lang_server_cmd_path,
                 socket_strategy,
                 watch_timeout,
+                execution_timeout,
                 limit_requets,
+                resource_limits,
+                env_allow_list,
                 ping,
                 resolver,
                 action,
@@ -403,7 +447,14 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 if let Some(watch_timeout) = watch_timeout {
                     builder.watch_timeout(watch_timeout);
                 }
+                if let Some(execution_timeout) = execution_timeout {
+                    builder.execution_timeout(execution_timeout);
+                }
                 builder.limit_requests(limit_requets);
+                builder.resource_limits(resource_limits);
+                if let Some(env_allow_list) = env_allow_list {
+                    builder.env_allow_list(env_allow_list);
+                }
                 if ping {
                     builder.ping();
                 }
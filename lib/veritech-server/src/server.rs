@@ -16,6 +16,7 @@ use tokio::{
     signal::unix,
     sync::{broadcast, mpsc},
 };
+use veritech_core::nats_cancel_execution_subject;
 
 use crate::{config::CycloneSpec, Config, FunctionSubscriber, Publisher, PublisherError};
 
@@ -32,6 +33,8 @@ pub enum ServerError {
     CycloneProgress(#[source] Box<dyn std::error::Error + Sync + Send + 'static>),
     #[error("cyclone spec builder error: {0}")]
     CycloneSpec(#[source] Box<dyn std::error::Error + Sync + Send + 'static>),
+    #[error("nats error: {0}")]
+    Nats(#[source] si_data_nats::NatsError),
     #[error("error connecting to nats: {0}")]
     NatsConnect(#[source] si_data_nats::NatsError),
     #[error("no reply mailbox found")]
@@ -628,6 +631,7 @@ async fn process_action_run_requests(
                         // Spawn a task an process the request
                         tokio::spawn(action_run_request_task(
                             nats.clone(),
+                            subject_prefix.clone(),
                             cyclone_pool.clone(),
                             request,
                         ));
@@ -657,21 +661,33 @@ async fn process_action_run_requests(
 
 async fn action_run_request_task(
     nats: NatsClient,
+    subject_prefix: Option<String>,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
     request: Request<ActionRunRequest>,
 ) {
-    if let Err(err) = action_run_request(nats, cyclone_pool, request).await {
+    if let Err(err) = action_run_request(nats, subject_prefix, cyclone_pool, request).await {
         warn!(error = ?err, "action run execution failed");
     }
 }
 
 async fn action_run_request(
     nats: NatsClient,
+    subject_prefix: Option<String>,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
     request: Request<ActionRunRequest>,
 ) -> ServerResult<()> {
     let (cyclone_request, reply_mailbox) = request.into_parts();
     let reply_mailbox = reply_mailbox.ok_or(ServerError::NoReplyMailboxFound)?;
+    let execution_id = cyclone_request.execution_id.clone();
+
+    // Actions can be long running, so listen for cancellation requests while this one executes
+    let mut cancel_subscription = nats
+        .subscribe(nats_cancel_execution_subject(
+            subject_prefix.as_deref(),
+            &execution_id,
+        ))
+        .await
+        .map_err(ServerError::Nats)?;
 
     let publisher = Publisher::new(&nats, &reply_mailbox);
     let mut client = cyclone_pool
@@ -685,20 +701,32 @@ async fn action_run_request(
         .start()
         .await?;
 
-    while let Some(msg) = progress.next().await {
-        match msg {
-            Ok(ProgressMessage::OutputStream(output)) => {
-                publisher.publish_output(&output).await?;
-            }
-            Ok(ProgressMessage::Heartbeat) => {
-                trace!("received heartbeat message");
-            }
-            Err(err) => {
-                warn!(error = ?err, "next progress message was an error, bailing out");
-                break;
+    let mut cancelled = false;
+    loop {
+        tokio::select! {
+            msg = progress.next() => match msg {
+                Some(Ok(ProgressMessage::OutputStream(output))) => {
+                    publisher.publish_output(&output).await?;
+                }
+                Some(Ok(ProgressMessage::Heartbeat)) => {
+                    trace!("received heartbeat message");
+                }
+                Some(Err(err)) => {
+                    warn!(error = ?err, "next progress message was an error, bailing out");
+                    break;
+                }
+                None => break,
+            },
+            Some(_) = cancel_subscription.next(), if !cancelled => {
+                info!(execution_id = %execution_id, "cancelling action run");
+                cancelled = true;
+                progress.cancel().await?;
             }
         }
     }
+    if let Err(err) = cancel_subscription.unsubscribe().await {
+        warn!(error = ?err, "error when unsubscribing from cancel subscription");
+    }
     publisher.finalize_output().await?;
 
     let function_result = progress.finish().await?;